@@ -0,0 +1,22 @@
+//! CLI `backup` command — write a consistent snapshot of the database.
+
+use anyhow::Result;
+use std::path::Path;
+
+use crate::config::LociConfig;
+
+/// Back up the database to `dest` while it may still be in use.
+pub fn backup(config: &LociConfig, dest: &Path) -> Result<()> {
+    let db_path = config.resolved_db_path();
+    let conn = crate::db::open_database_with(&db_path, &crate::db::OpenOptions::from(config))?;
+
+    let size = crate::db::backup_to(&conn, dest, config.storage.encryption_key.as_deref())?;
+
+    println!(
+        "Backed up {} to {} ({})",
+        db_path.display(),
+        dest.display(),
+        super::doctor::format_bytes(size)
+    );
+    Ok(())
+}
@@ -4,6 +4,9 @@ use anyhow::{Context, Result};
 
 use crate::config::LociConfig;
 use crate::db;
+use crate::memory::maintenance;
+use crate::memory::stats::{self, CONFIDENCE_BUCKETS};
+use crate::memory::types::MemoryType;
 
 /// Run database diagnostics and print a health report.
 pub fn doctor(config: &LociConfig) -> Result<()> {
@@ -19,7 +22,7 @@ pub fn doctor(config: &LociConfig) -> Result<()> {
         .map(|m| m.len())
         .unwrap_or(0);
 
-    let conn = db::open_database(&db_path)
+    let conn = db::open_database_with(&db_path, &db::OpenOptions::from(config))
         .context("failed to open database (may be corrupt)")?;
 
     let report = db::check_database_health(&conn)
@@ -43,12 +46,61 @@ pub fn doctor(config: &LociConfig) -> Result<()> {
             println!("  Status:          OK (match)");
         }
     }
+    let current_hash = crate::embedding::model_file_hash(&config.embedding)
+        .context("failed to hash embedding model file")?;
+    println!("  Stored hash:     {}", short_hash(report.embedding_model_hash.as_deref()));
+    println!("  File hash:       {}", short_hash(current_hash.as_deref()));
+    if let (Some(stored), Some(current)) = (&report.embedding_model_hash, &current_hash)
+        && stored != current
+    {
+        println!("  WARNING: model file changed! Run `loci re-embed` to update vectors.");
+    }
+    println!(
+        "  Dimensions:      {} (configured {})",
+        report.embedding_dim.map_or("(not set)".to_string(), |dim| dim.to_string()),
+        config.embedding.dimensions
+    );
     println!();
     println!("Row counts:");
     println!("  Memories:        {}", report.memory_count);
+    println!("  FTS index:       {}", report.fts_count);
+    if report.fts_count != report.memory_count {
+        println!("  WARNING: keyword index out of sync with memories! Run `loci reindex`.");
+    }
     println!("  Relations:       {}", report.relation_count);
     println!("  Audit log:       {}", report.log_count);
     println!();
+
+    let histogram = stats::confidence_histogram(&conn).context("failed to bucket confidence")?;
+    let widest = histogram.iter().copied().max().unwrap_or(0);
+    println!("Confidence (active memories):");
+    for (i, count) in histogram.iter().enumerate() {
+        let bar_len = if widest == 0 { 0 } else { (count * 30).div_ceil(widest) as usize };
+        println!(
+            "  {:.1}-{:.1}  {:>7}  {}",
+            i as f64 / CONFIDENCE_BUCKETS as f64,
+            (i + 1) as f64 / CONFIDENCE_BUCKETS as f64,
+            count,
+            "#".repeat(bar_len),
+        );
+    }
+    println!();
+
+    let crossing = maintenance::project_decay(&conn, &config.maintenance)
+        .context("failed to project decay")?;
+    let total: u64 = crossing.values().sum();
+    println!(
+        "Decay projection (next maintenance run, floor {}):",
+        config.maintenance.cleanup_confidence_floor
+    );
+    println!("  Falling below:   {total}");
+    for memory_type in MemoryType::ALL {
+        let count = crossing.get(memory_type.as_str()).copied().unwrap_or(0);
+        if count > 0 {
+            println!("    {:<14} {count}", format!("{memory_type}:"));
+        }
+    }
+    println!();
     if report.integrity_ok {
         println!("Integrity check:   PASSED");
     } else {
@@ -58,7 +110,7 @@ pub fn doctor(config: &LociConfig) -> Result<()> {
     if !report.integrity_ok {
         println!();
         println!("Recovery steps:");
-        println!("  1. Restore from a backup: cp backup.db ~/.loci/memory.db");
+        println!("  1. Restore from a backup (see `loci backup`): cp backup.db ~/.loci/memory.db");
         println!("  2. Or export from a good copy and reimport:");
         println!("     loci export > backup.json");
         println!("     loci reset && loci import backup.json");
@@ -67,7 +119,15 @@ pub fn doctor(config: &LociConfig) -> Result<()> {
     Ok(())
 }
 
-fn format_bytes(bytes: u64) -> String {
+/// The first 12 hex digits of a hash, or a placeholder when there is none.
+fn short_hash(hash: Option<&str>) -> &str {
+    match hash {
+        Some(hash) => &hash[..hash.len().min(12)],
+        None => "(not set)",
+    }
+}
+
+pub(crate) fn format_bytes(bytes: u64) -> String {
     if bytes < 1024 {
         format!("{bytes} B")
     } else if bytes < 1024 * 1024 {
@@ -1,88 +1,58 @@
 //! CLI `export` command — dump all memories and relations as JSON to stdout.
 
 use anyhow::Result;
-use rusqlite::params;
-use serde::Serialize;
 
 use crate::config::LociConfig;
-use crate::memory::types::{EntityRelation, Memory};
-
-/// Export format — wraps all memories and relations.
-#[derive(Debug, Serialize)]
-struct ExportData {
-    memories: Vec<Memory>,
-    relations: Vec<EntityRelation>,
-}
-
-/// Export all memories and relations as JSON to stdout.
-pub fn export(config: &LociConfig) -> Result<()> {
+use crate::memory::export::ExportFormat;
+
+/// Export all memories and relations to stdout, as one JSON document or as
+/// streamed JSON Lines (see [`crate::memory::export`]).
+///
+/// With `since_updated`, exports only what changed after that timestamp (see
+/// [`changed_since`](crate::memory::search::changed_since)), plus a `cursor` for the next call.
+/// With `with_embeddings`, each memory carries its stored vector so `import` can skip
+/// re-embedding.
+pub fn export(
+    config: &LociConfig,
+    since_updated: Option<&str>,
+    format: ExportFormat,
+    with_embeddings: bool,
+) -> Result<()> {
     let db_path = config.resolved_db_path();
-    let conn = crate::db::open_database(&db_path)?;
-
-    // Fetch all memories
-    let mut stmt = conn.prepare(
-        "SELECT id, type, content, source_group, scope, confidence, access_count, \
-         last_accessed, created_at, updated_at, superseded_by, metadata \
-         FROM memories ORDER BY created_at",
-    )?;
-
-    let memories: Vec<Memory> = stmt
-        .query_map([], |row| {
-            let metadata_str: Option<String> = row.get(11)?;
-            let memory_type_str: String = row.get(1)?;
-            let scope_str: String = row.get(4)?;
-            Ok(Memory {
-                id: row.get(0)?,
-                memory_type: memory_type_str
-                    .parse()
-                    .map_err(|_| rusqlite::Error::InvalidQuery)?,
-                content: row.get(2)?,
-                source_group: row.get(3)?,
-                scope: scope_str
-                    .parse()
-                    .map_err(|_| rusqlite::Error::InvalidQuery)?,
-                confidence: row.get(5)?,
-                access_count: row.get(6)?,
-                last_accessed: row.get(7)?,
-                created_at: row.get(8)?,
-                updated_at: row.get(9)?,
-                superseded_by: row.get(10)?,
-                metadata: metadata_str.and_then(|s| serde_json::from_str(&s).ok()),
-            })
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
-
-    // Fetch all relations
-    let mut stmt = conn.prepare(
-        "SELECT id, subject_id, predicate, object_id, created_at \
-         FROM entity_relations ORDER BY created_at",
-    )?;
-
-    let relations: Vec<EntityRelation> = stmt
-        .query_map(params![], |row| {
-            Ok(EntityRelation {
-                id: row.get(0)?,
-                subject_id: row.get(1)?,
-                predicate: row.get(2)?,
-                object_id: row.get(3)?,
-                created_at: row.get(4)?,
-            })
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
-
-    let data = ExportData {
-        memories,
-        relations,
+    let conn = crate::db::open_database_with(&db_path, &crate::db::OpenOptions::from(config))?;
+
+    if let Some(since) = since_updated {
+        if format == ExportFormat::Jsonl {
+            anyhow::bail!("--since-updated only supports --format json");
+        }
+        if with_embeddings {
+            anyhow::bail!("--since-updated does not support --with-embeddings");
+        }
+        let changes = crate::memory::search::changed_since(&conn, since)?;
+        println!("{}", serde_json::to_string_pretty(&changes)?);
+        eprintln!(
+            "Exported {} changed memories and {} new relations. Next cursor: {}",
+            changes.memories.len(),
+            changes.relations.len(),
+            changes.cursor
+        );
+        return Ok(());
+    }
+
+    let (memories, relations) = match format {
+        ExportFormat::Json => {
+            let data = crate::memory::export::export_data(&conn, with_embeddings)?;
+            println!("{}", serde_json::to_string_pretty(&data)?);
+            (data.memories.len(), data.relations.len())
+        }
+        ExportFormat::Jsonl => {
+            let out = std::io::BufWriter::new(std::io::stdout().lock());
+            let counts = crate::memory::export::write_jsonl(&conn, out, with_embeddings)?;
+            (counts.memories, counts.relations)
+        }
     };
 
-    let json = serde_json::to_string_pretty(&data)?;
-    println!("{json}");
-
-    eprintln!(
-        "Exported {} memories and {} relations.",
-        data.memories.len(),
-        data.relations.len()
-    );
+    eprintln!("Exported {memories} memories and {relations} relations.");
 
     Ok(())
 }
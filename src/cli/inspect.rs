@@ -4,18 +4,29 @@ use anyhow::Result;
 
 use crate::config::LociConfig;
 
+/// Number of components shown at each end of the vector summary.
+const VECTOR_EDGE: usize = 5;
+
 /// Inspect a single memory by ID and display full details.
-pub fn inspect(config: &LociConfig, id: &str) -> Result<()> {
+///
+/// With `raw_vector`, also prints the stored embedding's dimension, norm, and components.
+pub fn inspect(config: &LociConfig, id: &str, raw_vector: bool) -> Result<()> {
     let db_path = config.resolved_db_path();
-    let conn = crate::db::open_database(&db_path)?;
+    let conn = crate::db::open_database_with(&db_path, &crate::db::OpenOptions::from(config))?;
 
-    let response = crate::memory::search::inspect_memory(&conn, id, true, true)?;
+    let mut response = crate::memory::search::inspect_memory(&conn, id, true, true)?;
+    response.incoming_relations =
+        crate::memory::search::incoming_relations(&conn, &response.memory.id)?;
+    if raw_vector {
+        response.embedding = crate::memory::search::stored_embedding(&conn, &response.memory.id)?;
+    }
 
     let m = &response.memory;
     println!("Memory: {}", m.id);
     println!("{}", "=".repeat(50));
     println!("  Type:           {}", m.memory_type);
     println!("  Confidence:     {:.2}", m.confidence);
+    println!("  Importance:     {:.2}", m.importance);
     println!("  Access count:   {}", m.access_count);
     if let Some(ref la) = m.last_accessed {
         println!("  Last accessed:  {la}");
@@ -25,6 +36,12 @@ pub fn inspect(config: &LociConfig, id: &str) -> Result<()> {
     if let Some(ref sb) = m.superseded_by {
         println!("  Superseded by:  {sb}");
     }
+    if let Some(ref uri) = m.source_uri {
+        println!("  Source:         {uri}");
+    }
+    if let Some(ref hash) = m.content_hash {
+        println!("  Content hash:   {hash}");
+    }
     if let Some(ref meta) = m.metadata {
         println!("  Metadata:       {}", serde_json::to_string_pretty(meta)?);
     }
@@ -45,6 +62,17 @@ pub fn inspect(config: &LociConfig, id: &str) -> Result<()> {
         }
     }
 
+    if let Some(ref incoming) = response.incoming_relations {
+        println!();
+        println!("Incoming relations:");
+        for rel in incoming {
+            println!(
+                "  <--[{}]-- {} ({}: {})",
+                rel.predicate, rel.subject.id, rel.subject.memory_type, rel.subject.preview,
+            );
+        }
+    }
+
     if let Some(ref log) = response.log {
         if !log.is_empty() {
             println!();
@@ -60,5 +88,22 @@ pub fn inspect(config: &LociConfig, id: &str) -> Result<()> {
         }
     }
 
+    if raw_vector {
+        println!();
+        println!("Embedding:");
+        match response.embedding {
+            Some(ref e) => {
+                println!("  Dimension:      {}", e.dimension);
+                println!("  Norm:           {:.6}", e.norm);
+                if e.values.len() > 2 * VECTOR_EDGE {
+                    println!("  First:          {:?}", &e.values[..VECTOR_EDGE]);
+                    println!("  Last:           {:?}", &e.values[e.values.len() - VECTOR_EDGE..]);
+                }
+                println!("  Values:         {:?}", e.values);
+            }
+            None => println!("  (no vector stored)"),
+        }
+    }
+
     Ok(())
 }
@@ -0,0 +1,81 @@
+//! CLI `list` command — enumerate memories without a search query.
+
+use anyhow::Result;
+
+use crate::cli::OutputFormat;
+use crate::config::LociConfig;
+use crate::memory::search::ListFilter;
+use crate::memory::types::Memory;
+
+/// Bytes of content shown per memory, before the ellipsis.
+const PREVIEW_CHARS: usize = 120;
+
+/// List active memories matching `filter`, in its sort order (newest first by default).
+pub fn list(config: &LociConfig, filter: &ListFilter, format: &str) -> Result<()> {
+    let format: OutputFormat = format.parse().map_err(anyhow::Error::msg)?;
+    let db_path = config.resolved_db_path();
+    let conn = crate::db::open_database_with(&db_path, &crate::db::OpenOptions::from(config))?;
+
+    let memories = crate::memory::search::list_memories(&conn, filter)?;
+
+    if format == OutputFormat::Csv {
+        return write_csv(&memories, std::io::stdout().lock());
+    }
+
+    if memories.is_empty() {
+        println!("No memories found.");
+        return Ok(());
+    }
+
+    println!("{} memor{}\n", memories.len(), if memories.len() == 1 { "y" } else { "ies" });
+
+    for memory in &memories {
+        println!(
+            "  [{}] {} (confidence: {:.2}, created: {})",
+            memory.memory_type, memory.id, memory.confidence, memory.created_at,
+        );
+        if let Some(ref uri) = memory.source_uri {
+            println!("     source: {uri}");
+        }
+        println!("     {}", preview(&memory.content));
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Write one CSV row per memory, after a header row.
+fn write_csv(memories: &[Memory], out: impl std::io::Write) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record([
+        "id",
+        "type",
+        "group",
+        "confidence",
+        "access_count",
+        "created_at",
+        "preview",
+    ])?;
+    for memory in memories {
+        writer.write_record([
+            memory.id.as_str(),
+            memory.memory_type.as_str(),
+            memory.source_group.as_deref().unwrap_or(""),
+            &memory.confidence.to_string(),
+            &memory.access_count.to_string(),
+            &memory.created_at,
+            &preview(&memory.content),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// The first [`PREVIEW_CHARS`] bytes of `content`, cut at a char boundary.
+fn preview(content: &str) -> String {
+    if content.len() > PREVIEW_CHARS {
+        format!("{}...", &content[..content.floor_char_boundary(PREVIEW_CHARS)])
+    } else {
+        content.to_string()
+    }
+}
@@ -1,26 +1,35 @@
-//! CLI maintenance commands — `compact` and `cleanup` for memory lifecycle management.
+//! CLI maintenance commands — `compact`, `cleanup`, and `purge` for memory lifecycle management.
 
 use anyhow::Result;
 
+use super::doctor::format_bytes;
 use crate::config::LociConfig;
 use crate::memory::maintenance;
 
-/// Run full compaction cycle: decay + compact + promote.
+/// Run full compaction cycle: decay + compact + promote, then optionally vacuum.
 ///
 /// Async because compaction and promotion need the embedding provider.
-pub async fn compact(config: &LociConfig) -> Result<()> {
+pub async fn compact(config: &LociConfig, vacuum: bool) -> Result<()> {
     let db_path = config.resolved_db_path();
-    let mut conn = crate::db::open_database(&db_path)?;
+    let conn = crate::db::open_database_with(&db_path, &crate::db::OpenOptions::from(config))?;
     let embedding = crate::embedding::create_provider(&config.embedding)?;
 
-    // 1. Confidence decay
-    println!("Applying confidence decay...");
-    let decay_result = maintenance::apply_decay(&conn, &config.maintenance)?;
+    // Runs under the maintenance lock so overlapping runs can't double-process memories
+    let conn = std::sync::Mutex::new(conn);
+    let report = maintenance::run_maintenance(
+        &conn,
+        embedding.as_ref(),
+        &config.embedding.content_template,
+        &config.maintenance,
+    )?;
+    let conn = conn.into_inner().map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
 
-    let total_decayed: usize = decay_result.affected_by_type.values().sum();
+    // 1. Confidence decay
+    println!("Applied confidence decay ({} ms):", report.timing.decay_ms);
+    let total_decayed: usize = report.decay.affected_by_type.values().sum();
     if total_decayed > 0 {
         println!("  Decayed {total_decayed} memories:");
-        for (mem_type, count) in &decay_result.affected_by_type {
+        for (mem_type, count) in &report.decay.affected_by_type {
             if *count > 0 {
                 println!("    {mem_type}: {count}");
             }
@@ -30,48 +39,76 @@ pub async fn compact(config: &LociConfig) -> Result<()> {
     }
 
     // 2. Episodic compaction
-    println!("Running episodic compaction...");
-    let compact_result =
-        maintenance::compact_episodic(&mut conn, embedding.as_ref(), &config.maintenance)?;
-
-    if compact_result.summaries_created > 0 {
+    println!("Ran episodic compaction ({} ms):", report.timing.compact_ms);
+    if report.compact.summaries_created > 0 {
         println!(
             "  Compacted {} memories across {} groups into {} summaries.",
-            compact_result.memories_compacted,
-            compact_result.groups_compacted,
-            compact_result.summaries_created,
+            report.compact.memories_compacted,
+            report.compact.groups_compacted,
+            report.compact.summaries_created,
         );
     } else {
         println!("  No episodic groups eligible for compaction.");
     }
 
     // 3. Episodic-to-semantic promotion
-    println!("Checking for episodic-to-semantic promotions...");
-    let promote_result = maintenance::promote_episodic_to_semantic(
-        &mut conn,
-        embedding.as_ref(),
-        &config.maintenance,
-    )?;
-
-    if promote_result.semantics_created > 0 {
+    println!("Checked for episodic-to-semantic promotions ({} ms):", report.timing.promote_ms);
+    if report.promote.semantics_created > 0 {
         println!(
             "  Found {} clusters, created {} semantic memories.",
-            promote_result.clusters_found, promote_result.semantics_created,
+            report.promote.clusters_found, report.promote.semantics_created,
         );
     } else {
         println!("  No episodic clusters eligible for promotion.");
     }
 
+    // 4. Reclaim space freed by deletions
+    if vacuum {
+        let before = on_disk_size(&db_path);
+        crate::db::vacuum(&conn)?;
+        let after = on_disk_size(&db_path);
+        println!(
+            "Vacuumed database: {} -> {} (reclaimed {}).",
+            format_bytes(before),
+            format_bytes(after),
+            format_bytes(before.saturating_sub(after)),
+        );
+    }
+
     println!("Compaction complete.");
     Ok(())
 }
 
+/// Size of the database file plus its WAL, in bytes.
+fn on_disk_size(db_path: &std::path::Path) -> u64 {
+    let mut wal = db_path.as_os_str().to_owned();
+    wal.push("-wal");
+    [db_path.to_path_buf(), std::path::PathBuf::from(wal)]
+        .iter()
+        .filter_map(|p| std::fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
 /// Run cleanup of stale, low-confidence memories.
 pub fn cleanup(config: &LociConfig, dry_run: bool) -> Result<()> {
     let db_path = config.resolved_db_path();
-    let mut conn = crate::db::open_database(&db_path)?;
+    let mut conn = crate::db::open_database_with(&db_path, &crate::db::OpenOptions::from(config))?;
 
-    let result = maintenance::cleanup_stale(&mut conn, &config.maintenance, dry_run)?;
+    // Dry runs only read, so they don't need the maintenance lock
+    let result = if dry_run {
+        maintenance::cleanup_stale(&mut conn, &config.maintenance, true)?
+    } else {
+        let (expired, result) =
+            maintenance::with_maintenance_lock(&mut conn, &config.maintenance, "cleanup", |conn| {
+                let expired = maintenance::purge_expired(conn)?;
+                Ok((expired, maintenance::cleanup_stale(conn, &config.maintenance, false)?))
+            })?;
+        if expired > 0 {
+            println!("Purged {expired} expired memories.");
+        }
+        result
+    };
 
     if result.candidates.is_empty() {
         println!("No stale memories found.");
@@ -100,3 +137,41 @@ pub fn cleanup(config: &LociConfig, dry_run: bool) -> Result<()> {
 
     Ok(())
 }
+
+/// Permanently delete old superseded and forgotten memories.
+///
+/// `older_than_days` overrides `maintenance.purge_superseded_days`.
+pub fn purge(
+    config: &LociConfig,
+    superseded: bool,
+    older_than_days: Option<u64>,
+    dry_run: bool,
+) -> Result<()> {
+    if !superseded {
+        anyhow::bail!("nothing to purge: pass --superseded");
+    }
+    let db_path = config.resolved_db_path();
+    let mut conn = crate::db::open_database_with(&db_path, &crate::db::OpenOptions::from(config))?;
+    let older_than_days = older_than_days.unwrap_or(config.maintenance.purge_superseded_days);
+
+    let result = if dry_run {
+        maintenance::purge_superseded(&mut conn, older_than_days, true)?
+    } else {
+        maintenance::with_maintenance_lock(&mut conn, &config.maintenance, "purge", |conn| {
+            maintenance::purge_superseded(conn, older_than_days, false)
+        })?
+    };
+
+    if dry_run {
+        println!(
+            "{} superseded memories older than {older_than_days} days would be purged (dry run — nothing deleted).",
+            result.matched
+        );
+    } else {
+        println!(
+            "Purged {} superseded memories older than {older_than_days} days.",
+            result.deleted
+        );
+    }
+    Ok(())
+}
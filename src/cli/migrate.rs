@@ -0,0 +1,19 @@
+//! CLI `migrate` command — take the database down to an older schema version.
+
+use anyhow::Result;
+
+use crate::config::LociConfig;
+
+/// Reverse schema migrations until the database is at `target`.
+pub fn migrate(config: &LociConfig, target: u32) -> Result<()> {
+    let db_path = config.resolved_db_path();
+    let from = crate::db::downgrade_database(&db_path, target, &crate::db::OpenOptions::from(config))?;
+
+    if from == target {
+        println!("Database is already at schema version {target}.");
+    } else {
+        println!("Migrated {} from schema version {from} to {target}.", db_path.display());
+        println!("Opening it with this Loci version migrates it forward again.");
+    }
+    Ok(())
+}
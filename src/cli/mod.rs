@@ -3,21 +3,49 @@
 //! Provides terminal-facing commands for searching, inspecting, exporting, importing,
 //! and maintaining the memory database. Also handles ONNX model download.
 
+pub mod backup;
 pub mod doctor;
 pub mod export;
 pub mod import;
 pub mod inspect;
+pub mod list;
 pub mod maintenance;
+pub mod migrate;
+pub mod queries;
 pub mod re_embed;
+pub mod reindex;
+pub mod relink;
 pub mod reset;
 pub mod search;
 pub mod stats;
+pub mod watch;
 
 use anyhow::{Context, Result};
 use indicatif::{ProgressBar, ProgressStyle};
 use std::path::PathBuf;
 use tokio::io::AsyncWriteExt;
 
+/// Output format for listing-style commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable text.
+    Text,
+    /// CSV with a header row, for spreadsheets.
+    Csv,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "csv" => Ok(Self::Csv),
+            _ => Err(format!("unknown output format: {s} (expected text or csv)")),
+        }
+    }
+}
+
 const MODEL_URL: &str =
     "https://huggingface.co/sentence-transformers/all-MiniLM-L6-v2/resolve/main/onnx/model.onnx";
 const TOKENIZER_URL: &str =
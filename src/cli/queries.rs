@@ -0,0 +1,30 @@
+//! CLI `queries` command — summarize the recall query history.
+
+use anyhow::Result;
+
+use crate::config::LociConfig;
+
+/// Print the most frequent recall queries with their average result count.
+pub fn queries(config: &LociConfig, top: usize) -> Result<()> {
+    let db_path = config.resolved_db_path();
+    let conn = crate::db::open_database_with(&db_path, &crate::db::OpenOptions::from(config))?;
+
+    let stats = crate::memory::search::top_queries(&conn, top)?;
+    if stats.is_empty() {
+        if config.retrieval.log_queries {
+            println!("No recall queries logged yet.");
+        } else {
+            println!("No recall queries logged. Set retrieval.log_queries = true to record them.");
+        }
+        return Ok(());
+    }
+
+    println!("{:>6}  {:>11}  {:>6}  {:<25}  QUERY", "COUNT", "AVG RESULTS", "EMPTY", "LAST RUN");
+    for stat in &stats {
+        println!(
+            "{:>6}  {:>11.1}  {:>6}  {:<25}  {}",
+            stat.count, stat.avg_results, stat.empty_count, stat.last_run, stat.query
+        );
+    }
+    Ok(())
+}
@@ -1,6 +1,7 @@
 //! CLI `re-embed` command — regenerate all embeddings with the current model.
 
 use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt};
 use indicatif::{ProgressBar, ProgressStyle};
 use std::sync::Arc;
 
@@ -10,36 +11,74 @@ use crate::embedding;
 use crate::memory::embedding_to_bytes;
 
 /// Re-embed all active memories with the currently configured model.
+///
+/// Batches are embedded concurrently across `embedding.session_count` model sessions and
+/// held in memory; the vectors, model, and dimension are then written in one transaction,
+/// so a failure part-way leaves the old index intact. If `embedding.dimensions` changed,
+/// the vector table is rebuilt at the new size, and superseded memories are re-embedded
+/// too so they keep a vector.
 pub async fn re_embed(config: &LociConfig) -> Result<()> {
     let db_path = config.resolved_db_path();
-    let conn = db::open_database(&db_path)
-        .context("failed to open database")?;
+    // Skip the dimension check: fixing a mismatch is what this command is for
+    let options = db::OpenOptions {
+        embedding_dim: None,
+        ..db::OpenOptions::from(config)
+    };
+    let conn = db::open_database_with(&db_path, &options).context("failed to open database")?;
+
+    // Load one embedding provider per session
+    let providers = embedding::create_provider_pool(&config.embedding)
+        .context("failed to create embedding provider")?;
 
-    // Load embedding provider
-    let provider: Arc<dyn embedding::EmbeddingProvider> =
-        Arc::from(embedding::create_provider(&config.embedding)
-            .context("failed to create embedding provider")?);
+    let dimensions = config.embedding.dimensions;
+    let stored_dimensions = db::migrations::get_embedding_dim(&conn)?;
+    let rebuild = stored_dimensions != Some(dimensions);
 
-    // Fetch all active memories
+    // Fetch the memories to embed, rendered through the content template. A rebuilt
+    // table starts empty, so superseded memories need new vectors as well.
     let memories: Vec<(String, String)> = {
         let mut stmt = conn.prepare(
-            "SELECT id, content FROM memories WHERE superseded_by IS NULL"
+            "SELECT id, content, type, scope, source_group FROM memories \
+             WHERE ?1 OR superseded_by IS NULL",
         )?;
         let rows = stmt
-            .query_map([], |row| {
-                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            .query_map([rebuild], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                ))
             })?
             .collect::<Result<Vec<_>, _>>()?;
-        rows
+        rows.into_iter()
+            .map(|(id, content, memory_type, scope, group)| {
+                let memory_type = memory_type.parse().map_err(anyhow::Error::msg)?;
+                let scope = scope.parse().map_err(anyhow::Error::msg)?;
+                let text = embedding::render_content_template(
+                    &config.embedding.content_template,
+                    &content,
+                    memory_type,
+                    scope,
+                    group.as_deref(),
+                );
+                Ok((id, text))
+            })
+            .collect::<Result<Vec<_>>>()?
     };
 
     let total = memories.len();
-    if total == 0 {
+    if total == 0 && !rebuild {
         println!("No active memories to re-embed.");
         return Ok(());
     }
 
-    println!("Re-embedding {total} memories with model '{}'...", config.embedding.model);
+    println!(
+        "Re-embedding {total} memories with model '{}' ({} session(s))...",
+        config.embedding.model,
+        providers.len()
+    );
 
     let pb = ProgressBar::new(total as u64);
     pb.set_style(
@@ -49,27 +88,27 @@ pub async fn re_embed(config: &LociConfig) -> Result<()> {
             .progress_chars("##-"),
     );
 
-    // Process in batches of 32
+    // Process in batches of 32. At most one batch per session is in flight, and
+    // `buffered` yields results in batch order so writes line up with `chunks`.
     const BATCH_SIZE: usize = 32;
-    for chunk in memories.chunks(BATCH_SIZE) {
-        let texts: Vec<String> = chunk.iter().map(|(_, content)| content.clone()).collect();
-        let provider = Arc::clone(&provider);
-
-        let embeddings = tokio::task::spawn_blocking(move || {
-            let text_refs: Vec<&str> = texts.iter().map(|s| s.as_str()).collect();
-            provider.embed_batch(&text_refs)
+    let chunks: Vec<&[(String, String)]> = memories.chunks(BATCH_SIZE).collect();
+    let mut embedded = stream::iter(chunks.iter().enumerate())
+        .map(|(i, chunk)| {
+            let texts: Vec<String> = chunk.iter().map(|(_, content)| content.clone()).collect();
+            Arc::clone(&providers[i % providers.len()]).embed_batch_async(texts)
         })
-        .await?
-        .context("embedding batch failed")?;
-
-        for ((id, _), emb) in chunk.iter().zip(embeddings.iter()) {
-            let bytes = embedding_to_bytes(emb);
-            // Delete old vector and insert new one
-            conn.execute("DELETE FROM memories_vec WHERE id = ?1", [id])?;
-            conn.execute(
-                "INSERT INTO memories_vec (id, embedding) VALUES (?1, ?2)",
-                rusqlite::params![id, bytes],
-            )?;
+        .buffered(providers.len());
+
+    let mut vectors: Vec<(&str, Vec<f32>)> = Vec::with_capacity(total);
+    for chunk in &chunks {
+        let embeddings = embedded
+            .next()
+            .await
+            .context("embedding stream ended early")?
+            .context("embedding batch failed")?;
+
+        for ((id, _), emb) in chunk.iter().zip(embeddings) {
+            vectors.push((id, emb));
         }
 
         pb.inc(chunk.len() as u64);
@@ -77,8 +116,33 @@ pub async fn re_embed(config: &LociConfig) -> Result<()> {
 
     pb.finish_and_clear();
 
-    // Update stored model identifier
-    db::migrations::set_embedding_model(&conn, &config.embedding.model)?;
+    let model_hash = crate::embedding::model_file_hash(&config.embedding)?;
+
+    // Swap the new vectors in, and record the model and dimension they belong to
+    let tx = conn.unchecked_transaction()?;
+    if rebuild {
+        db::schema::recreate_vec_table(&tx, dimensions)
+            .context("failed to rebuild vector table")?;
+    }
+    for (id, emb) in &vectors {
+        tx.execute("DELETE FROM memories_vec WHERE id = ?1", [id])?;
+        tx.execute(
+            "INSERT INTO memories_vec (id, embedding) VALUES (?1, ?2)",
+            rusqlite::params![id, embedding_to_bytes(emb)],
+        )?;
+    }
+    db::migrations::set_embedding_model(&tx, &config.embedding.model)?;
+    if let Some(hash) = model_hash {
+        db::migrations::set_embedding_model_hash(&tx, &hash)?;
+    }
+    tx.commit()?;
+
+    if rebuild {
+        println!(
+            "Rebuilt vector table for {dimensions}-dimensional embeddings (was {}).",
+            stored_dimensions.map_or("unset".to_string(), |dim| dim.to_string())
+        );
+    }
 
     println!("Re-embedded {total} memories with model '{}'.", config.embedding.model);
     Ok(())
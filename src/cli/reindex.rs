@@ -0,0 +1,17 @@
+//! CLI `reindex` command — rebuild the FTS5 keyword index.
+
+use anyhow::Result;
+
+use crate::config::LociConfig;
+
+/// Rebuild the keyword index from the memories table.
+pub fn reindex(config: &LociConfig) -> Result<()> {
+    let db_path = config.resolved_db_path();
+    let conn = crate::db::open_database_with(&db_path, &crate::db::OpenOptions::from(config))?;
+
+    let before = crate::db::fts_row_count(&conn)?;
+    let indexed = crate::db::rebuild_fts(&conn)?;
+
+    println!("Reindexed {indexed} memories (index previously had {before} rows).");
+    Ok(())
+}
@@ -0,0 +1,22 @@
+//! CLI `relink` command — repair a memory's supersession link.
+
+use anyhow::Result;
+
+use crate::config::LociConfig;
+
+/// Set a memory's `superseded_by` to `target`, or clear it when `target` is `"none"`.
+pub fn relink(config: &LociConfig, id: &str, target: &str) -> Result<()> {
+    let db_path = config.resolved_db_path();
+    let mut conn = crate::db::open_database_with(&db_path, &crate::db::OpenOptions::from(config))?;
+
+    let target = (target != "none").then_some(target);
+    let result = crate::memory::relink::relink_memory(&mut conn, id, target)?;
+
+    println!(
+        "Relinked {}: superseded_by {} -> {}",
+        result.id,
+        result.previous.as_deref().unwrap_or("none"),
+        result.superseded_by.as_deref().unwrap_or("none"),
+    );
+    Ok(())
+}
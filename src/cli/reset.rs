@@ -21,7 +21,7 @@ pub fn reset(config: &LociConfig) -> Result<()> {
         bail!("reset cancelled");
     }
 
-    let conn = crate::db::open_database(&db_path)?;
+    let conn = crate::db::open_database_with(&db_path, &crate::db::OpenOptions::from(config))?;
 
     // Drop all data — order matters for FK constraints
     conn.execute_batch(
@@ -4,33 +4,53 @@ use anyhow::Result;
 use std::sync::Arc;
 
 use crate::config::LociConfig;
-use crate::memory::search::{SearchConfig, SearchFilter};
+use crate::memory::search::{SearchConfig, SearchFilter, SearchMode};
 
 /// Run an interactive search from the terminal.
-pub async fn search(config: &LociConfig, query: &str) -> Result<()> {
+pub async fn search(config: &LociConfig, query: &str, mode: &str) -> Result<()> {
+    let mode: SearchMode = mode.parse().map_err(anyhow::Error::msg)?;
+
     let db_path = config.resolved_db_path();
-    let conn = crate::db::open_database(&db_path)?;
+    let conn = crate::db::open_database_with(&db_path, &crate::db::OpenOptions::from(config))?;
 
-    // Create embedding provider
-    let provider = crate::embedding::create_provider(&config.embedding)?;
-    let embedding_provider: Arc<dyn crate::embedding::EmbeddingProvider> = Arc::from(provider);
+    // Keyword search doesn't need the model at all
+    let query_embedding = if mode == SearchMode::Keyword {
+        Vec::new()
+    } else {
+        let provider = crate::embedding::create_provider(&config.embedding)?;
+        let embedding_provider: Arc<dyn crate::embedding::EmbeddingProvider> = Arc::from(provider);
 
-    // Embed the query
-    let query_text = query.to_string();
-    let ep = Arc::clone(&embedding_provider);
-    let query_embedding = tokio::task::spawn_blocking(move || ep.embed(&query_text)).await??;
+        let query_text = crate::embedding::render_query_template(
+            &config.embedding.query_template,
+            query,
+            &config.storage.default_group,
+        );
+        embedding_provider.embed_async(query_text).await?
+    };
 
     let filter = SearchFilter {
-        memory_type: None,
-        scope: None,
-        group: config.storage.default_group.clone(),
         min_confidence: 0.1,
+        ..SearchFilter::for_group(config.storage.default_group.as_str())
     };
 
     let search_config = SearchConfig {
         max_results: config.retrieval.default_max_results,
         token_budget: config.retrieval.recall_token_budget,
         rrf_k: config.retrieval.rrf_k,
+        stopwords: config.retrieval.fts_stopwords.clone(),
+        mode,
+        importance_beta: config.retrieval.importance_weight,
+        log_queries: config.retrieval.log_queries,
+        balance_sources: config.retrieval.balance_sources,
+        vector_candidate_multiplier: config.retrieval.vector_candidate_multiplier,
+        fts_candidate_multiplier: config.retrieval.fts_candidate_multiplier,
+        vec_weight: config.retrieval.vec_weight,
+        fts_weight: config.retrieval.fts_weight,
+        offset: 0,
+        mmr_lambda: config.retrieval.mmr_lambda,
+        recency_half_life_days: config.retrieval.recency_half_life_days,
+        track_access: true,
+        reinforce_amount: config.maintenance.access_reinforcement(),
     };
 
     let response = crate::memory::search::recall_by_query(
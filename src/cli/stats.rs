@@ -2,15 +2,22 @@
 
 use anyhow::Result;
 
+use crate::cli::OutputFormat;
 use crate::config::LociConfig;
+use crate::memory::stats::StatsResponse;
 
 /// Display memory statistics in the terminal.
-pub fn stats(config: &LociConfig, group: Option<&str>) -> Result<()> {
+pub fn stats(config: &LociConfig, group: Option<&str>, format: &str) -> Result<()> {
+    let format: OutputFormat = format.parse().map_err(anyhow::Error::msg)?;
     let db_path = config.resolved_db_path();
-    let conn = crate::db::open_database(&db_path)?;
+    let conn = crate::db::open_database_with(&db_path, &crate::db::OpenOptions::from(config))?;
 
     let response = crate::memory::stats::memory_stats(&conn, group, Some(&db_path))?;
 
+    if format == OutputFormat::Csv {
+        return write_csv(&response, std::io::stdout().lock());
+    }
+
     println!("Memory Statistics");
     println!("{}", "=".repeat(40));
     println!("  Total memories:      {}", response.total_memories);
@@ -21,7 +28,8 @@ pub fn stats(config: &LociConfig, group: Option<&str>) -> Result<()> {
     println!("By Type:");
     for t in &["episodic", "semantic", "procedural", "entity"] {
         let count = response.by_type.get(*t).copied().unwrap_or(0);
-        println!("  {:<12} {}", t, count);
+        let tokens = response.tokens_by_type.get(*t).copied().unwrap_or(0);
+        println!("  {:<12} {:<8} ~{} tokens", t, count, tokens);
     }
     println!();
 
@@ -32,8 +40,19 @@ pub fn stats(config: &LociConfig, group: Option<&str>) -> Result<()> {
     }
     println!();
 
+    if !response.tags.is_empty() {
+        println!("By Tag:");
+        for (tag, count) in &response.tags {
+            println!("  {:<12} {}", tag, count);
+        }
+        println!();
+    }
+
     println!("Entity relations:      {}", response.entity_relations);
     println!("Database size:         {} bytes", response.db_size_bytes);
+    if let Some(avg) = response.avg_importance {
+        println!("Average importance:    {avg:.2}");
+    }
 
     if let Some(ref oldest) = response.oldest_memory {
         println!("Oldest memory:         {oldest}");
@@ -44,3 +63,44 @@ pub fn stats(config: &LociConfig, group: Option<&str>) -> Result<()> {
 
     Ok(())
 }
+
+/// Write the statistics as `section,key,value` CSV rows, after a header row.
+fn write_csv(response: &StatsResponse, out: impl std::io::Write) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(["section", "key", "value"])?;
+
+    let totals = [
+        ("total_memories", response.total_memories),
+        ("active_memories", response.active_memories),
+        ("superseded_memories", response.superseded_memories),
+        ("entity_relations", response.entity_relations),
+        ("db_size_bytes", response.db_size_bytes),
+    ];
+    for (key, value) in totals {
+        writer.write_record(["total", key, &value.to_string()])?;
+    }
+    for t in &["episodic", "semantic", "procedural", "entity"] {
+        let count = response.by_type.get(*t).copied().unwrap_or(0);
+        let tokens = response.tokens_by_type.get(*t).copied().unwrap_or(0);
+        writer.write_record(["type", t, &count.to_string()])?;
+        writer.write_record(["tokens_by_type", t, &tokens.to_string()])?;
+    }
+    for (scope, count) in &response.by_scope {
+        writer.write_record(["scope", scope, &count.to_string()])?;
+    }
+    for (tag, count) in &response.tags {
+        writer.write_record(["tag", tag, &count.to_string()])?;
+    }
+    if let Some(avg) = response.avg_importance {
+        writer.write_record(["total", "avg_importance", &format!("{avg:.2}")])?;
+    }
+    if let Some(ref oldest) = response.oldest_memory {
+        writer.write_record(["total", "oldest_memory", oldest])?;
+    }
+    if let Some(ref newest) = response.newest_memory {
+        writer.write_record(["total", "newest_memory", newest])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
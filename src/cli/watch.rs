@@ -0,0 +1,41 @@
+//! CLI `watch` command — stream new audit log entries as they are written.
+
+use anyhow::Result;
+use std::time::Duration;
+
+use crate::config::LociConfig;
+use crate::memory::search::LogFilter;
+
+/// How often the audit log is polled for new entries.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Print audit log entries written after startup until interrupted.
+pub async fn watch(config: &LociConfig, operation: Option<&str>, group: Option<&str>) -> Result<()> {
+    let db_path = config.resolved_db_path();
+    let conn = crate::db::open_database_with(&db_path, &crate::db::OpenOptions::from(config))?;
+
+    let filter = LogFilter {
+        operation: operation.map(str::to_string),
+        group: group.map(str::to_string),
+    };
+    let mut last_id = crate::memory::search::max_log_id(&conn)?;
+
+    eprintln!("Watching {} for memory operations (Ctrl-C to stop)...", db_path.display());
+
+    loop {
+        for event in crate::memory::search::log_entries_after(&conn, last_id, &filter)? {
+            let details = event
+                .entry
+                .details
+                .as_ref()
+                .map(|d| d.to_string())
+                .unwrap_or_default();
+            println!(
+                "  {} [{}] {} {}",
+                event.entry.created_at, event.entry.operation, event.memory_id, details
+            );
+            last_id = event.id;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
@@ -1,14 +1,17 @@
 //! Configuration loading and management.
 //!
 //! Loci reads configuration from `~/.loci/config.toml` (if present) with environment
-//! variable overrides (`LOCI_DB`, `LOCI_GROUP`, `LOCI_LOG_LEVEL`). All fields have
+//! variable overrides (`LOCI_DB`, `LOCI_GROUP`, `LOCI_LOG_LEVEL`, `LOCI_DB_KEY`). All fields have
 //! sensible defaults — no configuration file is required.
 
 use anyhow::{Context, Result};
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use tracing::info;
 
+use crate::memory::types::MemoryType;
+
 /// Top-level Loci configuration, deserialized from `config.toml`.
 #[derive(Debug, Deserialize, Clone)]
 #[serde(default)]
@@ -23,6 +26,8 @@ pub struct LociConfig {
     pub retrieval: RetrievalConfig,
     /// Lifecycle management (decay, compaction, promotion, cleanup).
     pub maintenance: MaintenanceConfig,
+    /// Entity graph rules (predicate allowlist, single-valued predicates).
+    pub relations: RelationsConfig,
 }
 
 /// MCP server transport and logging settings.
@@ -33,10 +38,38 @@ pub struct ServerConfig {
     pub transport: String,
     /// Tracing log level (e.g. `"info"`, `"debug"`, `"trace"`).
     pub log_level: String,
+    /// Log line format on stderr: `"text"` (default) or `"json"` (one object per line).
+    pub log_format: LogFormat,
     /// Bind address for SSE transport (default `"127.0.0.1"`).
     pub host: String,
     /// Port for SSE transport (default `8080`).
     pub port: u16,
+    /// Path the MCP endpoint is served at over SSE (default `"/mcp"`).
+    pub sse_path: String,
+    /// Serve SSE on this Unix domain socket (supports `~` expansion) instead of
+    /// `host`/`port`. Unset by default; Unix only.
+    pub unix_socket: Option<String>,
+    /// Expose the `run_maintenance` tool, letting clients trigger decay, compaction,
+    /// and promotion (default `false`).
+    pub allow_maintenance_tool: bool,
+    /// Read-only connections per database file, letting concurrent recalls and
+    /// inspections run in parallel; writes still serialize on one connection
+    /// (default `4`, `0` routes reads through the write connection).
+    pub read_connections: usize,
+    /// Require `Authorization: Bearer <token>` on every SSE/HTTP request (default unset,
+    /// no authentication). Ignored by the stdio transport.
+    pub auth_token: Option<String>,
+}
+
+/// Formatting of log lines written to stderr.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines.
+    #[default]
+    Text,
+    /// JSON lines, with event and span fields as JSON values.
+    Json,
 }
 
 /// Database path and default memory group.
@@ -47,18 +80,92 @@ pub struct StorageConfig {
     pub db_path: String,
     /// Default `source_group` for new memories (default `"default"`).
     pub default_group: String,
+    /// Copy the database to `<db>.v<n>.bak` before applying migrations (default `true`).
+    pub backup_before_migrate: bool,
+    /// Store group-scoped memories in `<db dir>/groups/<group>.db`, keeping only
+    /// global-scope memories in `db_path` (default `false`).
+    pub shard_by_group: bool,
+    /// Run a quick integrity check every time the database is opened (default `true`).
+    /// Large databases with frequent cold starts can disable it and rely on `loci doctor`.
+    pub integrity_check_on_open: bool,
+    /// Reject content shorter than this many characters, ignoring surrounding
+    /// whitespace (default 0, disabled).
+    pub min_content_chars: usize,
+    /// Content longer than this many characters is handled per `over_length_policy`
+    /// (default 100000, 0 disables the limit).
+    pub max_content_chars: usize,
+    /// What to do with over-length content: `"reject"` or `"truncate"` (default `"reject"`).
+    pub over_length_policy: OverLengthPolicy,
+    /// SQLCipher key used to encrypt the database at rest (default unset). Prefer the
+    /// `LOCI_DB_KEY` environment variable over writing the key into the config file.
+    /// Requires a build with the `sqlcipher` feature.
+    pub encryption_key: Option<String>,
+}
+
+/// Handling of content longer than `storage.max_content_chars`.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OverLengthPolicy {
+    /// Fail the store with an error.
+    #[default]
+    Reject,
+    /// Keep the first `max_content_chars` characters and note the original length in metadata.
+    Truncate,
 }
 
 /// Embedding model configuration.
 #[derive(Debug, Deserialize, Clone)]
 #[serde(default)]
 pub struct EmbeddingConfig {
-    /// Provider type: `"local"` for ONNX Runtime (only option currently).
+    /// Provider type: `"local"` for ONNX Runtime, or `"remote"` for an
+    /// OpenAI-compatible embeddings API.
     pub provider: String,
-    /// Model identifier (default `"all-MiniLM-L6-v2"`).
+    /// Model identifier (default `"all-MiniLM-L6-v2"`). Sent as `model` to a remote API.
     pub model: String,
+    /// Base URL of the remote API; requests go to `{api_base}/embeddings`
+    /// (default `"https://api.openai.com/v1"`).
+    pub api_base: String,
+    /// Bearer token for the remote API (default empty: no `Authorization` header).
+    pub api_key: String,
+    /// Length of the model's embedding vectors (default 384). The database records the
+    /// dimension it was created with; changing it requires `loci re-embed`.
+    pub dimensions: usize,
     /// Directory to cache model files (supports `~` expansion).
     pub cache_dir: String,
+    /// Number of independent model sessions used by `loci re-embed` (default 1).
+    /// Each session holds its own copy of the model in memory.
+    pub session_count: usize,
+    /// Text embedded for each stored memory; placeholders `{content}`, `{type}`,
+    /// `{scope}`, `{group}` (default `"{content}"`). Stored content is unchanged.
+    pub content_template: String,
+    /// Text embedded for each recall query; placeholders `{query}`, `{group}`
+    /// (default `"{query}"`).
+    pub query_template: String,
+    /// Name of the ONNX output to read embeddings from (default empty: try
+    /// `token_embeddings`, then `last_hidden_state`, then the first output).
+    pub output_tensor: String,
+    /// How the model output becomes one vector per text (default `"mean"`).
+    pub pooling: Pooling,
+    /// ONNX Runtime execution provider for the local model: `"cpu"`, `"cuda"`, or
+    /// `"coreml"` (default unset: CPU). Best-effort: falls back to CPU with a warning
+    /// if the provider wasn't compiled in (`--features cuda`/`coreml`) or isn't available.
+    pub execution_provider: Option<String>,
+    /// Threads used within each ONNX operator (default unset: 4).
+    pub intra_threads: Option<usize>,
+    /// Recently embedded texts whose vectors are kept in memory, so repeated content
+    /// isn't embedded again (default 1024; 0 disables the cache).
+    pub cache_size: usize,
+}
+
+/// Pooling applied to the local model's output tensor.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Pooling {
+    /// Mask-weighted mean over tokens of a `[batch, seq, dim]` output.
+    #[default]
+    Mean,
+    /// The output is already pooled to `[batch, dim]` (e.g. `sentence_embedding`).
+    None,
 }
 
 /// Search and deduplication parameters.
@@ -67,6 +174,8 @@ pub struct EmbeddingConfig {
 pub struct RetrievalConfig {
     /// Maximum results returned by `recall_memory` (default 5).
     pub default_max_results: usize,
+    /// Upper bound on a caller's `max_results`; larger requests are clamped (default 20).
+    pub max_results_ceiling: usize,
     /// Token budget for preload/summary mode (default 2000).
     pub preload_token_budget: usize,
     /// Token budget for full recall (default 4000).
@@ -75,32 +184,115 @@ pub struct RetrievalConfig {
     pub rrf_k: usize,
     /// Cosine similarity threshold for deduplication (default 0.92).
     pub dedup_threshold: f64,
+    /// Confidence added to an existing memory each time a store is deduplicated into
+    /// it, capped at 1.0 (default 0.1; 0 only bumps the access count).
+    pub dedup_confidence_boost: f64,
+    /// Words dropped from keyword (FTS) queries; the embedded text is unaffected
+    /// (default empty, disabled).
+    pub fts_stopwords: Vec<String>,
+    /// Exponent applied to memory importance when ranking recall results:
+    /// `score * importance^weight` (default 0.0, disabled).
+    pub importance_weight: f64,
+    /// Record each recall query in the `recall_log` table for `loci queries`
+    /// (default false).
+    pub log_queries: bool,
+    /// When a recall query cannot be embedded (e.g. the model is not downloaded), fall
+    /// back to keyword-only search and mark the response `degraded` instead of failing.
+    /// Also lets the server start without a model. Disable to hard-fail (default true).
+    pub keyword_fallback: bool,
+    /// Cap vector and keyword search at half the hybrid candidate pool each, so one
+    /// can't crowd out the other before RRF merging (default false).
+    pub balance_sources: bool,
+    /// Vector search candidates fetched per requested result (default 3).
+    pub vector_candidate_multiplier: usize,
+    /// Keyword search candidates fetched per requested result (default 3).
+    pub fts_candidate_multiplier: usize,
+    /// Weight of vector ranks in hybrid RRF fusion (default 1.0).
+    pub vec_weight: f64,
+    /// Weight of keyword ranks in hybrid RRF fusion (default 1.0).
+    pub fts_weight: f64,
+    /// MMR trade-off for diversifying recall results, or `None` (default) to disable.
+    pub mmr_lambda: Option<f64>,
+    /// Half-life in days for recency-weighted recall scores, or `None` (default) to ignore age.
+    pub recency_half_life_days: Option<f64>,
+    /// Confidence given to new episodic memories stored without one (default 1.0).
+    pub episodic_default_confidence: f64,
+    /// Confidence given to new semantic memories stored without one (default 1.0).
+    pub semantic_default_confidence: f64,
+    /// Confidence given to new procedural memories stored without one (default 1.0).
+    pub procedural_default_confidence: f64,
+    /// Confidence given to new entity memories stored without one (default 1.0).
+    pub entity_default_confidence: f64,
 }
 
 /// Memory lifecycle management settings.
 #[derive(Debug, Deserialize, Clone)]
 #[serde(default)]
 pub struct MaintenanceConfig {
-    /// Enable automatic maintenance on startup (default `false`).
+    /// Run maintenance in the background while serving (default `false`).
     pub enabled: bool,
     /// Days between automatic maintenance runs (default 7).
     pub interval_days: u64,
-    /// Per-cycle decay multiplier for episodic memories (default 0.95).
+    /// Decay multiplier per `interval_days` elapsed, for episodic memories (default 0.95).
     pub episodic_decay_factor: f64,
-    /// Per-cycle decay multiplier for semantic/procedural/entity memories (default 0.99).
+    /// Decay multiplier per `interval_days` elapsed, for semantic/procedural/entity memories (default 0.99).
     pub semantic_decay_factor: f64,
     /// Minimum age in days before episodic memories are eligible for compaction (default 30).
     pub compaction_age_days: u64,
     /// Minimum group size for episodic compaction (default 5).
     pub compaction_min_group_size: usize,
+    /// Text placed between member contents in a compaction summary (default `"\n---\n"`).
+    pub compaction_separator: String,
+    /// Compaction summaries are truncated to this many characters (default 4000).
+    pub compaction_max_chars: usize,
+    /// Prefix each member's content with its `[created_at]` timestamp (default `false`).
+    pub compaction_include_timestamps: bool,
     /// Minimum cluster size for episodic-to-semantic promotion (default 3).
     pub promotion_threshold: usize,
     /// Cosine similarity threshold for promotion clustering (default 0.88).
     pub promotion_similarity: f64,
+    /// Cosine similarity at which a promoted memory is folded into an existing semantic
+    /// memory instead of being stored anew (default 0.88).
+    pub promotion_dedup_threshold: f64,
+    /// Record the source episodic IDs in a promoted memory's `metadata.promoted_from_ids`
+    /// (default `false`). Sources stay active either way.
+    pub promotion_link_sources: bool,
     /// Confidence below this floor makes a memory eligible for cleanup (default 0.05).
     pub cleanup_confidence_floor: f64,
     /// Days without access before a low-confidence memory is cleaned up (default 90).
     pub cleanup_no_access_days: u64,
+    /// Days after which a session-scoped memory is cleaned up whatever its confidence
+    /// (default 1).
+    pub cleanup_session_days: u64,
+    /// Memories with importance at or above this value are never cleaned up (default 0.8).
+    pub cleanup_importance_exempt: f64,
+    /// Minutes after which a held maintenance lock is treated as abandoned (default 60).
+    pub lock_stale_minutes: u64,
+    /// Days a memory must have been superseded or forgotten before `loci purge
+    /// --superseded` deletes it (default 30).
+    pub purge_superseded_days: u64,
+    /// Raise the confidence of memories each time recall returns them (default `false`),
+    /// so useful memories resist decay.
+    pub reinforce_on_access: bool,
+    /// Confidence added per recall when `reinforce_on_access` is set, capped at 1.0
+    /// (default 0.02).
+    pub reinforce_amount: f64,
+}
+
+/// Rules enforced when storing entity relations. All are off by default.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct RelationsConfig {
+    /// Predicates `store_relation` accepts; any other is rejected (default empty,
+    /// allow anything).
+    pub allowed_predicates: Vec<String>,
+    /// Predicates a subject may have at most one object for, e.g. `"born_in"`
+    /// (default empty).
+    pub single_valued_predicates: Vec<String>,
+    /// Predicate pairs that state the same fact in opposite directions, e.g.
+    /// `{ manages = "managed_by" }`. Storing one deduplicates against an existing edge
+    /// of the other; map a predicate to itself to make it symmetric (default empty).
+    pub inverse_predicates: HashMap<String, String>,
 }
 
 impl Default for LociConfig {
@@ -111,6 +303,7 @@ impl Default for LociConfig {
             embedding: EmbeddingConfig::default(),
             retrieval: RetrievalConfig::default(),
             maintenance: MaintenanceConfig::default(),
+            relations: RelationsConfig::default(),
         }
     }
 }
@@ -120,8 +313,14 @@ impl Default for ServerConfig {
         Self {
             transport: "stdio".into(),
             log_level: "info".into(),
+            log_format: LogFormat::Text,
             host: "127.0.0.1".into(),
             port: 8080,
+            sse_path: "/mcp".into(),
+            unix_socket: None,
+            allow_maintenance_tool: false,
+            read_connections: 4,
+            auth_token: None,
         }
     }
 }
@@ -135,6 +334,13 @@ impl Default for StorageConfig {
         Self {
             db_path,
             default_group: "default".into(),
+            backup_before_migrate: true,
+            shard_by_group: false,
+            integrity_check_on_open: true,
+            min_content_chars: 0,
+            max_content_chars: 100_000,
+            over_length_policy: OverLengthPolicy::Reject,
+            encryption_key: None,
         }
     }
 }
@@ -148,7 +354,18 @@ impl Default for EmbeddingConfig {
         Self {
             provider: "local".into(),
             model: "all-MiniLM-L6-v2".into(),
+            api_base: "https://api.openai.com/v1".into(),
+            api_key: String::new(),
+            dimensions: crate::embedding::EMBEDDING_DIM,
             cache_dir,
+            session_count: 1,
+            content_template: "{content}".into(),
+            query_template: "{query}".into(),
+            output_tensor: String::new(),
+            pooling: Pooling::Mean,
+            execution_provider: None,
+            intra_threads: None,
+            cache_size: 1024,
         }
     }
 }
@@ -157,10 +374,39 @@ impl Default for RetrievalConfig {
     fn default() -> Self {
         Self {
             default_max_results: 5,
+            max_results_ceiling: 20,
             preload_token_budget: 2000,
             recall_token_budget: 4000,
             rrf_k: 60,
             dedup_threshold: 0.92,
+            dedup_confidence_boost: 0.1,
+            fts_stopwords: Vec::new(),
+            importance_weight: 0.0,
+            log_queries: false,
+            keyword_fallback: true,
+            balance_sources: false,
+            vector_candidate_multiplier: 3,
+            fts_candidate_multiplier: 3,
+            vec_weight: 1.0,
+            fts_weight: 1.0,
+            mmr_lambda: None,
+            recency_half_life_days: None,
+            episodic_default_confidence: 1.0,
+            semantic_default_confidence: 1.0,
+            procedural_default_confidence: 1.0,
+            entity_default_confidence: 1.0,
+        }
+    }
+}
+
+impl RetrievalConfig {
+    /// Initial confidence for a new memory of the given type when the caller omits one.
+    pub fn default_confidence(&self, memory_type: MemoryType) -> f64 {
+        match memory_type {
+            MemoryType::Episodic => self.episodic_default_confidence,
+            MemoryType::Semantic => self.semantic_default_confidence,
+            MemoryType::Procedural => self.procedural_default_confidence,
+            MemoryType::Entity => self.entity_default_confidence,
         }
     }
 }
@@ -174,12 +420,52 @@ impl Default for MaintenanceConfig {
             semantic_decay_factor: 0.99,
             compaction_age_days: 30,
             compaction_min_group_size: 5,
+            compaction_separator: "\n---\n".into(),
+            compaction_max_chars: 4000,
+            compaction_include_timestamps: false,
             promotion_threshold: 3,
             promotion_similarity: 0.88,
+            promotion_dedup_threshold: 0.88,
+            promotion_link_sources: false,
             cleanup_confidence_floor: 0.05,
             cleanup_no_access_days: 90,
+            cleanup_session_days: 1,
+            cleanup_importance_exempt: 0.8,
+            lock_stale_minutes: 60,
+            purge_superseded_days: 30,
+            reinforce_on_access: false,
+            reinforce_amount: 0.02,
+        }
+    }
+}
+
+impl MaintenanceConfig {
+    /// Per-cycle decay multiplier for the given memory type.
+    pub fn decay_factor(&self, memory_type: MemoryType) -> f64 {
+        match memory_type {
+            MemoryType::Episodic => self.episodic_decay_factor,
+            MemoryType::Semantic | MemoryType::Procedural | MemoryType::Entity => {
+                self.semantic_decay_factor
+            }
+        }
+    }
+
+    /// Confidence added to each recalled memory: `reinforce_amount` if
+    /// `reinforce_on_access` is set, otherwise 0.
+    pub fn access_reinforcement(&self) -> f64 {
+        if self.reinforce_on_access {
+            self.reinforce_amount
+        } else {
+            0.0
         }
     }
+
+    /// Confidence multiplier for `elapsed_days` of decay: the per-cycle factor applied
+    /// once per `interval_days`, pro rata.
+    pub fn decay_multiplier(&self, memory_type: MemoryType, elapsed_days: f64) -> f64 {
+        let cycles = elapsed_days.max(0.0) / self.interval_days.max(1) as f64;
+        self.decay_factor(memory_type).powf(cycles)
+    }
 }
 
 /// Returns `~/.loci/`
@@ -216,7 +502,7 @@ impl LociConfig {
         Ok(config)
     }
 
-    /// Apply environment variable overrides (LOCI_DB, LOCI_GROUP, LOCI_LOG_LEVEL).
+    /// Apply environment variable overrides (LOCI_DB, LOCI_GROUP, LOCI_LOG_LEVEL, LOCI_DB_KEY).
     fn apply_env_overrides(&mut self) {
         self.apply_env_overrides_with(|key| std::env::var(key));
     }
@@ -232,6 +518,11 @@ impl LociConfig {
         if let Ok(val) = env("LOCI_LOG_LEVEL") {
             self.server.log_level = val;
         }
+        if let Ok(val) = env("LOCI_DB_KEY")
+            && !val.is_empty()
+        {
+            self.storage.encryption_key = Some(val);
+        }
     }
 
     /// Resolve the database path, expanding `~` if needed.
@@ -260,7 +551,9 @@ mod tests {
         assert_eq!(config.server.transport, "stdio");
         assert_eq!(config.server.log_level, "info");
         assert_eq!(config.storage.default_group, "default");
+        assert!(config.storage.backup_before_migrate);
         assert_eq!(config.retrieval.rrf_k, 60);
+        assert_eq!(config.retrieval.max_results_ceiling, 20);
         assert!(config.storage.db_path.ends_with("memory.db"));
     }
 
@@ -269,23 +562,38 @@ mod tests {
         let toml_str = r#"
 [server]
 log_level = "debug"
+log_format = "json"
 
 [storage]
 db_path = "/tmp/test.db"
 default_group = "myproject"
+over_length_policy = "truncate"
 
 [retrieval]
 default_max_results = 10
 "#;
         let config: LociConfig = toml::from_str(toml_str).unwrap();
         assert_eq!(config.server.log_level, "debug");
+        assert_eq!(config.server.log_format, LogFormat::Json);
         assert_eq!(config.storage.db_path, "/tmp/test.db");
         assert_eq!(config.storage.default_group, "myproject");
+        assert_eq!(config.storage.over_length_policy, OverLengthPolicy::Truncate);
         assert_eq!(config.retrieval.default_max_results, 10);
         // defaults still apply for unset fields
         assert_eq!(config.retrieval.rrf_k, 60);
     }
 
+    #[test]
+    fn default_confidence_is_per_type() {
+        let toml_str = r#"
+[retrieval]
+episodic_default_confidence = 0.8
+"#;
+        let config: LociConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.retrieval.default_confidence(MemoryType::Episodic), 0.8);
+        assert_eq!(config.retrieval.default_confidence(MemoryType::Semantic), 1.0);
+    }
+
     #[test]
     fn env_overrides_apply() {
         let mut config = LociConfig::default();
@@ -293,6 +601,7 @@ default_max_results = 10
             "LOCI_DB" => Ok("/tmp/override.db".into()),
             "LOCI_GROUP" => Ok("env-group".into()),
             "LOCI_LOG_LEVEL" => Ok("trace".into()),
+            "LOCI_DB_KEY" => Ok("s3cret".into()),
             _ => Err(std::env::VarError::NotPresent),
         };
 
@@ -301,5 +610,6 @@ default_max_results = 10
         assert_eq!(config.storage.db_path, "/tmp/override.db");
         assert_eq!(config.storage.default_group, "env-group");
         assert_eq!(config.server.log_level, "trace");
+        assert_eq!(config.storage.encryption_key.as_deref(), Some("s3cret"));
     }
 }
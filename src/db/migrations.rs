@@ -1,12 +1,14 @@
-//! Forward-only schema migration framework.
+//! Schema migration framework.
 //!
 //! Tracks the schema version in `schema_meta` and runs sequential migrations
-//! to bring the database up to [`CURRENT_SCHEMA_VERSION`].
+//! to bring the database up to [`CURRENT_SCHEMA_VERSION`]. Opening a database only
+//! ever migrates forward; [`migrate_down`] reverses steps on request, so a database
+//! can be handed back to an older binary after a rollback.
 
 use rusqlite::Connection;
 
 /// The schema version that the current binary expects.
-pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+pub const CURRENT_SCHEMA_VERSION: u32 = 12;
 
 /// Get the current schema version from the database.
 pub fn get_schema_version(conn: &Connection) -> rusqlite::Result<u32> {
@@ -51,30 +53,167 @@ pub fn set_embedding_model(conn: &Connection, model: &str) -> rusqlite::Result<(
     Ok(())
 }
 
-/// Run any pending forward-only migrations. Each migration runs in a transaction.
+/// Get the stored SHA-256 of the embedding model file, if any.
+pub fn get_embedding_model_hash(conn: &Connection) -> rusqlite::Result<Option<String>> {
+    match conn.query_row(
+        "SELECT value FROM schema_meta WHERE key = 'embedding_model_hash'",
+        [],
+        |row| row.get::<_, String>(0),
+    ) {
+        Ok(val) => Ok(Some(val)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Set the stored SHA-256 of the embedding model file.
+pub fn set_embedding_model_hash(conn: &Connection, hash: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO schema_meta (key, value) VALUES ('embedding_model_hash', ?1)",
+        [hash],
+    )?;
+    Ok(())
+}
+
+/// Get the stored embedding vector length, if any.
+pub fn get_embedding_dim(conn: &Connection) -> rusqlite::Result<Option<usize>> {
+    match conn.query_row(
+        "SELECT value FROM schema_meta WHERE key = 'embedding_dim'",
+        [],
+        |row| row.get::<_, String>(0),
+    ) {
+        Ok(val) => Ok(val.parse().ok()),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Set the stored embedding vector length.
+pub fn set_embedding_dim(conn: &Connection, dimensions: usize) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO schema_meta (key, value) VALUES ('embedding_dim', ?1)",
+        [dimensions.to_string()],
+    )?;
+    Ok(())
+}
+
+/// Run any pending forward-only migrations.
+///
+/// Each migration runs in a transaction together with its schema version bump, so an
+/// interrupted upgrade resumes from the last completed step. Foreign keys are off while
+/// migrating (SQLite ignores the pragma inside a transaction), so table rebuilds don't
+/// cascade deletes into `entity_relations`.
 pub fn run_migrations(conn: &Connection) -> rusqlite::Result<()> {
-    let mut version = get_schema_version(conn)?;
+    let version = get_schema_version(conn)?;
     tracing::debug!(schema_version = version, target = CURRENT_SCHEMA_VERSION, "checking migrations");
+    if version >= CURRENT_SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    let foreign_keys: bool = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0))?;
+    conn.pragma_update(None, "foreign_keys", false)?;
+    let result = migrate_up_from(conn, version);
+    conn.pragma_update(None, "foreign_keys", foreign_keys)?;
+    result
+}
+
+/// Migration into a schema version, taking the database forward one version.
+type UpStep = fn(&Connection) -> rusqlite::Result<()>;
 
+/// The migration into `version`, or `None` if this Loci version doesn't know it.
+fn up_step(version: u32) -> Option<UpStep> {
+    match version {
+        2 => Some(migrate_v1_to_v2),
+        3 => Some(migrate_v2_to_v3),
+        4 => Some(migrate_v3_to_v4),
+        5 => Some(migrate_v4_to_v5),
+        6 => Some(migrate_v5_to_v6),
+        7 => Some(migrate_v6_to_v7),
+        8 => Some(migrate_v7_to_v8),
+        9 => Some(migrate_v8_to_v9),
+        10 => Some(migrate_v9_to_v10),
+        11 => Some(migrate_v10_to_v11),
+        12 => Some(migrate_v11_to_v12),
+        _ => None,
+    }
+}
+
+fn migrate_up_from(conn: &Connection, mut version: u32) -> rusqlite::Result<()> {
     while version < CURRENT_SCHEMA_VERSION {
         let next = version + 1;
         tracing::info!(from = version, to = next, "running migration");
 
-        match next {
-            2 => migrate_v1_to_v2(conn)?,
-            _ => {
-                tracing::error!(version = next, "unknown migration target");
-                break;
-            }
-        }
+        let Some(step) = up_step(next) else {
+            tracing::error!(version = next, "unknown migration target");
+            break;
+        };
 
-        update_schema_version(conn, next)?;
+        let tx = conn.unchecked_transaction()?;
+        step(&tx)?;
+        update_schema_version(&tx, next)?;
+        tx.commit()?;
         version = next;
     }
 
     Ok(())
 }
 
+/// Reversal of the migration into a schema version, taking the database back one version.
+type DownStep = fn(&Connection) -> anyhow::Result<()>;
+
+/// The registered reversal of the migration into `version`, or `None` if it can't be undone.
+fn down_step(version: u32) -> Option<DownStep> {
+    match version {
+        2 => Some(revert_v2_to_v1),
+        3 => Some(revert_v3_to_v2),
+        4 => Some(revert_v4_to_v3),
+        5 => Some(revert_v5_to_v4),
+        6 => Some(revert_v6_to_v5),
+        7 => Some(revert_v7_to_v6),
+        8 => Some(revert_v8_to_v7),
+        9 => Some(revert_v9_to_v8),
+        10 => Some(revert_v10_to_v9),
+        11 => Some(revert_v11_to_v10),
+        12 => Some(revert_v12_to_v11),
+        _ => None,
+    }
+}
+
+/// Reverse migrations until the database is at schema version `target`.
+///
+/// Every step between the current version and `target` must have a registered
+/// reversal; otherwise nothing is changed. All steps run in one transaction, so a
+/// step that refuses (e.g. because rows exist the older schema can't hold) leaves
+/// the database at its original version. Returns the version migrated down from.
+pub fn migrate_down(conn: &Connection, target: u32) -> anyhow::Result<u32> {
+    let version = get_schema_version(conn)?;
+    if target == 0 {
+        anyhow::bail!("schema versions start at 1");
+    }
+    if target > version {
+        anyhow::bail!(
+            "database is at schema version {version}, below the requested {target}; \
+             opening it with Loci migrates it forward automatically"
+        );
+    }
+    if let Some(blocked) = (target + 1..=version).rev().find(|v| down_step(*v).is_none()) {
+        anyhow::bail!(
+            "the migration to schema version {blocked} can't be reversed by this Loci \
+             version, so the database can't be taken below version {blocked}"
+        );
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    for from in (target + 1..=version).rev() {
+        tracing::info!(from, to = from - 1, "reverting migration");
+        let step = down_step(from).expect("checked above");
+        step(&tx).map_err(|e| anyhow::anyhow!("reverting v{from} → v{}: {e}", from - 1))?;
+        update_schema_version(&tx, from - 1)?;
+    }
+    tx.commit()?;
+    Ok(version)
+}
+
 /// Migration v1 → v2: Store embedding model identifier in schema_meta.
 fn migrate_v1_to_v2(conn: &Connection) -> rusqlite::Result<()> {
     conn.execute(
@@ -84,6 +223,260 @@ fn migrate_v1_to_v2(conn: &Connection) -> rusqlite::Result<()> {
     Ok(())
 }
 
+/// Migration v2 → v3: Add an indexed `source_uri` column for citations.
+fn migrate_v2_to_v3(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE memories ADD COLUMN source_uri TEXT;
+         CREATE INDEX IF NOT EXISTS idx_memories_source_uri ON memories(source_uri);",
+    )?;
+    Ok(())
+}
+
+/// Migration v3 → v4: Add an indexed `content_hash` column and backfill it.
+fn migrate_v3_to_v4(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE memories ADD COLUMN content_hash TEXT;
+         CREATE INDEX IF NOT EXISTS idx_memories_content_hash ON memories(content_hash);",
+    )?;
+
+    let rows: Vec<(String, String)> = conn
+        .prepare("SELECT id, content FROM memories")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut update = conn.prepare("UPDATE memories SET content_hash = ?1 WHERE id = ?2")?;
+    for (id, content) in &rows {
+        update.execute([crate::memory::content_hash(content), id.clone()])?;
+    }
+    tracing::info!(backfilled = rows.len(), "content hashes computed");
+    Ok(())
+}
+
+/// Migration v4 → v5: Add `importance` (retrieval salience, separate from confidence).
+fn migrate_v4_to_v5(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch("ALTER TABLE memories ADD COLUMN importance REAL NOT NULL DEFAULT 0.5;")?;
+    Ok(())
+}
+
+/// Migration v5 → v6: Add the opt-in `recall_log` query history table.
+fn migrate_v5_to_v6(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS recall_log (
+             id INTEGER PRIMARY KEY AUTOINCREMENT,
+             query TEXT NOT NULL,
+             filters TEXT,
+             result_count INTEGER NOT NULL,
+             created_at TEXT NOT NULL
+         );
+         CREATE INDEX IF NOT EXISTS idx_recall_log_query ON recall_log(query);",
+    )?;
+    Ok(())
+}
+
+/// Migration v6 → v7: Allow `scope = 'session'` and add an indexed `session_id` column.
+///
+/// SQLite can't alter a CHECK constraint, so `memories` is rebuilt, keeping rowids (the
+/// FTS index refers to them). [`run_migrations`] turns foreign keys off, so dropping the
+/// old table doesn't cascade into `entity_relations`.
+fn migrate_v6_to_v7(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE memories_v7 (
+             id TEXT PRIMARY KEY,
+             type TEXT NOT NULL CHECK(type IN ('episodic','semantic','procedural','entity')),
+             content TEXT NOT NULL,
+             source_group TEXT,
+             scope TEXT NOT NULL DEFAULT 'global' CHECK(scope IN ('global','group','session')),
+             confidence REAL NOT NULL DEFAULT 1.0 CHECK(confidence >= 0.0 AND confidence <= 1.0),
+             access_count INTEGER NOT NULL DEFAULT 0,
+             last_accessed TEXT,
+             created_at TEXT NOT NULL,
+             updated_at TEXT NOT NULL,
+             superseded_by TEXT,
+             metadata TEXT,
+             source_uri TEXT,
+             content_hash TEXT,
+             importance REAL NOT NULL DEFAULT 0.5,
+             session_id TEXT
+         );
+         INSERT INTO memories_v7 (rowid, id, type, content, source_group, scope, confidence,
+             access_count, last_accessed, created_at, updated_at, superseded_by, metadata,
+             source_uri, content_hash, importance)
+         SELECT rowid, id, type, content, source_group, scope, confidence,
+             access_count, last_accessed, created_at, updated_at, superseded_by, metadata,
+             source_uri, content_hash, importance
+         FROM memories;
+         DROP TABLE memories;
+         ALTER TABLE memories_v7 RENAME TO memories;
+         CREATE INDEX IF NOT EXISTS idx_memories_type ON memories(type);
+         CREATE INDEX IF NOT EXISTS idx_memories_scope ON memories(scope);
+         CREATE INDEX IF NOT EXISTS idx_memories_group ON memories(source_group);
+         CREATE INDEX IF NOT EXISTS idx_memories_confidence ON memories(confidence);
+         CREATE INDEX IF NOT EXISTS idx_memories_superseded ON memories(superseded_by);
+         CREATE INDEX IF NOT EXISTS idx_memories_source_uri ON memories(source_uri);
+         CREATE INDEX IF NOT EXISTS idx_memories_content_hash ON memories(content_hash);
+         CREATE INDEX IF NOT EXISTS idx_memories_session ON memories(session_id);",
+    )?;
+    Ok(())
+}
+
+/// Migration v7 → v8: Add an optional, indexed `expires_at` timestamp.
+fn migrate_v7_to_v8(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE memories ADD COLUMN expires_at TEXT;
+         CREATE INDEX IF NOT EXISTS idx_memories_expires_at ON memories(expires_at);",
+    )?;
+    Ok(())
+}
+
+/// Migration v8 → v9: Add `tags`, a JSON array of labels.
+fn migrate_v8_to_v9(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch("ALTER TABLE memories ADD COLUMN tags TEXT;")?;
+    Ok(())
+}
+
+/// Migration v9 → v10: Record the embedding dimension in schema_meta. Databases
+/// created before it was configurable always used 384-dimensional vectors.
+fn migrate_v9_to_v10(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO schema_meta (key, value) VALUES ('embedding_dim', '384')",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Migration v10 → v11: Allow the `relation_delete` audit operation.
+///
+/// SQLite can't alter a CHECK constraint, so `memory_log` is rebuilt, keeping entry IDs
+/// (`loci watch` resumes from them).
+fn migrate_v10_to_v11(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE memory_log_v11 (
+             id INTEGER PRIMARY KEY AUTOINCREMENT,
+             operation TEXT NOT NULL CHECK(operation IN ('create','update','supersede','decay','compact','delete','relation_delete')),
+             memory_id TEXT NOT NULL,
+             details TEXT,
+             created_at TEXT NOT NULL
+         );
+         INSERT INTO memory_log_v11 (id, operation, memory_id, details, created_at)
+         SELECT id, operation, memory_id, details, created_at FROM memory_log;
+         DROP TABLE memory_log;
+         ALTER TABLE memory_log_v11 RENAME TO memory_log;",
+    )?;
+    Ok(())
+}
+
+/// Migration v11 → v12: Add `last_decayed_at`, so decay is applied by elapsed time
+/// rather than once per run. Existing memories decay from `created_at`.
+fn migrate_v11_to_v12(conn: &Connection) -> rusqlite::Result<()> {
+    // Earlier versions decayed every row on each run and bumped `updated_at`, so that is
+    // when decay last applied; leaving it NULL would re-apply the whole lifetime's decay
+    conn.execute_batch(
+        "ALTER TABLE memories ADD COLUMN last_decayed_at TEXT;
+         UPDATE memories SET last_decayed_at = updated_at;",
+    )?;
+    Ok(())
+}
+
+/// Reverse v1 → v2: Forget the stored embedding model identifier.
+fn revert_v2_to_v1(conn: &Connection) -> anyhow::Result<()> {
+    conn.execute("DELETE FROM schema_meta WHERE key = 'embedding_model'", [])?;
+    Ok(())
+}
+
+/// Reverse v2 → v3: Drop `source_uri` and its index.
+fn revert_v3_to_v2(conn: &Connection) -> anyhow::Result<()> {
+    conn.execute_batch(
+        "DROP INDEX IF EXISTS idx_memories_source_uri;
+         ALTER TABLE memories DROP COLUMN source_uri;",
+    )?;
+    Ok(())
+}
+
+/// Reverse v3 → v4: Drop `content_hash` and its index; upgrading again recomputes it.
+fn revert_v4_to_v3(conn: &Connection) -> anyhow::Result<()> {
+    conn.execute_batch(
+        "DROP INDEX IF EXISTS idx_memories_content_hash;
+         ALTER TABLE memories DROP COLUMN content_hash;",
+    )?;
+    Ok(())
+}
+
+/// Reverse v4 → v5: Drop `importance`.
+fn revert_v5_to_v4(conn: &Connection) -> anyhow::Result<()> {
+    conn.execute_batch("ALTER TABLE memories DROP COLUMN importance;")?;
+    Ok(())
+}
+
+/// Reverse v5 → v6: Drop the `recall_log` query history.
+fn revert_v6_to_v5(conn: &Connection) -> anyhow::Result<()> {
+    conn.execute_batch("DROP TABLE IF EXISTS recall_log;")?;
+    Ok(())
+}
+
+/// Reverse v6 → v7: Drop `session_id`. Refuses while session-scoped memories exist,
+/// since older versions can't represent them.
+///
+/// The widened `scope` CHECK is left in place: older versions never write `'session'`,
+/// and upgrading again rebuilds the table anyway.
+fn revert_v7_to_v6(conn: &Connection) -> anyhow::Result<()> {
+    let sessions: i64 =
+        conn.query_row("SELECT COUNT(*) FROM memories WHERE scope = 'session'", [], |row| {
+            row.get(0)
+        })?;
+    if sessions > 0 {
+        anyhow::bail!(
+            "{sessions} session-scoped memories exist; schema versions before 7 can't hold \
+             them. Forget them or wait for session cleanup first."
+        );
+    }
+    conn.execute_batch(
+        "DROP INDEX IF EXISTS idx_memories_session;
+         ALTER TABLE memories DROP COLUMN session_id;",
+    )?;
+    Ok(())
+}
+
+/// Reverse v7 → v8: Drop `expires_at` and its index.
+fn revert_v8_to_v7(conn: &Connection) -> anyhow::Result<()> {
+    conn.execute_batch(
+        "DROP INDEX IF EXISTS idx_memories_expires_at;
+         ALTER TABLE memories DROP COLUMN expires_at;",
+    )?;
+    Ok(())
+}
+
+/// Reverse v8 → v9: Drop `tags`.
+fn revert_v9_to_v8(conn: &Connection) -> anyhow::Result<()> {
+    conn.execute_batch("ALTER TABLE memories DROP COLUMN tags;")?;
+    Ok(())
+}
+
+/// Reverse v9 → v10: Forget the stored embedding dimension. Refuses unless it is 384,
+/// the only dimension older versions support.
+fn revert_v10_to_v9(conn: &Connection) -> anyhow::Result<()> {
+    if let Some(dim) = get_embedding_dim(conn)?
+        && dim != 384
+    {
+        anyhow::bail!(
+            "the database stores {dim}-dimensional embeddings; schema versions before 10 \
+             only support 384. Re-embed with a 384-dimensional model first."
+        );
+    }
+    conn.execute("DELETE FROM schema_meta WHERE key = 'embedding_dim'", [])?;
+    Ok(())
+}
+
+/// Reverse v10 → v11: Nothing to undo. Older versions never write `relation_delete`
+/// entries, and upgrading again rebuilds `memory_log`.
+fn revert_v11_to_v10(_conn: &Connection) -> anyhow::Result<()> {
+    Ok(())
+}
+
+/// Reverse v11 → v12: Drop `last_decayed_at`; decay restarts from `created_at`.
+fn revert_v12_to_v11(conn: &Connection) -> anyhow::Result<()> {
+    conn.execute_batch("ALTER TABLE memories DROP COLUMN last_decayed_at;")?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,6 +502,31 @@ mod tests {
         assert_eq!(get_schema_version(&conn).unwrap(), CURRENT_SCHEMA_VERSION);
     }
 
+    #[test]
+    fn failed_migration_rolls_back_with_its_version() {
+        let conn = test_db();
+        conn.execute_batch(
+            "INSERT INTO memories (id, type, content, scope, created_at, updated_at) \
+             VALUES ('m1', 'semantic', 'Rust is fast', 'global', '2025-01-01', '2025-01-01');
+             CREATE TRIGGER block_updates BEFORE UPDATE ON memories \
+             BEGIN SELECT RAISE(ABORT, 'interrupted'); END;",
+        )
+        .unwrap();
+
+        // v3 → v4 adds content_hash, then fails backfilling it
+        assert!(run_migrations(&conn).is_err());
+
+        assert_eq!(get_schema_version(&conn).unwrap(), 3);
+        let has_column: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('memories') WHERE name = 'content_hash'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(!has_column);
+    }
+
     #[test]
     fn migration_v1_to_v2_adds_embedding_model() {
         let conn = test_db();
@@ -121,6 +539,212 @@ mod tests {
         assert_eq!(model, Some("all-MiniLM-L6-v2".to_string()));
     }
 
+    #[test]
+    fn migration_v2_to_v3_adds_source_uri_column() {
+        let conn = test_db();
+        run_migrations(&conn).unwrap();
+
+        let has_column: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('memories') WHERE name = 'source_uri'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(has_column);
+    }
+
+    #[test]
+    fn migration_v3_to_v4_backfills_content_hash() {
+        let conn = test_db();
+        conn.execute(
+            "INSERT INTO memories (id, type, content, scope, created_at, updated_at) \
+             VALUES ('m1', 'semantic', '  Rust   is fast ', 'global', '2025-01-01', '2025-01-01')",
+            [],
+        )
+        .unwrap();
+        run_migrations(&conn).unwrap();
+
+        let hash: Option<String> = conn
+            .query_row("SELECT content_hash FROM memories WHERE id = 'm1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(hash, Some(crate::memory::content_hash("Rust is fast")));
+    }
+
+    #[test]
+    fn migration_v4_to_v5_defaults_importance() {
+        let conn = test_db();
+        conn.execute(
+            "INSERT INTO memories (id, type, content, scope, created_at, updated_at) \
+             VALUES ('m1', 'semantic', 'Rust is fast', 'global', '2025-01-01', '2025-01-01')",
+            [],
+        )
+        .unwrap();
+        run_migrations(&conn).unwrap();
+
+        let importance: f64 = conn
+            .query_row("SELECT importance FROM memories WHERE id = 'm1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(importance, 0.5);
+    }
+
+    #[test]
+    fn migration_v5_to_v6_adds_recall_log() {
+        let conn = test_db();
+        run_migrations(&conn).unwrap();
+
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM recall_log", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn migration_v6_to_v7_allows_session_scope_and_keeps_relations() {
+        let conn = test_db();
+        conn.execute_batch(
+            "INSERT INTO memories (id, type, content, scope, created_at, updated_at) \
+             VALUES ('e1', 'entity', 'Alice', 'global', '2025-01-01', '2025-01-01'), \
+                    ('e2', 'entity', 'Acme', 'global', '2025-01-01', '2025-01-01');
+             INSERT INTO entity_relations (id, subject_id, predicate, object_id, created_at) \
+             VALUES ('r1', 'e1', 'works_at', 'e2', '2025-01-01');",
+        )
+        .unwrap();
+        let rowid_before: i64 = conn
+            .query_row("SELECT rowid FROM memories WHERE id = 'e2'", [], |row| row.get(0))
+            .unwrap();
+
+        run_migrations(&conn).unwrap();
+
+        let rowid_after: i64 = conn
+            .query_row("SELECT rowid FROM memories WHERE id = 'e2'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rowid_after, rowid_before);
+        let relations: i64 = conn
+            .query_row("SELECT COUNT(*) FROM entity_relations", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(relations, 1);
+        let foreign_keys: bool = conn
+            .query_row("PRAGMA foreign_keys", [], |row| row.get(0))
+            .unwrap();
+        assert!(foreign_keys);
+
+        conn.execute(
+            "INSERT INTO memories (id, type, content, scope, session_id, created_at, updated_at) \
+             VALUES ('s1', 'episodic', 'Just said hi', 'session', 'conv-1', '2025-01-01', '2025-01-01')",
+            [],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn migration_v7_to_v8_adds_nullable_expires_at() {
+        let conn = test_db();
+        conn.execute(
+            "INSERT INTO memories (id, type, content, scope, created_at, updated_at) \
+             VALUES ('m1', 'episodic', 'Debugging the parser', 'global', '2025-01-01', '2025-01-01')",
+            [],
+        )
+        .unwrap();
+        run_migrations(&conn).unwrap();
+
+        let expires_at: Option<String> = conn
+            .query_row("SELECT expires_at FROM memories WHERE id = 'm1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(expires_at, None);
+    }
+
+    #[test]
+    fn migration_v8_to_v9_adds_tags_column() {
+        let conn = test_db();
+        run_migrations(&conn).unwrap();
+
+        let has_column: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('memories') WHERE name = 'tags'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(has_column);
+    }
+
+    #[test]
+    fn migration_v11_to_v12_adds_last_decayed_at_column() {
+        let conn = test_db();
+        run_migrations(&conn).unwrap();
+
+        let has_column: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('memories') WHERE name = 'last_decayed_at'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(has_column);
+    }
+
+    #[test]
+    fn migration_v11_to_v12_does_not_decay_upgraded_rows_again() {
+        let conn = test_db();
+        let year_ago = (chrono::Utc::now() - chrono::Duration::days(365)).to_rfc3339();
+        let just_decayed = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO memories (id, type, content, scope, confidence, created_at, updated_at) \
+             VALUES ('m1', 'episodic', 'Deployed v1', 'group', 0.8, ?1, ?2)",
+            [&year_ago, &just_decayed],
+        )
+        .unwrap();
+        run_migrations(&conn).unwrap();
+
+        crate::memory::maintenance::apply_decay(&conn, &crate::config::MaintenanceConfig::default())
+            .unwrap();
+        let confidence: f64 = conn
+            .query_row("SELECT confidence FROM memories WHERE id = 'm1'", [], |row| row.get(0))
+            .unwrap();
+        assert!(confidence > 0.79, "upgraded row lost confidence: {confidence}");
+    }
+
+    #[test]
+    fn migration_v9_to_v10_records_legacy_dimension() {
+        let conn = test_db();
+        // Simulate a database created before the dimension was recorded
+        conn.execute("DELETE FROM schema_meta WHERE key = 'embedding_dim'", [])
+            .unwrap();
+        assert_eq!(get_embedding_dim(&conn).unwrap(), None);
+
+        run_migrations(&conn).unwrap();
+        assert_eq!(get_embedding_dim(&conn).unwrap(), Some(384));
+    }
+
+    #[test]
+    fn migration_v10_to_v11_allows_relation_delete_and_keeps_log() {
+        let conn = test_db();
+        conn.execute(
+            "INSERT INTO memory_log (operation, memory_id, created_at) VALUES ('create', 'm1', '2025-01-01T00:00:00Z')",
+            [],
+        )
+        .unwrap();
+        let insert_relation_delete = |conn: &Connection| {
+            conn.execute(
+                "INSERT INTO memory_log (operation, memory_id, created_at) VALUES ('relation_delete', 'm1', '2025-01-02T00:00:00Z')",
+                [],
+            )
+        };
+        assert!(insert_relation_delete(&conn).is_err());
+
+        run_migrations(&conn).unwrap();
+        insert_relation_delete(&conn).unwrap();
+        let ids: Vec<i64> = conn
+            .prepare("SELECT id FROM memory_log ORDER BY id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(ids, vec![1, 2]);
+    }
+
     #[test]
     fn migrations_are_idempotent() {
         let conn = test_db();
@@ -139,5 +763,77 @@ mod tests {
             get_embedding_model(&conn).unwrap(),
             Some("new-model-v3".to_string())
         );
+
+        assert_eq!(get_embedding_model_hash(&conn).unwrap(), None);
+        set_embedding_model_hash(&conn, "abc123").unwrap();
+        assert_eq!(get_embedding_model_hash(&conn).unwrap(), Some("abc123".to_string()));
+    }
+
+    #[test]
+    fn migrate_down_to_v1_and_back_up() {
+        let conn = test_db();
+        run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO memories (id, type, content, scope, created_at, updated_at) \
+             VALUES ('m1', 'semantic', 'Rust is fast', 'global', '2025-01-01', '2025-01-01')",
+            [],
+        )
+        .unwrap();
+
+        assert_eq!(migrate_down(&conn, 1).unwrap(), CURRENT_SCHEMA_VERSION);
+        assert_eq!(get_schema_version(&conn).unwrap(), 1);
+        assert!(get_embedding_model(&conn).unwrap().is_none());
+        let columns: i64 = conn
+            .query_row("SELECT COUNT(*) FROM pragma_table_info('memories')", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(columns, 12, "only the v1 columns remain");
+
+        // The memory survives, and upgrading again restores everything
+        run_migrations(&conn).unwrap();
+        assert_eq!(get_schema_version(&conn).unwrap(), CURRENT_SCHEMA_VERSION);
+        let hash: Option<String> = conn
+            .query_row("SELECT content_hash FROM memories WHERE id = 'm1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(hash, Some(crate::memory::content_hash("Rust is fast")));
+    }
+
+    #[test]
+    fn migrate_down_refuses_without_changing_anything() {
+        let conn = test_db();
+        run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO memories (id, type, content, scope, session_id, created_at, updated_at) \
+             VALUES ('s1', 'episodic', 'Chat note', 'session', 'abc', '2025-01-01', '2025-01-01')",
+            [],
+        )
+        .unwrap();
+
+        let err = migrate_down(&conn, 6).unwrap_err().to_string();
+        assert!(err.contains("session-scoped"), "{err}");
+        assert_eq!(get_schema_version(&conn).unwrap(), CURRENT_SCHEMA_VERSION);
+        let has_decay_column: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('memories') WHERE name = 'last_decayed_at'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(has_decay_column, "earlier steps were rolled back");
+
+        // Above the refusing step is fine
+        migrate_down(&conn, 7).unwrap();
+        assert_eq!(get_schema_version(&conn).unwrap(), 7);
+    }
+
+    #[test]
+    fn migrate_down_rejects_unknown_versions() {
+        let conn = test_db();
+        run_migrations(&conn).unwrap();
+        assert!(migrate_down(&conn, 0).is_err());
+        assert!(migrate_down(&conn, CURRENT_SCHEMA_VERSION + 1).is_err());
+
+        update_schema_version(&conn, CURRENT_SCHEMA_VERSION + 1).unwrap();
+        let err = migrate_down(&conn, CURRENT_SCHEMA_VERSION).unwrap_err().to_string();
+        assert!(err.contains("can't be reversed"), "{err}");
     }
 }
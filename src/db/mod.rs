@@ -2,16 +2,21 @@
 //!
 //! Opens SQLite with WAL mode, loads the sqlite-vec extension for vector search,
 //! initializes the schema, runs forward-only migrations, and validates integrity.
+//! [`open_reader`] opens the read-only connections that [`pool::ReadPool`] hands out.
 
 pub mod migrations;
+pub mod pool;
 pub mod schema;
+pub mod shard;
 
 use anyhow::{Context, Result};
 use rusqlite::Connection;
 use sqlite_vec::sqlite3_vec_init;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Once;
 
+use crate::config::LociConfig;
+
 static SQLITE_VEC_INIT: Once = Once::new();
 
 /// Register the sqlite-vec extension globally. Safe to call multiple times.
@@ -23,10 +28,95 @@ pub fn load_sqlite_vec() {
     });
 }
 
+/// Options controlling how [`open_database_with`] prepares the database.
+#[derive(Debug, Clone)]
+pub struct OpenOptions {
+    /// Copy the database to `<db>.v<n>.bak` before running pending migrations.
+    pub backup_before_migrate: bool,
+    /// Run `PRAGMA quick_check` after opening; slow on very large databases.
+    pub integrity_check: bool,
+    /// Expected embedding dimension. Opening fails if the database was built for a
+    /// different one; `None` skips the check (for `loci re-embed`, which rebuilds vectors).
+    pub embedding_dim: Option<usize>,
+    /// SQLCipher key applied before any other statement; `None` opens the database
+    /// unencrypted. Requires the `sqlcipher` feature.
+    pub encryption_key: Option<String>,
+}
+
+impl Default for OpenOptions {
+    fn default() -> Self {
+        Self {
+            backup_before_migrate: true,
+            integrity_check: true,
+            embedding_dim: Some(crate::embedding::EMBEDDING_DIM),
+            encryption_key: None,
+        }
+    }
+}
+
+impl From<&LociConfig> for OpenOptions {
+    fn from(config: &LociConfig) -> Self {
+        Self {
+            backup_before_migrate: config.storage.backup_before_migrate,
+            integrity_check: config.storage.integrity_check_on_open,
+            embedding_dim: Some(config.embedding.dimensions),
+            encryption_key: config.storage.encryption_key.clone(),
+        }
+    }
+}
+
 /// Open (or create) the Loci database at the given path, with all extensions
 /// loaded and schema initialized.
 pub fn open_database(path: impl AsRef<Path>) -> Result<Connection> {
+    open_database_with(path, &OpenOptions::default())
+}
+
+/// Key a freshly opened connection with SQLCipher and confirm the key is correct.
+///
+/// Must run before any other statement. SQLCipher accepts any key without complaint;
+/// a wrong one only surfaces when the first page is read, so probe `sqlite_master`.
+fn apply_encryption_key(conn: &Connection, key: &str, path: &Path) -> Result<()> {
+    if !cfg!(feature = "sqlcipher") {
+        anyhow::bail!(
+            "storage.encryption_key (or LOCI_DB_KEY) is set, but this Loci binary was built \
+             without SQLCipher. Rebuild with `cargo install loci --features sqlcipher`, or \
+             unset the key to open an unencrypted database."
+        );
+    }
+
+    conn.pragma_update(None, "key", key)?;
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| row.get::<_, i64>(0))
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "failed to unlock database at {}: the encryption key is wrong, or the \
+                 database is not encrypted. Check LOCI_DB_KEY / storage.encryption_key.",
+                path.display()
+            )
+        })?;
+    Ok(())
+}
+
+/// Confirm an existing database opened without a key can be read, so an encrypted
+/// file fails with a pointer to the key rather than "file is not a database".
+fn check_readable(conn: &Connection, path: &Path) -> Result<()> {
+    match conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| row.get::<_, i64>(0)) {
+        Err(rusqlite::Error::SqliteFailure(e, _)) if e.code == rusqlite::ErrorCode::NotADatabase => {
+            anyhow::bail!(
+                "failed to read database at {}: it is encrypted and the encryption key is wrong \
+                 or missing, or it is not a Loci database. Check LOCI_DB_KEY / \
+                 storage.encryption_key.",
+                path.display()
+            )
+        }
+        Err(e) => Err(e.into()),
+        Ok(_) => Ok(()),
+    }
+}
+
+/// Open (or create) the Loci database at the given path using explicit [`OpenOptions`].
+pub fn open_database_with(path: impl AsRef<Path>, options: &OpenOptions) -> Result<Connection> {
     let path = path.as_ref();
+    let existed = path.exists();
 
     // Ensure parent directory exists
     if let Some(parent) = path.parent() {
@@ -44,6 +134,12 @@ pub fn open_database(path: impl AsRef<Path>) -> Result<Connection> {
         )
     })?;
 
+    match &options.encryption_key {
+        Some(key) => apply_encryption_key(&conn, key, path)?,
+        None if existed => check_readable(&conn, path)?,
+        None => {}
+    }
+
     // Enable WAL mode for better concurrent read performance
     conn.pragma_update(None, "journal_mode", "WAL")?;
     // Enable foreign keys
@@ -51,29 +147,184 @@ pub fn open_database(path: impl AsRef<Path>) -> Result<Connection> {
     // Wait up to 5 seconds for locks instead of failing immediately
     conn.pragma_update(None, "busy_timeout", "5000")?;
 
-    schema::init_schema(&conn).context("failed to initialize schema")?;
-    migrations::run_migrations(&conn).context("failed to run migrations")?;
+    let dimensions = options.embedding_dim.unwrap_or(crate::embedding::EMBEDDING_DIM);
+    schema::init_schema_with_dimensions(&conn, dimensions).context("failed to initialize schema")?;
 
-    // Quick integrity check after schema init
-    let integrity: String = conn.pragma_query_value(None, "quick_check", |row| row.get(0))?;
-    if integrity != "ok" {
+    let version = migrations::get_schema_version(&conn).context("failed to read schema version")?;
+    if version > migrations::CURRENT_SCHEMA_VERSION {
         anyhow::bail!(
-            "database integrity check failed: {integrity}. \
-             Try restoring from a backup (`loci export` from a good copy, \
-             then `loci reset && loci import backup.json`)."
+            "database at {} has schema version {version}, but this binary only supports \
+             up to version {}. It was created by a newer Loci version — upgrade Loci \
+             to open it.",
+            path.display(),
+            migrations::CURRENT_SCHEMA_VERSION
         );
     }
 
+    // Snapshot existing databases before upgrading them in place
+    if existed && options.backup_before_migrate && version < migrations::CURRENT_SCHEMA_VERSION {
+        let backup = backup_database(&conn, path, version)?;
+        tracing::info!(
+            backup = %backup.display(),
+            from = version,
+            to = migrations::CURRENT_SCHEMA_VERSION,
+            "backed up database before migration"
+        );
+    }
+
+    migrations::run_migrations(&conn).context("failed to run migrations")?;
+
+    if let Some(expected) = options.embedding_dim {
+        let stored = migrations::get_embedding_dim(&conn)
+            .context("failed to read embedding dimension")?;
+        if let Some(stored) = stored
+            && stored != expected
+        {
+            anyhow::bail!(
+                "database at {} stores {stored}-dimensional embeddings, but \
+                 embedding.dimensions is {expected}. Run `loci re-embed` to rebuild the \
+                 vectors with the configured model, or set embedding.dimensions = {stored}.",
+                path.display()
+            );
+        }
+    }
+
+    // Quick integrity check after schema init
+    if options.integrity_check {
+        let integrity: String = conn.pragma_query_value(None, "quick_check", |row| row.get(0))?;
+        if integrity != "ok" {
+            anyhow::bail!(
+                "database integrity check failed: {integrity}. \
+                 Try restoring from a backup (`loci export` from a good copy, \
+                 then `loci reset && loci import backup.json`)."
+            );
+        }
+    } else {
+        tracing::info!(path = %path.display(), "integrity check skipped; run `loci doctor` to verify");
+    }
+
     tracing::info!(path = %path.display(), "database initialized");
     Ok(conn)
 }
 
+/// Open a read-only connection to an existing database for a [`pool::ReadPool`].
+///
+/// Skips schema setup and migrations: the write connection has already run them.
+pub fn open_reader(path: &Path, encryption_key: Option<&str>) -> Result<Connection> {
+    use rusqlite::OpenFlags;
+
+    load_sqlite_vec();
+
+    let flags = OpenFlags::SQLITE_OPEN_READ_ONLY
+        | OpenFlags::SQLITE_OPEN_NO_MUTEX
+        | OpenFlags::SQLITE_OPEN_URI;
+    let conn = Connection::open_with_flags(path, flags)
+        .with_context(|| format!("failed to open read connection to {}", path.display()))?;
+    if let Some(key) = encryption_key {
+        apply_encryption_key(&conn, key, path)?;
+    }
+    conn.pragma_update(None, "busy_timeout", "5000")?;
+    Ok(conn)
+}
+
+/// Take the database at `path` down to schema version `target`, so an older Loci
+/// can open it again. Returns the version it was at.
+///
+/// Opens the file without running forward migrations, and snapshots it to
+/// `<db>.v<version>.bak` first when `options.backup_before_migrate` is set.
+pub fn downgrade_database(path: &Path, target: u32, options: &OpenOptions) -> Result<u32> {
+    if !path.exists() {
+        anyhow::bail!("no database at {}", path.display());
+    }
+
+    load_sqlite_vec();
+    let conn = Connection::open(path)
+        .with_context(|| format!("failed to open database at {}", path.display()))?;
+    match &options.encryption_key {
+        Some(key) => apply_encryption_key(&conn, key, path)?,
+        None => check_readable(&conn, path)?,
+    }
+    conn.pragma_update(None, "busy_timeout", "5000")?;
+
+    let version = migrations::get_schema_version(&conn).context("failed to read schema version")?;
+    if options.backup_before_migrate && target < version {
+        let backup = backup_database(&conn, path, version)?;
+        tracing::info!(
+            backup = %backup.display(),
+            from = version,
+            to = target,
+            "backed up database before downgrade"
+        );
+    }
+
+    migrations::migrate_down(&conn, target)
+}
+
+/// Write a consistent copy of the database to `<db>.v<version>.bak`, replacing any
+/// previous backup for the same version.
+fn backup_database(conn: &Connection, path: &Path, version: u32) -> Result<PathBuf> {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".v{version}.bak"));
+    let backup = PathBuf::from(name);
+
+    if backup.exists() {
+        std::fs::remove_file(&backup)
+            .with_context(|| format!("failed to remove stale backup {}", backup.display()))?;
+    }
+
+    // VACUUM INTO includes any pages still sitting in the WAL, unlike a raw file copy
+    conn.execute("VACUUM INTO ?1", [backup.to_string_lossy()])
+        .with_context(|| format!("failed to back up database to {}", backup.display()))?;
+
+    Ok(backup)
+}
+
+/// Snapshot the database to a new file at `dest` with SQLite's online backup API.
+///
+/// Safe while other connections are reading or writing, unlike copying the file, which
+/// can miss pages still in the WAL. Refuses to overwrite an existing file. Returns the
+/// size of the backup in bytes.
+///
+/// An encrypted database (`encryption_key` set) is exported with SQLCipher's
+/// `sqlcipher_export` into a copy keyed with the same key instead, since the backup
+/// API can't key its destination.
+pub fn backup_to(conn: &Connection, dest: &Path, encryption_key: Option<&str>) -> Result<u64> {
+    if dest.exists() {
+        anyhow::bail!("{} already exists; choose a new backup path", dest.display());
+    }
+    match encryption_key {
+        Some(key) => export_encrypted(conn, dest, key),
+        None => conn.backup(rusqlite::MAIN_DB, dest, None).map_err(Into::into),
+    }
+    .with_context(|| format!("failed to back up database to {}", dest.display()))?;
+    let size = std::fs::metadata(dest)
+        .with_context(|| format!("failed to read backup {}", dest.display()))?
+        .len();
+    Ok(size)
+}
+
+/// Copy an encrypted database into a new file at `dest`, keyed with `key`.
+fn export_encrypted(conn: &Connection, dest: &Path, key: &str) -> Result<()> {
+    conn.execute(
+        "ATTACH DATABASE ?1 AS backup KEY ?2",
+        rusqlite::params![dest.to_string_lossy(), key],
+    )?;
+    let exported = conn.query_row("SELECT sqlcipher_export('backup')", [], |_| Ok(()));
+    conn.execute("DETACH DATABASE backup", [])?;
+    exported?;
+    Ok(())
+}
+
 /// Result of a full database health check.
 pub struct HealthReport {
     /// Current schema version number.
     pub schema_version: u32,
     /// Embedding model identifier stored in `schema_meta`, or `None` if unset.
     pub embedding_model: Option<String>,
+    /// SHA-256 of the model file recorded in `schema_meta`, or `None` if unset.
+    pub embedding_model_hash: Option<String>,
+    /// Embedding vector length recorded in `schema_meta`, or `None` if unset.
+    pub embedding_dim: Option<usize>,
     /// `true` if `PRAGMA integrity_check` returned `"ok"`.
     pub integrity_ok: bool,
     /// Raw output from `PRAGMA integrity_check`.
@@ -82,6 +333,8 @@ pub struct HealthReport {
     pub sqlite_vec_version: String,
     /// Row count from the `memories` table.
     pub memory_count: i64,
+    /// Rows in the FTS5 index; differs from `memory_count` when the index has drifted.
+    pub fts_count: i64,
     /// Row count from the `entity_relations` table.
     pub relation_count: i64,
     /// Row count from the `memory_log` audit table.
@@ -95,6 +348,10 @@ pub fn check_database_health(conn: &Connection) -> Result<HealthReport> {
 
     let embedding_model = migrations::get_embedding_model(conn)
         .context("failed to read embedding model")?;
+    let embedding_model_hash = migrations::get_embedding_model_hash(conn)
+        .context("failed to read embedding model hash")?;
+    let embedding_dim = migrations::get_embedding_dim(conn)
+        .context("failed to read embedding dimension")?;
 
     let integrity_details: String = conn
         .pragma_query_value(None, "integrity_check", |row| row.get(0))
@@ -109,6 +366,8 @@ pub fn check_database_health(conn: &Connection) -> Result<HealthReport> {
         .query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))
         .unwrap_or(0);
 
+    let fts_count = fts_row_count(conn).unwrap_or(0);
+
     let relation_count: i64 = conn
         .query_row("SELECT COUNT(*) FROM entity_relations", [], |row| row.get(0))
         .unwrap_or(0);
@@ -120,15 +379,69 @@ pub fn check_database_health(conn: &Connection) -> Result<HealthReport> {
     Ok(HealthReport {
         schema_version,
         embedding_model,
+        embedding_model_hash,
+        embedding_dim,
         integrity_ok,
         integrity_details,
         sqlite_vec_version,
         memory_count,
+        fts_count,
         relation_count,
         log_count,
     })
 }
 
+/// Number of rows actually indexed in `memories_fts`.
+///
+/// `COUNT(*)` on an external-content FTS5 table reads the content table, so it can't
+/// see drift; the `docsize` shadow table holds one row per indexed document.
+pub fn fts_row_count(conn: &Connection) -> Result<i64> {
+    conn.query_row("SELECT COUNT(*) FROM memories_fts_docsize", [], |row| row.get(0))
+        .context("failed to count FTS index rows")
+}
+
+/// Rebuild the FTS5 index from the `memories` table, returning how many rows it holds.
+///
+/// Fixes keyword search after the index drifted (manual edits, a crash mid-write).
+/// Fails if the rebuilt index still doesn't match the memory count.
+pub fn rebuild_fts(conn: &Connection) -> Result<usize> {
+    conn.execute("INSERT INTO memories_fts(memories_fts) VALUES('rebuild')", [])
+        .context("failed to rebuild FTS index")?;
+
+    let indexed = fts_row_count(conn)?;
+    let memories: i64 = conn.query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))?;
+    if indexed != memories {
+        anyhow::bail!("FTS index has {indexed} rows after rebuild, expected {memories}");
+    }
+    Ok(indexed as usize)
+}
+
+/// Copy the WAL into the main database file and truncate it to zero bytes.
+///
+/// Returns `false` if another connection kept the checkpoint from completing.
+pub fn checkpoint_wal(conn: &Connection) -> Result<bool> {
+    let busy: i64 = conn
+        .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| row.get(0))
+        .context("failed to checkpoint WAL")?;
+    Ok(busy == 0)
+}
+
+/// Rewrite the database to drop free pages, then checkpoint so the file shrinks.
+///
+/// Hard deletes leave freed pages inside the file; this returns them to the OS.
+/// `VACUUM` can't run inside a transaction, so this fails if one is open on `conn`.
+pub fn vacuum(conn: &Connection) -> Result<()> {
+    if !conn.is_autocommit() {
+        anyhow::bail!("cannot vacuum while a transaction is open");
+    }
+    conn.execute_batch("VACUUM").context("failed to vacuum database")?;
+    // In WAL mode the rewritten pages sit in the WAL until checkpointed
+    if !checkpoint_wal(conn)? {
+        tracing::warn!("WAL checkpoint after vacuum incomplete (database busy)");
+    }
+    Ok(())
+}
+
 /// Open an in-memory database for testing.
 #[cfg(test)]
 pub fn open_memory_database() -> Result<Connection> {
@@ -0,0 +1,143 @@
+//! Read-only connection pool for one database file.
+//!
+//! WAL mode lets readers run alongside the writer, so searches and inspections check
+//! out one of a small set of read-only connections instead of queuing behind the
+//! single write connection. Connections are opened on first use, up to the pool
+//! size; further callers wait until one is returned.
+
+use anyhow::Result;
+use rusqlite::Connection;
+use std::ops::Deref;
+use std::path::PathBuf;
+use std::sync::{Condvar, Mutex};
+
+/// A bounded set of read-only connections to one database file.
+pub struct ReadPool {
+    path: PathBuf,
+    size: usize,
+    encryption_key: Option<String>,
+    state: Mutex<PoolState>,
+    returned: Condvar,
+}
+
+/// Idle connections and how many have been opened in total.
+struct PoolState {
+    idle: Vec<Connection>,
+    opened: usize,
+}
+
+impl ReadPool {
+    /// Pool up to `size` read-only connections to `path`, keyed with `encryption_key`
+    /// when the database is encrypted.
+    pub fn new(path: impl Into<PathBuf>, size: usize, encryption_key: Option<String>) -> Self {
+        Self {
+            path: path.into(),
+            size: size.max(1),
+            encryption_key,
+            state: Mutex::new(PoolState {
+                idle: Vec::new(),
+                opened: 0,
+            }),
+            returned: Condvar::new(),
+        }
+    }
+
+    /// Check out a connection, opening one if the pool isn't full and waiting otherwise.
+    pub fn get(&self) -> Result<PooledConnection<'_>> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|e| anyhow::anyhow!("read pool lock poisoned: {e}"))?;
+        loop {
+            if let Some(conn) = state.idle.pop() {
+                return Ok(PooledConnection {
+                    pool: self,
+                    conn: Some(conn),
+                });
+            }
+            if state.opened < self.size {
+                state.opened += 1;
+                drop(state);
+                // Open outside the lock so other readers aren't held up by the file open
+                return match super::open_reader(&self.path, self.encryption_key.as_deref()) {
+                    Ok(conn) => Ok(PooledConnection {
+                        pool: self,
+                        conn: Some(conn),
+                    }),
+                    Err(e) => {
+                        self.release_slot();
+                        Err(e)
+                    }
+                };
+            }
+            state = self
+                .returned
+                .wait(state)
+                .map_err(|e| anyhow::anyhow!("read pool lock poisoned: {e}"))?;
+        }
+    }
+
+    /// Give back the slot of a connection that failed to open.
+    fn release_slot(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.opened -= 1;
+        self.returned.notify_one();
+    }
+}
+
+/// A read-only connection checked out of a [`ReadPool`]; returned to it on drop.
+pub struct PooledConnection<'a> {
+    pool: &'a ReadPool,
+    conn: Option<Connection>,
+}
+
+impl Deref for PooledConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("connection present until drop")
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            let mut state = self.pool.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.idle.push(conn);
+            self.pool.returned.notify_one();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_pool_reuses_and_bounds_connections() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("memory.db");
+        crate::db::open_database(&path).unwrap();
+
+        let pool = Arc::new(ReadPool::new(&path, 1, None));
+        let first = pool.get().unwrap();
+        let count: i64 = first
+            .query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+        assert!(first.execute("DELETE FROM memories", []).is_err(), "reader must be read-only");
+
+        // A second checkout waits until the only connection comes back
+        let waiter = {
+            let pool = Arc::clone(&pool);
+            std::thread::spawn(move || pool.get().map(|_| ()).is_ok())
+        };
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(!waiter.is_finished());
+        drop(first);
+        assert!(waiter.join().unwrap());
+        assert_eq!(pool.state.lock().unwrap().opened, 1);
+    }
+}
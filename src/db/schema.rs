@@ -68,18 +68,36 @@ CREATE TABLE IF NOT EXISTS schema_meta (
 );
 "#;
 
-/// vec0 virtual table must be created separately (sqlite-vec syntax).
-const VEC_TABLE_SQL: &str = r#"
-CREATE VIRTUAL TABLE IF NOT EXISTS memories_vec USING vec0(
+/// vec0 virtual table must be created separately (sqlite-vec syntax), sized to the
+/// embedding dimension.
+fn vec_table_sql(dimensions: usize) -> String {
+    format!(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS memories_vec USING vec0(
     id TEXT PRIMARY KEY,
-    embedding FLOAT[384]
-);
-"#;
+    embedding FLOAT[{dimensions}]
+);"
+    )
+}
 
-/// Initialize all schema tables. Idempotent (uses IF NOT EXISTS).
+/// Initialize all schema tables for the default embedding dimension. Idempotent
+/// (uses IF NOT EXISTS).
 pub fn init_schema(conn: &Connection) -> rusqlite::Result<()> {
+    init_schema_with_dimensions(conn, crate::embedding::EMBEDDING_DIM)
+}
+
+/// Initialize all schema tables. A new `memories_vec` holds `dimensions`-length vectors,
+/// recorded as `schema_meta.embedding_dim`; an existing one is left untouched.
+pub fn init_schema_with_dimensions(conn: &Connection, dimensions: usize) -> rusqlite::Result<()> {
     conn.execute_batch(SCHEMA_SQL)?;
-    conn.execute_batch(VEC_TABLE_SQL)?;
+    let vec_table_exists: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE name = 'memories_vec'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !vec_table_exists {
+        conn.execute_batch(&vec_table_sql(dimensions))?;
+        super::migrations::set_embedding_dim(conn, dimensions)?;
+    }
 
     // Set initial schema version if not already present
     conn.execute(
@@ -90,6 +108,15 @@ pub fn init_schema(conn: &Connection) -> rusqlite::Result<()> {
     Ok(())
 }
 
+/// Drop and recreate `memories_vec` for `dimensions`-length vectors, discarding every
+/// stored vector. Used by `loci re-embed` when the configured dimension changes, inside
+/// the transaction that writes the new vectors.
+pub fn recreate_vec_table(conn: &Connection, dimensions: usize) -> rusqlite::Result<()> {
+    conn.execute_batch("DROP TABLE IF EXISTS memories_vec;")?;
+    conn.execute_batch(&vec_table_sql(dimensions))?;
+    super::migrations::set_embedding_dim(conn, dimensions)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -128,4 +155,31 @@ mod tests {
         init_schema(&conn).unwrap();
         init_schema(&conn).unwrap(); // second call should not error
     }
+
+    #[test]
+    fn vec_table_uses_requested_dimensions() {
+        crate::db::load_sqlite_vec();
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema_with_dimensions(&conn, 8).unwrap();
+        assert_eq!(super::super::migrations::get_embedding_dim(&conn).unwrap(), Some(8));
+
+        let insert = |conn: &Connection, len: usize| {
+            let embedding = vec![0.5f32; len];
+            let bytes = crate::memory::embedding_to_bytes(&embedding);
+            conn.execute(
+                "INSERT INTO memories_vec (id, embedding) VALUES ('m', ?1)",
+                [bytes],
+            )
+        };
+        assert!(insert(&conn, 384).is_err());
+        insert(&conn, 8).unwrap();
+
+        // Re-running init keeps the existing table and its recorded dimension
+        init_schema(&conn).unwrap();
+        assert_eq!(super::super::migrations::get_embedding_dim(&conn).unwrap(), Some(8));
+
+        recreate_vec_table(&conn, 384).unwrap();
+        assert_eq!(super::super::migrations::get_embedding_dim(&conn).unwrap(), Some(384));
+        insert(&conn, 384).unwrap();
+    }
 }
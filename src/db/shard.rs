@@ -0,0 +1,297 @@
+//! Connection routing for per-group database files.
+//!
+//! With `storage.shard_by_group` off, [`Databases`] wraps the single database at
+//! `storage.db_path`. With it on, group-scoped memories live in
+//! `<db dir>/groups/<group>.db` while global-scope memories stay in the main
+//! database, which every group's reads consult alongside its own shard.
+
+use anyhow::{bail, Result};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::pool::{PooledConnection, ReadPool};
+use super::OpenOptions;
+use crate::memory::search;
+use crate::memory::types::Scope;
+
+/// Directory (next to the main database) that holds per-group shard files.
+const SHARD_DIR: &str = "groups";
+
+/// A database file, its shared write connection, and optionally a pool of readers.
+#[derive(Clone)]
+pub struct DbHandle {
+    /// Path of the SQLite file, used for size reporting.
+    pub path: PathBuf,
+    /// Connection shared by all requests routed to this file; writes serialize on it.
+    pub conn: Arc<Mutex<Connection>>,
+    /// Read-only connections, so reads don't queue behind the write connection.
+    readers: Option<Arc<ReadPool>>,
+}
+
+/// A connection checked out for reading: pooled when the handle has readers,
+/// otherwise the locked write connection.
+pub enum ReadConnection<'a> {
+    Pooled(PooledConnection<'a>),
+    Shared(MutexGuard<'a, Connection>),
+}
+
+impl std::ops::Deref for ReadConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        match self {
+            Self::Pooled(conn) => conn,
+            Self::Shared(conn) => conn,
+        }
+    }
+}
+
+impl DbHandle {
+    fn new(path: PathBuf, conn: Connection, readers: &ReadPoolConfig) -> Self {
+        Self {
+            readers: readers.pool_for(&path),
+            path,
+            conn: Arc::new(Mutex::new(conn)),
+        }
+    }
+
+    /// Check out a connection for read-only work.
+    ///
+    /// Concurrent callers each get their own pooled connection; without a pool this
+    /// locks the write connection, serializing with everything else on the file.
+    pub fn read(&self) -> Result<ReadConnection<'_>> {
+        match self.readers {
+            Some(ref pool) => Ok(ReadConnection::Pooled(pool.get()?)),
+            None => Ok(ReadConnection::Shared(
+                self.conn
+                    .lock()
+                    .map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?,
+            )),
+        }
+    }
+}
+
+/// Size and key for the read pools of every handle; size 0 disables pooling.
+#[derive(Clone, Default)]
+struct ReadPoolConfig {
+    size: usize,
+    encryption_key: Option<String>,
+}
+
+impl ReadPoolConfig {
+    /// A read pool for the file at `path`, or `None` when pooling is disabled.
+    fn pool_for(&self, path: &Path) -> Option<Arc<ReadPool>> {
+        (self.size > 0)
+            .then(|| Arc::new(ReadPool::new(path, self.size, self.encryption_key.clone())))
+    }
+}
+
+/// All database handles the server routes requests across.
+pub struct Databases {
+    /// The main database: everything in single-file mode, global scope when sharded.
+    main: DbHandle,
+    /// Per-group shards, present only when sharding is enabled.
+    shards: Option<GroupShards>,
+}
+
+/// Lazily opened per-group shard files.
+struct GroupShards {
+    dir: PathBuf,
+    options: OpenOptions,
+    readers: ReadPoolConfig,
+    open: Mutex<HashMap<String, DbHandle>>,
+}
+
+impl Databases {
+    /// Route every request to a single database.
+    pub fn single(path: impl Into<PathBuf>, conn: Connection) -> Self {
+        Self {
+            main: DbHandle::new(path.into(), conn, &ReadPoolConfig::default()),
+            shards: None,
+        }
+    }
+
+    /// Keep global memories in `main_path` and open group shards beside it on demand;
+    /// a shard file is created by the first write to its group.
+    pub fn sharded(main_path: &Path, conn: Connection, options: OpenOptions) -> Self {
+        let dir = main_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(SHARD_DIR);
+        Self {
+            main: DbHandle::new(main_path.to_path_buf(), conn, &ReadPoolConfig::default()),
+            shards: Some(GroupShards {
+                dir,
+                options,
+                readers: ReadPoolConfig::default(),
+                open: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Give every database file a pool of up to `size` read-only connections, so
+    /// concurrent reads run in parallel under WAL instead of taking the write lock.
+    /// Call before serving requests; `size` 0 keeps all reads on the write connection.
+    pub fn with_read_pool(mut self, size: usize, encryption_key: Option<String>) -> Self {
+        let readers = ReadPoolConfig {
+            size,
+            encryption_key,
+        };
+        self.main.readers = readers.pool_for(&self.main.path);
+        if let Some(ref mut shards) = self.shards {
+            shards.readers = readers;
+        }
+        self
+    }
+
+    /// Whether group-scoped memories live in their own shard files.
+    pub fn is_sharded(&self) -> bool {
+        self.shards.is_some()
+    }
+
+    /// Database that a new memory with this scope and group is written to. Session
+    /// memories live with their group.
+    pub fn for_write(&self, scope: Scope, group: &str) -> Result<DbHandle> {
+        match (&self.shards, scope) {
+            (Some(shards), Scope::Group | Scope::Session) => shards.get(group),
+            _ => Ok(self.main.clone()),
+        }
+    }
+
+    /// Databases a group's searches must consult: its shard (if sharded and it
+    /// exists), then the main file. Reads never create a shard.
+    pub fn for_group(&self, group: &str) -> Result<Vec<DbHandle>> {
+        let mut handles = Vec::new();
+        if let Some(ref shards) = self.shards {
+            handles.extend(shards.existing(group)?);
+        }
+        handles.push(self.main.clone());
+        Ok(handles)
+    }
+
+    /// Databases a multi-group search must consult: each existing group shard, then
+    /// the main file.
+    pub fn for_groups(&self, groups: &[String]) -> Result<Vec<DbHandle>> {
+        let mut handles = Vec::new();
+        if let Some(ref shards) = self.shards {
+            for (i, group) in groups.iter().enumerate() {
+                if !groups[..i].contains(group) {
+                    handles.extend(shards.existing(group)?);
+                }
+            }
+        }
+        handles.push(self.main.clone());
+        Ok(handles)
+    }
+
+    /// Every database: the main file plus all shard files that exist on disk.
+    pub fn all(&self) -> Result<Vec<DbHandle>> {
+        let mut handles = vec![self.main.clone()];
+        if let Some(ref shards) = self.shards {
+            for group in shards.existing_groups()? {
+                handles.extend(shards.existing(&group)?);
+            }
+        }
+        Ok(handles)
+    }
+
+    /// Find the database holding a memory, given its full ID or a unique prefix.
+    ///
+    /// Returns the handle and the full ID. Fails if nothing matches or the prefix is
+    /// ambiguous across all files.
+    pub fn resolve(&self, id_or_prefix: &str) -> Result<(DbHandle, String)> {
+        if id_or_prefix.is_empty() {
+            bail!("memory ID must not be empty");
+        }
+
+        let mut matches = Vec::new();
+        for handle in self.all()? {
+            let conn = handle.read()?;
+            let ids = search::find_memory_ids(&conn, id_or_prefix)?;
+            drop(conn);
+            // An exact hit wins outright, even if other files have longer IDs with this prefix
+            if ids.len() == 1 && ids[0] == id_or_prefix {
+                return Ok((handle, ids[0].clone()));
+            }
+            matches.extend(ids.into_iter().map(|id| (handle.clone(), id)));
+        }
+
+        let candidates = matches.iter().map(|(_, id)| id.clone()).collect();
+        let id = search::unique_memory_id(id_or_prefix, candidates)?;
+        let (handle, _) = matches.swap_remove(0);
+        Ok((handle, id))
+    }
+}
+
+impl GroupShards {
+    /// Get (opening, and creating if needed) the shard for a group. Only the write
+    /// path creates shards.
+    fn get(&self, group: &str) -> Result<DbHandle> {
+        self.open_shard(group, true)?
+            .ok_or_else(|| anyhow::anyhow!("shard for group '{group}' was not created"))
+    }
+
+    /// Get (opening if needed) the shard for a group, or `None` if it has no file yet.
+    fn existing(&self, group: &str) -> Result<Option<DbHandle>> {
+        self.open_shard(group, false)
+    }
+
+    fn open_shard(&self, group: &str, create: bool) -> Result<Option<DbHandle>> {
+        validate_group_name(group)?;
+
+        let mut open = self
+            .open
+            .lock()
+            .map_err(|e| anyhow::anyhow!("shard map lock poisoned: {e}"))?;
+        if let Some(handle) = open.get(group) {
+            return Ok(Some(handle.clone()));
+        }
+
+        let path = self.dir.join(format!("{group}.db"));
+        if !create && !path.exists() {
+            return Ok(None);
+        }
+        let conn = super::open_database_with(&path, &self.options)?;
+        tracing::info!(group = %group, path = %path.display(), "opened group shard");
+
+        let handle = DbHandle::new(path, conn, &self.readers);
+        open.insert(group.to_string(), handle.clone());
+        Ok(Some(handle))
+    }
+
+    /// Group names with a shard file on disk.
+    fn existing_groups(&self) -> Result<Vec<String>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut groups = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "db")
+                && let Some(stem) = path.file_stem().and_then(|s| s.to_str())
+            {
+                groups.push(stem.to_string());
+            }
+        }
+        groups.sort();
+        Ok(groups)
+    }
+}
+
+/// Group names become file names, so only allow a conservative character set.
+fn validate_group_name(group: &str) -> Result<()> {
+    let valid = !group.is_empty()
+        && !group.starts_with('.')
+        && group
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        bail!(
+            "group name '{group}' cannot be used as a shard file name; \
+             use letters, digits, '-', '_' or '.'"
+        );
+    }
+    Ok(())
+}
@@ -1,25 +1,35 @@
 //! Local ONNX Runtime embedding provider.
 //!
 //! Implements [`EmbeddingProvider`] using the all-MiniLM-L6-v2
-//! model via `ort`. Handles tokenization, inference, mean pooling, and L2 normalization.
+//! model via `ort`. Handles tokenization, inference, mean pooling (unless the model
+//! pools itself, see [`Pooling`]), and L2 normalization.
 
 use std::sync::Mutex;
 
 use anyhow::{Context, Result};
+use ort::session::builder::SessionBuilder;
 use ort::session::Session;
 use ort::value::Tensor;
 use tokenizers::Tokenizer;
 
-use super::{EmbeddingProvider, EMBEDDING_DIM};
-use crate::config::EmbeddingConfig;
+use super::EmbeddingProvider;
+use crate::config::{EmbeddingConfig, Pooling};
 
 /// Maximum sequence length for all-MiniLM-L6-v2 (trained at 256).
 const MAX_SEQ_LEN: usize = 256;
 
+/// Intra-op threads when `embedding.intra_threads` is unset.
+const DEFAULT_INTRA_THREADS: usize = 4;
+
 /// Local ONNX-based embedding provider using all-MiniLM-L6-v2.
 pub struct LocalEmbeddingProvider {
     session: Mutex<Session>,
     tokenizer: Tokenizer,
+    /// Configured output name; empty means guess (see [`EmbeddingConfig::output_tensor`]).
+    output_tensor: String,
+    pooling: Pooling,
+    /// Expected vector length, checked against the model's output shape.
+    dimensions: usize,
 }
 
 // Safety: Tokenizer is Send+Sync. Session is behind a Mutex.
@@ -44,9 +54,10 @@ impl LocalEmbeddingProvider {
             tokenizer_path.display()
         );
 
-        let session = Session::builder()?
+        let builder = Session::builder()?
             .with_optimization_level(ort::session::builder::GraphOptimizationLevel::Level3)?
-            .with_intra_threads(4)?
+            .with_intra_threads(config.intra_threads.unwrap_or(DEFAULT_INTRA_THREADS))?;
+        let session = with_execution_provider(builder, config.execution_provider.as_deref())?
             .commit_from_file(&model_path)
             .context("failed to load ONNX model")?;
 
@@ -72,6 +83,9 @@ impl LocalEmbeddingProvider {
         Ok(Self {
             session: Mutex::new(session),
             tokenizer,
+            output_tensor: config.output_tensor.clone(),
+            pooling: config.pooling,
+            dimensions: config.dimensions,
         })
     }
 }
@@ -82,6 +96,10 @@ impl EmbeddingProvider for LocalEmbeddingProvider {
         Ok(results.into_iter().next().expect("batch had one input"))
     }
 
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
     fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
         if texts.is_empty() {
             return Ok(vec![]);
@@ -131,58 +149,138 @@ impl EmbeddingProvider for LocalEmbeddingProvider {
             "token_type_ids" => token_type_ids_tensor,
         })?;
 
-        // Step 4: Extract token embeddings — shape [batch, seq_len, 384]
-        // The output name varies by ONNX export. Try common names, fall back to index 0.
-        let token_emb_value = outputs
-            .get("token_embeddings")
-            .or_else(|| outputs.get("last_hidden_state"))
-            .unwrap_or_else(|| &outputs[0]);
-
-        let (shape, data) = token_emb_value
+        // Step 4: Extract the output tensor
+        let output_value = if self.output_tensor.is_empty() {
+            // The output name varies by ONNX export. Try common names, fall back to index 0.
+            outputs
+                .get("token_embeddings")
+                .or_else(|| outputs.get("last_hidden_state"))
+                .unwrap_or_else(|| &outputs[0])
+        } else {
+            outputs.get(self.output_tensor.as_str()).with_context(|| {
+                format!("model has no output named '{}'", self.output_tensor)
+            })?
+        };
+
+        let (shape, data) = output_value
             .try_extract_tensor::<f32>()
-            .context("failed to extract token_embeddings tensor")?;
+            .context("failed to extract embedding output tensor")?;
+
+        // Step 5: Pool to one vector per text, then L2 normalize
+        let pooled = pool(
+            self.pooling,
+            self.dimensions,
+            shape,
+            data,
+            &attention_mask_flat,
+            batch_size,
+            seq_len,
+        )?;
+        Ok(pooled.iter().map(|v| l2_normalize(v)).collect())
+    }
+}
 
-        let dims: &[i64] = &shape;
-        anyhow::ensure!(
-            dims.len() == 3 && dims[2] == EMBEDDING_DIM as i64,
-            "unexpected token_embeddings shape: {dims:?}, expected [batch, seq, {EMBEDDING_DIM}]"
+/// Register the requested execution provider on `builder`.
+///
+/// `None` and `"cpu"` leave ONNX Runtime's default CPU provider. Any other known provider
+/// is best-effort: if it wasn't compiled in or fails to register (e.g. no GPU or driver),
+/// this warns and returns the builder unchanged so inference runs on CPU.
+fn with_execution_provider(builder: SessionBuilder, name: Option<&str>) -> Result<SessionBuilder> {
+    let Some(name) = name.map(str::to_ascii_lowercase).filter(|name| name != "cpu") else {
+        return Ok(builder);
+    };
+    let (compiled_in, provider) = match name.as_str() {
+        "cuda" => (cfg!(feature = "cuda"), ort::ep::CUDA::default().build()),
+        "coreml" => (cfg!(feature = "coreml"), ort::ep::CoreML::default().build()),
+        other => anyhow::bail!(
+            "unknown embedding.execution_provider: {other}. Supported: cpu, cuda, coreml"
+        ),
+    };
+    if !compiled_in {
+        tracing::warn!(
+            execution_provider = %name,
+            "execution provider not compiled in (rebuild with --features {name}); using CPU"
         );
-        let hidden_dim = dims[2] as usize;
-        let actual_seq_len = dims[1] as usize;
-
-        // Step 5: Mean pooling with attention mask
-        let mut results = Vec::with_capacity(batch_size);
-        for b in 0..batch_size {
-            let mut sum = vec![0.0f32; hidden_dim];
-            let mut count = 0.0f32;
-
-            for s in 0..actual_seq_len {
-                let mask = attention_mask_flat[b * seq_len + s] as f32;
-                if mask > 0.0 {
-                    let offset = (b * actual_seq_len + s) * hidden_dim;
-                    for d in 0..hidden_dim {
-                        sum[d] += data[offset + d] * mask;
+        return Ok(builder);
+    }
+
+    match builder
+        .clone()
+        .with_execution_providers([provider.error_on_failure()])
+    {
+        Ok(builder) => {
+            tracing::info!(execution_provider = %name, "execution provider registered");
+            Ok(builder)
+        }
+        Err(e) => {
+            tracing::warn!(execution_provider = %name, error = %e, "execution provider unavailable; using CPU");
+            Ok(builder)
+        }
+    }
+}
+
+/// Reduce a model output to one vector per input, validating its shape for `pooling`.
+///
+/// Mean pooling expects `[batch, seq, dim]` and averages tokens weighted by the
+/// attention mask (laid out as `[batch, mask_seq_len]`); `none` expects `[batch, dim]`,
+/// where `dim` must equal the configured `dimensions`.
+fn pool(
+    pooling: Pooling,
+    dimensions: usize,
+    dims: &[i64],
+    data: &[f32],
+    attention_mask: &[i64],
+    batch_size: usize,
+    mask_seq_len: usize,
+) -> Result<Vec<Vec<f32>>> {
+    match pooling {
+        Pooling::None => {
+            anyhow::ensure!(
+                dims.len() == 2 && dims[0] == batch_size as i64 && dims[1] == dimensions as i64,
+                "unexpected output shape for pooling = \"none\": {dims:?}, expected [{batch_size}, {dimensions}]; \
+                 check embedding.dimensions"
+            );
+            Ok(data.chunks_exact(dimensions).map(<[f32]>::to_vec).collect())
+        }
+        Pooling::Mean => {
+            anyhow::ensure!(
+                dims.len() == 3 && dims[0] == batch_size as i64 && dims[2] == dimensions as i64,
+                "unexpected output shape for pooling = \"mean\": {dims:?}, expected [{batch_size}, seq, {dimensions}]; \
+                 check embedding.dimensions, or set embedding.pooling = \"none\" if the model already pools"
+            );
+            let hidden_dim = dims[2] as usize;
+            let actual_seq_len = dims[1] as usize;
+
+            let mut results = Vec::with_capacity(batch_size);
+            for b in 0..batch_size {
+                let mut sum = vec![0.0f32; hidden_dim];
+                let mut count = 0.0f32;
+
+                for s in 0..actual_seq_len {
+                    let mask = attention_mask[b * mask_seq_len + s] as f32;
+                    if mask > 0.0 {
+                        let offset = (b * actual_seq_len + s) * hidden_dim;
+                        for d in 0..hidden_dim {
+                            sum[d] += data[offset + d] * mask;
+                        }
+                        count += mask;
                     }
-                    count += mask;
                 }
-            }
 
-            if count > 0.0 {
-                for d in 0..hidden_dim {
-                    sum[d] /= count;
+                if count > 0.0 {
+                    for d in 0..hidden_dim {
+                        sum[d] /= count;
+                    }
                 }
+                results.push(sum);
             }
-
-            // Step 6: L2 normalize
-            results.push(l2_normalize(&sum));
+            Ok(results)
         }
-
-        Ok(results)
     }
 }
 
 /// L2-normalize a vector. Returns a zero vector if the input norm is zero.
-fn l2_normalize(v: &[f32]) -> Vec<f32> {
+pub(super) fn l2_normalize(v: &[f32]) -> Vec<f32> {
     let norm: f32 = v.iter().map(|x| x * x).sum::<f32>().sqrt();
     if norm > 0.0 {
         v.iter().map(|x| x / norm).collect()
@@ -194,6 +292,7 @@ fn l2_normalize(v: &[f32]) -> Vec<f32> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::embedding::EMBEDDING_DIM;
 
     #[test]
     fn test_l2_normalize() {
@@ -212,6 +311,38 @@ mod tests {
         assert_eq!(normalized, vec![0.0, 0.0, 0.0]);
     }
 
+    #[test]
+    fn test_mean_pool_skips_masked_tokens() {
+        // batch 1, seq 2: the second token is padding
+        let mut data = vec![0.0f32; 2 * EMBEDDING_DIM];
+        data[0] = 2.0;
+        data[EMBEDDING_DIM] = 100.0;
+        let pooled = pool(Pooling::Mean, EMBEDDING_DIM, &[1, 2, EMBEDDING_DIM as i64], &data, &[1, 0], 1, 2).unwrap();
+        assert_eq!(pooled.len(), 1);
+        assert_eq!(pooled[0][0], 2.0);
+    }
+
+    #[test]
+    fn test_pool_validates_shape() {
+        let pooled_output = vec![0.5f32; 2 * EMBEDDING_DIM];
+        let dims = [2, EMBEDDING_DIM as i64];
+        let pooled = pool(Pooling::None, EMBEDDING_DIM, &dims, &pooled_output, &[1, 1], 2, 1).unwrap();
+        assert_eq!(pooled.len(), 2);
+        assert_eq!(pooled[1].len(), EMBEDDING_DIM);
+
+        // An already-pooled output can't be mean-pooled, and vice versa
+        let err = pool(Pooling::Mean, EMBEDDING_DIM, &dims, &pooled_output, &[1, 1], 2, 1).unwrap_err();
+        assert!(err.to_string().contains("pooling = \"none\""));
+        let token_dims = [1, 2, EMBEDDING_DIM as i64];
+        assert!(pool(Pooling::None, EMBEDDING_DIM, &token_dims, &pooled_output, &[1, 1], 1, 2).is_err());
+
+        // The expected width follows the configured dimension, not the 384 default
+        let wide_output = vec![0.5f32; 768];
+        assert!(pool(Pooling::None, EMBEDDING_DIM, &[1, 768], &wide_output, &[1], 1, 1).is_err());
+        let pooled = pool(Pooling::None, 768, &[1, 768], &wide_output, &[1], 1, 1).unwrap();
+        assert_eq!(pooled[0].len(), 768);
+    }
+
     fn test_config() -> EmbeddingConfig {
         EmbeddingConfig {
             provider: "local".into(),
@@ -221,6 +352,8 @@ mod tests {
                 .join(".loci/models")
                 .to_string_lossy()
                 .into_owned(),
+            session_count: 1,
+            ..Default::default()
         }
     }
 
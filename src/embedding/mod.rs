@@ -1,21 +1,31 @@
 //! Text-to-vector embedding pipeline.
 //!
-//! Provides the [`EmbeddingProvider`] trait and a local implementation using
-//! all-MiniLM-L6-v2 (384 dimensions, L2-normalized). The provider is created
-//! via [`create_provider`] from configuration.
+//! Provides the [`EmbeddingProvider`] trait, a local implementation using
+//! all-MiniLM-L6-v2 (384 dimensions, L2-normalized), and a remote implementation
+//! for OpenAI-compatible APIs. The provider is created via [`create_provider`]
+//! from configuration.
 
 pub mod local;
+pub mod remote;
 
 use anyhow::Result;
+use futures::future::BoxFuture;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
 
-/// Number of dimensions in the embedding vectors (all-MiniLM-L6-v2).
+use crate::memory::types::{MemoryType, Scope};
+
+/// Default number of dimensions in the embedding vectors (all-MiniLM-L6-v2).
+/// Override with `embedding.dimensions` for other models.
 pub const EMBEDDING_DIM: usize = 384;
 
 /// Trait for embedding text into vectors.
 ///
-/// Implementations produce L2-normalized vectors of exactly [`EMBEDDING_DIM`] dimensions.
-/// All methods are synchronous — callers in async contexts should use
-/// `tokio::task::spawn_blocking`.
+/// Implementations produce L2-normalized vectors of exactly [`dimensions`](Self::dimensions)
+/// dimensions ([`EMBEDDING_DIM`] unless configured otherwise).
+/// `embed` and `embed_batch` are synchronous; async callers should use
+/// [`embed_async`](Self::embed_async) and [`embed_batch_async`](Self::embed_batch_async),
+/// which run them on the blocking thread pool.
 #[allow(dead_code)]
 pub trait EmbeddingProvider: Send + Sync {
     /// Embed a single text string into a vector.
@@ -30,20 +40,400 @@ pub trait EmbeddingProvider: Send + Sync {
     fn dimensions(&self) -> usize {
         EMBEDDING_DIM
     }
+
+    /// Embed a single text without blocking the async runtime.
+    ///
+    /// The default runs [`embed`](Self::embed) via `spawn_blocking`. Providers that call
+    /// a remote API can override this to be natively async.
+    fn embed_async(self: Arc<Self>, text: String) -> BoxFuture<'static, Result<Vec<f32>>>
+    where
+        Self: 'static,
+    {
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || self.embed(&text))
+                .await
+                .map_err(|e| anyhow::anyhow!("embedding task failed: {e}"))?
+        })
+    }
+
+    /// Embed a batch of texts without blocking the async runtime.
+    ///
+    /// The default runs [`embed_batch`](Self::embed_batch) via `spawn_blocking`.
+    fn embed_batch_async(self: Arc<Self>, texts: Vec<String>) -> BoxFuture<'static, Result<Vec<Vec<f32>>>>
+    where
+        Self: 'static,
+    {
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || {
+                let text_refs: Vec<&str> = texts.iter().map(String::as_str).collect();
+                self.embed_batch(&text_refs)
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("embedding task failed: {e}"))?
+        })
+    }
+}
+
+/// Stand-in provider used when the real one could not be created (e.g. the model is
+/// not downloaded). Every call fails with the original reason, so recall can fall back
+/// to keyword search while stores report why they cannot embed.
+pub struct UnavailableProvider {
+    reason: String,
+}
+
+impl UnavailableProvider {
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+}
+
+impl EmbeddingProvider for UnavailableProvider {
+    fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+        anyhow::bail!("embedding provider unavailable: {}", self.reason)
+    }
+}
+
+/// Decorator that remembers the vectors of recently embedded texts, so repeated
+/// content (e.g. the same fact across several imports) is embedded once.
+///
+/// Entries are keyed by the SHA-256 of the exact text and evicted least recently used
+/// once `capacity` is reached.
+pub struct CachingEmbeddingProvider {
+    inner: Box<dyn EmbeddingProvider>,
+    capacity: usize,
+    cache: Mutex<EmbeddingCache>,
+}
+
+/// LRU state: `entries` maps a text hash to its last-use tick and vector, and `order`
+/// maps ticks back to hashes so the oldest entry is found without a scan.
+#[derive(Default)]
+struct EmbeddingCache {
+    entries: HashMap<[u8; 32], (u64, Vec<f32>)>,
+    order: BTreeMap<u64, [u8; 32]>,
+    tick: u64,
+}
+
+impl EmbeddingCache {
+    fn get(&mut self, key: &[u8; 32]) -> Option<Vec<f32>> {
+        let (tick, vector) = self.entries.get_mut(key)?;
+        self.order.remove(tick);
+        self.tick += 1;
+        *tick = self.tick;
+        self.order.insert(self.tick, *key);
+        Some(vector.clone())
+    }
+
+    fn insert(&mut self, key: [u8; 32], vector: Vec<f32>, capacity: usize) {
+        if let Some((tick, _)) = self.entries.remove(&key) {
+            self.order.remove(&tick);
+        }
+        while self.entries.len() >= capacity {
+            let Some((_, oldest)) = self.order.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+        self.tick += 1;
+        self.order.insert(self.tick, key);
+        self.entries.insert(key, (self.tick, vector));
+    }
+}
+
+impl CachingEmbeddingProvider {
+    /// Wrap `inner`, caching up to `capacity` vectors (at least one).
+    pub fn new(inner: Box<dyn EmbeddingProvider>, capacity: usize) -> Self {
+        Self {
+            inner,
+            capacity: capacity.max(1),
+            cache: Mutex::new(EmbeddingCache::default()),
+        }
+    }
+
+    fn key(text: &str) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
+        Sha256::digest(text.as_bytes()).into()
+    }
+
+    fn cache(&self) -> std::sync::MutexGuard<'_, EmbeddingCache> {
+        // A panic mid-update leaves at worst a stale entry, so keep using the cache
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl EmbeddingProvider for CachingEmbeddingProvider {
+    fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let key = Self::key(text);
+        if let Some(vector) = self.cache().get(&key) {
+            return Ok(vector);
+        }
+        let vector = self.inner.embed(text)?;
+        self.cache().insert(key, vector.clone(), self.capacity);
+        Ok(vector)
+    }
+
+    fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let keys: Vec<[u8; 32]> = texts.iter().map(|text| Self::key(text)).collect();
+        let mut vectors: Vec<Option<Vec<f32>>> = {
+            let mut cache = self.cache();
+            keys.iter().map(|key| cache.get(key)).collect()
+        };
+
+        // Embed each distinct missing text once, even if it repeats within the batch
+        let mut missing: Vec<usize> = Vec::new();
+        for (i, key) in keys.iter().enumerate() {
+            if vectors[i].is_none() && !missing.iter().any(|&j| keys[j] == *key) {
+                missing.push(i);
+            }
+        }
+        if !missing.is_empty() {
+            let missing_texts: Vec<&str> = missing.iter().map(|&i| texts[i]).collect();
+            let embedded = self.inner.embed_batch(&missing_texts)?;
+            let mut cache = self.cache();
+            for (&i, vector) in missing.iter().zip(embedded) {
+                cache.insert(keys[i], vector.clone(), self.capacity);
+                for (j, key) in keys.iter().enumerate() {
+                    if vectors[j].is_none() && *key == keys[i] {
+                        vectors[j] = Some(vector.clone());
+                    }
+                }
+            }
+        }
+
+        vectors
+            .into_iter()
+            .map(|vector| vector.ok_or_else(|| anyhow::anyhow!("embedding provider returned too few vectors")))
+            .collect()
+    }
+
+    fn dimensions(&self) -> usize {
+        self.inner.dimensions()
+    }
 }
 
 /// Create an embedding provider from config.
 ///
-/// Currently only `"local"` is supported (ONNX Runtime + all-MiniLM-L6-v2).
-/// Returns an error if model files are not found — run `loci model download` first.
+/// `"local"` runs ONNX Runtime + all-MiniLM-L6-v2 and returns an error if model files
+/// are not found — run `loci model download` first. `"remote"` calls the OpenAI-style
+/// endpoint at `embedding.api_base`. Unless `embedding.cache_size` is 0, the provider
+/// is wrapped in a [`CachingEmbeddingProvider`].
 pub fn create_provider(
     config: &crate::config::EmbeddingConfig,
 ) -> Result<Box<dyn EmbeddingProvider>> {
-    match config.provider.as_str() {
-        "local" => {
-            let provider = local::LocalEmbeddingProvider::new(config)?;
-            Ok(Box::new(provider))
+    let provider: Box<dyn EmbeddingProvider> = match config.provider.as_str() {
+        "local" => Box::new(local::LocalEmbeddingProvider::new(config)?),
+        "remote" => Box::new(remote::RemoteEmbeddingProvider::new(config)?),
+        other => anyhow::bail!("unknown embedding provider: {other}. Supported: local, remote"),
+    };
+    if config.cache_size == 0 {
+        return Ok(provider);
+    }
+    Ok(Box::new(CachingEmbeddingProvider::new(provider, config.cache_size)))
+}
+
+/// SHA-256 (hex) of the configured provider's model file, or `None` if the provider
+/// has no model file or it hasn't been downloaded yet.
+///
+/// Stored as `schema_meta.embedding_model_hash` so a swapped model file is caught even
+/// when the configured model name is unchanged. Reads the whole file, so call it once.
+pub fn model_file_hash(config: &crate::config::EmbeddingConfig) -> Result<Option<String>> {
+    use sha2::{Digest, Sha256};
+    use std::io::Read;
+
+    if config.provider != "local" {
+        return Ok(None);
+    }
+    let model_path = crate::config::expand_tilde(&config.cache_dir).join("model.onnx");
+    let mut file = match std::fs::File::open(&model_path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1 << 16];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(Some(
+        hasher.finalize().iter().map(|b| format!("{b:02x}")).collect(),
+    ))
+}
+
+/// Build the text embedded for a memory from `embedding.content_template`.
+///
+/// Placeholders: `{content}`, `{type}`, `{scope}`, `{group}` (empty when there is none).
+/// The stored content is never affected, only what the model sees.
+pub fn render_content_template(
+    template: &str,
+    content: &str,
+    memory_type: MemoryType,
+    scope: Scope,
+    group: Option<&str>,
+) -> String {
+    fill_template(
+        template,
+        &[
+            ("content", content),
+            ("type", memory_type.as_str()),
+            ("scope", scope.as_str()),
+            ("group", group.unwrap_or("")),
+        ],
+    )
+}
+
+/// Build the text embedded for a recall query from `embedding.query_template`.
+///
+/// Placeholders: `{query}`, `{group}`.
+pub fn render_query_template(template: &str, query: &str, group: &str) -> String {
+    fill_template(template, &[("query", query), ("group", group)])
+}
+
+/// Substitute `{name}` placeholders in a single pass, so values that themselves
+/// contain braces are inserted verbatim. Unknown placeholders are left as written.
+fn fill_template(template: &str, values: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after.find('}').and_then(|end| {
+            values
+                .iter()
+                .find(|(name, _)| *name == &after[..end])
+                .map(|(_, value)| (end, *value))
+        });
+        match value {
+            Some((end, value)) => {
+                out.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Create `config.session_count` independent providers (at least one).
+///
+/// Each provider owns its own inference session, so batches dispatched to different
+/// providers run in parallel instead of queueing on a single session lock.
+pub fn create_provider_pool(
+    config: &crate::config::EmbeddingConfig,
+) -> Result<Vec<std::sync::Arc<dyn EmbeddingProvider>>> {
+    (0..config.session_count.max(1))
+        .map(|_| create_provider(config).map(std::sync::Arc::from))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn model_file_hash_reads_the_cached_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = crate::config::EmbeddingConfig {
+            cache_dir: dir.path().to_string_lossy().into_owned(),
+            ..Default::default()
+        };
+        assert_eq!(model_file_hash(&config).unwrap(), None);
+
+        std::fs::write(dir.path().join("model.onnx"), b"abc").unwrap();
+        assert_eq!(
+            model_file_hash(&config).unwrap().as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+    }
+
+    #[test]
+    fn default_templates_pass_text_through() {
+        let text = render_content_template(
+            "{content}",
+            "Rust is fast",
+            MemoryType::Semantic,
+            Scope::Global,
+            None,
+        );
+        assert_eq!(text, "Rust is fast");
+        assert_eq!(render_query_template("{query}", "rust", "default"), "rust");
+    }
+
+    #[test]
+    fn content_template_fills_placeholders_once() {
+        let text = render_content_template(
+            "{type} [{group}]: {content} {unknown}",
+            "uses {type} literally",
+            MemoryType::Episodic,
+            Scope::Group,
+            Some("project-a"),
+        );
+        assert_eq!(text, "episodic [project-a]: uses {type} literally {unknown}");
+    }
+
+    #[tokio::test]
+    async fn async_embedding_runs_the_sync_methods() {
+        struct Length;
+        impl EmbeddingProvider for Length {
+            fn embed(&self, text: &str) -> Result<Vec<f32>> {
+                Ok(vec![text.len() as f32])
+            }
         }
-        other => anyhow::bail!("unknown embedding provider: {other}. Supported: local"),
+
+        let provider: Arc<dyn EmbeddingProvider> = Arc::new(Length);
+        assert_eq!(Arc::clone(&provider).embed_async("abc".into()).await.unwrap(), vec![3.0]);
+        let batch = provider
+            .embed_batch_async(vec!["a".into(), "bb".into()])
+            .await
+            .unwrap();
+        assert_eq!(batch, vec![vec![1.0], vec![2.0]]);
+    }
+
+    #[test]
+    fn caching_provider_embeds_repeated_text_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct Counting(Arc<AtomicUsize>);
+        impl EmbeddingProvider for Counting {
+            fn embed(&self, text: &str) -> Result<Vec<f32>> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Ok(vec![text.len() as f32])
+            }
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = CachingEmbeddingProvider::new(Box::new(Counting(Arc::clone(&calls))), 2);
+
+        assert_eq!(provider.embed("fact").unwrap(), vec![4.0]);
+        assert_eq!(provider.embed("fact").unwrap(), vec![4.0]);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Batches reuse cached vectors and embed in-batch repeats once
+        let batch = provider.embed_batch(&["fact", "other", "other"]).unwrap();
+        assert_eq!(batch, vec![vec![4.0], vec![5.0], vec![5.0]]);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // "fact" was used less recently than "other", so a third text evicts it
+        provider.embed("third").unwrap();
+        provider.embed("other").unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        provider.embed("fact").unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn unavailable_provider_reports_reason() {
+        let provider = UnavailableProvider::new("model not found");
+        let err = provider.embed("anything").unwrap_err();
+        assert!(err.to_string().contains("model not found"));
     }
 }
@@ -0,0 +1,224 @@
+//! Remote embedding provider for OpenAI-compatible APIs.
+//!
+//! Implements [`EmbeddingProvider`] by POSTing to `{api_base}/embeddings`, for hosts
+//! that can't load the local ONNX model. Returned vectors are L2-normalized so the
+//! cosine-to-L2 conversions used by search and dedup still hold.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::local::l2_normalize;
+use super::EmbeddingProvider;
+use crate::config::EmbeddingConfig;
+
+/// How long a single embeddings request may take before it fails.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Embedding provider backed by an OpenAI-style `/embeddings` endpoint.
+pub struct RemoteEmbeddingProvider {
+    url: String,
+    api_key: String,
+    model: String,
+    dimensions: usize,
+    /// Built on first use, since a blocking client must not be created on an async
+    /// runtime thread and `embed` always runs off the runtime.
+    client: OnceLock<reqwest::blocking::Client>,
+}
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [&'a str],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl RemoteEmbeddingProvider {
+    pub fn new(config: &EmbeddingConfig) -> Result<Self> {
+        let api_base = config.api_base.trim().trim_end_matches('/');
+        anyhow::ensure!(
+            !api_base.is_empty(),
+            "embedding.api_base must be set when embedding.provider = \"remote\""
+        );
+        Ok(Self {
+            url: format!("{api_base}/embeddings"),
+            api_key: config.api_key.clone(),
+            model: config.model.clone(),
+            dimensions: config.dimensions,
+            client: OnceLock::new(),
+        })
+    }
+
+    fn client(&self) -> Result<&reqwest::blocking::Client> {
+        if let Some(client) = self.client.get() {
+            return Ok(client);
+        }
+        let client = reqwest::blocking::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .context("failed to build HTTP client")?;
+        Ok(self.client.get_or_init(|| client))
+    }
+}
+
+impl EmbeddingProvider for RemoteEmbeddingProvider {
+    fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.embed_batch(&[text])?
+            .pop()
+            .context("embedding API returned no vectors")
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut request = self.client()?.post(&self.url).json(&EmbeddingRequest {
+            model: &self.model,
+            input: texts,
+        });
+        if !self.api_key.is_empty() {
+            request = request.bearer_auth(&self.api_key);
+        }
+        let response = request
+            .send()
+            .with_context(|| format!("embedding request to {} failed", self.url))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+            anyhow::bail!("embedding API at {} returned {status}: {}", self.url, body.trim());
+        }
+        let mut data = response
+            .json::<EmbeddingResponse>()
+            .context("embedding API returned an invalid response")?
+            .data;
+        anyhow::ensure!(
+            data.len() == texts.len(),
+            "embedding API returned {} vectors for {} inputs",
+            data.len(),
+            texts.len()
+        );
+
+        // The API may answer out of order; `index` ties each vector to its input
+        data.sort_by_key(|d| d.index);
+        data.into_iter()
+            .map(|d| {
+                anyhow::ensure!(
+                    d.embedding.len() == self.dimensions(),
+                    "embedding model {:?} returned {} dimensions, expected {}",
+                    self.model,
+                    d.embedding.len(),
+                    self.dimensions()
+                );
+                Ok(l2_normalize(&d.embedding))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedding::EMBEDDING_DIM;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    /// Serve one HTTP request with `body` as the JSON response and return the base URL.
+    fn serve_once(body: String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut request_body = vec![0; content_length];
+            reader.read_exact(&mut request_body).unwrap();
+
+            let mut stream = reader.into_inner();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        });
+        format!("http://{addr}/v1/")
+    }
+
+    fn provider(api_base: String) -> RemoteEmbeddingProvider {
+        RemoteEmbeddingProvider::new(&EmbeddingConfig {
+            provider: "remote".into(),
+            api_base,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn remote_vectors_are_reordered_and_normalized() {
+        let vector = |value: f32| vec![value; EMBEDDING_DIM];
+        let body = serde_json::json!({
+            "data": [
+                { "index": 1, "embedding": vector(2.0) },
+                { "index": 0, "embedding": vector(-3.0) },
+            ]
+        })
+        .to_string();
+
+        let embeddings = provider(serve_once(body)).embed_batch(&["a", "b"]).unwrap();
+        assert_eq!(embeddings.len(), 2);
+        assert!(embeddings[0][0] < 0.0 && embeddings[1][0] > 0.0);
+        for embedding in &embeddings {
+            let norm: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+            assert!((norm - 1.0).abs() < 1e-5, "norm {norm}");
+        }
+    }
+
+    #[test]
+    fn remote_dimension_mismatch_is_an_error() {
+        let body = serde_json::json!({
+            "data": [{ "index": 0, "embedding": vec![1.0f32; 1536] }]
+        })
+        .to_string();
+
+        let err = provider(serve_once(body)).embed("a").unwrap_err();
+        assert!(err.to_string().contains("returned 1536 dimensions, expected 384"), "{err}");
+    }
+
+    #[test]
+    fn remote_provider_requires_api_base() {
+        assert!(RemoteEmbeddingProvider::new(&EmbeddingConfig {
+            provider: "remote".into(),
+            api_base: " ".into(),
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
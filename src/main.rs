@@ -1,11 +1,9 @@
 mod cli;
-mod config;
-mod db;
-mod embedding;
-mod memory;
 mod server;
 mod tools;
 
+use loci::{config, db, embedding, memory};
+
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
@@ -35,39 +33,155 @@ enum Command {
     Search {
         /// Natural language query
         query: String,
+        /// Retrieval mode: "hybrid", "vector", or "keyword"
+        #[arg(long, default_value = "hybrid")]
+        mode: String,
     },
     /// Display memory statistics
     Stats {
         /// Filter stats to a specific group
         #[arg(long)]
         group: Option<String>,
+        /// Output format: "text" or "csv"
+        #[arg(long, default_value = "text")]
+        format: String,
     },
     /// Inspect a memory by ID
     Inspect {
-        /// Memory ID to inspect
+        /// Memory ID (or unique prefix) to inspect
         id: String,
+        /// Also print the stored embedding vector
+        #[arg(long)]
+        raw_vector: bool,
+    },
+    /// List memories without a search query
+    List {
+        /// Only list memories derived from this source URI
+        #[arg(long)]
+        source_uri: Option<String>,
+        /// Only list this memory type (episodic, semantic, procedural, entity)
+        #[arg(long = "type")]
+        memory_type: Option<memory::types::MemoryType>,
+        /// Only list this scope (global, group, session)
+        #[arg(long)]
+        scope: Option<memory::types::Scope>,
+        /// Only list global memories plus this group's own
+        #[arg(long)]
+        group: Option<String>,
+        /// Only list memories with at least this confidence
+        #[arg(long)]
+        min_confidence: Option<f64>,
+        /// Only list memories with at most this confidence
+        #[arg(long)]
+        max_confidence: Option<f64>,
+        /// Only list memories created at or after this RFC 3339 timestamp
+        #[arg(long)]
+        created_after: Option<String>,
+        /// Only list memories created at or before this RFC 3339 timestamp
+        #[arg(long)]
+        created_before: Option<String>,
+        /// Sort order, descending: created_at, confidence, or access_count
+        #[arg(long, default_value = "created_at")]
+        sort: memory::search::ListSort,
+        /// Maximum number of memories to list
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Number of memories to skip first
+        #[arg(long, default_value_t = 0)]
+        offset: usize,
+        /// Output format: "text" or "csv"
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+    /// Stream audit log entries as they are written
+    Watch {
+        /// Only show this operation (e.g. "create", "update", "delete")
+        #[arg(long)]
+        operation: Option<String>,
+        /// Only show operations on memories in this group
+        #[arg(long)]
+        group: Option<String>,
+    },
+    /// Show the most frequent recall queries (requires retrieval.log_queries)
+    Queries {
+        /// Number of distinct queries to show
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+    },
+    /// Set or clear a memory's superseded_by link (repair tool)
+    Relink {
+        /// Memory ID (or unique prefix) to relink
+        id: String,
+        /// Memory ID that supersedes it, "forgotten", or "none" to make it active again
+        #[arg(long)]
+        superseded_by: String,
     },
     /// Export all memories as JSON
-    Export,
-    /// Import memories from a JSON file
+    Export {
+        /// Only export memories updated (and relations created) after this RFC 3339 timestamp
+        #[arg(long)]
+        since_updated: Option<String>,
+        /// Output format: "json" (one document) or "jsonl" (streamed, one record per line)
+        #[arg(long, default_value = "json")]
+        format: memory::export::ExportFormat,
+        /// Include each memory's stored vector so import can skip re-embedding
+        #[arg(long)]
+        with_embeddings: bool,
+    },
+    /// Import memories from a JSON or JSONL export file
     Import {
-        /// Path to JSON file
+        /// Path to the export file (format is detected)
         file: PathBuf,
     },
+    /// Write a consistent snapshot of the database, safe while the server is running
+    Backup {
+        /// Path of the new backup file (must not exist)
+        path: PathBuf,
+    },
     /// Delete all memories (requires confirmation)
     Reset,
     /// Run maintenance compaction (decay + compact + promote)
-    Compact,
+    Compact {
+        /// Afterwards, VACUUM the database so space freed by deletions is returned to disk
+        #[arg(long)]
+        vacuum: bool,
+    },
     /// Clean up stale low-confidence memories
     Cleanup {
         /// Preview what would be deleted without actually deleting
         #[arg(long)]
         dry_run: bool,
     },
+    /// Permanently delete old superseded memories
+    Purge {
+        /// Delete superseded and forgotten memories
+        #[arg(long)]
+        superseded: bool,
+        /// Only those superseded at least this many days ago (default
+        /// maintenance.purge_superseded_days)
+        #[arg(long)]
+        older_than_days: Option<u64>,
+        /// Report how many would be deleted without deleting them
+        #[arg(long)]
+        dry_run: bool,
+    },
     /// Run database diagnostics and health check
     Doctor,
+    /// Rebuild the keyword (FTS5) index from the memories table
+    Reindex,
+    /// Reverse schema migrations, so an older Loci binary can open the database
+    Migrate {
+        /// Schema version to migrate down to
+        #[arg(long)]
+        to: u32,
+    },
     /// Re-embed all memories with the currently configured model
-    ReEmbed,
+    ReEmbed {
+        /// ONNX execution provider for this run ("cpu", "cuda", "coreml"),
+        /// overriding embedding.execution_provider
+        #[arg(long)]
+        execution_provider: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -87,10 +201,13 @@ async fn main() -> Result<()> {
     // Log to stderr so stdout stays clean for MCP JSON-RPC.
     let filter = EnvFilter::try_new(&config.server.log_level)
         .unwrap_or_else(|_| EnvFilter::new("info"));
-    tracing_subscriber::fmt()
+    let subscriber = tracing_subscriber::fmt()
         .with_env_filter(filter)
-        .with_writer(std::io::stderr)
-        .init();
+        .with_writer(std::io::stderr);
+    match config.server.log_format {
+        config::LogFormat::Text => subscriber.init(),
+        config::LogFormat::Json => subscriber.json().init(),
+    }
 
     match cli.command {
         Command::Serve { transport } => {
@@ -108,34 +225,98 @@ async fn main() -> Result<()> {
                 cli::model_download(&config.embedding).await?;
             }
         },
-        Command::Search { query } => {
-            cli::search::search(&config, &query).await?;
+        Command::Search { query, mode } => {
+            cli::search::search(&config, &query, &mode).await?;
+        }
+        Command::Stats { group, format } => {
+            cli::stats::stats(&config, group.as_deref(), &format)?;
+        }
+        Command::Inspect { id, raw_vector } => {
+            cli::inspect::inspect(&config, &id, raw_vector)?;
+        }
+        Command::List {
+            source_uri,
+            memory_type,
+            scope,
+            group,
+            min_confidence,
+            max_confidence,
+            created_after,
+            created_before,
+            sort,
+            limit,
+            offset,
+            format,
+        } => {
+            let filter = memory::search::ListFilter {
+                source_uri,
+                memory_type,
+                scope,
+                group,
+                all_sessions: true,
+                min_confidence,
+                max_confidence,
+                created_after,
+                created_before,
+                sort,
+                limit,
+                offset,
+                ..Default::default()
+            };
+            cli::list::list(&config, &filter, &format)?;
+        }
+        Command::Watch { operation, group } => {
+            cli::watch::watch(&config, operation.as_deref(), group.as_deref()).await?;
         }
-        Command::Stats { group } => {
-            cli::stats::stats(&config, group.as_deref())?;
+        Command::Queries { top } => {
+            cli::queries::queries(&config, top)?;
         }
-        Command::Inspect { id } => {
-            cli::inspect::inspect(&config, &id)?;
+        Command::Relink { id, superseded_by } => {
+            cli::relink::relink(&config, &id, &superseded_by)?;
         }
-        Command::Export => {
-            cli::export::export(&config)?;
+        Command::Export {
+            since_updated,
+            format,
+            with_embeddings,
+        } => {
+            cli::export::export(&config, since_updated.as_deref(), format, with_embeddings)?;
         }
         Command::Import { file } => {
             cli::import::import(&config, &file).await?;
         }
+        Command::Backup { path } => {
+            cli::backup::backup(&config, &path)?;
+        }
         Command::Reset => {
             cli::reset::reset(&config)?;
         }
-        Command::Compact => {
-            cli::maintenance::compact(&config).await?;
+        Command::Compact { vacuum } => {
+            cli::maintenance::compact(&config, vacuum).await?;
         }
         Command::Cleanup { dry_run } => {
             cli::maintenance::cleanup(&config, dry_run)?;
         }
+        Command::Purge {
+            superseded,
+            older_than_days,
+            dry_run,
+        } => {
+            cli::maintenance::purge(&config, superseded, older_than_days, dry_run)?;
+        }
         Command::Doctor => {
             cli::doctor::doctor(&config)?;
         }
-        Command::ReEmbed => {
+        Command::Reindex => {
+            cli::reindex::reindex(&config)?;
+        }
+        Command::Migrate { to } => {
+            cli::migrate::migrate(&config, to)?;
+        }
+        Command::ReEmbed { execution_provider } => {
+            let mut config = config;
+            if execution_provider.is_some() {
+                config.embedding.execution_provider = execution_provider;
+            }
             cli::re_embed::re_embed(&config).await?;
         }
     }
@@ -0,0 +1,358 @@
+//! Export and import of whole memory stores.
+//!
+//! Two formats are supported. [`ExportFormat::Json`] is a single pretty-printed
+//! document built in memory. [`ExportFormat::Jsonl`] streams one record per line as
+//! rows are read — every memory, then every relation — so exporting a large store
+//! never holds it all at once. Each line is tagged with its kind:
+//!
+//! ```text
+//! {"memory":{"id":"01953a2b-...","type":"semantic","content":"...",...}}
+//! {"relation":{"id":"...","subject_id":"...","predicate":"works_at",...}}
+//! ```
+//!
+//! Exports made with embeddings carry each stored vector and the model that produced
+//! it (in JSON Lines, as a leading `{"embedding_model":"..."}` line).
+//!
+//! [`parse_export`] detects which format it was given, and [`import_data`] stores the
+//! result through the normal write path. Vectors are reused when the export's model
+//! matches the target database's; otherwise every memory is re-embedded.
+
+use std::io::Write;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::embedding::EmbeddingProvider;
+use crate::memory::search::{memory_from_row, MEMORY_COLUMNS};
+use crate::memory::types::{EntityRelation, Memory};
+
+/// Output format of `loci export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    /// One pretty-printed JSON document with `memories` and `relations` arrays.
+    #[default]
+    Json,
+    /// One tagged JSON object per line, written as rows are read.
+    Jsonl,
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "jsonl" => Ok(Self::Jsonl),
+            _ => Err(format!("unknown export format: {s} (expected json or jsonl)")),
+        }
+    }
+}
+
+/// The JSON document format — all memories and relations.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportData {
+    /// Model the included embeddings were produced with; set only when they are included.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_model: Option<String>,
+    pub memories: Vec<Memory>,
+    #[serde(default)]
+    pub relations: Vec<EntityRelation>,
+}
+
+/// One line of a JSON Lines export.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ExportRecord {
+    #[serde(rename = "embedding_model")]
+    EmbeddingModel(String),
+    Memory(Box<Memory>),
+    Relation(EntityRelation),
+}
+
+/// How many records an export wrote.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExportCounts {
+    pub memories: usize,
+    pub relations: usize,
+}
+
+/// Outcome of [`import_data`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Memories stored.
+    pub imported: usize,
+    /// Memories skipped because their ID already exists.
+    pub skipped: usize,
+    /// Relations re-created.
+    pub relations_created: usize,
+    /// Relations skipped because an endpoint is missing or the insert failed.
+    pub relations_skipped: usize,
+    /// Imported memories stored with their exported vector instead of being re-embedded.
+    pub embeddings_reused: usize,
+}
+
+const RELATION_COLUMNS: &str = "id, subject_id, predicate, object_id, created_at";
+
+fn relation_from_row(row: &rusqlite::Row) -> rusqlite::Result<EntityRelation> {
+    Ok(EntityRelation {
+        id: row.get(0)?,
+        subject_id: row.get(1)?,
+        predicate: row.get(2)?,
+        object_id: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+/// Reads stored vectors for `--with-embeddings` exports.
+struct VectorReader<'conn> {
+    stmt: rusqlite::Statement<'conn>,
+}
+
+impl<'conn> VectorReader<'conn> {
+    fn new(conn: &'conn Connection) -> Result<Self> {
+        let stmt = conn.prepare("SELECT embedding FROM memories_vec WHERE id = ?1")?;
+        Ok(Self { stmt })
+    }
+
+    fn attach(&mut self, memory: &mut Memory) -> Result<()> {
+        let bytes: Option<Vec<u8>> = self
+            .stmt
+            .query_row(params![memory.id], |row| row.get(0))
+            .optional()?;
+        memory.embedding = bytes.map(|b| crate::memory::bytes_to_embedding(&b));
+        Ok(())
+    }
+}
+
+/// Read every memory and relation into one [`ExportData`] document, with each
+/// memory's stored vector when `with_embeddings` is set.
+pub fn export_data(conn: &Connection, with_embeddings: bool) -> Result<ExportData> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {MEMORY_COLUMNS} FROM memories ORDER BY created_at"
+    ))?;
+    let mut memories = stmt
+        .query_map([], memory_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut embedding_model = None;
+    if with_embeddings {
+        embedding_model = crate::db::migrations::get_embedding_model(conn)?;
+        let mut vectors = VectorReader::new(conn)?;
+        for memory in &mut memories {
+            vectors.attach(memory)?;
+        }
+    }
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {RELATION_COLUMNS} FROM entity_relations ORDER BY created_at"
+    ))?;
+    let relations = stmt
+        .query_map(params![], relation_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ExportData {
+        embedding_model,
+        memories,
+        relations,
+    })
+}
+
+/// Stream every memory, then every relation, to `out` as JSON Lines. With
+/// `with_embeddings`, a leading line names the embedding model and each memory
+/// carries its stored vector.
+///
+/// Each row is written as soon as it is read; nothing is collected.
+pub fn write_jsonl(conn: &Connection, mut out: impl Write, with_embeddings: bool) -> Result<ExportCounts> {
+    let mut counts = ExportCounts::default();
+
+    let mut vectors = None;
+    if with_embeddings {
+        if let Some(model) = crate::db::migrations::get_embedding_model(conn)? {
+            write_record(&mut out, &ExportRecord::EmbeddingModel(model))?;
+        }
+        vectors = Some(VectorReader::new(conn)?);
+    }
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {MEMORY_COLUMNS} FROM memories ORDER BY created_at"
+    ))?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let mut memory = memory_from_row(row)?;
+        if let Some(ref mut vectors) = vectors {
+            vectors.attach(&mut memory)?;
+        }
+        write_record(&mut out, &ExportRecord::Memory(Box::new(memory)))?;
+        counts.memories += 1;
+    }
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {RELATION_COLUMNS} FROM entity_relations ORDER BY created_at"
+    ))?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        write_record(&mut out, &ExportRecord::Relation(relation_from_row(row)?))?;
+        counts.relations += 1;
+    }
+
+    out.flush()?;
+    Ok(counts)
+}
+
+fn write_record(out: &mut impl Write, record: &ExportRecord) -> Result<()> {
+    serde_json::to_writer(&mut *out, record)?;
+    out.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Parse an export in either format.
+///
+/// Input whose first non-blank line is a complete tagged record is read as JSON Lines;
+/// anything else is read as the JSON document.
+pub fn parse_export(text: &str) -> Result<ExportData> {
+    let first_line = text.lines().find(|line| !line.trim().is_empty());
+    let is_jsonl = first_line
+        .is_some_and(|line| serde_json::from_str::<ExportRecord>(line).is_ok());
+    if !is_jsonl {
+        return serde_json::from_str(text).context("failed to parse import JSON");
+    }
+
+    let mut data = ExportData {
+        embedding_model: None,
+        memories: Vec::new(),
+        relations: Vec::new(),
+    };
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: ExportRecord = serde_json::from_str(line)
+            .with_context(|| format!("failed to parse import JSONL line {}", index + 1))?;
+        match record {
+            ExportRecord::EmbeddingModel(model) => data.embedding_model = Some(model),
+            ExportRecord::Memory(memory) => data.memories.push(*memory),
+            ExportRecord::Relation(relation) => data.relations.push(relation),
+        }
+    }
+    Ok(data)
+}
+
+/// Store exported memories and relations.
+///
+/// Exported vectors are stored as-is when the export's embedding model matches the
+/// one recorded in `conn` and the vector has the provider's dimensions; every other
+/// memory is re-embedded through `content_template`. Memories keep their exported IDs
+/// and are stored verbatim, without deduplication; those whose ID already exists in
+/// the database are skipped. Relations are re-created if both endpoints exist.
+pub async fn import_data(
+    conn: &mut Connection,
+    data: &ExportData,
+    embedding: Arc<dyn EmbeddingProvider>,
+    content_template: &str,
+) -> Result<ImportReport> {
+    let mut report = ImportReport::default();
+
+    // An export is restored as-is, so nothing is folded into existing memories
+    let options = crate::memory::store::StoreOptions {
+        skip_dedup: true,
+        ..Default::default()
+    };
+
+    let stored_model = crate::db::migrations::get_embedding_model(conn)?;
+    let reuse_vectors = data.embedding_model.is_some() && data.embedding_model == stored_model;
+    if !reuse_vectors && data.memories.iter().any(|m| m.embedding.is_some()) {
+        tracing::warn!(
+            exported = data.embedding_model.as_deref().unwrap_or("unknown"),
+            stored = stored_model.as_deref().unwrap_or("unknown"),
+            "export was embedded with a different model; re-embedding"
+        );
+    }
+
+    for memory in &data.memories {
+        // Check if ID already exists
+        let exists: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM memories WHERE id = ?1",
+            params![memory.id],
+            |row| row.get(0),
+        )?;
+        if exists {
+            report.skipped += 1;
+            continue;
+        }
+
+        // A hash that no longer matches means the content was edited after export
+        if let Some(ref hash) = memory.content_hash
+            && *hash != crate::memory::content_hash(&memory.content)
+        {
+            tracing::warn!(id = %memory.id, "content hash mismatch; storing the content as given");
+        }
+
+        // Store using the full write path
+        let new_memory = crate::memory::store::NewMemory {
+            content: &memory.content,
+            memory_type: memory.memory_type,
+            scope: memory.scope,
+            group: memory.source_group.as_deref(),
+            confidence: memory.confidence,
+            importance: memory.importance,
+            metadata: memory.metadata.as_ref(),
+            supersedes: None, // don't re-apply supersession chains
+            source_uri: memory.source_uri.as_deref(),
+            id: Some(&memory.id), // keep IDs so exported relations still resolve
+            session_id: memory.session_id.as_deref(),
+            expires_at: memory.expires_at.as_deref(),
+            tags: memory.tags.as_deref(),
+        };
+
+        let exported = memory
+            .embedding
+            .as_ref()
+            .filter(|v| reuse_vectors && v.len() == embedding.dimensions());
+        let vector = match exported {
+            Some(vector) => {
+                report.embeddings_reused += 1;
+                vector.clone()
+            }
+            None => {
+                let content = crate::embedding::render_content_template(
+                    content_template,
+                    &memory.content,
+                    memory.memory_type,
+                    memory.scope,
+                    memory.source_group.as_deref(),
+                );
+                Arc::clone(&embedding).embed_async(content).await?
+            }
+        };
+        crate::memory::store::store_memory_with(conn, &new_memory, &vector, &options)?;
+        report.imported += 1;
+    }
+
+    for rel in &data.relations {
+        let exists = |id: &str| -> rusqlite::Result<bool> {
+            conn.query_row("SELECT COUNT(*) > 0 FROM memories WHERE id = ?1", params![id], |row| {
+                row.get(0)
+            })
+        };
+        if !(exists(&rel.subject_id)? && exists(&rel.object_id)?) {
+            report.relations_skipped += 1;
+            continue;
+        }
+        match crate::memory::relations::store_relation(
+            conn,
+            &rel.subject_id,
+            &rel.predicate,
+            &rel.object_id,
+        ) {
+            Ok(_) => report.relations_created += 1,
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to create relation");
+                report.relations_skipped += 1;
+            }
+        }
+    }
+
+    Ok(report)
+}
@@ -2,9 +2,10 @@
 //!
 //! Soft delete marks a memory as superseded (by "forgotten"); hard delete permanently
 //! removes it from the memories table, FTS5 index, vector index, and cascades to relations.
+//! A soft delete can be undone with [`restore_memory`].
 
 use anyhow::{bail, Result};
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use serde::Serialize;
 
 use super::store::write_audit_log;
@@ -18,6 +19,15 @@ pub struct ForgetResult {
     pub hard_deleted: bool,
 }
 
+/// Result returned from restoring a soft-deleted memory.
+#[derive(Debug, Serialize)]
+pub struct RestoreResult {
+    /// ID of the restored memory.
+    pub id: String,
+    /// Always `true`; failures are errors.
+    pub restored: bool,
+}
+
 /// Forget a memory by ID.
 ///
 /// Soft delete (default): sets `superseded_by = "forgotten"` and logs reason.
@@ -126,19 +136,63 @@ fn hard_delete_memory(
     })
 }
 
+/// Undo a soft delete, making the memory active again.
+///
+/// Only clears `superseded_by` when it is `"forgotten"`: a memory replaced by another
+/// memory stays superseded, so replacement chains are never broken. Fails if the memory
+/// doesn't exist (it may have been hard-deleted), is superseded by a real memory, or
+/// is already active.
+pub fn restore_memory(
+    conn: &mut Connection,
+    memory_id: &str,
+    reason: Option<&str>,
+) -> Result<RestoreResult> {
+    let tx = conn.transaction()?;
+
+    let superseded_by: Option<String> = tx
+        .query_row(
+            "SELECT superseded_by FROM memories WHERE id = ?1",
+            params![memory_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| anyhow::anyhow!("memory not found (hard-deleted memories can't be restored): {memory_id}"))?;
+
+    match superseded_by.as_deref() {
+        Some("forgotten") => {}
+        Some(replacement) => {
+            bail!("memory {memory_id} is superseded by {replacement}, not forgotten; it can't be restored")
+        }
+        None => bail!("memory {memory_id} is not forgotten"),
+    }
+
+    tx.execute(
+        "UPDATE memories SET superseded_by = NULL, updated_at = ?1 WHERE id = ?2",
+        params![chrono::Utc::now().to_rfc3339(), memory_id],
+    )?;
+
+    let details = serde_json::json!({
+        "restored": true,
+        "reason": reason,
+    });
+    write_audit_log(&tx, "update", memory_id, Some(&details))?;
+
+    tx.commit()?;
+
+    Ok(RestoreResult {
+        id: memory_id.to_string(),
+        restored: true,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db;
     use crate::memory::store;
     use crate::memory::types::{MemoryType, Scope};
 
     fn test_db() -> Connection {
-        db::load_sqlite_vec();
-        let conn = Connection::open_in_memory().unwrap();
-        conn.pragma_update(None, "foreign_keys", "ON").unwrap();
-        crate::db::schema::init_schema(&conn).unwrap();
-        conn
+        crate::db::open_memory_database().unwrap()
     }
 
     fn embedding_a() -> Vec<f32> {
@@ -334,4 +388,73 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("memory not found"));
     }
+
+    #[test]
+    fn test_restore_undoes_soft_delete() {
+        let mut conn = test_db();
+        let id = insert_memory(&mut conn, "Forgotten by mistake", &embedding_a());
+        forget_memory(&mut conn, &id, None, false).unwrap();
+
+        let result = restore_memory(&mut conn, &id, Some("still relevant")).unwrap();
+        assert_eq!(result.id, id);
+        assert!(result.restored);
+
+        let superseded: Option<String> = conn
+            .query_row(
+                "SELECT superseded_by FROM memories WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(superseded, None);
+
+        let details_str: String = conn
+            .query_row(
+                "SELECT details FROM memory_log WHERE memory_id = ?1 AND operation = 'update'",
+                params![id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let details: serde_json::Value = serde_json::from_str(&details_str).unwrap();
+        assert_eq!(details["restored"], true);
+        assert_eq!(details["reason"], "still relevant");
+
+        // Already active now
+        assert!(restore_memory(&mut conn, &id, None).is_err());
+    }
+
+    #[test]
+    fn test_restore_refuses_genuinely_superseded_memory() {
+        let mut conn = test_db();
+        let old_id = insert_memory(&mut conn, "Old version", &embedding_a());
+        let new_id = store::store_memory(
+            &mut conn,
+            "New version",
+            MemoryType::Semantic,
+            Scope::Global,
+            Some("default"),
+            1.0,
+            None,
+            Some(&old_id),
+            &embedding_b(),
+            0.92,
+        )
+        .unwrap()
+        .id;
+
+        let err = restore_memory(&mut conn, &old_id, None).unwrap_err();
+        assert!(err.to_string().contains("superseded by"));
+
+        let superseded: String = conn
+            .query_row(
+                "SELECT superseded_by FROM memories WHERE id = ?1",
+                params![old_id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(superseded, new_id);
+
+        let err = restore_memory(&mut conn, "nonexistent-id", None).unwrap_err();
+        assert!(err.to_string().contains("memory not found"));
+    }
 }
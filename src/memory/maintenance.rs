@@ -4,15 +4,22 @@
 //! - [`compact_episodic`]: Group old episodic memories by week into summaries
 //! - [`promote_episodic_to_semantic`]: Cluster similar episodics into semantic knowledge
 //! - [`cleanup_stale`]: Remove low-confidence, long-unaccessed memories
+//! - [`purge_superseded`]: Remove old superseded and forgotten memories for good
+//! - [`run_maintenance`]: Decay, compact, and promote, each phase locked, with timings
+//! - [`with_maintenance_lock`]: Keep overlapping compact/cleanup runs from racing
+//! - [`maintenance_due`]: Decide whether the server's scheduler should run again
 
-use anyhow::Result;
-use rusqlite::{params, Connection};
+use anyhow::{bail, Result};
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
 use serde::Serialize;
 use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
 
 use super::store::write_audit_log;
+use super::summarize::{ConcatSummarizer, Summarizer};
+use super::types::MemoryType;
 use crate::config::MaintenanceConfig;
-use crate::embedding::EmbeddingProvider;
+use crate::embedding::{render_content_template, EmbeddingProvider};
 
 // ── Result types ─────────────────────────────────────────────────────────────
 
@@ -43,6 +50,48 @@ pub struct PromoteResult {
     pub semantics_created: usize,
 }
 
+/// Combined result of a [`run_maintenance`] cycle.
+#[derive(Debug, Serialize)]
+pub struct MaintenanceReport {
+    /// Confidence decay pass.
+    pub decay: DecayResult,
+    /// Episodic compaction.
+    pub compact: CompactResult,
+    /// Episodic-to-semantic promotion.
+    pub promote: PromoteResult,
+    /// Wall-clock time spent in each phase.
+    pub timing: PhaseTiming,
+}
+
+/// Milliseconds spent in each [`run_maintenance`] phase.
+#[derive(Debug, Default, Serialize)]
+pub struct PhaseTiming {
+    /// Time in [`apply_decay`].
+    pub decay_ms: u64,
+    /// Time in [`compact_episodic`], including embedding summaries.
+    pub compact_ms: u64,
+    /// Time in [`promote_episodic_to_semantic`], including embedding promotions.
+    pub promote_ms: u64,
+}
+
+impl MaintenanceReport {
+    /// Add another database's report to this one (used for sharded storage).
+    pub fn merge(mut self, other: MaintenanceReport) -> Self {
+        for (memory_type, affected) in other.decay.affected_by_type {
+            *self.decay.affected_by_type.entry(memory_type).or_default() += affected;
+        }
+        self.compact.groups_compacted += other.compact.groups_compacted;
+        self.compact.memories_compacted += other.compact.memories_compacted;
+        self.compact.summaries_created += other.compact.summaries_created;
+        self.promote.clusters_found += other.promote.clusters_found;
+        self.promote.semantics_created += other.promote.semantics_created;
+        self.timing.decay_ms += other.timing.decay_ms;
+        self.timing.compact_ms += other.timing.compact_ms;
+        self.timing.promote_ms += other.timing.promote_ms;
+        self
+    }
+}
+
 /// Result of stale memory cleanup.
 #[derive(Debug, Serialize)]
 pub struct CleanupResult {
@@ -54,6 +103,17 @@ pub struct CleanupResult {
     pub dry_run: bool,
 }
 
+/// Result of purging superseded memories.
+#[derive(Debug, Serialize)]
+pub struct PurgeResult {
+    /// Superseded memories old enough to purge and no longer needed by a supersession chain.
+    pub matched: usize,
+    /// Number of memories actually deleted (0 in dry-run mode).
+    pub deleted: usize,
+    /// `true` if this was a dry run (no deletions performed).
+    pub dry_run: bool,
+}
+
 /// A memory identified as a candidate for cleanup.
 #[derive(Debug, Serialize)]
 pub struct CleanupCandidate {
@@ -62,7 +122,7 @@ pub struct CleanupCandidate {
     /// Memory type.
     #[serde(rename = "type")]
     pub memory_type: String,
-    /// Current confidence score (below the cleanup floor).
+    /// Current confidence score (below the cleanup floor unless session-scoped).
     pub confidence: f64,
     /// Truncated content preview (up to 80 chars).
     pub content_preview: String,
@@ -80,6 +140,7 @@ struct EpisodicRow {
     content: String,
     source_group: Option<String>,
     scope: String,
+    created_at: String,
     /// ISO year-week string like "2026-W08"
     week_key: String,
 }
@@ -108,35 +169,42 @@ fn truncate(content: &str, max_chars: usize) -> String {
 
 /// Apply confidence decay to all active memories, per-type.
 ///
-/// Episodic memories decay faster (default 0.95) than semantic/procedural/entity (0.99).
+/// Decay follows wall-clock time, not the number of runs: each memory is multiplied by
+/// its type's factor (episodic 0.95, semantic/procedural/entity 0.99 by default) once per
+/// `interval_days` elapsed since it was last decayed (or created), pro rata. Running it
+/// twice in a row therefore barely changes anything the second time.
 /// Only non-superseded memories with confidence > 0 are affected.
 pub fn apply_decay(conn: &Connection, config: &MaintenanceConfig) -> Result<DecayResult> {
-    let now = chrono::Utc::now().to_rfc3339();
+    let now = chrono::Utc::now();
+    let now_str = now.to_rfc3339();
     let mut affected_by_type = HashMap::new();
 
-    let type_factors = [
-        ("episodic", config.episodic_decay_factor),
-        ("semantic", config.semantic_decay_factor),
-        ("procedural", config.semantic_decay_factor),
-        ("entity", config.semantic_decay_factor),
-    ];
-
-    for (memory_type, factor) in &type_factors {
-        let affected = conn.execute(
-            "UPDATE memories SET confidence = confidence * ?1, updated_at = ?2 \
-             WHERE type = ?3 AND superseded_by IS NULL AND confidence > 0.0",
-            params![factor, now, memory_type],
-        )?;
+    let tx = conn.unchecked_transaction()?;
+    for memory_type in MemoryType::ALL {
+        let rows = decay_candidates(&tx, memory_type)?;
 
+        for (id, confidence, last_decayed) in &rows {
+            let multiplier = config.decay_multiplier(memory_type, elapsed_days(last_decayed, now));
+            tx.execute(
+                "UPDATE memories SET confidence = ?1, last_decayed_at = ?2, updated_at = ?2 \
+                 WHERE id = ?3",
+                params![confidence * multiplier, now_str, id],
+            )?;
+        }
+
+        let factor = config.decay_factor(memory_type);
+        let memory_type = memory_type.as_str();
+        let affected = rows.len();
         if affected > 0 {
             // Use a synthetic memory_id for decay audit entries (batch operation)
             write_audit_log(
-                conn,
+                &tx,
                 "decay",
                 &format!("batch:{memory_type}"),
                 Some(&serde_json::json!({
                     "type": memory_type,
                     "factor": factor,
+                    "interval_days": config.interval_days,
                     "affected": affected,
                 })),
             )?;
@@ -144,19 +212,82 @@ pub fn apply_decay(conn: &Connection, config: &MaintenanceConfig) -> Result<Deca
 
         affected_by_type.insert(memory_type.to_string(), affected);
     }
+    tx.commit()?;
 
     Ok(DecayResult { affected_by_type })
 }
 
+/// Count active memories, per type, that will have decayed below the cleanup floor
+/// one `interval_days` from now.
+///
+/// Uses the same time-based decay as [`apply_decay`]; memories already below the floor
+/// are not counted. Nothing is written.
+pub fn project_decay(conn: &Connection, config: &MaintenanceConfig) -> Result<HashMap<String, u64>> {
+    let floor = config.cleanup_confidence_floor;
+    let horizon = chrono::Utc::now() + chrono::Duration::days(config.interval_days.max(1) as i64);
+    let mut crossing_by_type = HashMap::new();
+
+    for memory_type in MemoryType::ALL {
+        let crossing = decay_candidates(conn, memory_type)?
+            .into_iter()
+            .filter(|(_, confidence, last_decayed)| {
+                let projected = confidence
+                    * config.decay_multiplier(memory_type, elapsed_days(last_decayed, horizon));
+                *confidence >= floor && projected < floor
+            })
+            .count();
+        crossing_by_type.insert(memory_type.as_str().to_string(), crossing as u64);
+    }
+
+    Ok(crossing_by_type)
+}
+
+/// Active memories of `memory_type` with confidence left to lose, as
+/// `(id, confidence, last decayed or created timestamp)`.
+fn decay_candidates(conn: &Connection, memory_type: MemoryType) -> Result<Vec<(String, f64, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, confidence, COALESCE(last_decayed_at, created_at) FROM memories \
+         WHERE type = ?1 AND superseded_by IS NULL AND confidence > 0.0",
+    )?;
+    let rows = stmt
+        .query_map(params![memory_type.as_str()], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// Fractional days from the RFC 3339 timestamp `since` to `until` (0 if unparseable).
+fn elapsed_days(since: &str, until: chrono::DateTime<chrono::Utc>) -> f64 {
+    chrono::DateTime::parse_from_rfc3339(since)
+        .map(|t| until.signed_duration_since(t).num_seconds() as f64 / 86_400.0)
+        .unwrap_or(0.0)
+}
+
 // ── Episodic Compaction ──────────────────────────────────────────────────────
 
 /// Compact old episodic memories by grouping them by week + source_group,
 /// concatenating their content, and creating a summary memory.
 ///
-/// Originals are superseded by the new summary.
+/// Originals are superseded by the new summary. Summaries are embedded through
+/// `content_template` (see [`render_content_template`]). Uses [`ConcatSummarizer`];
+/// see [`compact_episodic_with`] to supply another [`Summarizer`].
 pub fn compact_episodic(
     conn: &mut Connection,
     embedding_provider: &dyn EmbeddingProvider,
+    content_template: &str,
+    config: &MaintenanceConfig,
+) -> Result<CompactResult> {
+    let summarizer = ConcatSummarizer::from(config);
+    compact_episodic_with(conn, embedding_provider, &summarizer, content_template, config)
+}
+
+/// [`compact_episodic`], with each group's summary written by `summarizer`.
+pub fn compact_episodic_with(
+    conn: &mut Connection,
+    embedding_provider: &dyn EmbeddingProvider,
+    summarizer: &dyn Summarizer,
+    content_template: &str,
     config: &MaintenanceConfig,
 ) -> Result<CompactResult> {
     let cutoff = chrono::Utc::now() - chrono::Duration::days(config.compaction_age_days as i64);
@@ -165,7 +296,7 @@ pub fn compact_episodic(
     // Fetch qualifying episodic memories (scoped to drop stmt before mutable ops)
     let rows: Vec<EpisodicRow> = {
         let mut stmt = conn.prepare(
-            "SELECT id, content, source_group, scope, \
+            "SELECT id, content, source_group, scope, created_at, \
              strftime('%Y-W%W', created_at) as week_key \
              FROM memories \
              WHERE type = 'episodic' \
@@ -180,7 +311,8 @@ pub fn compact_episodic(
                     content: row.get(1)?,
                     source_group: row.get(2)?,
                     scope: row.get(3)?,
-                    week_key: row.get(4)?,
+                    created_at: row.get(4)?,
+                    week_key: row.get(5)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -205,25 +337,44 @@ pub fn compact_episodic(
             continue;
         }
 
-        // Concatenate content
-        let combined: String = members
+        // Summarize the contents (members are in created_at order)
+        let contents: Vec<String> = members
             .iter()
-            .map(|m| m.content.as_str())
-            .collect::<Vec<_>>()
-            .join("\n---\n");
-        let summary_content = truncate(&combined, 4000);
-
-        // Embed the summary
-        let embedding = embedding_provider.embed(&summary_content)?;
+            .map(|m| {
+                if config.compaction_include_timestamps {
+                    format!("[{}] {}", m.created_at, m.content)
+                } else {
+                    m.content.clone()
+                }
+            })
+            .collect();
+        let contents: Vec<&str> = contents.iter().map(String::as_str).collect();
+        let summary_content = summarizer.summarize(&contents)?;
 
         // Determine group/scope from first member
         let group = members[0].source_group.as_deref();
+        // A summary outlives the sessions it covers, so session members stay in their group
         let scope = match members[0].scope.as_str() {
-            "group" => crate::memory::types::Scope::Group,
+            "group" | "session" => crate::memory::types::Scope::Group,
             _ => crate::memory::types::Scope::Global,
         };
 
-        let metadata = serde_json::json!({"summary": true});
+        // Embed the summary
+        let embedding = embedding_provider.embed(&render_content_template(
+            content_template,
+            &summary_content,
+            MemoryType::Episodic,
+            scope,
+            group,
+        ))?;
+
+        // Record where the summary came from so it can be traced back
+        let metadata = serde_json::json!({
+            "summary": true,
+            "source_ids": members.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(),
+            "window_start": members.first().map(|m| m.created_at.as_str()),
+            "window_end": members.last().map(|m| m.created_at.as_str()),
+        });
 
         // Store the summary memory (dedup threshold set high to avoid matching)
         let store_result = super::store::store_memory(
@@ -272,10 +423,12 @@ pub fn compact_episodic(
 ///
 /// Episodic memories with cosine similarity > promotion_similarity that appear
 /// in clusters of >= promotion_threshold are distilled into a semantic memory.
-/// The episodic sources are NOT superseded (they retain event context).
+/// The episodic sources are NOT superseded (they retain event context); with
+/// `promotion_link_sources` their IDs are listed in `metadata.promoted_from_ids`.
 pub fn promote_episodic_to_semantic(
     conn: &mut Connection,
     embedding_provider: &dyn EmbeddingProvider,
+    content_template: &str,
     config: &MaintenanceConfig,
 ) -> Result<PromoteResult> {
     struct EpisodicCandidate {
@@ -296,7 +449,7 @@ pub fn promote_episodic_to_semantic(
         let collected = stmt
             .query_map([], |row| {
                 let embedding_bytes: Vec<u8> = row.get(3)?;
-                let embedding = bytes_to_embedding(&embedding_bytes);
+                let embedding = super::bytes_to_embedding(&embedding_bytes);
                 Ok(EpisodicCandidate {
                     id: row.get(0)?,
                     content: row.get(1)?,
@@ -377,9 +530,19 @@ pub fn promote_episodic_to_semantic(
             .unwrap_or(candidate);
 
         // Embed the distilled fact
-        let embedding = embedding_provider.embed(&best.content)?;
+        let embedding = embedding_provider.embed(&render_content_template(
+            content_template,
+            &best.content,
+            MemoryType::Semantic,
+            crate::memory::types::Scope::Global,
+            None,
+        ))?;
 
         // Store as semantic memory (dedup gate will catch existing similar semantics)
+        let mut metadata = serde_json::json!({"promoted_from": "episodic"});
+        if config.promotion_link_sources {
+            metadata["promoted_from_ids"] = serde_json::json!(eligible_ids);
+        }
         let store_result = super::store::store_memory(
             conn,
             &best.content,
@@ -387,10 +550,10 @@ pub fn promote_episodic_to_semantic(
             crate::memory::types::Scope::Global,
             None,
             1.0,
-            Some(&serde_json::json!({"promoted_from": "episodic"})),
+            Some(&metadata),
             None,
             &embedding,
-            config.promotion_similarity,
+            config.promotion_dedup_threshold,
         )?;
 
         if !store_result.deduplicated {
@@ -416,19 +579,13 @@ pub fn promote_episodic_to_semantic(
     Ok(result)
 }
 
-/// Convert raw bytes back to f32 embedding.
-fn bytes_to_embedding(bytes: &[u8]) -> Vec<f32> {
-    bytes
-        .chunks_exact(4)
-        .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))
-        .collect()
-}
-
 // ── Cleanup ──────────────────────────────────────────────────────────────────
 
 /// Find and optionally delete stale, low-confidence memories.
 ///
-/// Candidates: confidence < floor AND (never accessed and old, OR last accessed long ago).
+/// Candidates: confidence < floor AND (never accessed and old, OR last accessed long ago),
+/// plus session-scoped memories older than `cleanup_session_days` at any confidence.
+/// Memories with importance at or above `cleanup_importance_exempt` are never candidates.
 /// In dry_run mode, returns candidates without deleting.
 pub fn cleanup_stale(
     conn: &mut Connection,
@@ -438,30 +595,43 @@ pub fn cleanup_stale(
     let threshold =
         chrono::Utc::now() - chrono::Duration::days(config.cleanup_no_access_days as i64);
     let threshold_str = threshold.to_rfc3339();
+    let session_threshold =
+        chrono::Utc::now() - chrono::Duration::days(config.cleanup_session_days as i64);
 
     let candidates: Vec<CleanupCandidate> = {
         let mut stmt = conn.prepare(
             "SELECT id, type, confidence, content, last_accessed, created_at \
              FROM memories \
              WHERE superseded_by IS NULL \
-               AND confidence < ?1 \
+               AND importance < ?3 \
                AND ( \
-                   (last_accessed IS NULL AND created_at < ?2) \
-                   OR (last_accessed IS NOT NULL AND last_accessed < ?2) \
+                   (confidence < ?1 AND ( \
+                       (last_accessed IS NULL AND created_at < ?2) \
+                       OR (last_accessed IS NOT NULL AND last_accessed < ?2) \
+                   )) \
+                   OR (scope = 'session' AND created_at < ?4) \
                )",
         )?;
         let collected = stmt
-            .query_map(params![config.cleanup_confidence_floor, threshold_str], |row| {
-                let content: String = row.get(3)?;
-                Ok(CleanupCandidate {
-                    id: row.get(0)?,
-                    memory_type: row.get(1)?,
-                    confidence: row.get(2)?,
-                    content_preview: truncate(&content, 80),
-                    last_accessed: row.get(4)?,
-                    created_at: row.get(5)?,
-                })
-            })?
+            .query_map(
+                params![
+                    config.cleanup_confidence_floor,
+                    threshold_str,
+                    config.cleanup_importance_exempt,
+                    session_threshold.to_rfc3339()
+                ],
+                |row| {
+                    let content: String = row.get(3)?;
+                    Ok(CleanupCandidate {
+                        id: row.get(0)?,
+                        memory_type: row.get(1)?,
+                        confidence: row.get(2)?,
+                        content_preview: truncate(&content, 80),
+                        last_accessed: row.get(4)?,
+                        created_at: row.get(5)?,
+                    })
+                },
+            )?
             .collect::<Result<Vec<_>, _>>()?;
         collected
     };
@@ -476,7 +646,7 @@ pub fn cleanup_stale(
 
     let mut deleted = 0;
     for candidate in &candidates {
-        hard_delete_memory(conn, &candidate.id)?;
+        hard_delete_memory(conn, &candidate.id, "cleanup")?;
         deleted += 1;
     }
 
@@ -487,11 +657,280 @@ pub fn cleanup_stale(
     })
 }
 
+/// Hard-delete superseded (and forgotten) memories not updated for `older_than_days`.
+///
+/// A memory became superseded when its `updated_at` was last bumped, so that is the age
+/// used. A memory is kept while a younger superseded memory still names it in
+/// `superseded_by`, so chains never point at a deleted row; it becomes eligible once
+/// the rest of the chain ages out. In dry_run mode, only counts are returned.
+pub fn purge_superseded(
+    conn: &mut Connection,
+    older_than_days: u64,
+    dry_run: bool,
+) -> Result<PurgeResult> {
+    let cutoff = (chrono::Utc::now() - chrono::Duration::days(older_than_days as i64)).to_rfc3339();
+
+    let mut purge: HashSet<String> = {
+        let mut stmt = conn.prepare(
+            "SELECT id FROM memories WHERE superseded_by IS NOT NULL AND updated_at < ?1",
+        )?;
+        stmt.query_map(params![cutoff], |row| row.get(0))?
+            .collect::<Result<_, _>>()?
+    };
+    keep_chain_targets(conn, &mut purge)?;
+
+    let matched = purge.len();
+    if dry_run {
+        return Ok(PurgeResult {
+            matched,
+            deleted: 0,
+            dry_run: true,
+        });
+    }
+
+    for id in &purge {
+        hard_delete_memory(conn, id, "purge_superseded")?;
+    }
+
+    Ok(PurgeResult {
+        matched,
+        deleted: matched,
+        dry_run: false,
+    })
+}
+
+/// Drop from `purge` every memory still named in `superseded_by` by a memory that
+/// stays, so chains never point at a deleted row.
+fn keep_chain_targets(conn: &Connection, purge: &mut HashSet<String>) -> Result<()> {
+    let links: Vec<(String, String)> = {
+        let mut stmt =
+            conn.prepare("SELECT id, superseded_by FROM memories WHERE superseded_by IS NOT NULL")?;
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<_, _>>()?
+    };
+
+    // Repeat, since keeping one memory can protect the memory it points at in turn
+    loop {
+        let protected: Vec<&String> = links
+            .iter()
+            .filter(|(id, target)| !purge.contains(id) && purge.contains(target))
+            .map(|(_, target)| target)
+            .collect();
+        if protected.is_empty() {
+            return Ok(());
+        }
+        for target in protected {
+            purge.remove(target.as_str());
+        }
+    }
+}
+
+/// Hard-delete every memory whose `expires_at` has passed. Returns how many were deleted.
+///
+/// Expiries are compared as instants, so one stored with any offset is handled. Like
+/// [`purge_superseded`], an expired memory is kept while a memory that stays still
+/// names it in `superseded_by`; it is deleted once that memory is purged.
+pub fn purge_expired(conn: &mut Connection) -> Result<usize> {
+    let now = chrono::Utc::now();
+    let mut expired: HashSet<String> = {
+        let mut stmt =
+            conn.prepare("SELECT id, expires_at FROM memories WHERE expires_at IS NOT NULL")?;
+        stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|(_, expires_at)| {
+                chrono::DateTime::parse_from_rfc3339(expires_at).is_ok_and(|t| t <= now)
+            })
+            .map(|(id, _)| id)
+            .collect()
+    };
+    keep_chain_targets(conn, &mut expired)?;
+
+    for id in &expired {
+        hard_delete_memory(conn, id, "expired")?;
+    }
+    Ok(expired.len())
+}
+
+// ── Full Cycle ───────────────────────────────────────────────────────────────
+
+/// Run decay, compaction, and promotion, each under the maintenance lock.
+///
+/// The connection mutex is taken separately for each phase rather than held for the
+/// whole cycle, so other users of the connection get a turn in between. Compaction and
+/// promotion still hold it while embedding their output. Returns each phase's result
+/// and timing; shared by `loci compact`, the `run_maintenance` tool, and the scheduler.
+pub fn run_maintenance(
+    conn: &Mutex<Connection>,
+    embedding_provider: &dyn EmbeddingProvider,
+    content_template: &str,
+    config: &MaintenanceConfig,
+) -> Result<MaintenanceReport> {
+    let (decay, decay_ms) = locked_phase(conn, config, "decay", |conn| apply_decay(conn, config))?;
+    let (compact, compact_ms) = locked_phase(conn, config, "compact", |conn| {
+        compact_episodic(conn, embedding_provider, content_template, config)
+    })?;
+    let (promote, promote_ms) = locked_phase(conn, config, "promote", |conn| {
+        promote_episodic_to_semantic(conn, embedding_provider, content_template, config)
+    })?;
+
+    Ok(MaintenanceReport {
+        decay,
+        compact,
+        promote,
+        timing: PhaseTiming {
+            decay_ms,
+            compact_ms,
+            promote_ms,
+        },
+    })
+}
+
+/// Lock the connection and run one maintenance phase under the maintenance lock,
+/// returning its result and how long it took.
+fn locked_phase<T>(
+    conn: &Mutex<Connection>,
+    config: &MaintenanceConfig,
+    operation: &str,
+    f: impl FnOnce(&mut Connection) -> Result<T>,
+) -> Result<(T, u64)> {
+    let mut conn = conn.lock().map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
+    let started = std::time::Instant::now();
+    let value = with_maintenance_lock(&mut conn, config, operation, f)?;
+    Ok((value, started.elapsed().as_millis() as u64))
+}
+
+// ── Maintenance Lock ─────────────────────────────────────────────────────────
+
+/// `schema_meta` key holding the advisory maintenance lock.
+const MAINTENANCE_LOCK_KEY: &str = "maintenance_running";
+
+/// Take the advisory maintenance lock, run `f`, and release the lock (even if `f` fails).
+///
+/// Refuses to start while another run holds a lock younger than
+/// `config.lock_stale_minutes`; older locks are assumed abandoned and taken over.
+pub fn with_maintenance_lock<T>(
+    conn: &mut Connection,
+    config: &MaintenanceConfig,
+    operation: &str,
+    f: impl FnOnce(&mut Connection) -> Result<T>,
+) -> Result<T> {
+    let token = acquire_maintenance_lock(conn, config, operation)?;
+    let result = f(conn);
+    let released = release_maintenance_lock(conn, &token);
+    let value = result?;
+    released?;
+    Ok(value)
+}
+
+/// Claim the lock, returning a token identifying this holder.
+fn acquire_maintenance_lock(
+    conn: &mut Connection,
+    config: &MaintenanceConfig,
+    operation: &str,
+) -> Result<String> {
+    // IMMEDIATE so two processes can't both see the lock as free
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+    let existing: Option<String> = tx
+        .query_row(
+            "SELECT value FROM schema_meta WHERE key = ?1",
+            params![MAINTENANCE_LOCK_KEY],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    if let Some(existing) = existing {
+        let held: serde_json::Value = serde_json::from_str(&existing).unwrap_or_default();
+        let started_at = held["started_at"].as_str().unwrap_or_default();
+        let age = chrono::DateTime::parse_from_rfc3339(started_at)
+            .map(|t| chrono::Utc::now().signed_duration_since(t))
+            .ok();
+        let stale_after = chrono::Duration::minutes(config.lock_stale_minutes as i64);
+
+        match age {
+            Some(age) if age < stale_after => bail!(
+                "maintenance is already running ({} started at {started_at}); \
+                 try again later, or wait {} minutes for the lock to expire",
+                held["operation"].as_str().unwrap_or("unknown"),
+                config.lock_stale_minutes
+            ),
+            _ => tracing::warn!(lock = %existing, "taking over stale maintenance lock"),
+        }
+    }
+
+    let token = uuid::Uuid::now_v7().to_string();
+    let value = serde_json::json!({
+        "token": &token,
+        "operation": operation,
+        "pid": std::process::id(),
+        "started_at": chrono::Utc::now().to_rfc3339(),
+    });
+    tx.execute(
+        "INSERT OR REPLACE INTO schema_meta (key, value) VALUES (?1, ?2)",
+        params![MAINTENANCE_LOCK_KEY, value.to_string()],
+    )?;
+    tx.commit()?;
+
+    tracing::debug!(operation, "maintenance lock acquired");
+    Ok(token)
+}
+
+/// Release the lock if we still hold it (a takeover means someone else owns it now).
+fn release_maintenance_lock(conn: &Connection, token: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM schema_meta WHERE key = ?1 AND json_extract(value, '$.token') = ?2",
+        params![MAINTENANCE_LOCK_KEY, token],
+    )?;
+    Ok(())
+}
+
+// ── Scheduling ───────────────────────────────────────────────────────────────
+
+/// `schema_meta` key holding the RFC 3339 time of the last scheduled maintenance run.
+const LAST_RUN_KEY: &str = "maintenance_last_run";
+
+/// When scheduled maintenance last completed on this database, if ever.
+pub fn last_maintenance_run(conn: &Connection) -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM schema_meta WHERE key = ?1",
+            params![LAST_RUN_KEY],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(value
+        .and_then(|v| chrono::DateTime::parse_from_rfc3339(&v).ok())
+        .map(|t| t.with_timezone(&chrono::Utc)))
+}
+
+/// Record `at` as the last scheduled maintenance run, so restarts don't re-run early.
+pub fn record_maintenance_run(conn: &Connection, at: chrono::DateTime<chrono::Utc>) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO schema_meta (key, value) VALUES (?1, ?2)",
+        params![LAST_RUN_KEY, at.to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+/// Whether `config.interval_days` have passed since the last recorded run at `now`.
+///
+/// A database that has never been maintained is always due.
+pub fn maintenance_due(
+    conn: &Connection,
+    config: &MaintenanceConfig,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<bool> {
+    let interval = chrono::Duration::days(config.interval_days as i64);
+    Ok(last_maintenance_run(conn)?.is_none_or(|last| now.signed_duration_since(last) >= interval))
+}
+
 /// Hard delete a single memory from all tables (memories, FTS, vec).
 ///
 /// Replicates the pattern from forget.rs but without the existence check
-/// (caller already verified the row exists via the candidate query).
-fn hard_delete_memory(conn: &mut Connection, memory_id: &str) -> Result<()> {
+/// (caller already verified the row exists via the candidate query). `reason` is
+/// recorded in the audit log.
+fn hard_delete_memory(conn: &mut Connection, memory_id: &str, reason: &str) -> Result<()> {
     let tx = conn.transaction()?;
 
     // Fetch rowid, content, type for FTS cleanup
@@ -518,7 +957,7 @@ fn hard_delete_memory(conn: &mut Connection, memory_id: &str) -> Result<()> {
         &tx,
         "delete",
         memory_id,
-        Some(&serde_json::json!({"reason": "cleanup", "hard_delete": true})),
+        Some(&serde_json::json!({"reason": reason, "hard_delete": true})),
     )?;
 
     // Delete from memories (cascades entity_relations via FK)
@@ -531,16 +970,11 @@ fn hard_delete_memory(conn: &mut Connection, memory_id: &str) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db;
     use crate::memory::store;
     use crate::memory::types::{MemoryType, Scope};
 
     fn test_db() -> Connection {
-        db::load_sqlite_vec();
-        let conn = Connection::open_in_memory().unwrap();
-        conn.pragma_update(None, "foreign_keys", "ON").unwrap();
-        crate::db::schema::init_schema(&conn).unwrap();
-        conn
+        crate::db::open_memory_database().unwrap()
     }
 
     fn default_config() -> MaintenanceConfig {
@@ -559,6 +993,16 @@ mod tests {
         v
     }
 
+    /// Pretend the memory was last decayed `days_ago` days ago.
+    fn backdate_last_decay(conn: &Connection, id: &str, days_ago: i64) {
+        let then = (chrono::Utc::now() - chrono::Duration::days(days_ago)).to_rfc3339();
+        conn.execute(
+            "UPDATE memories SET last_decayed_at = ?1 WHERE id = ?2",
+            params![then, id],
+        )
+        .unwrap();
+    }
+
     fn insert_memory(
         conn: &mut Connection,
         content: &str,
@@ -629,6 +1073,9 @@ mod tests {
             1.0,
             &embedding_b(),
         );
+        // One full interval since the last decay
+        backdate_last_decay(&conn, &id_epi, config.interval_days as i64);
+        backdate_last_decay(&conn, &id_sem, config.interval_days as i64);
 
         apply_decay(&conn, &config).unwrap();
 
@@ -654,6 +1101,36 @@ mod tests {
         assert!(epi_conf < sem_conf);
     }
 
+    #[test]
+    fn test_repeated_decay_does_not_over_decay() {
+        let mut conn = test_db();
+        let config = default_config();
+
+        let id = insert_memory(
+            &mut conn,
+            "Episodic event",
+            MemoryType::Episodic,
+            Scope::Group,
+            "default",
+            1.0,
+            &embedding_a(),
+        );
+        backdate_last_decay(&conn, &id, config.interval_days as i64);
+
+        // Two back-to-back runs decay once per elapsed interval, not once per run
+        apply_decay(&conn, &config).unwrap();
+        apply_decay(&conn, &config).unwrap();
+
+        let conf: f64 = conn
+            .query_row(
+                "SELECT confidence FROM memories WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!((conf - 0.95).abs() < 0.001);
+    }
+
     #[test]
     fn test_decay_skips_superseded() {
         let mut conn = test_db();
@@ -689,6 +1166,29 @@ mod tests {
         assert!((conf - 0.8).abs() < 0.001);
     }
 
+    #[test]
+    fn test_project_decay_counts_floor_crossings() {
+        let mut conn = test_db();
+        let config = default_config();
+
+        // Floor 0.05: 0.052 * 0.95 and 0.0502 * 0.99 cross it; 0.06 * 0.99 doesn't
+        insert_memory(&mut conn, "Fading event", MemoryType::Episodic, Scope::Group, "default", 0.052, &embedding_a());
+        insert_memory(&mut conn, "Already faded event", MemoryType::Episodic, Scope::Group, "default", 0.04, &embedding_b());
+        insert_memory(&mut conn, "Fading fact", MemoryType::Semantic, Scope::Global, "default", 0.0502, &embedding_a());
+        insert_memory(&mut conn, "Steady fact", MemoryType::Semantic, Scope::Global, "default", 0.06, &embedding_b());
+
+        let crossing = project_decay(&conn, &config).unwrap();
+        assert_eq!(crossing["episodic"], 1);
+        assert_eq!(crossing["semantic"], 1);
+        assert_eq!(crossing["procedural"], 0);
+
+        // Projection is read-only
+        let below: i64 = conn
+            .query_row("SELECT COUNT(*) FROM memories WHERE confidence < 0.05", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(below, 1);
+    }
+
     #[test]
     fn test_decay_audit_log() {
         let mut conn = test_db();
@@ -790,6 +1290,143 @@ mod tests {
         assert_eq!(vec_count, 0);
     }
 
+    #[test]
+    fn test_cleanup_removes_old_session_memories() {
+        let mut conn = test_db();
+        let config = default_config();
+
+        let memory = store::NewMemory {
+            content: "Said we'd revisit the flaky test",
+            memory_type: MemoryType::Episodic,
+            scope: Scope::Session,
+            group: Some("default"),
+            confidence: 1.0,
+            importance: store::DEFAULT_IMPORTANCE,
+            metadata: None,
+            supersedes: None,
+            source_uri: None,
+            id: None,
+            session_id: Some("conv-1"),
+            expires_at: None,
+            tags: None,
+        };
+        let old = store::store_memory_with(&mut conn, &memory, &embedding_a(), &store::StoreOptions::default())
+            .unwrap()
+            .id;
+        let two_days_ago = (chrono::Utc::now() - chrono::Duration::days(2)).to_rfc3339();
+        conn.execute(
+            "UPDATE memories SET created_at = ?1 WHERE id = ?2",
+            params![two_days_ago, old],
+        )
+        .unwrap();
+        let fresh = store::NewMemory {
+            content: "Just opened the CI logs",
+            ..memory
+        };
+        store::store_memory_with(&mut conn, &fresh, &embedding_b(), &store::StoreOptions::default())
+            .unwrap();
+
+        // Full confidence doesn't protect an expired session memory
+        let result = cleanup_stale(&mut conn, &config, true).unwrap();
+        assert_eq!(result.candidates.len(), 1);
+        assert_eq!(result.candidates[0].id, old);
+    }
+
+    #[test]
+    fn test_purge_expired_deletes_only_past_expiry() {
+        let mut conn = test_db();
+        let yesterday = (chrono::Utc::now() - chrono::Duration::days(1)).to_rfc3339();
+        let tomorrow = (chrono::Utc::now() + chrono::Duration::days(1)).to_rfc3339();
+
+        let memory = store::NewMemory {
+            content: "User is debugging the parser",
+            memory_type: MemoryType::Episodic,
+            scope: Scope::Group,
+            group: Some("default"),
+            confidence: 1.0,
+            importance: store::DEFAULT_IMPORTANCE,
+            metadata: None,
+            supersedes: None,
+            source_uri: None,
+            id: None,
+            session_id: None,
+            expires_at: Some(&yesterday),
+            tags: None,
+        };
+        let expired = store::store_memory_with(&mut conn, &memory, &embedding_a(), &store::StoreOptions::default())
+            .unwrap()
+            .id;
+        let pending = store::NewMemory {
+            content: "User is reviewing the release notes",
+            expires_at: Some(&tomorrow),
+            ..memory
+        };
+        let pending = store::store_memory_with(&mut conn, &pending, &embedding_b(), &store::StoreOptions::default())
+            .unwrap()
+            .id;
+
+        assert_eq!(purge_expired(&mut conn).unwrap(), 1);
+
+        let remaining: Vec<String> = conn
+            .prepare("SELECT id FROM memories")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(remaining, vec![pending]);
+        let vec_count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM memories_vec WHERE id = ?1", params![expired], |row| row.get(0))
+            .unwrap();
+        assert_eq!(vec_count, 0);
+    }
+
+    #[test]
+    fn test_purge_expired_keeps_supersession_targets() {
+        let mut conn = test_db();
+        let yesterday = (chrono::Utc::now() - chrono::Duration::days(1)).to_rfc3339();
+        let options = store::StoreOptions::default();
+
+        let old = store::NewMemory {
+            content: "The API listens on port 8080",
+            memory_type: MemoryType::Semantic,
+            scope: Scope::Global,
+            group: Some("default"),
+            confidence: 1.0,
+            importance: store::DEFAULT_IMPORTANCE,
+            metadata: None,
+            supersedes: None,
+            source_uri: None,
+            id: None,
+            session_id: None,
+            expires_at: None,
+            tags: None,
+        };
+        let old_id = store::store_memory_with(&mut conn, &old, &embedding_b(), &options).unwrap().id;
+        let replacement = store::NewMemory {
+            content: "The API listens on port 9090 during the migration",
+            supersedes: Some(&old_id),
+            expires_at: Some(&yesterday),
+            ..old
+        };
+        let new_id = store::store_memory_with(&mut conn, &replacement, &embedding_a(), &options)
+            .unwrap()
+            .id;
+
+        // The old memory still points at the expired one, so it stays
+        assert_eq!(purge_expired(&mut conn).unwrap(), 0);
+        let exists = |conn: &Connection, id: &str| -> bool {
+            conn.query_row("SELECT COUNT(*) > 0 FROM memories WHERE id = ?1", params![id], |row| row.get(0))
+                .unwrap()
+        };
+        assert!(exists(&conn, &new_id));
+
+        // Once the superseded memory is purged, the expired one goes too
+        assert_eq!(purge_superseded(&mut conn, 0, false).unwrap().deleted, 1);
+        assert_eq!(purge_expired(&mut conn).unwrap(), 1);
+        assert!(!exists(&conn, &new_id));
+    }
+
     #[test]
     fn test_cleanup_skips_recent() {
         let mut conn = test_db();
@@ -830,6 +1467,28 @@ mod tests {
         assert_eq!(result.candidates.len(), 0);
     }
 
+    #[test]
+    fn test_cleanup_skips_important() {
+        let mut conn = test_db();
+        let config = default_config();
+
+        // Old and low confidence, but marked important
+        let id = insert_old_memory(
+            &mut conn,
+            "Rarely needed but critical",
+            MemoryType::Semantic,
+            "default",
+            0.01,
+            &embedding_a(),
+            120,
+        );
+        conn.execute("UPDATE memories SET importance = 0.9 WHERE id = ?1", params![id])
+            .unwrap();
+
+        let result = cleanup_stale(&mut conn, &config, true).unwrap();
+        assert_eq!(result.candidates.len(), 0);
+    }
+
     // ── Compaction tests ─────────────────────────────────────────────────────
 
     /// Test embedding provider that returns a fixed embedding.
@@ -867,7 +1526,7 @@ mod tests {
         }
 
         let result =
-            compact_episodic(&mut conn, &TestEmbeddingProvider, &config).unwrap();
+            compact_episodic(&mut conn, &TestEmbeddingProvider, "{content}", &config).unwrap();
 
         assert_eq!(result.groups_compacted, 1);
         assert_eq!(result.memories_compacted, 4);
@@ -884,6 +1543,56 @@ mod tests {
         assert_eq!(superseded_count, 4);
     }
 
+    /// Ignores its input and always returns the same summary.
+    struct FixedSummarizer;
+
+    impl Summarizer for FixedSummarizer {
+        fn summarize(&self, contents: &[&str]) -> Result<String> {
+            assert_eq!(contents.len(), 3);
+            Ok("The team shipped the billing migration".to_string())
+        }
+    }
+
+    #[test]
+    fn test_compact_uses_supplied_summarizer() {
+        let mut conn = test_db();
+        let mut config = default_config();
+        config.compaction_min_group_size = 3;
+
+        for i in 0..3 {
+            let mut emb = vec![0.0f32; 384];
+            emb[i + 1] = 1.0;
+            insert_old_memory(
+                &mut conn,
+                &format!("Billing migration step {i}"),
+                MemoryType::Episodic,
+                "project-s",
+                1.0,
+                &emb,
+                45,
+            );
+        }
+
+        let result = compact_episodic_with(
+            &mut conn,
+            &TestEmbeddingProvider,
+            &FixedSummarizer,
+            "{content}",
+            &config,
+        )
+        .unwrap();
+        assert_eq!(result.summaries_created, 1);
+
+        let summary: String = conn
+            .query_row(
+                "SELECT content FROM memories WHERE superseded_by IS NULL AND source_group = 'project-s'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(summary, "The team shipped the billing migration");
+    }
+
     #[test]
     fn test_compact_skips_small_groups() {
         let mut conn = test_db();
@@ -906,7 +1615,7 @@ mod tests {
         }
 
         let result =
-            compact_episodic(&mut conn, &TestEmbeddingProvider, &config).unwrap();
+            compact_episodic(&mut conn, &TestEmbeddingProvider, "{content}", &config).unwrap();
 
         assert_eq!(result.groups_compacted, 0);
         assert_eq!(result.memories_compacted, 0);
@@ -934,7 +1643,7 @@ mod tests {
             })
             .collect();
 
-        compact_episodic(&mut conn, &TestEmbeddingProvider, &config).unwrap();
+        compact_episodic(&mut conn, &TestEmbeddingProvider, "{content}", &config).unwrap();
 
         // All originals should have superseded_by set to the same summary ID
         let superseded_bys: Vec<String> = ids
@@ -962,22 +1671,109 @@ mod tests {
             .unwrap();
         let metadata: serde_json::Value = serde_json::from_str(&metadata_str).unwrap();
         assert_eq!(metadata["summary"], true);
+
+        // ...and record its sources and time window
+        let mut source_ids: Vec<String> =
+            serde_json::from_value(metadata["source_ids"].clone()).unwrap();
+        source_ids.sort();
+        let mut expected = ids.clone();
+        expected.sort();
+        assert_eq!(source_ids, expected);
+        assert!(metadata["window_start"].as_str().unwrap() <= metadata["window_end"].as_str().unwrap());
     }
 
-    // ── Promotion tests ──────────────────────────────────────────────────────
+    #[test]
+    fn test_compact_custom_separator_and_timestamps() {
+        let mut conn = test_db();
+        let mut config = default_config();
+        config.compaction_min_group_size = 2;
+        config.compaction_separator = " | ".into();
+        config.compaction_include_timestamps = true;
+
+        for i in 0..2 {
+            let mut emb = vec![0.0f32; 384];
+            emb[i + 1] = 1.0;
+            insert_old_memory(
+                &mut conn,
+                &format!("Dated event {i}"),
+                MemoryType::Episodic,
+                "project-d",
+                1.0,
+                &emb,
+                45,
+            );
+        }
+
+        compact_episodic(&mut conn, &TestEmbeddingProvider, "{content}", &config).unwrap();
+
+        let content: String = conn
+            .query_row(
+                "SELECT content FROM memories WHERE json_extract(metadata, '$.summary') = 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(content.starts_with('['));
+        assert!(content.contains("] Dated event"));
+        assert!(content.contains(" | ["));
+    }
+
+    /// Records every text it is asked to embed.
+    struct RecordingEmbeddingProvider(std::sync::Mutex<Vec<String>>);
+
+    impl EmbeddingProvider for RecordingEmbeddingProvider {
+        fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            self.0.lock().unwrap().push(text.to_string());
+            TestEmbeddingProvider.embed(text)
+        }
+    }
 
     #[test]
-    fn test_promotion_creates_semantic() {
+    fn test_compact_embeds_through_content_template() {
         let mut conn = test_db();
         let mut config = default_config();
-        config.promotion_threshold = 3;
-        config.promotion_similarity = 0.88;
+        config.compaction_min_group_size = 2;
+
+        for i in 0..2 {
+            let mut emb = vec![0.0f32; 384];
+            emb[i + 1] = 1.0;
+            insert_old_memory(
+                &mut conn,
+                &format!("Templated event {i}"),
+                MemoryType::Episodic,
+                "project-t",
+                1.0,
+                &emb,
+                45,
+            );
+        }
+
+        let provider = RecordingEmbeddingProvider(std::sync::Mutex::new(Vec::new()));
+        compact_episodic(&mut conn, &provider, "{type} in {group}: {content}", &config).unwrap();
+
+        let embedded = provider.0.lock().unwrap();
+        assert_eq!(embedded.len(), 1);
+        assert!(embedded[0].starts_with("episodic in project-t: Templated event"));
+
+        // The stored summary is the plain content
+        let content: String = conn
+            .query_row(
+                "SELECT content FROM memories WHERE json_extract(metadata, '$.summary') = 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(content.starts_with("Templated event"));
+    }
+
+    // ── Promotion tests ──────────────────────────────────────────────────────
 
-        // Insert 3 episodic memories with embeddings that are:
-        // - similar enough for promotion (pairwise cosine sim > 0.88)
-        // - different enough to avoid dedup (pairwise cosine sim < 0.99)
+    /// Three embeddings that are:
+    /// - similar enough for promotion (pairwise cosine sim > 0.88)
+    /// - different enough to avoid dedup (pairwise cosine sim < 0.99)
+    fn promotion_cluster_embeddings() -> Vec<Vec<f32>> {
         // Spread perturbations across different secondary dimensions to avoid dedup
-        let embeddings: Vec<Vec<f32>> = vec![
+        vec![
             {
                 let mut v = vec![0.0f32; 384];
                 v[0] = 1.0;
@@ -999,8 +1795,18 @@ mod tests {
                 v.iter_mut().for_each(|x| *x /= n);
                 v
             },
-        ];
-        for (i, emb) in embeddings.iter().enumerate() {
+        ]
+    }
+
+    #[test]
+    fn test_promotion_creates_semantic() {
+        let mut conn = test_db();
+        let mut config = default_config();
+        config.promotion_threshold = 3;
+        config.promotion_similarity = 0.88;
+        config.promotion_dedup_threshold = 0.92;
+
+        for (i, emb) in promotion_cluster_embeddings().iter().enumerate() {
             insert_memory(
                 &mut conn,
                 &format!("Similar episodic fact #{i}"),
@@ -1013,7 +1819,7 @@ mod tests {
         }
 
         let result =
-            promote_episodic_to_semantic(&mut conn, &TestEmbeddingProvider, &config).unwrap();
+            promote_episodic_to_semantic(&mut conn, &TestEmbeddingProvider, "{content}", &config).unwrap();
 
         assert_eq!(result.clusters_found, 1);
         assert_eq!(result.semantics_created, 1);
@@ -1037,6 +1843,107 @@ mod tests {
             )
             .unwrap();
         assert_eq!(epi_superseded, 0);
+
+        // Sources are only marked, not listed, unless linking is enabled
+        let metadata: String = conn
+            .query_row("SELECT metadata FROM memories WHERE type = 'semantic'", [], |row| row.get(0))
+            .unwrap();
+        let metadata: serde_json::Value = serde_json::from_str(&metadata).unwrap();
+        assert_eq!(metadata["promoted_from"], "episodic");
+        assert!(metadata.get("promoted_from_ids").is_none());
+    }
+
+    #[test]
+    fn test_promotion_dedup_threshold_is_independent() {
+        // TestEmbeddingProvider embeds the 24-char cluster content as a unit vector on dim 24;
+        // this existing semantic memory sits at cosine 0.8 from it
+        let mut existing = vec![0.0f32; 384];
+        existing[24] = 0.8;
+        existing[200] = 0.6;
+
+        for (dedup_threshold, created) in [(0.75, 0), (0.88, 1)] {
+            let mut conn = test_db();
+            insert_memory(
+                &mut conn,
+                "An existing semantic fact",
+                MemoryType::Semantic,
+                Scope::Global,
+                "default",
+                1.0,
+                &existing,
+            );
+            for (i, emb) in promotion_cluster_embeddings().iter().enumerate() {
+                insert_memory(
+                    &mut conn,
+                    &format!("Similar episodic fact #{i}"),
+                    MemoryType::Episodic,
+                    Scope::Group,
+                    "default",
+                    1.0,
+                    emb,
+                );
+            }
+
+            // Clustering is unchanged; only the store step's dedup threshold differs
+            let config = MaintenanceConfig {
+                promotion_similarity: 0.88,
+                promotion_dedup_threshold: dedup_threshold,
+                ..default_config()
+            };
+            let result =
+                promote_episodic_to_semantic(&mut conn, &TestEmbeddingProvider, "{content}", &config)
+                    .unwrap();
+            assert_eq!(result.clusters_found, 1);
+            assert_eq!(result.semantics_created, created, "dedup threshold {dedup_threshold}");
+        }
+    }
+
+    #[test]
+    fn test_promotion_links_sources() {
+        let mut conn = test_db();
+        let config = MaintenanceConfig {
+            promotion_link_sources: true,
+            ..default_config()
+        };
+
+        let mut source_ids: Vec<String> = promotion_cluster_embeddings()
+            .iter()
+            .enumerate()
+            .map(|(i, emb)| {
+                insert_memory(
+                    &mut conn,
+                    &format!("Similar episodic fact #{i}"),
+                    MemoryType::Episodic,
+                    Scope::Group,
+                    "default",
+                    1.0,
+                    emb,
+                )
+            })
+            .collect();
+
+        let result =
+            promote_episodic_to_semantic(&mut conn, &TestEmbeddingProvider, "{content}", &config).unwrap();
+        assert_eq!(result.semantics_created, 1);
+
+        let metadata: String = conn
+            .query_row("SELECT metadata FROM memories WHERE type = 'semantic'", [], |row| row.get(0))
+            .unwrap();
+        let metadata: serde_json::Value = serde_json::from_str(&metadata).unwrap();
+        let mut linked: Vec<String> = serde_json::from_value(metadata["promoted_from_ids"].clone()).unwrap();
+        linked.sort();
+        source_ids.sort();
+        assert_eq!(linked, source_ids);
+
+        // Linking doesn't retire the sources
+        let active: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM memories WHERE type = 'episodic' AND superseded_by IS NULL",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(active, 3);
     }
 
     #[test]
@@ -1064,7 +1971,7 @@ mod tests {
         }
 
         let result =
-            promote_episodic_to_semantic(&mut conn, &TestEmbeddingProvider, &config).unwrap();
+            promote_episodic_to_semantic(&mut conn, &TestEmbeddingProvider, "{content}", &config).unwrap();
 
         assert_eq!(result.clusters_found, 0);
         assert_eq!(result.semantics_created, 0);
@@ -1076,6 +1983,7 @@ mod tests {
         let mut config = default_config();
         config.promotion_threshold = 3;
         config.promotion_similarity = 0.88;
+        config.promotion_dedup_threshold = 0.88;
 
         // Insert 4 episodic memories: similar enough for promotion, different enough to avoid dedup
         // Each uses a different secondary dimension to avoid pairwise dedup
@@ -1103,9 +2011,187 @@ mod tests {
 
         // Run promotion — should create exactly 1 semantic (not multiple for overlapping clusters)
         let result =
-            promote_episodic_to_semantic(&mut conn, &TestEmbeddingProvider, &config).unwrap();
+            promote_episodic_to_semantic(&mut conn, &TestEmbeddingProvider, "{content}", &config).unwrap();
 
         assert_eq!(result.clusters_found, 1);
         assert_eq!(result.semantics_created, 1);
     }
+
+    #[test]
+    fn test_run_maintenance_reports_every_phase() {
+        let mut conn = test_db();
+        let mut config = default_config();
+        config.compaction_min_group_size = 3;
+
+        for i in 0..4 {
+            let mut emb = vec![0.0f32; 384];
+            emb[i + 1] = 1.0;
+            insert_old_memory(
+                &mut conn,
+                &format!("Episodic event {i} from the past"),
+                MemoryType::Episodic,
+                "project-a",
+                1.0,
+                &emb,
+                45,
+            );
+        }
+
+        let conn = Mutex::new(conn);
+        let report =
+            run_maintenance(&conn, &TestEmbeddingProvider, "{content}", &config).unwrap();
+        assert_eq!(report.decay.affected_by_type.get("episodic"), Some(&4));
+        assert_eq!(report.compact.summaries_created, 1);
+        assert_eq!(report.compact.memories_compacted, 4);
+
+        // The lock is released for the next run
+        let mut conn = conn.into_inner().unwrap();
+        assert!(with_maintenance_lock(&mut conn, &config, "compact", |_| Ok(())).is_ok());
+    }
+
+    #[test]
+    fn test_maintenance_lock_blocks_overlapping_runs() {
+        let mut conn = test_db();
+        let config = default_config();
+
+        let token = acquire_maintenance_lock(&mut conn, &config, "compact").unwrap();
+        let err = with_maintenance_lock(&mut conn, &config, "cleanup", |_| Ok(()))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("already running"), "got: {err}");
+        assert!(err.contains("compact"));
+
+        release_maintenance_lock(&conn, &token).unwrap();
+        assert!(with_maintenance_lock(&mut conn, &config, "cleanup", |_| Ok(())).is_ok());
+    }
+
+    #[test]
+    fn test_maintenance_lock_released_on_error() {
+        let mut conn = test_db();
+        let config = default_config();
+
+        let result: Result<()> =
+            with_maintenance_lock(&mut conn, &config, "compact", |_| bail!("boom"));
+        assert!(result.is_err());
+        assert!(with_maintenance_lock(&mut conn, &config, "compact", |_| Ok(())).is_ok());
+    }
+
+    #[test]
+    fn test_stale_maintenance_lock_is_taken_over() {
+        let mut conn = test_db();
+        let config = default_config();
+
+        let started = (chrono::Utc::now() - chrono::Duration::hours(2)).to_rfc3339();
+        let stale = serde_json::json!({"token": "old", "operation": "compact", "started_at": started});
+        conn.execute(
+            "INSERT INTO schema_meta (key, value) VALUES ('maintenance_running', ?1)",
+            params![stale.to_string()],
+        )
+        .unwrap();
+
+        assert!(with_maintenance_lock(&mut conn, &config, "cleanup", |_| Ok(())).is_ok());
+        let remaining: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM schema_meta WHERE key = 'maintenance_running'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(remaining, 0);
+    }
+
+    #[test]
+    fn test_scheduler_honors_stored_last_run() {
+        let conn = test_db();
+        let config = MaintenanceConfig {
+            interval_days: 7,
+            ..default_config()
+        };
+        let now = chrono::Utc::now();
+
+        // Never maintained: due immediately
+        assert!(maintenance_due(&conn, &config, now).unwrap());
+
+        record_maintenance_run(&conn, now - chrono::Duration::days(3)).unwrap();
+        assert!(!maintenance_due(&conn, &config, now).unwrap());
+
+        record_maintenance_run(&conn, now - chrono::Duration::days(7)).unwrap();
+        assert!(maintenance_due(&conn, &config, now).unwrap());
+    }
+
+    /// Mark `id` as superseded by `by`, `days_ago` days ago.
+    fn supersede(conn: &Connection, id: &str, by: &str, days_ago: i64) {
+        let then = (chrono::Utc::now() - chrono::Duration::days(days_ago)).to_rfc3339();
+        conn.execute(
+            "UPDATE memories SET superseded_by = ?1, updated_at = ?2 WHERE id = ?3",
+            params![by, then, id],
+        )
+        .unwrap();
+    }
+
+    fn memory_exists(conn: &Connection, id: &str) -> bool {
+        conn.query_row(
+            "SELECT COUNT(*) > 0 FROM memories WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_purge_superseded_respects_age() {
+        let mut conn = test_db();
+        let current = insert_memory(&mut conn, "Current version", MemoryType::Semantic, Scope::Global, "default", 1.0, &embedding_a());
+        let old = insert_memory(&mut conn, "Old version", MemoryType::Semantic, Scope::Global, "default", 1.0, &embedding_b());
+        let recent = insert_memory(&mut conn, "Recently forgotten", MemoryType::Episodic, Scope::Group, "default", 1.0, &embedding_a());
+        supersede(&conn, &old, &current, 60);
+        supersede(&conn, &recent, "forgotten", 5);
+
+        let result = purge_superseded(&mut conn, 30, false).unwrap();
+        assert_eq!(result.matched, 1);
+        assert_eq!(result.deleted, 1);
+        assert!(!memory_exists(&conn, &old));
+        assert!(memory_exists(&conn, &recent));
+        assert!(memory_exists(&conn, &current));
+
+        let vec_rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM memories_vec WHERE id = ?1", params![old], |row| row.get(0))
+            .unwrap();
+        assert_eq!(vec_rows, 0);
+    }
+
+    #[test]
+    fn test_purge_superseded_dry_run_only_counts() {
+        let mut conn = test_db();
+        let current = insert_memory(&mut conn, "Current version", MemoryType::Semantic, Scope::Global, "default", 1.0, &embedding_a());
+        let old = insert_memory(&mut conn, "Old version", MemoryType::Semantic, Scope::Global, "default", 1.0, &embedding_b());
+        supersede(&conn, &old, &current, 60);
+
+        let result = purge_superseded(&mut conn, 30, true).unwrap();
+        assert!(result.dry_run);
+        assert_eq!(result.matched, 1);
+        assert_eq!(result.deleted, 0);
+        assert!(memory_exists(&conn, &old));
+    }
+
+    #[test]
+    fn test_purge_superseded_keeps_targets_of_remaining_chain() {
+        let mut conn = test_db();
+        let v3 = insert_memory(&mut conn, "Version three", MemoryType::Semantic, Scope::Global, "default", 1.0, &embedding_a());
+        let v2 = insert_memory(&mut conn, "Version two", MemoryType::Semantic, Scope::Global, "default", 1.0, &embedding_b());
+        let v1 = insert_memory(&mut conn, "Version one", MemoryType::Procedural, Scope::Global, "default", 1.0, &embedding_a());
+        // v2 is old enough, but v1 (too young to purge) still points at it
+        supersede(&conn, &v2, &v3, 60);
+        supersede(&conn, &v1, &v2, 5);
+
+        let result = purge_superseded(&mut conn, 30, false).unwrap();
+        assert_eq!(result.deleted, 0);
+        assert!(memory_exists(&conn, &v2));
+
+        // Once v1 ages out, the whole old chain goes
+        supersede(&conn, &v1, &v2, 40);
+        let result = purge_superseded(&mut conn, 30, false).unwrap();
+        assert_eq!(result.deleted, 2);
+        assert!(memory_exists(&conn, &v3));
+    }
 }
@@ -1,15 +1,24 @@
 //! Core memory engine — storage, search, relations, and maintenance.
 //!
-//! This module contains the write path ([`store`]), read path ([`search`]),
+//! This module contains the write path ([`store`]), read path ([`search`], with
+//! [`recall`] adding embedding and multi-database search on top),
 //! entity graph ([`relations`]), deletion ([`forget`]), statistics ([`stats`]),
-//! and lifecycle management ([`maintenance`]). Type definitions live in [`types`].
+//! lifecycle management ([`maintenance`], with pluggable compaction summaries in
+//! [`summarize`]), supersession repair ([`relink`]), type changes ([`reclassify`]),
+//! and whole-store export and import ([`export`]).
+//! Type definitions live in [`types`].
 
+pub mod export;
 pub mod forget;
 pub mod maintenance;
+pub mod recall;
+pub mod reclassify;
 pub mod relations;
+pub mod relink;
 pub mod search;
 pub mod stats;
 pub mod store;
+pub mod summarize;
 pub mod types;
 
 /// Convert an f32 embedding slice to raw bytes for sqlite-vec.
@@ -22,6 +31,27 @@ pub fn embedding_to_bytes(embedding: &[f32]) -> &[u8] {
     }
 }
 
+/// Convert raw sqlite-vec bytes back to an f32 embedding.
+pub fn bytes_to_embedding(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))
+        .collect()
+}
+
+/// SHA-256 (hex) of content with whitespace runs collapsed and ends trimmed.
+///
+/// Stored as `memories.content_hash` so exact repeats can be caught without embedding.
+pub fn content_hash(content: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    let normalized = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    Sha256::digest(normalized.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
 /// Convert a cosine similarity threshold to L2 distance threshold.
 ///
 /// sqlite-vec defaults to L2 distance. For L2-normalized vectors:
@@ -29,3 +59,26 @@ pub fn embedding_to_bytes(embedding: &[f32]) -> &[u8] {
 pub fn cosine_threshold_to_l2(cosine_threshold: f64) -> f64 {
     (2.0 * (1.0 - cosine_threshold)).sqrt()
 }
+
+/// Convert an L2 distance between normalized vectors back to cosine similarity.
+///
+/// Inverse of [`cosine_threshold_to_l2`]: `cosine_similarity = 1 - L2_dist² / 2`.
+/// Use this wherever a distance is reported to users, who think in cosine terms.
+pub fn l2_to_cosine(l2_distance: f64) -> f64 {
+    1.0 - (l2_distance * l2_distance) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_l2_cosine_round_trip() {
+        for cosine in [1.0, 0.92, 0.88, 0.5, 0.0, -1.0] {
+            let back = l2_to_cosine(cosine_threshold_to_l2(cosine));
+            assert!((back - cosine).abs() < 1e-12, "{cosine} -> {back}");
+        }
+        assert_eq!(l2_to_cosine(0.0), 1.0);
+        assert!((l2_to_cosine(2.0_f64.sqrt())).abs() < 1e-12);
+    }
+}
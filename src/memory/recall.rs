@@ -0,0 +1,164 @@
+//! Typed recall — embedding plus search across every database a caller can see.
+//!
+//! [`recall`] is the library entry point behind the `recall_memory` tool's query mode:
+//! it renders and embeds the query, searches the group's shard(s) and the main file
+//! with [`recall_by_query`], and merges the results, returning a [`RecallResponse`]
+//! rather than serialized JSON. [`embed_query`] and [`search_databases`] are its two
+//! halves, for callers that time or reuse them separately.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::config::LociConfig;
+use crate::db::shard::Databases;
+use crate::embedding::EmbeddingProvider;
+use crate::memory::search::{
+    log_recall_query, merge_query_responses, recall_by_query, update_access, RecallResponse,
+    SearchConfig, SearchFilter, SearchMode,
+};
+
+/// A query embedding and the search mode it supports.
+#[derive(Debug, Clone)]
+pub struct QueryEmbedding {
+    /// The embedded query; empty in keyword mode.
+    pub embedding: Vec<f32>,
+    /// The mode to search with — keyword if embedding failed and fallback is enabled.
+    pub mode: SearchMode,
+    /// Set when embedding failed and the search fell back to keyword mode.
+    pub degraded: bool,
+}
+
+/// Embed `query` through `embedding.query_template` for `mode`.
+///
+/// Keyword mode skips embedding. If embedding fails and `retrieval.keyword_fallback`
+/// is on, returns keyword mode flagged `degraded` instead of an error.
+pub async fn embed_query(
+    embedding: Arc<dyn EmbeddingProvider>,
+    config: &LociConfig,
+    query: &str,
+    group: &str,
+    mode: SearchMode,
+) -> Result<QueryEmbedding> {
+    if mode == SearchMode::Keyword {
+        return Ok(QueryEmbedding {
+            embedding: Vec::new(),
+            mode,
+            degraded: false,
+        });
+    }
+
+    let text = crate::embedding::render_query_template(&config.embedding.query_template, query, group);
+    match embedding.embed_async(text).await {
+        Ok(embedding) => Ok(QueryEmbedding {
+            embedding,
+            mode,
+            degraded: false,
+        }),
+        Err(e) if config.retrieval.keyword_fallback => {
+            tracing::warn!(error = %e, "recall: embedding failed, falling back to keyword search");
+            Ok(QueryEmbedding {
+                embedding: Vec::new(),
+                mode: SearchMode::Keyword,
+                degraded: true,
+            })
+        }
+        Err(e) => Err(anyhow::anyhow!("embedding failed: {e}")),
+    }
+}
+
+/// Search every database visible to `filter` and merge the results.
+///
+/// Consults the shards of `filter.groups` (or `filter.group`) plus the main file. With
+/// `search_config.log_queries`, the merged query is logged once to the main database
+/// rather than once per file.
+///
+/// With more than one file, each returns its top `offset + max_results` unbudgeted;
+/// the offset and budget apply to the merged list. Searches run on pooled read
+/// connections (see [`DbHandle::read`](crate::db::shard::DbHandle::read)), so access is
+/// recorded afterwards on each file's write connection, only for the page returned.
+pub async fn search_databases(
+    dbs: Arc<Databases>,
+    query: String,
+    query_embedding: Vec<f32>,
+    filter: SearchFilter,
+    search_config: SearchConfig,
+) -> Result<RecallResponse> {
+    tokio::task::spawn_blocking(move || {
+        let handles = match filter.groups {
+            Some(ref groups) => dbs.for_groups(groups)?,
+            None => dbs.for_group(&filter.group)?,
+        };
+        let per_db_config = if handles.len() > 1 {
+            SearchConfig {
+                log_queries: false,
+                track_access: false,
+                offset: 0,
+                max_results: search_config.max_results + search_config.offset,
+                token_budget: usize::MAX,
+                ..search_config.clone()
+            }
+        } else {
+            SearchConfig {
+                log_queries: false,
+                track_access: false,
+                ..search_config.clone()
+            }
+        };
+        let mut responses = Vec::new();
+        for handle in &handles {
+            let conn = handle.read()?;
+            responses.push(recall_by_query(
+                &conn,
+                &query_embedding,
+                &query,
+                &filter,
+                &per_db_config,
+            )?);
+        }
+        let merged = merge_query_responses(responses, &search_config);
+        if search_config.track_access {
+            let ids: Vec<&str> = merged.results.iter().map(|r| r.id.as_str()).collect();
+            for handle in &handles {
+                let conn = handle
+                    .conn
+                    .lock()
+                    .map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
+                update_access(&conn, &ids, search_config.reinforce_amount)?;
+            }
+        }
+        // The main database is always the last handle
+        if search_config.log_queries
+            && let Some(main) = handles.last()
+            && let Ok(conn) = main.conn.lock()
+        {
+            log_recall_query(&conn, &query, &filter, search_config.mode, merged.results.len());
+        }
+        Ok(merged)
+    })
+    .await
+    .map_err(|e| anyhow::anyhow!("search task failed: {e}"))?
+}
+
+/// Embed `query` and search every database visible to `filter`.
+///
+/// `search_config.mode` picks the retrievers; the response is `degraded` if it had to
+/// fall back to keyword search (see [`embed_query`]).
+pub async fn recall(
+    dbs: Arc<Databases>,
+    embedding: Arc<dyn EmbeddingProvider>,
+    config: &LociConfig,
+    query: &str,
+    filter: SearchFilter,
+    search_config: SearchConfig,
+) -> Result<RecallResponse> {
+    let embedded = embed_query(embedding, config, query, &filter.group, search_config.mode).await?;
+    let search_config = SearchConfig {
+        mode: embedded.mode,
+        ..search_config
+    };
+    let mut response =
+        search_databases(dbs, query.to_string(), embedded.embedding, filter, search_config).await?;
+    response.degraded = embedded.degraded;
+    Ok(response)
+}
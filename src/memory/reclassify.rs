@@ -0,0 +1,227 @@
+//! Changing a stored memory's type.
+//!
+//! [`reclassify_memory`] moves a memory to another type in place — e.g. promoting an
+//! episodic note that turned out to be a durable fact — without re-storing it. The new
+//! type's decay factor and dedup grouping apply from then on.
+
+use anyhow::{bail, Result};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use super::store::write_audit_log;
+use super::types::{MemoryType, Scope};
+
+/// Result returned from a reclassify operation.
+#[derive(Debug, Serialize)]
+pub struct ReclassifyResult {
+    /// ID of the reclassified memory.
+    pub id: String,
+    /// Type before the change.
+    pub previous_type: String,
+    /// Type after the change.
+    #[serde(rename = "type")]
+    pub memory_type: String,
+    /// Scope after the change.
+    pub scope: String,
+    /// `true` if the scope followed the type to its new default.
+    pub scope_changed: bool,
+}
+
+/// Change an active memory's type, keeping its FTS row in sync.
+///
+/// If the memory still has the old type's default scope, it moves to the new type's
+/// default scope too, unless `keep_scope` is set (sharded storage, where scope decides
+/// which file the memory lives in). Reclassifying to the current type is a no-op.
+/// Writes an `update` audit entry.
+pub fn reclassify_memory(
+    conn: &mut Connection,
+    memory_id: &str,
+    new_type: MemoryType,
+    keep_scope: bool,
+) -> Result<ReclassifyResult> {
+    let tx = conn.transaction()?;
+
+    let (rowid, content, old_type, old_scope, superseded_by): (
+        i64,
+        String,
+        String,
+        String,
+        Option<String>,
+    ) = tx
+        .query_row(
+            "SELECT rowid, content, type, scope, superseded_by FROM memories WHERE id = ?1",
+            params![memory_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => {
+                anyhow::anyhow!("memory not found: {memory_id}")
+            }
+            other => anyhow::anyhow!("database error: {other}"),
+        })?;
+    if let Some(superseded_by) = superseded_by {
+        bail!("memory {memory_id} is superseded by {superseded_by}; only active memories can be reclassified");
+    }
+
+    let previous: MemoryType = old_type.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+    if previous == new_type {
+        return Ok(ReclassifyResult {
+            id: memory_id.to_string(),
+            previous_type: old_type.clone(),
+            memory_type: old_type,
+            scope: old_scope,
+            scope_changed: false,
+        });
+    }
+
+    let scope: Scope = old_scope.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+    // A session-scoped memory stays in its session whatever its type
+    let new_scope = if !keep_scope && scope == previous.default_scope(false) {
+        new_type.default_scope(false)
+    } else {
+        scope
+    };
+
+    tx.execute(
+        "UPDATE memories SET type = ?1, scope = ?2, updated_at = ?3 WHERE id = ?4",
+        params![
+            new_type.as_str(),
+            new_scope.as_str(),
+            chrono::Utc::now().to_rfc3339(),
+            memory_id
+        ],
+    )?;
+
+    // External-content FTS rows are replaced, not updated
+    tx.execute(
+        "INSERT INTO memories_fts(memories_fts, rowid, content, id, type) VALUES('delete', ?1, ?2, ?3, ?4)",
+        params![rowid, content, memory_id, old_type],
+    )?;
+    tx.execute(
+        "INSERT INTO memories_fts (rowid, content, id, type) VALUES (?1, ?2, ?3, ?4)",
+        params![rowid, content, memory_id, new_type.as_str()],
+    )?;
+
+    write_audit_log(
+        &tx,
+        "update",
+        memory_id,
+        Some(&serde_json::json!({
+            "action": "reclassify",
+            "previous_type": old_type,
+            "type": new_type.as_str(),
+            "previous_scope": old_scope,
+            "scope": new_scope.as_str(),
+        })),
+    )?;
+
+    tx.commit()?;
+
+    Ok(ReclassifyResult {
+        id: memory_id.to_string(),
+        previous_type: old_type,
+        memory_type: new_type.as_str().to_string(),
+        scope: new_scope.as_str().to_string(),
+        scope_changed: new_scope != scope,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::store;
+
+    fn test_db() -> Connection {
+        crate::db::open_memory_database().unwrap()
+    }
+
+    fn embedding_a() -> Vec<f32> {
+        let mut v = vec![0.0f32; 384];
+        v[0] = 1.0;
+        v
+    }
+
+    fn insert_episodic(conn: &mut Connection, content: &str, scope: Scope) -> String {
+        store::store_memory(
+            conn,
+            content,
+            MemoryType::Episodic,
+            scope,
+            Some("default"),
+            1.0,
+            None,
+            None,
+            &embedding_a(),
+            0.92,
+        )
+        .unwrap()
+        .id
+    }
+
+    #[test]
+    fn test_reclassify_episodic_to_semantic() {
+        let mut conn = test_db();
+        let id = insert_episodic(&mut conn, "The staging cluster runs on ARM", Scope::Group);
+
+        let result = reclassify_memory(&mut conn, &id, MemoryType::Semantic, false).unwrap();
+        assert_eq!(result.previous_type, "episodic");
+        assert_eq!(result.memory_type, "semantic");
+        // Group was the episodic default, so it follows the type to global
+        assert_eq!(result.scope, "global");
+        assert!(result.scope_changed);
+
+        let (memory_type, scope): (String, String) = conn
+            .query_row("SELECT type, scope FROM memories WHERE id = ?1", params![id], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((memory_type.as_str(), scope.as_str()), ("semantic", "global"));
+
+        let fts_type: String = conn
+            .query_row(
+                "SELECT type FROM memories_fts WHERE memories_fts MATCH 'staging'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(fts_type, "semantic");
+
+        let details: String = conn
+            .query_row(
+                "SELECT details FROM memory_log WHERE memory_id = ?1 AND operation = 'update'",
+                params![id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let details: serde_json::Value = serde_json::from_str(&details).unwrap();
+        assert_eq!(details["action"], "reclassify");
+        assert_eq!(details["previous_type"], "episodic");
+    }
+
+    #[test]
+    fn test_reclassify_keeps_explicit_scope() {
+        let mut conn = test_db();
+        // Global is not the episodic default, so the caller chose it deliberately
+        let id = insert_episodic(&mut conn, "Org-wide outage on Monday", Scope::Global);
+        let result = reclassify_memory(&mut conn, &id, MemoryType::Procedural, false).unwrap();
+        assert_eq!(result.scope, "global");
+        assert!(!result.scope_changed);
+
+        let id = insert_episodic(&mut conn, "Deploy froze at step three", Scope::Group);
+        let result = reclassify_memory(&mut conn, &id, MemoryType::Semantic, true).unwrap();
+        assert_eq!(result.scope, "group");
+        assert!(!result.scope_changed);
+    }
+
+    #[test]
+    fn test_reclassify_rejects_missing_and_superseded() {
+        let mut conn = test_db();
+        assert!(reclassify_memory(&mut conn, "missing", MemoryType::Semantic, false).is_err());
+
+        let id = insert_episodic(&mut conn, "Old note", Scope::Group);
+        conn.execute("UPDATE memories SET superseded_by = 'forgotten' WHERE id = ?1", params![id])
+            .unwrap();
+        let err = reclassify_memory(&mut conn, &id, MemoryType::Semantic, false).unwrap_err();
+        assert!(err.to_string().contains("superseded"));
+    }
+}
@@ -1,19 +1,191 @@
 //! Entity relationship storage and deduplication.
 //!
 //! Stores directed (subject, predicate, object) triples between entity-type memories,
-//! with automatic deduplication on the full triple.
+//! with automatic deduplication on the full triple. Optional [`RelationsConfig`] rules
+//! restrict predicates to an allowlist, cap single-valued predicates at one object
+//! per subject, and deduplicate against the inverse edge of configured predicate pairs.
+//! [`traverse_relations`] walks the resulting graph breadth-first.
 
 use anyhow::{bail, Result};
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
+
+use crate::config::RelationsConfig;
+use crate::memory::search::{truncate_preview, RelationTarget};
 
 /// Result returned from a store_relation operation.
 #[derive(Debug, Serialize)]
 pub struct StoreRelationResult {
     /// UUID of the created (or existing) relation.
     pub id: String,
-    /// `true` if this exact (subject, predicate, object) triple already existed.
+    /// `true` if this exact (subject, predicate, object) triple already existed, or its
+    /// inverse under [`RelationsConfig::inverse_predicates`] did (`id` is then that edge's).
     pub deduplicated: bool,
+    /// The reverse edge, when stored with [`store_bidirectional_relation`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inverse: Option<Box<StoreRelationResult>>,
+}
+
+/// Result of [`forget_relation`].
+#[derive(Debug, Serialize)]
+pub struct ForgetRelationResult {
+    /// ID of the deleted relation, if the triple existed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// `true` if a relation was deleted.
+    pub removed: bool,
+}
+
+/// One triple to create with [`store_relations_batch`].
+#[derive(Debug, Clone, Copy)]
+pub struct NewRelation<'a> {
+    /// ID of the source entity memory.
+    pub subject_id: &'a str,
+    /// Relationship label.
+    pub predicate: &'a str,
+    /// ID of the target entity memory.
+    pub object_id: &'a str,
+}
+
+/// Result of [`store_relations_batch`].
+#[derive(Debug, Serialize)]
+pub struct StoreRelationsBatchResult {
+    /// Per-triple results, in input order.
+    pub results: Vec<StoreRelationResult>,
+    /// Number of new relations inserted.
+    pub created: usize,
+    /// Number of triples that already existed (in the database or earlier in the batch).
+    pub deduplicated: usize,
+}
+
+/// Which edges [`traverse_relations`] follows out of each entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// From subject to object (`Alice works_with Bob` leads from Alice to Bob).
+    #[default]
+    Outgoing,
+    /// From object back to subject.
+    Incoming,
+    /// Either way.
+    Both,
+}
+
+impl std::str::FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "outgoing" => Ok(Self::Outgoing),
+            "incoming" => Ok(Self::Incoming),
+            "both" => Ok(Self::Both),
+            _ => Err(format!("unknown direction: {s} (expected outgoing, incoming, or both)")),
+        }
+    }
+}
+
+/// One edge on the path to a [`ReachedEntity`].
+#[derive(Debug, Clone, Serialize)]
+pub struct PathStep {
+    /// Relationship label of the edge.
+    pub predicate: String,
+    /// [`Direction::Outgoing`] if the edge was followed subject → object,
+    /// [`Direction::Incoming`] if object → subject.
+    pub direction: Direction,
+}
+
+/// An entity reached by [`traverse_relations`].
+#[derive(Debug, Serialize)]
+pub struct ReachedEntity {
+    /// The reached entity.
+    #[serde(flatten)]
+    pub entity: RelationTarget,
+    /// Number of hops from the start entity.
+    pub depth: usize,
+    /// Edges taken from the start entity, in order.
+    pub path: Vec<PathStep>,
+}
+
+/// Find the entities reachable from `start_id` within `max_depth` hops.
+///
+/// Breadth-first, so each entity is reported once, at its shortest distance, with the
+/// first path found to it; cycles are cut by a visited set. `predicate` restricts every
+/// hop to one relationship label. The start entity itself is never included.
+pub fn traverse_relations(
+    conn: &Connection,
+    start_id: &str,
+    predicate: Option<&str>,
+    max_depth: usize,
+    direction: Direction,
+) -> Result<Vec<ReachedEntity>> {
+    validate_entity(conn, start_id, "start")?;
+
+    let mut outgoing = conn.prepare(
+        "SELECT predicate, object_id FROM entity_relations \
+         WHERE subject_id = ?1 AND (?2 IS NULL OR predicate = ?2) ORDER BY created_at, id",
+    )?;
+    let mut incoming = conn.prepare(
+        "SELECT predicate, subject_id FROM entity_relations \
+         WHERE object_id = ?1 AND (?2 IS NULL OR predicate = ?2) ORDER BY created_at, id",
+    )?;
+    let mut target = conn.prepare("SELECT type, content FROM memories WHERE id = ?1")?;
+
+    let mut visited: HashSet<String> = HashSet::from([start_id.to_string()]);
+    let mut queue: VecDeque<(String, Vec<PathStep>)> = VecDeque::from([(start_id.to_string(), Vec::new())]);
+    let mut reached = Vec::new();
+
+    while let Some((id, path)) = queue.pop_front() {
+        if path.len() >= max_depth {
+            continue;
+        }
+
+        let mut edges: Vec<(String, String, Direction)> = Vec::new();
+        if direction != Direction::Incoming {
+            let rows = outgoing.query_map(params![id, predicate], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            for row in rows {
+                let (predicate, next) = row?;
+                edges.push((predicate, next, Direction::Outgoing));
+            }
+        }
+        if direction != Direction::Outgoing {
+            let rows = incoming.query_map(params![id, predicate], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            for row in rows {
+                let (predicate, next) = row?;
+                edges.push((predicate, next, Direction::Incoming));
+            }
+        }
+
+        for (predicate, next, step_direction) in edges {
+            if !visited.insert(next.clone()) {
+                continue;
+            }
+            let Some((memory_type, content)) = target
+                .query_row(params![next], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+                .optional()?
+            else {
+                continue;
+            };
+
+            let mut next_path = path.clone();
+            next_path.push(PathStep {
+                predicate,
+                direction: step_direction,
+            });
+            reached.push(ReachedEntity {
+                entity: RelationTarget {
+                    id: next.clone(),
+                    memory_type,
+                    preview: truncate_preview(&content, 100),
+                },
+                depth: next_path.len(),
+                path: next_path.clone(),
+            });
+            queue.push_back((next, next_path));
+        }
+    }
+
+    Ok(reached)
 }
 
 /// Store a relationship between two entity memories.
@@ -27,26 +199,171 @@ pub fn store_relation(
     predicate: &str,
     object_id: &str,
 ) -> Result<StoreRelationResult> {
+    store_relation_with(conn, subject_id, predicate, object_id, &RelationsConfig::default())
+}
+
+/// Same as [`store_relation`], enforcing the predicate rules in `rules`.
+pub fn store_relation_with(
+    conn: &Connection,
+    subject_id: &str,
+    predicate: &str,
+    object_id: &str,
+    rules: &RelationsConfig,
+) -> Result<StoreRelationResult> {
+    validate_predicate(rules, predicate)?;
+
     // Validate subject exists and is entity type
     validate_entity(conn, subject_id, "subject")?;
 
     // Validate object exists and is entity type
     validate_entity(conn, object_id, "object")?;
 
-    // Dedup: check for existing (subject, predicate, object) tuple
-    let existing_id: Option<String> = conn
-        .query_row(
-            "SELECT id FROM entity_relations \
-             WHERE subject_id = ?1 AND predicate = ?2 AND object_id = ?3",
-            params![subject_id, predicate, object_id],
-            |row| row.get(0),
+    check_single_valued(conn, rules, subject_id, predicate, object_id)?;
+    insert_or_find(conn, rules, subject_id, predicate, object_id)
+}
+
+/// Store a relation and its reverse in one transaction: `subject --predicate--> object`
+/// and `object --inverse_predicate--> subject`, so the link shows up from both entities.
+///
+/// Each edge is validated and deduplicated like [`store_relation_with`]; the reverse
+/// edge's result is returned as `inverse`. If `rules` already pairs the two predicates,
+/// the reverse edge deduplicates onto the forward one.
+pub fn store_bidirectional_relation(
+    conn: &mut Connection,
+    subject_id: &str,
+    predicate: &str,
+    object_id: &str,
+    inverse_predicate: &str,
+    rules: &RelationsConfig,
+) -> Result<StoreRelationResult> {
+    validate_predicate(rules, predicate)?;
+    validate_predicate(rules, inverse_predicate)?;
+    validate_entity(conn, subject_id, "subject")?;
+    validate_entity(conn, object_id, "object")?;
+
+    let tx = conn.transaction()?;
+    check_single_valued(&tx, rules, subject_id, predicate, object_id)?;
+    let mut result = insert_or_find(&tx, rules, subject_id, predicate, object_id)?;
+    check_single_valued(&tx, rules, object_id, inverse_predicate, subject_id)?;
+    let inverse = insert_or_find(&tx, rules, object_id, inverse_predicate, subject_id)?;
+    tx.commit()?;
+
+    result.inverse = Some(Box::new(inverse));
+    Ok(result)
+}
+
+/// Predicate for the reverse of a `predicate` edge: the configured inverse under
+/// [`RelationsConfig::inverse_predicates`], else `predicate` itself (a symmetric
+/// relation like `works_with`).
+pub fn reverse_predicate<'a>(rules: &'a RelationsConfig, predicate: &'a str) -> &'a str {
+    inverse_predicate(rules, predicate).unwrap_or(predicate)
+}
+
+/// Store many relations atomically.
+///
+/// Every endpoint and predicate is validated before anything is written; the first
+/// invalid triple fails the whole batch with its index. Triples are then inserted in
+/// one transaction, deduplicating against existing relations and earlier triples in
+/// the batch. A single-valued predicate given a second object (in the database or the
+/// batch) also fails the whole batch.
+pub fn store_relations_batch(
+    conn: &mut Connection,
+    relations: &[NewRelation],
+    rules: &RelationsConfig,
+) -> Result<StoreRelationsBatchResult> {
+    for (index, relation) in relations.iter().enumerate() {
+        if relation.predicate.is_empty() {
+            bail!("relation {index}: predicate must not be empty");
+        }
+        validate_predicate(rules, relation.predicate)
+            .and_then(|()| validate_entity(conn, relation.subject_id, "subject"))
+            .and_then(|()| validate_entity(conn, relation.object_id, "object"))
+            .map_err(|e| anyhow::anyhow!("relation {index}: {e}"))?;
+    }
+
+    let tx = conn.transaction()?;
+    let mut results = Vec::with_capacity(relations.len());
+    for (index, relation) in relations.iter().enumerate() {
+        check_single_valued(
+            &tx,
+            rules,
+            relation.subject_id,
+            relation.predicate,
+            relation.object_id,
         )
-        .optional()?;
+        .map_err(|e| anyhow::anyhow!("relation {index}: {e}"))?;
+        results.push(insert_or_find(
+            &tx,
+            rules,
+            relation.subject_id,
+            relation.predicate,
+            relation.object_id,
+        )?);
+    }
+    tx.commit()?;
+
+    let deduplicated = results.iter().filter(|r| r.deduplicated).count();
+    Ok(StoreRelationsBatchResult {
+        created: results.len() - deduplicated,
+        deduplicated,
+        results,
+    })
+}
+
+/// Delete the relation with exactly this triple, leaving both entities in place.
+///
+/// The deletion is recorded in the audit log under the subject as `relation_delete`.
+/// Forgetting a triple that doesn't exist is not an error; `removed` is then `false`.
+pub fn forget_relation(
+    conn: &mut Connection,
+    subject_id: &str,
+    predicate: &str,
+    object_id: &str,
+) -> Result<ForgetRelationResult> {
+    let tx = conn.transaction()?;
+    let Some(id) = find_relation(&tx, subject_id, predicate, object_id)? else {
+        return Ok(ForgetRelationResult {
+            id: None,
+            removed: false,
+        });
+    };
+
+    tx.execute("DELETE FROM entity_relations WHERE id = ?1", params![id])?;
+    let details = serde_json::json!({
+        "relation_id": id,
+        "predicate": predicate,
+        "object_id": object_id,
+    });
+    crate::memory::store::write_audit_log(&tx, "relation_delete", subject_id, Some(&details))?;
+    tx.commit()?;
+
+    Ok(ForgetRelationResult {
+        id: Some(id),
+        removed: true,
+    })
+}
+
+/// Return the existing relation for this triple (or its inverse), or insert it.
+fn insert_or_find(
+    conn: &Connection,
+    rules: &RelationsConfig,
+    subject_id: &str,
+    predicate: &str,
+    object_id: &str,
+) -> Result<StoreRelationResult> {
+    // Dedup: check for existing (subject, predicate, object) tuple, then the inverse edge
+    let mut existing_id = find_relation(conn, subject_id, predicate, object_id)?;
+    if existing_id.is_none()
+        && let Some(inverse) = inverse_predicate(rules, predicate)
+    {
+        existing_id = find_relation(conn, object_id, inverse, subject_id)?;
+    }
 
     if let Some(id) = existing_id {
         return Ok(StoreRelationResult {
             id,
             deduplicated: true,
+            inverse: None,
         });
     }
 
@@ -63,9 +380,80 @@ pub fn store_relation(
     Ok(StoreRelationResult {
         id,
         deduplicated: false,
+        inverse: None,
     })
 }
 
+/// ID of the relation with exactly this triple, if any.
+fn find_relation(
+    conn: &Connection,
+    subject_id: &str,
+    predicate: &str,
+    object_id: &str,
+) -> Result<Option<String>> {
+    let id = conn
+        .query_row(
+            "SELECT id FROM entity_relations \
+             WHERE subject_id = ?1 AND predicate = ?2 AND object_id = ?3",
+            params![subject_id, predicate, object_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(id)
+}
+
+/// The configured inverse of `predicate`, looked up in either direction of the map.
+fn inverse_predicate<'a>(rules: &'a RelationsConfig, predicate: &str) -> Option<&'a str> {
+    if let Some(inverse) = rules.inverse_predicates.get(predicate) {
+        return Some(inverse);
+    }
+    rules
+        .inverse_predicates
+        .iter()
+        .find(|(_, inverse)| *inverse == predicate)
+        .map(|(forward, _)| forward.as_str())
+}
+
+/// Reject predicates outside a non-empty allowlist.
+fn validate_predicate(rules: &RelationsConfig, predicate: &str) -> Result<()> {
+    if rules.allowed_predicates.is_empty() || rules.allowed_predicates.iter().any(|p| p == predicate) {
+        return Ok(());
+    }
+    bail!(
+        "predicate '{predicate}' is not allowed; allowed predicates: {}",
+        rules.allowed_predicates.join(", ")
+    )
+}
+
+/// Reject a second object for a single-valued predicate. Re-storing the existing
+/// triple is fine (it deduplicates).
+fn check_single_valued(
+    conn: &Connection,
+    rules: &RelationsConfig,
+    subject_id: &str,
+    predicate: &str,
+    object_id: &str,
+) -> Result<()> {
+    if !rules.single_valued_predicates.iter().any(|p| p == predicate) {
+        return Ok(());
+    }
+    let existing: Option<String> = conn
+        .query_row(
+            "SELECT object_id FROM entity_relations \
+             WHERE subject_id = ?1 AND predicate = ?2 AND object_id != ?3 LIMIT 1",
+            params![subject_id, predicate, object_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match existing {
+        Some(existing) => bail!(
+            "predicate '{predicate}' is single-valued and {subject_id} already has it \
+             pointing to {existing}"
+        ),
+        None => Ok(()),
+    }
+}
+
 /// Validate that a memory ID exists and is entity type.
 fn validate_entity(conn: &Connection, memory_id: &str, role: &str) -> Result<()> {
     let row: Option<String> = conn
@@ -88,16 +476,11 @@ fn validate_entity(conn: &Connection, memory_id: &str, role: &str) -> Result<()>
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db;
     use crate::memory::store;
     use crate::memory::types::{MemoryType, Scope};
 
     fn test_db() -> Connection {
-        db::load_sqlite_vec();
-        let conn = Connection::open_in_memory().unwrap();
-        conn.pragma_update(None, "foreign_keys", "ON").unwrap();
-        crate::db::schema::init_schema(&conn).unwrap();
-        conn
+        crate::db::open_memory_database().unwrap()
     }
 
     fn embedding_a() -> Vec<f32> {
@@ -226,6 +609,212 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("not found"));
     }
 
+    #[test]
+    fn test_store_relations_batch() {
+        let mut conn = test_db();
+        let id_a = insert_entity(&mut conn, "John Smith is an engineer", &embedding_a());
+        let id_b = insert_entity(&mut conn, "Acme Corp is a company", &embedding_b());
+        let existing = store_relation(&conn, &id_a, "works_at", &id_b).unwrap();
+
+        let batch = [
+            NewRelation { subject_id: &id_a, predicate: "works_at", object_id: &id_b },
+            NewRelation { subject_id: &id_b, predicate: "employs", object_id: &id_a },
+            NewRelation { subject_id: &id_b, predicate: "employs", object_id: &id_a },
+        ];
+        let result = store_relations_batch(&mut conn, &batch, &RelationsConfig::default()).unwrap();
+
+        assert_eq!(result.created, 1);
+        assert_eq!(result.deduplicated, 2);
+        assert_eq!(result.results[0].id, existing.id);
+        assert!(!result.results[1].deduplicated);
+        assert_eq!(result.results[2].id, result.results[1].id);
+    }
+
+    #[test]
+    fn test_store_relations_batch_fails_whole_batch() {
+        let mut conn = test_db();
+        let id_a = insert_entity(&mut conn, "John Smith is an engineer", &embedding_a());
+        let id_b = insert_entity(&mut conn, "Acme Corp is a company", &embedding_b());
+
+        let batch = [
+            NewRelation { subject_id: &id_a, predicate: "works_at", object_id: &id_b },
+            NewRelation { subject_id: &id_a, predicate: "knows", object_id: "missing" },
+        ];
+        let err = store_relations_batch(&mut conn, &batch, &RelationsConfig::default()).unwrap_err().to_string();
+        assert!(err.starts_with("relation 1:"), "got: {err}");
+        assert!(err.contains("object memory not found"));
+
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM entity_relations", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_predicate_allowlist() {
+        let mut conn = test_db();
+        let id_a = insert_entity(&mut conn, "John Smith is an engineer", &embedding_a());
+        let id_b = insert_entity(&mut conn, "Acme Corp is a company", &embedding_b());
+        let rules = RelationsConfig {
+            allowed_predicates: vec!["works_at".into(), "manages".into()],
+            ..Default::default()
+        };
+
+        store_relation_with(&conn, &id_a, "works_at", &id_b, &rules).unwrap();
+        let err = store_relation_with(&conn, &id_a, "likes", &id_b, &rules)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("'likes' is not allowed"), "got: {err}");
+        assert!(err.contains("works_at, manages"));
+
+        let batch = [NewRelation { subject_id: &id_b, predicate: "employs", object_id: &id_a }];
+        let err = store_relations_batch(&mut conn, &batch, &rules).unwrap_err().to_string();
+        assert!(err.starts_with("relation 0:"), "got: {err}");
+    }
+
+    #[test]
+    fn test_single_valued_predicate() {
+        let mut conn = test_db();
+        let person = insert_entity(&mut conn, "John Smith is an engineer", &embedding_a());
+        let city = insert_entity(&mut conn, "Springfield is a city", &embedding_b());
+        let mut other = vec![0.0f32; 384];
+        other[200] = 1.0;
+        let other_city = insert_entity(&mut conn, "Shelbyville is a city", &other);
+        let rules = RelationsConfig {
+            single_valued_predicates: vec!["born_in".into()],
+            ..Default::default()
+        };
+
+        let first = store_relation_with(&conn, &person, "born_in", &city, &rules).unwrap();
+        // Repeating the same triple still deduplicates
+        let again = store_relation_with(&conn, &person, "born_in", &city, &rules).unwrap();
+        assert_eq!(again.id, first.id);
+
+        let err = store_relation_with(&conn, &person, "born_in", &other_city, &rules)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("single-valued"), "got: {err}");
+        assert!(err.contains(&city));
+
+        // Two objects within one batch fail it too, leaving nothing behind
+        let batch = [
+            NewRelation { subject_id: &city, predicate: "born_in", object_id: &person },
+            NewRelation { subject_id: &city, predicate: "born_in", object_id: &other_city },
+        ];
+        let err = store_relations_batch(&mut conn, &batch, &rules).unwrap_err().to_string();
+        assert!(err.starts_with("relation 1:"), "got: {err}");
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM entity_relations", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_inverse_predicate_dedups_against_existing_edge() {
+        let mut conn = test_db();
+        let alice = insert_entity(&mut conn, "Alice is an engineering manager", &embedding_a());
+        let bob = insert_entity(&mut conn, "Bob is an engineer", &embedding_b());
+        let rules = RelationsConfig {
+            inverse_predicates: [("manages".to_string(), "managed_by".to_string())].into(),
+            ..Default::default()
+        };
+
+        let forward = store_relation_with(&conn, &alice, "manages", &bob, &rules).unwrap();
+        let inverse = store_relation_with(&conn, &bob, "managed_by", &alice, &rules).unwrap();
+        assert!(inverse.deduplicated);
+        assert_eq!(inverse.id, forward.id);
+
+        // Lookup works from either side of the map, and within a batch
+        let batch = [NewRelation { subject_id: &bob, predicate: "managed_by", object_id: &alice }];
+        let result = store_relations_batch(&mut conn, &batch, &rules).unwrap();
+        assert_eq!(result.deduplicated, 1);
+
+        // Without the rule the inverse is a separate edge
+        let separate = store_relation(&conn, &bob, "managed_by", &alice).unwrap();
+        assert!(!separate.deduplicated);
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM entity_relations", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_forget_relation_removes_triple() {
+        let mut conn = test_db();
+        let id_a = insert_entity(&mut conn, "John Smith", &embedding_a());
+        let id_b = insert_entity(&mut conn, "Acme Corp", &embedding_b());
+        let stored = store_relation(&conn, &id_a, "works_at", &id_b).unwrap();
+
+        // A different predicate doesn't match the stored triple
+        let missed = forget_relation(&mut conn, &id_a, "founded", &id_b).unwrap();
+        assert!(!missed.removed);
+
+        let result = forget_relation(&mut conn, &id_a, "works_at", &id_b).unwrap();
+        assert!(result.removed);
+        assert_eq!(result.id.as_deref(), Some(stored.id.as_str()));
+
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM entity_relations", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+        let memories: i64 = conn
+            .query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(memories, 2);
+
+        let details: String = conn
+            .query_row(
+                "SELECT details FROM memory_log WHERE operation = 'relation_delete' AND memory_id = ?1",
+                params![id_a],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let details: serde_json::Value = serde_json::from_str(&details).unwrap();
+        assert_eq!(details["relation_id"], stored.id);
+
+        // Forgetting again is a no-op
+        assert!(!forget_relation(&mut conn, &id_a, "works_at", &id_b).unwrap().removed);
+    }
+
+    #[test]
+    fn test_bidirectional_relation_is_visible_from_both_entities() {
+        let mut conn = test_db();
+        let alice = insert_entity(&mut conn, "Alice leads the platform team", &embedding_a());
+        let bob = insert_entity(&mut conn, "Bob is a platform engineer", &embedding_b());
+        let rules = RelationsConfig::default();
+
+        let result =
+            store_bidirectional_relation(&mut conn, &alice, "manages", &bob, "managed_by", &rules).unwrap();
+        assert!(!result.deduplicated);
+        let inverse = result.inverse.unwrap();
+        assert!(!inverse.deduplicated);
+        assert_ne!(inverse.id, result.id);
+
+        let alice_out = traverse_relations(&conn, &alice, None, 1, Direction::Outgoing).unwrap();
+        assert_eq!(alice_out[0].entity.id, bob);
+        assert_eq!(alice_out[0].path[0].predicate, "manages");
+        let bob_out = traverse_relations(&conn, &bob, None, 1, Direction::Outgoing).unwrap();
+        assert_eq!(bob_out[0].entity.id, alice);
+        assert_eq!(bob_out[0].path[0].predicate, "managed_by");
+
+        // Storing it again deduplicates both edges
+        let again =
+            store_bidirectional_relation(&mut conn, &alice, "manages", &bob, "managed_by", &rules).unwrap();
+        assert!(again.deduplicated);
+        assert!(again.inverse.unwrap().deduplicated);
+    }
+
+    #[test]
+    fn test_reverse_predicate_defaults() {
+        let rules = RelationsConfig {
+            inverse_predicates: [("manages".to_string(), "managed_by".to_string())].into(),
+            ..Default::default()
+        };
+        assert_eq!(reverse_predicate(&rules, "manages"), "managed_by");
+        assert_eq!(reverse_predicate(&rules, "managed_by"), "manages");
+        assert_eq!(reverse_predicate(&rules, "works_with"), "works_with");
+    }
+
     #[test]
     fn test_cascade_delete() {
         let mut conn = test_db();
@@ -253,4 +842,84 @@ mod tests {
             .unwrap();
         assert_eq!(count, 0);
     }
+
+    /// Helper: a chain Alice → Bob → Carol → Alice of `works_with` edges, plus
+    /// Bob `reports_to` Dana.
+    fn colleague_graph(conn: &mut Connection) -> [String; 4] {
+        let entity = |conn: &mut Connection, content: &str, dim: usize| {
+            let mut embedding = vec![0.0f32; 384];
+            embedding[dim] = 1.0;
+            insert_entity(conn, content, &embedding)
+        };
+        let alice = entity(conn, "Alice is an engineer", 10);
+        let bob = entity(conn, "Bob is a designer", 20);
+        let carol = entity(conn, "Carol is a product manager", 30);
+        let dana = entity(conn, "Dana is a director", 40);
+        store_relation(conn, &alice, "works_with", &bob).unwrap();
+        store_relation(conn, &bob, "works_with", &carol).unwrap();
+        store_relation(conn, &carol, "works_with", &alice).unwrap();
+        store_relation(conn, &bob, "reports_to", &dana).unwrap();
+        [alice, bob, carol, dana]
+    }
+
+    fn reached_ids(reached: &[ReachedEntity]) -> Vec<&str> {
+        reached.iter().map(|r| r.entity.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_traverse_one_hop() {
+        let mut conn = test_db();
+        let [alice, bob, _, _] = colleague_graph(&mut conn);
+
+        let reached = traverse_relations(&conn, &alice, None, 1, Direction::Outgoing).unwrap();
+        assert_eq!(reached_ids(&reached), vec![bob.as_str()]);
+        assert_eq!(reached[0].depth, 1);
+        assert_eq!(reached[0].path[0].predicate, "works_with");
+        assert_eq!(reached[0].entity.preview, "Bob is a designer");
+    }
+
+    #[test]
+    fn test_traverse_two_hops_with_predicate_filter() {
+        let mut conn = test_db();
+        let [alice, bob, carol, dana] = colleague_graph(&mut conn);
+
+        let reached = traverse_relations(&conn, &alice, None, 2, Direction::Outgoing).unwrap();
+        assert_eq!(reached_ids(&reached), vec![bob.as_str(), carol.as_str(), dana.as_str()]);
+        let predicates: Vec<&str> = reached[2].path.iter().map(|s| s.predicate.as_str()).collect();
+        assert_eq!(predicates, vec!["works_with", "reports_to"]);
+        assert_eq!(reached[2].depth, 2);
+
+        let reached =
+            traverse_relations(&conn, &alice, Some("works_with"), 2, Direction::Outgoing).unwrap();
+        assert_eq!(reached_ids(&reached), vec![bob.as_str(), carol.as_str()]);
+
+        // Incoming edges walk the chain backwards
+        let reached = traverse_relations(&conn, &alice, None, 1, Direction::Incoming).unwrap();
+        assert_eq!(reached_ids(&reached), vec![carol.as_str()]);
+        assert_eq!(reached[0].path[0].direction, Direction::Incoming);
+    }
+
+    #[test]
+    fn test_traverse_is_cycle_safe() {
+        let mut conn = test_db();
+        let [alice, bob, carol, dana] = colleague_graph(&mut conn);
+
+        // The works_with cycle leads back to Alice, who is never reported or revisited
+        let reached = traverse_relations(&conn, &alice, None, 10, Direction::Both).unwrap();
+        let mut ids = reached_ids(&reached);
+        ids.sort();
+        let mut expected = vec![bob.as_str(), carol.as_str(), dana.as_str()];
+        expected.sort();
+        assert_eq!(ids, expected);
+        // Carol is one hop away via the incoming edge, not two via Bob
+        let carol_entry = reached.iter().find(|r| r.entity.id == carol).unwrap();
+        assert_eq!(carol_entry.depth, 1);
+    }
+
+    #[test]
+    fn test_traverse_requires_entity_start() {
+        let conn = test_db();
+        let err = traverse_relations(&conn, "missing", None, 1, Direction::Outgoing).unwrap_err();
+        assert!(err.to_string().contains("start memory not found"));
+    }
 }
@@ -0,0 +1,208 @@
+//! Low-level repair of supersession chains.
+//!
+//! [`relink_memory`] directly sets or clears a memory's `superseded_by`, for untangling
+//! chains after mistaken supersessions or merges. It is a CLI-only admin primitive and
+//! is deliberately not exposed as an MCP tool.
+
+use anyhow::{bail, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashSet;
+
+use super::store::write_audit_log;
+
+/// Marker stored in `superseded_by` for soft-deleted memories.
+pub const FORGOTTEN: &str = "forgotten";
+
+/// Result returned from a relink operation.
+#[derive(Debug, Serialize)]
+pub struct RelinkResult {
+    /// ID of the relinked memory.
+    pub id: String,
+    /// `superseded_by` before the change.
+    pub previous: Option<String>,
+    /// `superseded_by` after the change (`None` makes the memory active again).
+    pub superseded_by: Option<String>,
+}
+
+/// Set (or with `None`, clear) the `superseded_by` of a memory.
+///
+/// `target` is another memory's ID (or unique prefix) or [`FORGOTTEN`]. Fails if
+/// either memory does not exist, if the memory would supersede itself, or if the
+/// target's own chain leads back to the memory (a cycle).
+pub fn relink_memory(conn: &mut Connection, memory_id: &str, target: Option<&str>) -> Result<RelinkResult> {
+    let tx = conn.transaction()?;
+
+    let memory_id = super::search::resolve_memory_id(&tx, memory_id)?;
+    let target = match target {
+        None => None,
+        Some(FORGOTTEN) => Some(FORGOTTEN.to_string()),
+        Some(target) => {
+            let target = super::search::resolve_memory_id(&tx, target)?;
+            if target == memory_id {
+                bail!("memory {memory_id} cannot supersede itself");
+            }
+            if let Some(path) = chain_back_to(&tx, &target, &memory_id)? {
+                bail!(
+                    "relinking would create a supersession cycle: {memory_id} -> {}",
+                    path.join(" -> ")
+                );
+            }
+            Some(target)
+        }
+    };
+
+    let previous: Option<String> = tx.query_row(
+        "SELECT superseded_by FROM memories WHERE id = ?1",
+        params![memory_id],
+        |row| row.get(0),
+    )?;
+
+    tx.execute(
+        "UPDATE memories SET superseded_by = ?1, updated_at = ?2 WHERE id = ?3",
+        params![target, chrono::Utc::now().to_rfc3339(), memory_id],
+    )?;
+    write_audit_log(
+        &tx,
+        "supersede",
+        &memory_id,
+        Some(&serde_json::json!({
+            "action": "relink",
+            "previous": previous,
+            "superseded_by": target,
+        })),
+    )?;
+
+    tx.commit()?;
+
+    Ok(RelinkResult {
+        id: memory_id,
+        previous,
+        superseded_by: target,
+    })
+}
+
+/// Follow `superseded_by` links from `start`; if they reach `needle`, return the path.
+fn chain_back_to(conn: &Connection, start: &str, needle: &str) -> Result<Option<Vec<String>>> {
+    let mut path = vec![start.to_string()];
+    let mut seen = HashSet::from([start.to_string()]);
+    let mut current = start.to_string();
+
+    loop {
+        let next: Option<String> = conn
+            .query_row(
+                "SELECT superseded_by FROM memories WHERE id = ?1",
+                params![current],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        let Some(next) = next else {
+            return Ok(None);
+        };
+        path.push(next.clone());
+        if next == needle {
+            return Ok(Some(path));
+        }
+        // An existing cycle elsewhere in the chain must not loop forever
+        if !seen.insert(next.clone()) {
+            return Ok(None);
+        }
+        current = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::store;
+    use crate::memory::types::{MemoryType, Scope};
+
+    fn test_db() -> Connection {
+        crate::db::open_memory_database().unwrap()
+    }
+
+    fn insert(conn: &mut Connection, content: &str, dim: usize) -> String {
+        let mut embedding = vec![0.0f32; 384];
+        embedding[dim] = 1.0;
+        store::store_memory(
+            conn,
+            content,
+            MemoryType::Semantic,
+            Scope::Global,
+            Some("default"),
+            1.0,
+            None,
+            None,
+            &embedding,
+            0.92,
+        )
+        .unwrap()
+        .id
+    }
+
+    fn superseded_by(conn: &Connection, id: &str) -> Option<String> {
+        conn.query_row(
+            "SELECT superseded_by FROM memories WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_relink_sets_and_clears() {
+        let mut conn = test_db();
+        let a = insert(&mut conn, "Memory A", 0);
+        let b = insert(&mut conn, "Memory B", 1);
+
+        let result = relink_memory(&mut conn, &a, Some(&b)).unwrap();
+        assert_eq!(result.previous, None);
+        assert_eq!(superseded_by(&conn, &a), Some(b.clone()));
+
+        let result = relink_memory(&mut conn, &a, None).unwrap();
+        assert_eq!(result.previous, Some(b));
+        assert_eq!(superseded_by(&conn, &a), None);
+
+        relink_memory(&mut conn, &a, Some(FORGOTTEN)).unwrap();
+        assert_eq!(superseded_by(&conn, &a).as_deref(), Some(FORGOTTEN));
+
+        let logged: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM memory_log WHERE memory_id = ?1 AND operation = 'supersede'",
+                params![a],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(logged, 3);
+    }
+
+    #[test]
+    fn test_relink_rejects_cycles() {
+        let mut conn = test_db();
+        let a = insert(&mut conn, "Memory A", 0);
+        let b = insert(&mut conn, "Memory B", 1);
+        let c = insert(&mut conn, "Memory C", 2);
+
+        // a -> b -> c
+        relink_memory(&mut conn, &a, Some(&b)).unwrap();
+        relink_memory(&mut conn, &b, Some(&c)).unwrap();
+
+        // c -> a would close the loop
+        let err = relink_memory(&mut conn, &c, Some(&a)).unwrap_err().to_string();
+        assert!(err.contains("cycle"), "got: {err}");
+        assert_eq!(superseded_by(&conn, &c), None);
+
+        let err = relink_memory(&mut conn, &a, Some(&a)).unwrap_err().to_string();
+        assert!(err.contains("itself"), "got: {err}");
+    }
+
+    #[test]
+    fn test_relink_requires_existing_memories() {
+        let mut conn = test_db();
+        let a = insert(&mut conn, "Memory A", 0);
+
+        assert!(relink_memory(&mut conn, &a, Some("missing")).is_err());
+        assert!(relink_memory(&mut conn, "missing", None).is_err());
+    }
+}
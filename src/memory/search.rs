@@ -3,12 +3,12 @@
 //! The primary entry points are [`recall_by_query`] (hybrid search with post-filtering
 //! and token budgeting) and [`recall_by_ids`] (direct hydration for progressive disclosure).
 
-use anyhow::Result;
-use rusqlite::{params, Connection};
+use anyhow::{bail, Result};
+use rusqlite::{params, Connection, OptionalExtension};
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::memory::types::{MemoryType, Scope};
+use crate::memory::types::{EntityRelation, Memory, MemoryType, Scope};
 
 // ── Public types ──────────────────────────────────────────────────────────────
 
@@ -31,11 +31,46 @@ pub struct SearchResult {
     /// Arbitrary JSON metadata, if present.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<serde_json::Value>,
+    /// Document, URL, or message ID the memory was derived from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_uri: Option<String>,
+    /// ID of the replacement memory (or `"forgotten"`), set only for superseded results.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub superseded_by: Option<String>,
     /// Outbound entity relations (only populated for entity-type memories).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub relations: Option<Vec<RelationEntry>>,
+    /// Excerpt around the keyword matches, each wrapped in [`SNIPPET_MARKER`]. Only set
+    /// for results the keyword search found.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+}
+
+impl From<Memory> for SearchResult {
+    /// A listed memory as a result. Listing doesn't rank, so `score` is 0.
+    fn from(memory: Memory) -> Self {
+        Self {
+            id: memory.id,
+            memory_type: memory.memory_type.as_str().to_string(),
+            content: memory.content,
+            confidence: memory.confidence,
+            score: 0.0,
+            created_at: memory.created_at,
+            metadata: memory.metadata,
+            source_uri: memory.source_uri,
+            superseded_by: memory.superseded_by,
+            relations: None,
+            snippet: None,
+        }
+    }
 }
 
+/// Placed on both sides of each matched term in [`SearchResult::snippet`].
+pub const SNIPPET_MARKER: &str = "**";
+
+/// Tokens of context FTS5 keeps in a snippet window.
+const SNIPPET_TOKENS: i64 = 16;
+
 /// A compact summary result for progressive disclosure.
 #[derive(Debug, Clone, Serialize)]
 pub struct SummaryResult {
@@ -59,6 +94,12 @@ pub struct RecallResponse {
     pub total_matched: usize,
     /// Estimated token count of the returned results (`chars / 4`).
     pub token_estimate: usize,
+    /// The caller's `max_results`, echoed only when it was clamped to the allowed range.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requested_max_results: Option<usize>,
+    /// Set when the query could not be embedded and recall fell back to keyword search.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub degraded: bool,
 }
 
 /// Response with summary-only results (for progressive disclosure).
@@ -70,9 +111,16 @@ pub struct RecallSummaryResponse {
     pub total_matched: usize,
     /// Estimated token count of the summary results.
     pub token_estimate: usize,
+    /// The caller's `max_results`, echoed only when it was clamped to the allowed range.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requested_max_results: Option<usize>,
+    /// Set when recall fell back to keyword search (see [`RecallResponse::degraded`]).
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub degraded: bool,
 }
 
 /// Filters applied after RRF merge.
+#[derive(Debug, Clone, Default)]
 pub struct SearchFilter {
     /// Restrict results to a single memory type, or `None` for all types.
     pub memory_type: Option<MemoryType>,
@@ -80,11 +128,208 @@ pub struct SearchFilter {
     pub scope: Option<Scope>,
     /// The caller's group — group-scoped memories outside this group are excluded.
     pub group: String,
+    /// If set, the groups whose group-scoped memories are visible, replacing `group`.
+    pub groups: Option<Vec<String>>,
+    /// The caller's session — session-scoped memories are visible only to the session
+    /// that stored them, so with `None` they are all excluded.
+    pub session_id: Option<String>,
     /// Minimum confidence score to include in results.
     pub min_confidence: f64,
+    /// Restrict results to memories derived from this source URI.
+    pub source_uri: Option<String>,
+    /// Keep superseded and forgotten memories instead of dropping them.
+    pub include_superseded: bool,
+    /// Drop these memory IDs from the results (e.g. ones the caller already has).
+    pub exclude_ids: Vec<String>,
+    /// Only memories created at or after this RFC 3339 timestamp.
+    pub created_after: Option<String>,
+    /// Only memories created at or before this RFC 3339 timestamp.
+    pub created_before: Option<String>,
+    /// Only memories whose metadata maps every key to the given string value.
+    pub metadata_filters: Vec<(String, String)>,
+    /// Only memories carrying every one of these tags.
+    pub tags: Vec<String>,
+}
+
+impl SearchFilter {
+    /// A filter seeing global memories plus `group`'s group-scoped ones; set `groups`
+    /// to search several groups at once.
+    pub fn for_group(group: impl Into<String>) -> Self {
+        Self {
+            group: group.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Whether group-scoped memories from `group` are visible to this search.
+    pub fn allows_group(&self, group: &str) -> bool {
+        match self.groups {
+            Some(ref groups) => groups.iter().any(|g| g == group),
+            None => self.group == group,
+        }
+    }
+}
+
+/// Parse an optional RFC 3339 bound, naming `field` in the error.
+pub fn parse_timestamp_bound(
+    field: &str,
+    value: Option<&str>,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+    value
+        .map(|v| {
+            chrono::DateTime::parse_from_rfc3339(v)
+                .map(|t| t.with_timezone(&chrono::Utc))
+                .map_err(|e| anyhow::anyhow!("invalid {field} '{v}': {e} (expected RFC 3339)"))
+        })
+        .transpose()
+}
+
+/// Filters, order, and page for [`list_memories`].
+#[derive(Debug, Clone, Default)]
+pub struct ListFilter {
+    /// Only list memories derived from this source URI.
+    pub source_uri: Option<String>,
+    /// Include superseded and forgotten memories.
+    pub include_superseded: bool,
+    /// Only list memories of this type.
+    pub memory_type: Option<MemoryType>,
+    /// Only list memories with this scope.
+    pub scope: Option<Scope>,
+    /// Only list global memories plus this group's own; `None` lists every group.
+    pub group: Option<String>,
+    /// The caller's session. Session-scoped memories are listed only for the session
+    /// that stored them, so with `None` they are all excluded (unless `all_sessions`).
+    pub session_id: Option<String>,
+    /// List session-scoped memories from every session, as the CLI does.
+    pub all_sessions: bool,
+    /// Only memories with at least this confidence.
+    pub min_confidence: Option<f64>,
+    /// Only memories with at most this confidence.
+    pub max_confidence: Option<f64>,
+    /// Only memories created at or after this RFC 3339 timestamp.
+    pub created_after: Option<String>,
+    /// Only memories created at or before this RFC 3339 timestamp.
+    pub created_before: Option<String>,
+    /// Order of the listing.
+    pub sort: ListSort,
+    /// Most memories returned, or `None` for all.
+    pub limit: Option<usize>,
+    /// Matching memories skipped before the first one returned.
+    pub offset: usize,
+}
+
+/// Order of [`list_memories`]; every order is descending, ties broken newest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ListSort {
+    /// Newest first.
+    #[default]
+    CreatedAt,
+    /// Most confident first.
+    Confidence,
+    /// Most accessed first.
+    AccessCount,
+}
+
+impl ListSort {
+    /// The SQL `ORDER BY` clause for this order.
+    fn order_by(&self) -> &'static str {
+        match self {
+            Self::CreatedAt => "created_at DESC, id DESC",
+            Self::Confidence => "confidence DESC, created_at DESC, id DESC",
+            Self::AccessCount => "access_count DESC, created_at DESC, id DESC",
+        }
+    }
+
+    /// Compare two memories in this order, matching [`order_by`](Self::order_by).
+    fn compare(&self, a: &Memory, b: &Memory) -> std::cmp::Ordering {
+        let primary = match self {
+            Self::CreatedAt => std::cmp::Ordering::Equal,
+            Self::Confidence => b.confidence.total_cmp(&a.confidence),
+            Self::AccessCount => b.access_count.cmp(&a.access_count),
+        };
+        primary
+            .then_with(|| b.created_at.cmp(&a.created_at))
+            .then_with(|| b.id.cmp(&a.id))
+    }
+}
+
+impl std::str::FromStr for ListSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "created_at" => Ok(Self::CreatedAt),
+            "confidence" => Ok(Self::Confidence),
+            "access_count" => Ok(Self::AccessCount),
+            _ => Err(format!(
+                "unknown sort order: {s} (expected created_at, confidence, or access_count)"
+            )),
+        }
+    }
+}
+
+/// One page of [`list_memories`] results, as returned by the `list_memories` tool.
+#[derive(Debug, Clone, Serialize)]
+pub struct ListResponse {
+    /// The listed memories; `score` is always 0.
+    pub results: Vec<SearchResult>,
+    /// Matching memories skipped before this page.
+    pub offset: usize,
+    /// Whether more memories match after this page.
+    pub has_more: bool,
+}
+
+/// Filters for [`log_entries_after`].
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    /// Only entries for this operation (e.g. `"create"`).
+    pub operation: Option<String>,
+    /// Only entries for memories in this group. Hard-deleted memories no longer
+    /// have a group, so their `delete` entries are excluded.
+    pub group: Option<String>,
+}
+
+/// Which retrievers [`recall_by_query`] runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchMode {
+    /// Vector KNN + FTS5 BM25, fused with RRF (scores are RRF sums).
+    #[default]
+    Hybrid,
+    /// Vector KNN only (scores are cosine similarity).
+    Vector,
+    /// FTS5 BM25 only (scores are negated BM25 rank; higher is better). Needs no embedding.
+    Keyword,
+}
+
+impl SearchMode {
+    /// Return the lowercase string representation.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hybrid => "hybrid",
+            Self::Vector => "vector",
+            Self::Keyword => "keyword",
+        }
+    }
+}
+
+impl std::str::FromStr for SearchMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hybrid" => Ok(Self::Hybrid),
+            "vector" | "vector_only" => Ok(Self::Vector),
+            "keyword" | "keyword_only" => Ok(Self::Keyword),
+            _ => Err(format!("unknown search mode: {s} (expected hybrid, vector, or keyword)")),
+        }
+    }
 }
 
+/// Default candidates fetched from each retriever per requested result.
+pub const CANDIDATE_MULTIPLIER: usize = 3;
+
 /// Search configuration knobs.
+#[derive(Debug, Clone)]
 pub struct SearchConfig {
     /// Maximum number of results to return.
     pub max_results: usize,
@@ -92,6 +337,96 @@ pub struct SearchConfig {
     pub token_budget: usize,
     /// RRF constant `k` — controls rank-score decay (default 60).
     pub rrf_k: usize,
+    /// Words dropped from the keyword query (case-insensitive). The vector
+    /// search still sees the full query text.
+    pub stopwords: Vec<String>,
+    /// Retrievers to run (default hybrid).
+    pub mode: SearchMode,
+    /// Exponent `beta` in `score * importance^beta`; 0 disables importance weighting.
+    pub importance_beta: f64,
+    /// Record each query in `recall_log` (see [`log_recall_query`]).
+    pub log_queries: bool,
+    /// In hybrid mode, let vector and FTS each contribute at most half the candidate
+    /// pool, so a long keyword list can't crowd out strong vector hits (or vice versa).
+    pub balance_sources: bool,
+    /// Vector candidates fetched per requested result (default [`CANDIDATE_MULTIPLIER`]).
+    pub vector_candidate_multiplier: usize,
+    /// Keyword candidates fetched per requested result (default [`CANDIDATE_MULTIPLIER`]).
+    pub fts_candidate_multiplier: usize,
+    /// Multiplier on each vector hit's reciprocal rank in hybrid RRF (default 1.0).
+    pub vec_weight: f64,
+    /// Multiplier on each keyword hit's reciprocal rank in hybrid RRF (default 1.0).
+    pub fts_weight: f64,
+    /// Matches to skip before the returned page, for paging past `max_results`.
+    pub offset: usize,
+    /// Maximal-marginal-relevance trade-off in `[0, 1]`: after ranking, each pick scores
+    /// `lambda * relevance - (1 - lambda) * max cosine to earlier picks`, pushing
+    /// paraphrases of an already-chosen memory down. `None` keeps the plain ranking.
+    pub mmr_lambda: Option<f64>,
+    /// If set, scores are multiplied by `0.5^(age_days / half_life)` so newer memories
+    /// outrank slightly closer older ones. `None` ignores age.
+    pub recency_half_life_days: Option<f64>,
+    /// Record an access on each returned memory (default true). Multi-database
+    /// searches turn it off per database and track the merged page instead.
+    pub track_access: bool,
+    /// Confidence added to each returned memory when access is tracked, capped at 1.0
+    /// (0 leaves confidence alone).
+    pub reinforce_amount: f64,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            max_results: 5,
+            token_budget: 4000,
+            rrf_k: 60,
+            stopwords: Vec::new(),
+            mode: SearchMode::Hybrid,
+            importance_beta: 0.0,
+            log_queries: false,
+            balance_sources: false,
+            vector_candidate_multiplier: CANDIDATE_MULTIPLIER,
+            fts_candidate_multiplier: CANDIDATE_MULTIPLIER,
+            vec_weight: 1.0,
+            fts_weight: 1.0,
+            offset: 0,
+            mmr_lambda: None,
+            recency_half_life_days: None,
+            track_access: true,
+            reinforce_amount: 0.0,
+        }
+    }
+}
+
+/// How many candidates each retriever fetches for one query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CandidateLimits {
+    /// Limit passed to the vector KNN search.
+    pub vector: usize,
+    /// Limit passed to the FTS5 search.
+    pub fts: usize,
+}
+
+impl CandidateLimits {
+    /// Scale `max_results` (plus `offset`, so later pages stay reachable) by each
+    /// retriever's multiplier.
+    ///
+    /// Excluded IDs may occupy candidate slots, so each pool is widened by their count to
+    /// keep `max_results` reachable. With `balance_sources` in hybrid mode, each pool is halved.
+    pub fn new(config: &SearchConfig, filter: &SearchFilter) -> Self {
+        let excluded = filter.exclude_ids.len();
+        let wanted = config.max_results + config.offset;
+        let vector = wanted * config.vector_candidate_multiplier + excluded;
+        let fts = wanted * config.fts_candidate_multiplier + excluded;
+        if config.mode == SearchMode::Hybrid && config.balance_sources {
+            Self {
+                vector: (vector / 2).max(1),
+                fts: (fts / 2).max(1),
+            }
+        } else {
+            Self { vector, fts }
+        }
+    }
 }
 
 /// Full inspection response for a single memory.
@@ -102,9 +437,26 @@ pub struct InspectResponse {
     /// Outbound entity relations, if requested and the memory is entity-type.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub relations: Option<Vec<RelationEntry>>,
+    /// Relations pointing at this memory, if requested (see [`incoming_relations`]).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub incoming_relations: Option<Vec<IncomingRelationEntry>>,
     /// Audit log entries, if requested.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub log: Option<Vec<LogEntry>>,
+    /// Stored embedding vector, if requested (see [`stored_embedding`]).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<StoredEmbedding>,
+}
+
+/// A memory's embedding exactly as stored in `memories_vec`.
+#[derive(Debug, Serialize)]
+pub struct StoredEmbedding {
+    /// Number of components.
+    pub dimension: usize,
+    /// L2 norm (should be ~1.0 for normalized embeddings).
+    pub norm: f64,
+    /// Raw vector components.
+    pub values: Vec<f32>,
 }
 
 /// Full details of a single inspected memory.
@@ -119,6 +471,8 @@ pub struct InspectMemory {
     pub content: String,
     /// Current confidence score.
     pub confidence: f64,
+    /// Retrieval salience in `[0.0, 1.0]` (does not decay).
+    pub importance: f64,
     /// Number of times recalled.
     pub access_count: u32,
     /// Last recall timestamp, or `None`.
@@ -134,6 +488,12 @@ pub struct InspectMemory {
     /// Arbitrary JSON metadata, if present.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<serde_json::Value>,
+    /// Document, URL, or message ID the memory was derived from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_uri: Option<String>,
+    /// SHA-256 of the whitespace-normalized content, used for exact-duplicate detection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
 }
 
 /// An outbound relation from the inspected entity.
@@ -145,6 +505,15 @@ pub struct RelationEntry {
     pub object: RelationTarget,
 }
 
+/// An inbound relation, where the inspected entity is the object.
+#[derive(Debug, Clone, Serialize)]
+pub struct IncomingRelationEntry {
+    /// Relationship label (e.g. `"manages"`).
+    pub predicate: String,
+    /// The entity the relation starts from.
+    pub subject: RelationTarget,
+}
+
 /// Compact representation of a related entity.
 #[derive(Debug, Clone, Serialize)]
 pub struct RelationTarget {
@@ -169,6 +538,54 @@ pub struct LogEntry {
     pub created_at: String,
 }
 
+/// An audit log entry with its log row ID and memory, as streamed by `loci watch`.
+#[derive(Debug, Serialize)]
+pub struct LogEvent {
+    /// `memory_log` row ID (monotonically increasing).
+    pub id: i64,
+    /// Memory the operation applied to.
+    pub memory_id: String,
+    /// Operation, details, and timestamp.
+    #[serde(flatten)]
+    pub entry: LogEntry,
+}
+
+/// Memories and relations changed after a cursor, from [`changed_since`].
+#[derive(Debug, Serialize)]
+pub struct ChangeSet {
+    /// Memories updated after the cursor, including superseded ones.
+    pub memories: Vec<ChangedMemory>,
+    /// Relations created after the cursor.
+    pub relations: Vec<EntityRelation>,
+    /// Server time when the change set was read; pass it as the next cursor.
+    pub cursor: String,
+}
+
+/// A changed memory, flagged if it has been superseded or forgotten.
+#[derive(Debug, Serialize)]
+pub struct ChangedMemory {
+    /// The full memory record.
+    #[serde(flatten)]
+    pub memory: Memory,
+    /// `true` if `superseded_by` is set, so mirrors can drop it from their index.
+    pub superseded: bool,
+}
+
+/// Aggregate history for one distinct recall query, from [`top_queries`].
+#[derive(Debug, Serialize)]
+pub struct QueryStat {
+    /// The query text.
+    pub query: String,
+    /// How many times it was run.
+    pub count: u64,
+    /// Mean number of results returned.
+    pub avg_results: f64,
+    /// How many runs returned nothing.
+    pub empty_count: u64,
+    /// ISO 8601 timestamp of the most recent run.
+    pub last_run: String,
+}
+
 // ── Internal row struct for fetched memories ──────────────────────────────────
 
 struct MemoryRow {
@@ -177,16 +594,25 @@ struct MemoryRow {
     content: String,
     source_group: Option<String>,
     scope: String,
+    session_id: Option<String>,
     confidence: f64,
+    importance: f64,
     access_count: u32,
     superseded_by: Option<String>,
     created_at: String,
     metadata: Option<serde_json::Value>,
+    source_uri: Option<String>,
+    expires_at: Option<String>,
+    tags: Vec<String>,
 }
 
 // ── Public API ────────────────────────────────────────────────────────────────
 
 /// Hybrid search: vector KNN + FTS5 BM25 → RRF merge → filter → budget → track.
+///
+/// With [`SearchMode::Vector`] or [`SearchMode::Keyword`] only that retriever runs and
+/// results are ranked by its native score instead of RRF. `query_embedding` is unused
+/// (and may be empty) in keyword mode.
 pub fn recall_by_query(
     conn: &Connection,
     query_embedding: &[f32],
@@ -194,16 +620,37 @@ pub fn recall_by_query(
     filter: &SearchFilter,
     config: &SearchConfig,
 ) -> Result<RecallResponse> {
-    let candidate_limit = config.max_results * 3;
-
-    // 1. Vector KNN search
-    let vec_results = vector_search(conn, query_embedding, candidate_limit)?;
-
-    // 2. FTS5 BM25 search
-    let fts_results = fts_search(conn, query_text, candidate_limit)?;
-
-    // 3. RRF merge
-    let merged = rrf_merge(&vec_results, &fts_results, config.rrf_k);
+    let limits = CandidateLimits::new(config, filter);
+    let created_after = parse_timestamp_bound("created_after", filter.created_after.as_deref())?;
+    let created_before = parse_timestamp_bound("created_before", filter.created_before.as_deref())?;
+
+    let now = chrono::Utc::now();
+
+    // 1–3. Vector KNN and/or FTS5 BM25, RRF-merged when both run
+    let mut fts_ids: HashSet<String> = HashSet::new();
+    let merged = match config.mode {
+        SearchMode::Hybrid => {
+            let vec_results = vector_search(conn, query_embedding, limits.vector)?;
+            let fts_results = fts_search(conn, query_text, &config.stopwords, limits.fts)?;
+            fts_ids.extend(fts_results.iter().map(|(id, _)| id.clone()));
+            rrf_merge(
+                &vec_results,
+                &fts_results,
+                config.rrf_k,
+                config.vec_weight,
+                config.fts_weight,
+            )
+        }
+        SearchMode::Vector => vector_search(conn, query_embedding, limits.vector)?
+            .into_iter()
+            .map(|(id, distance)| (id, super::l2_to_cosine(distance)))
+            .collect(),
+        SearchMode::Keyword => {
+            let fts_results = fts_search(conn, query_text, &config.stopwords, limits.fts)?;
+            fts_ids.extend(fts_results.iter().map(|(id, _)| id.clone()));
+            fts_results.into_iter().map(|(id, rank)| (id, -rank)).collect()
+        }
+    };
 
     // 4. Fetch full records for all candidate IDs
     let candidate_ids: Vec<&str> = merged.iter().map(|(id, _)| id.as_str()).collect();
@@ -213,15 +660,24 @@ pub fn recall_by_query(
     let mut filtered: Vec<(MemoryRow, f64)> = Vec::new();
     for (id, score) in &merged {
         if let Some(mem) = memories.get(id.as_str()) {
-            // Skip superseded
-            if mem.superseded_by.is_some() {
+            // Skip superseded unless the caller asked for history
+            if mem.superseded_by.is_some() && !filter.include_superseded {
+                continue;
+            }
+            if filter.exclude_ids.contains(&mem.id) {
                 continue;
             }
-            // Scope filter: always include global; include group only if matching
+            // Scope filter: always include global; include group only if allowed, and
+            // session only within the same session
             match mem.scope.as_str() {
                 "global" => {}
                 "group" => {
-                    if mem.source_group.as_deref() != Some(filter.group.as_str()) {
+                    if !mem.source_group.as_deref().is_some_and(|g| filter.allows_group(g)) {
+                        continue;
+                    }
+                }
+                "session" => {
+                    if filter.session_id.is_none() || mem.session_id != filter.session_id {
                         continue;
                     }
                 }
@@ -243,6 +699,47 @@ pub fn recall_by_query(
             if mem.confidence < filter.min_confidence {
                 continue;
             }
+            // Source filter
+            if filter.source_uri.is_some() && mem.source_uri != filter.source_uri {
+                continue;
+            }
+            // Creation date range
+            if created_after.is_some() || created_before.is_some() {
+                let Ok(created) = chrono::DateTime::parse_from_rfc3339(&mem.created_at) else {
+                    continue;
+                };
+                if created_after.is_some_and(|after| created < after)
+                    || created_before.is_some_and(|before| created > before)
+                {
+                    continue;
+                }
+            }
+            // Expired memories are never recalled
+            if mem
+                .expires_at
+                .as_deref()
+                .and_then(|e| chrono::DateTime::parse_from_rfc3339(e).ok())
+                .is_some_and(|expires_at| expires_at <= now)
+            {
+                continue;
+            }
+            // Tag filter: every requested tag must be present
+            if !filter.tags.iter().all(|tag| mem.tags.contains(tag)) {
+                continue;
+            }
+            // Metadata key/value filter; memories without metadata never match
+            if !filter.metadata_filters.is_empty() {
+                let Some(ref metadata) = mem.metadata else {
+                    continue;
+                };
+                if !filter
+                    .metadata_filters
+                    .iter()
+                    .all(|(key, value)| metadata.get(key).and_then(|v| v.as_str()) == Some(value))
+                {
+                    continue;
+                }
+            }
             filtered.push((
                 MemoryRow {
                     id: mem.id.clone(),
@@ -250,11 +747,16 @@ pub fn recall_by_query(
                     content: mem.content.clone(),
                     source_group: mem.source_group.clone(),
                     scope: mem.scope.clone(),
+                    session_id: mem.session_id.clone(),
                     confidence: mem.confidence,
+                    importance: mem.importance,
                     access_count: mem.access_count,
                     superseded_by: mem.superseded_by.clone(),
                     created_at: mem.created_at.clone(),
                     metadata: mem.metadata.clone(),
+                    source_uri: mem.source_uri.clone(),
+                    expires_at: mem.expires_at.clone(),
+                    tags: mem.tags.clone(),
                 },
                 *score,
             ));
@@ -263,10 +765,35 @@ pub fn recall_by_query(
 
     let total_matched = filtered.len();
 
-    // 6. Token budget enforcement
+    // Optional salience weighting: importance^beta scales the retrieval score
+    if config.importance_beta > 0.0 {
+        for (mem, score) in &mut filtered {
+            *score *= mem.importance.powf(config.importance_beta);
+        }
+    }
+
+    // Optional recency weighting: halve the score per half-life of age
+    if let Some(half_life) = config.recency_half_life_days {
+        for (mem, score) in &mut filtered {
+            *score *= recency_factor(&mem.created_at, now, half_life);
+        }
+    }
+
+    if config.importance_beta > 0.0 || config.recency_half_life_days.is_some() {
+        filtered.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    }
+
+    // Optional MMR rerank to spread results away from near-duplicates
+    if let Some(lambda) = config.mmr_lambda {
+        let ids: Vec<&str> = filtered.iter().map(|(m, _)| m.id.as_str()).collect();
+        let embeddings = fetch_embeddings(conn, &ids)?;
+        filtered = mmr_rerank(filtered, &embeddings, lambda);
+    }
+
+    // 6. Token budget enforcement, on the page after `offset`
     let mut token_sum = 0usize;
     let mut budgeted: Vec<(MemoryRow, f64)> = Vec::new();
-    for (mem, score) in filtered {
+    for (mem, score) in filtered.into_iter().skip(config.offset) {
         let tokens = mem.content.len() / 4;
         if !budgeted.is_empty() && token_sum + tokens > config.token_budget {
             break;
@@ -279,10 +806,13 @@ pub fn recall_by_query(
     }
 
     // 7. Access tracking
-    let returned_ids: Vec<&str> = budgeted.iter().map(|(m, _)| m.id.as_str()).collect();
-    update_access(conn, &returned_ids)?;
+    if config.track_access {
+        let returned_ids: Vec<&str> = budgeted.iter().map(|(m, _)| m.id.as_str()).collect();
+        update_access(conn, &returned_ids, config.reinforce_amount)?;
+    }
 
-    // 8. Build response with entity-aware relation fetching
+    // 8. Build response with entity-aware relation fetching and keyword snippets
+    let match_expr = escape_fts_query(query_text, &config.stopwords);
     let mut results: Vec<SearchResult> = Vec::with_capacity(budgeted.len());
     for (mem, score) in budgeted {
         let relations = if mem.memory_type == "entity" {
@@ -290,6 +820,11 @@ pub fn recall_by_query(
         } else {
             None
         };
+        let snippet = if fts_ids.contains(&mem.id) {
+            fts_snippet(conn, &match_expr, &mem.id)
+        } else {
+            None
+        };
         results.push(SearchResult {
             id: mem.id,
             memory_type: mem.memory_type,
@@ -298,17 +833,79 @@ pub fn recall_by_query(
             score,
             created_at: mem.created_at,
             metadata: mem.metadata,
+            source_uri: mem.source_uri,
+            superseded_by: mem.superseded_by,
             relations,
+            snippet,
         });
     }
 
+    if config.log_queries {
+        log_recall_query(conn, query_text, filter, config.mode, results.len());
+    }
+
     Ok(RecallResponse {
         results,
         total_matched,
         token_estimate: token_sum,
+        requested_max_results: None,
+        degraded: false,
     })
 }
 
+/// Append a query to `recall_log` with a JSON summary of its filters.
+///
+/// Logging is best-effort: failures are traced and never fail the recall.
+pub fn log_recall_query(
+    conn: &Connection,
+    query_text: &str,
+    filter: &SearchFilter,
+    mode: SearchMode,
+    result_count: usize,
+) {
+    let filters = serde_json::json!({
+        "type": filter.memory_type.map(|t| t.as_str()),
+        "scope": filter.scope.map(|s| s.as_str()),
+        "group": filter.groups.as_ref().map_or_else(
+            || serde_json::json!(filter.group),
+            |groups| serde_json::json!(groups),
+        ),
+        "mode": mode.as_str(),
+    });
+    if let Err(e) = conn.execute(
+        "INSERT INTO recall_log (query, filters, result_count, created_at) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            query_text,
+            filters.to_string(),
+            result_count as i64,
+            chrono::Utc::now().to_rfc3339()
+        ],
+    ) {
+        tracing::warn!(error = %e, "failed to log recall query");
+    }
+}
+
+/// Most frequently run recall queries, most common first.
+pub fn top_queries(conn: &Connection, limit: usize) -> Result<Vec<QueryStat>> {
+    let mut stmt = conn.prepare(
+        "SELECT query, COUNT(*), AVG(result_count), SUM(result_count = 0), MAX(created_at) \
+         FROM recall_log GROUP BY query \
+         ORDER BY COUNT(*) DESC, MAX(created_at) DESC LIMIT ?1",
+    )?;
+    let stats = stmt
+        .query_map(params![limit as i64], |row| {
+            Ok(QueryStat {
+                query: row.get(0)?,
+                count: row.get::<_, i64>(1)? as u64,
+                avg_results: row.get(2)?,
+                empty_count: row.get::<_, i64>(3)? as u64,
+                last_run: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(stats)
+}
+
 /// Direct hydration by IDs — no search, no filtering.
 pub fn recall_by_ids(conn: &Connection, ids: &[String]) -> Result<RecallResponse> {
     let id_refs: Vec<&str> = ids.iter().map(|s| s.as_str()).collect();
@@ -334,21 +931,85 @@ pub fn recall_by_ids(conn: &Connection, ids: &[String]) -> Result<RecallResponse
                 score: 1.0, // No search score for direct hydration
                 created_at: mem.created_at.clone(),
                 metadata: mem.metadata.clone(),
+                source_uri: mem.source_uri.clone(),
+                superseded_by: mem.superseded_by.clone(),
                 relations,
+                snippet: None,
             });
         }
     }
 
     let total = results.len();
-    update_access(conn, &id_refs)?;
+    update_access(conn, &id_refs, 0.0)?;
 
     Ok(RecallResponse {
         results,
         total_matched: total,
         token_estimate: token_sum,
+        requested_max_results: None,
+        degraded: false,
     })
 }
 
+/// Combine per-database query responses into one ranking.
+///
+/// Results are re-sorted by score, `config.offset` results are skipped, and the result
+/// cap and token budget are applied again across the combined set.
+pub fn merge_query_responses(
+    responses: Vec<RecallResponse>,
+    config: &SearchConfig,
+) -> RecallResponse {
+    if responses.len() == 1 {
+        return responses.into_iter().next().expect("one response");
+    }
+
+    let total_matched = responses.iter().map(|r| r.total_matched).sum();
+    let mut all: Vec<SearchResult> = responses.into_iter().flat_map(|r| r.results).collect();
+    all.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut token_sum = 0usize;
+    let mut results = Vec::new();
+    for result in all.into_iter().skip(config.offset) {
+        let tokens = result.content.len() / 4;
+        if !results.is_empty() && token_sum + tokens > config.token_budget {
+            break;
+        }
+        token_sum += tokens;
+        results.push(result);
+        if results.len() >= config.max_results {
+            break;
+        }
+    }
+
+    RecallResponse {
+        results,
+        total_matched,
+        token_estimate: token_sum,
+        requested_max_results: None,
+        degraded: false,
+    }
+}
+
+/// Combine per-database ID hydration responses, preserving the order of `ids`.
+pub fn merge_id_responses(responses: Vec<RecallResponse>, ids: &[String]) -> RecallResponse {
+    let mut by_id: HashMap<String, SearchResult> = responses
+        .into_iter()
+        .flat_map(|r| r.results)
+        .map(|r| (r.id.clone(), r))
+        .collect();
+
+    let results: Vec<SearchResult> = ids.iter().filter_map(|id| by_id.remove(id)).collect();
+    let token_estimate = results.iter().map(|r| r.content.len() / 4).sum();
+
+    RecallResponse {
+        total_matched: results.len(),
+        results,
+        token_estimate,
+        requested_max_results: None,
+        degraded: false,
+    }
+}
+
 /// Convert full results to summary format.
 pub fn to_summary(response: &RecallResponse) -> RecallSummaryResponse {
     let results: Vec<SummaryResult> = response
@@ -371,21 +1032,228 @@ pub fn to_summary(response: &RecallResponse) -> RecallSummaryResponse {
         results,
         total_matched: response.total_matched,
         token_estimate,
+        requested_max_results: response.requested_max_results,
+        degraded: response.degraded,
+    }
+}
+
+/// Result fields that can be selected with [`project_fields`].
+pub const PROJECTABLE_FIELDS: &[&str] = &[
+    "id",
+    "type",
+    "content",
+    "confidence",
+    "score",
+    "created_at",
+    "metadata",
+    "source_uri",
+    "superseded_by",
+    "relations",
+    "snippet",
+];
+
+/// Check that every requested field name is in [`PROJECTABLE_FIELDS`].
+pub fn validate_fields(fields: &[String]) -> Result<()> {
+    if fields.is_empty() {
+        bail!("fields must not be empty");
+    }
+    if let Some(unknown) = fields.iter().find(|f| !PROJECTABLE_FIELDS.contains(&f.as_str())) {
+        bail!(
+            "unknown field '{unknown}'; expected one of: {}",
+            PROJECTABLE_FIELDS.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Keep only the named fields of each result.
+///
+/// Unknown field names are rejected. Optional fields that are unset on a result are
+/// still omitted. The token estimate is recomputed from the projected JSON.
+pub fn project_fields(response: &RecallResponse, fields: &[String]) -> Result<serde_json::Value> {
+    validate_fields(fields)?;
+
+    let mut token_estimate = 0;
+    let mut results = Vec::with_capacity(response.results.len());
+    for result in &response.results {
+        let serde_json::Value::Object(mut full) = serde_json::to_value(result)? else {
+            unreachable!("SearchResult serializes to an object");
+        };
+        full.retain(|key, _| fields.iter().any(|f| f == key));
+        let projected = serde_json::Value::Object(full);
+        token_estimate += projected.to_string().len() / 4;
+        results.push(projected);
+    }
+
+    let mut projected = serde_json::json!({
+        "results": results,
+        "total_matched": response.total_matched,
+        "token_estimate": token_estimate,
+    });
+    if let Some(requested) = response.requested_max_results {
+        projected["requested_max_results"] = requested.into();
+    }
+    if response.degraded {
+        projected["degraded"] = true.into();
+    }
+    Ok(projected)
+}
+
+/// List memories without a query, in `filter.sort` order, one page at a time.
+///
+/// Unlike recall, listing does not count as an access.
+pub fn list_memories(conn: &Connection, filter: &ListFilter) -> Result<Vec<Memory>> {
+    use rusqlite::types::Value;
+
+    let mut conditions: Vec<&str> = Vec::new();
+    let mut params: Vec<Value> = Vec::new();
+
+    if !filter.include_superseded {
+        conditions.push("superseded_by IS NULL");
+    }
+    if let Some(ref source_uri) = filter.source_uri {
+        conditions.push("source_uri = ?");
+        params.push(source_uri.clone().into());
+    }
+    if let Some(memory_type) = filter.memory_type {
+        conditions.push("type = ?");
+        params.push(memory_type.as_str().to_string().into());
+    }
+    if let Some(scope) = filter.scope {
+        conditions.push("scope = ?");
+        params.push(scope.as_str().to_string().into());
+    }
+    if let Some(ref group) = filter.group {
+        conditions.push("(scope = 'global' OR source_group = ?)");
+        params.push(group.clone().into());
+    }
+    if !filter.all_sessions {
+        conditions.push("(scope != 'session' OR session_id IS ?)");
+        params.push(filter.session_id.clone().into());
+    }
+    if let Some(min) = filter.min_confidence {
+        conditions.push("confidence >= ?");
+        params.push(min.into());
+    }
+    if let Some(max) = filter.max_confidence {
+        conditions.push("confidence <= ?");
+        params.push(max.into());
+    }
+    // julianday() compares instants, whatever offset each timestamp was written with
+    let after = parse_timestamp_bound("created_after", filter.created_after.as_deref())?;
+    if let Some(after) = after {
+        conditions.push("julianday(created_at) >= julianday(?)");
+        params.push(after.to_rfc3339().into());
+    }
+    let before = parse_timestamp_bound("created_before", filter.created_before.as_deref())?;
+    if let Some(before) = before {
+        conditions.push("julianday(created_at) <= julianday(?)");
+        params.push(before.to_rfc3339().into());
+    }
+
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    };
+    params.push(filter.limit.map_or(-1, |limit| limit as i64).into());
+    params.push((filter.offset as i64).into());
+    let sql = format!(
+        "SELECT {MEMORY_COLUMNS} FROM memories {where_clause} ORDER BY {} LIMIT ? OFFSET ?",
+        filter.sort.order_by()
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let results = stmt
+        .query_map(rusqlite::params_from_iter(params), memory_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(results)
+}
+
+/// Combine per-database listings into one page.
+///
+/// Each database must have been listed from offset 0 with a limit of
+/// `filter.offset + filter.limit`; the merged list is re-sorted, then the offset and
+/// limit are applied across it.
+pub fn merge_listings(listings: Vec<Vec<Memory>>, filter: &ListFilter) -> Vec<Memory> {
+    let mut merged: Vec<Memory> = listings.into_iter().flatten().collect();
+    merged.sort_by(|a, b| filter.sort.compare(a, b));
+    merged
+        .into_iter()
+        .skip(filter.offset)
+        .take(filter.limit.unwrap_or(usize::MAX))
+        .collect()
+}
+
+/// Most candidates listed when an ID prefix is ambiguous.
+const MAX_PREFIX_CANDIDATES: usize = 10;
+
+/// IDs matching `id_or_prefix`: just the ID itself on an exact match, otherwise every
+/// ID starting with it (up to one more than can be listed in an ambiguity error).
+pub fn find_memory_ids(conn: &Connection, id_or_prefix: &str) -> Result<Vec<String>> {
+    let exact: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM memories WHERE id = ?1",
+        params![id_or_prefix],
+        |row| row.get(0),
+    )?;
+    if exact {
+        return Ok(vec![id_or_prefix.to_string()]);
+    }
+
+    let escaped = id_or_prefix
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    let mut stmt = conn.prepare(
+        "SELECT id FROM memories WHERE id LIKE ?1 || '%' ESCAPE '\\' ORDER BY id LIMIT ?2",
+    )?;
+    let ids = stmt
+        .query_map(params![escaped, MAX_PREFIX_CANDIDATES as i64 + 1], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(ids)
+}
+
+/// Pick the single candidate for an ID prefix, or explain why there isn't one.
+pub fn unique_memory_id(id_or_prefix: &str, mut candidates: Vec<String>) -> Result<String> {
+    match candidates.len() {
+        0 => bail!("memory not found: {id_or_prefix}"),
+        1 => Ok(candidates.remove(0)),
+        n => {
+            let more = if n > MAX_PREFIX_CANDIDATES { ", ..." } else { "" };
+            candidates.truncate(MAX_PREFIX_CANDIDATES);
+            bail!(
+                "memory ID prefix '{id_or_prefix}' is ambiguous; candidates: {}{more}",
+                candidates.join(", ")
+            )
+        }
     }
 }
 
-/// Inspect a single memory by ID with optional relations and audit log.
+/// Resolve a full memory ID or a unique prefix of one (like git short hashes).
+pub fn resolve_memory_id(conn: &Connection, id_or_prefix: &str) -> Result<String> {
+    if id_or_prefix.is_empty() {
+        bail!("memory ID must not be empty");
+    }
+    unique_memory_id(id_or_prefix, find_memory_ids(conn, id_or_prefix)?)
+}
+
+/// Inspect a single memory by ID (or unique ID prefix) with optional relations and audit log.
 pub fn inspect_memory(
     conn: &Connection,
     memory_id: &str,
     include_relations: bool,
     include_log: bool,
 ) -> Result<InspectResponse> {
+    let memory_id = resolve_memory_id(conn, memory_id)?;
+    let memory_id = memory_id.as_str();
+
     // Fetch the memory
     let memory = conn
         .query_row(
             "SELECT id, type, content, source_group, scope, confidence, access_count, \
-             last_accessed, created_at, updated_at, superseded_by, metadata \
+             last_accessed, created_at, updated_at, superseded_by, metadata, source_uri, content_hash, \
+             importance \
              FROM memories WHERE id = ?1",
             params![memory_id],
             |row| {
@@ -395,6 +1263,7 @@ pub fn inspect_memory(
                     memory_type: row.get(1)?,
                     content: row.get(2)?,
                     confidence: row.get(5)?,
+                    importance: row.get(14)?,
                     access_count: row.get(6)?,
                     last_accessed: row.get(7)?,
                     created_at: row.get(8)?,
@@ -402,6 +1271,8 @@ pub fn inspect_memory(
                     superseded_by: row.get(10)?,
                     metadata: metadata_str
                         .and_then(|s| serde_json::from_str(&s).ok()),
+                    source_uri: row.get(12)?,
+                    content_hash: row.get(13)?,
                 })
             },
         )
@@ -444,32 +1315,30 @@ pub fn inspect_memory(
     Ok(InspectResponse {
         memory,
         relations,
+        incoming_relations: None,
         log,
+        embedding: None,
     })
 }
 
-// ── Internal helpers ──────────────────────────────────────────────────────────
-
-/// Fetch outbound relations for a memory.
-///
-/// Returns `Some(vec)` if the memory has relations (possibly empty),
-/// or `None` if there are no relations at all (for cleaner serialization).
-fn fetch_outbound_relations(
+/// Relations whose object is `memory_id`, e.g. `(Alice, manages, Bob)` when inspecting
+/// Bob. Returns `None` if there are none, like the outbound list.
+pub fn incoming_relations(
     conn: &Connection,
     memory_id: &str,
-) -> Result<Option<Vec<RelationEntry>>> {
+) -> Result<Option<Vec<IncomingRelationEntry>>> {
     let mut stmt = conn.prepare(
         "SELECT er.predicate, m.id, m.type, m.content \
          FROM entity_relations er \
-         JOIN memories m ON er.object_id = m.id \
-         WHERE er.subject_id = ?1",
+         JOIN memories m ON er.subject_id = m.id \
+         WHERE er.object_id = ?1",
     )?;
-    let rows: Vec<RelationEntry> = stmt
+    let rows: Vec<IncomingRelationEntry> = stmt
         .query_map(params![memory_id], |row| {
             let content: String = row.get(3)?;
-            Ok(RelationEntry {
+            Ok(IncomingRelationEntry {
                 predicate: row.get(0)?,
-                object: RelationTarget {
+                subject: RelationTarget {
                     id: row.get(1)?,
                     memory_type: row.get(2)?,
                     preview: truncate_preview(&content, 100),
@@ -485,9 +1354,321 @@ fn fetch_outbound_relations(
     }
 }
 
-/// Vector KNN search via sqlite-vec.
-fn vector_search(
-    conn: &Connection,
+/// Inspection results for several memories, from [`inspect_memories`].
+#[derive(Debug, Serialize)]
+pub struct InspectBatchResponse {
+    /// One entry per found memory, in request order.
+    pub results: Vec<InspectResponse>,
+    /// Requested IDs (or prefixes) that matched no memory.
+    pub not_found: Vec<String>,
+}
+
+/// Inspect several memories by ID (or unique ID prefix) on one connection.
+///
+/// IDs that match nothing are listed in `not_found` rather than failing the batch;
+/// an ambiguous prefix is still an error.
+pub fn inspect_memories(
+    conn: &Connection,
+    memory_ids: &[String],
+    include_relations: bool,
+    include_log: bool,
+) -> Result<InspectBatchResponse> {
+    let mut results = Vec::with_capacity(memory_ids.len());
+    let mut not_found = Vec::new();
+    for id in memory_ids {
+        if find_memory_ids(conn, id)?.is_empty() {
+            not_found.push(id.clone());
+            continue;
+        }
+        results.push(inspect_memory(conn, id, include_relations, include_log)?);
+    }
+    Ok(InspectBatchResponse { results, not_found })
+}
+
+/// Combine per-database batch inspections, preserving the order of `memory_ids`.
+///
+/// An ID is reported as not found only if no database had it.
+pub fn merge_inspect_responses(
+    responses: Vec<InspectBatchResponse>,
+    memory_ids: &[String],
+) -> InspectBatchResponse {
+    if responses.len() == 1 {
+        return responses.into_iter().next().expect("one response");
+    }
+
+    let mut found: Vec<Option<InspectResponse>> = responses
+        .into_iter()
+        .flat_map(|r| r.results)
+        .map(Some)
+        .collect();
+
+    let mut results = Vec::new();
+    let mut not_found = Vec::new();
+    for id in memory_ids {
+        let slot = found
+            .iter_mut()
+            .find(|r| r.as_ref().is_some_and(|r| r.memory.id.starts_with(id.as_str())));
+        match slot.and_then(Option::take) {
+            Some(response) => results.push(response),
+            None => not_found.push(id.clone()),
+        }
+    }
+    InspectBatchResponse { results, not_found }
+}
+
+/// Result of [`touch_memories`].
+#[derive(Debug, Serialize)]
+pub struct TouchResponse {
+    /// Number of memories whose access count and `last_accessed` were bumped.
+    pub touched: usize,
+    /// Requested IDs that matched no memory.
+    pub not_found: Vec<String>,
+}
+
+/// Record an access to each memory in `memory_ids` without reading its content.
+///
+/// Takes full IDs. Bumps `access_count` and `last_accessed` exactly as a recall would,
+/// which protects the memories from cleanup; missing IDs are reported, not errors.
+pub fn touch_memories(conn: &Connection, memory_ids: &[String]) -> Result<TouchResponse> {
+    let mut existing = Vec::with_capacity(memory_ids.len());
+    let mut not_found = Vec::new();
+    for id in memory_ids {
+        let exists: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM memories WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )?;
+        if exists {
+            existing.push(id.as_str());
+        } else {
+            not_found.push(id.clone());
+        }
+    }
+    update_access(conn, &existing, 0.0)?;
+    Ok(TouchResponse {
+        touched: existing.len(),
+        not_found,
+    })
+}
+
+/// Combine per-database touches. An ID is reported as not found only if no database had it.
+pub fn merge_touch_responses(responses: Vec<TouchResponse>) -> TouchResponse {
+    let mut responses = responses.into_iter();
+    let Some(mut merged) = responses.next() else {
+        return TouchResponse {
+            touched: 0,
+            not_found: Vec::new(),
+        };
+    };
+    for response in responses {
+        merged.touched += response.touched;
+        merged.not_found.retain(|id| response.not_found.contains(id));
+    }
+    merged
+}
+
+/// Read a memory's stored embedding from `memories_vec`.
+///
+/// Takes a full memory ID. Returns `None` if the memory has no vector row.
+pub fn stored_embedding(conn: &Connection, memory_id: &str) -> Result<Option<StoredEmbedding>> {
+    let bytes: Option<Vec<u8>> = conn
+        .query_row(
+            "SELECT embedding FROM memories_vec WHERE id = ?1",
+            params![memory_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    Ok(bytes.map(|bytes| {
+        let values = super::bytes_to_embedding(&bytes);
+        let norm = values
+            .iter()
+            .map(|v| f64::from(*v) * f64::from(*v))
+            .sum::<f64>()
+            .sqrt();
+        StoredEmbedding {
+            dimension: values.len(),
+            norm,
+            values,
+        }
+    }))
+}
+
+/// Columns selected for a full [`Memory`] record, in [`memory_from_row`] order.
+pub(crate) const MEMORY_COLUMNS: &str = "id, type, content, source_group, scope, confidence, \
+     access_count, last_accessed, created_at, updated_at, superseded_by, metadata, source_uri, \
+     content_hash, importance, session_id, expires_at, tags";
+
+/// Build a [`Memory`] from a row selected with [`MEMORY_COLUMNS`].
+pub(crate) fn memory_from_row(row: &rusqlite::Row) -> rusqlite::Result<Memory> {
+    let metadata_str: Option<String> = row.get(11)?;
+    let memory_type_str: String = row.get(1)?;
+    let scope_str: String = row.get(4)?;
+    Ok(Memory {
+        id: row.get(0)?,
+        memory_type: memory_type_str
+            .parse()
+            .map_err(|_| rusqlite::Error::InvalidQuery)?,
+        content: row.get(2)?,
+        source_group: row.get(3)?,
+        scope: scope_str
+            .parse()
+            .map_err(|_| rusqlite::Error::InvalidQuery)?,
+        confidence: row.get(5)?,
+        importance: row.get(14)?,
+        access_count: row.get(6)?,
+        last_accessed: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+        superseded_by: row.get(10)?,
+        metadata: metadata_str.and_then(|s| serde_json::from_str(&s).ok()),
+        source_uri: row.get(12)?,
+        content_hash: row.get(13)?,
+        session_id: row.get(15)?,
+        expires_at: row.get(16)?,
+        tags: row
+            .get::<_, Option<String>>(17)?
+            .and_then(|s| serde_json::from_str(&s).ok()),
+        embedding: None,
+    })
+}
+
+/// Memories updated and relations created after `timestamp` (RFC 3339), for
+/// incremental sync.
+///
+/// Superseded memories are included and flagged; hard-deleted memories (from
+/// `loci cleanup`) leave no row behind. The returned `cursor` is read
+/// before querying, so a change racing with this call shows up again next time
+/// rather than being missed.
+pub fn changed_since(conn: &Connection, timestamp: &str) -> Result<ChangeSet> {
+    let since = chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map_err(|e| anyhow::anyhow!("invalid timestamp '{timestamp}': {e}"))?
+        .with_timezone(&chrono::Utc)
+        .to_rfc3339();
+    let cursor = chrono::Utc::now().to_rfc3339();
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {MEMORY_COLUMNS} FROM memories WHERE updated_at > ?1 ORDER BY updated_at"
+    ))?;
+    let memories = stmt
+        .query_map(params![since], memory_from_row)?
+        .map(|memory| {
+            memory.map(|memory| ChangedMemory {
+                superseded: memory.superseded_by.is_some(),
+                memory,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, subject_id, predicate, object_id, created_at \
+         FROM entity_relations WHERE created_at > ?1 ORDER BY created_at",
+    )?;
+    let relations = stmt
+        .query_map(params![since], |row| {
+            Ok(EntityRelation {
+                id: row.get(0)?,
+                subject_id: row.get(1)?,
+                predicate: row.get(2)?,
+                object_id: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ChangeSet {
+        memories,
+        relations,
+        cursor,
+    })
+}
+
+/// Highest `memory_log` row ID, or 0 if the log is empty.
+pub fn max_log_id(conn: &Connection) -> Result<i64> {
+    let id: Option<i64> = conn.query_row("SELECT MAX(id) FROM memory_log", [], |row| row.get(0))?;
+    Ok(id.unwrap_or(0))
+}
+
+/// Audit log entries with a row ID greater than `after_id`, oldest first.
+pub fn log_entries_after(conn: &Connection, after_id: i64, filter: &LogFilter) -> Result<Vec<LogEvent>> {
+    let mut conditions = vec!["l.id > ?"];
+    let mut params: Vec<&dyn rusqlite::types::ToSql> = vec![&after_id];
+
+    if let Some(ref operation) = filter.operation {
+        conditions.push("l.operation = ?");
+        params.push(operation);
+    }
+    if let Some(ref group) = filter.group {
+        conditions.push("m.source_group = ?");
+        params.push(group);
+    }
+
+    let sql = format!(
+        "SELECT l.id, l.memory_id, l.operation, l.details, l.created_at \
+         FROM memory_log l LEFT JOIN memories m ON m.id = l.memory_id \
+         WHERE {} ORDER BY l.id",
+        conditions.join(" AND ")
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let events = stmt
+        .query_map(params.as_slice(), |row| {
+            let details_str: Option<String> = row.get(3)?;
+            Ok(LogEvent {
+                id: row.get(0)?,
+                memory_id: row.get(1)?,
+                entry: LogEntry {
+                    operation: row.get(2)?,
+                    details: details_str.and_then(|s| serde_json::from_str(&s).ok()),
+                    created_at: row.get(4)?,
+                },
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(events)
+}
+
+// ── Internal helpers ──────────────────────────────────────────────────────────
+
+/// Fetch outbound relations for a memory.
+///
+/// Returns `Some(vec)` if the memory has relations (possibly empty),
+/// or `None` if there are no relations at all (for cleaner serialization).
+fn fetch_outbound_relations(
+    conn: &Connection,
+    memory_id: &str,
+) -> Result<Option<Vec<RelationEntry>>> {
+    let mut stmt = conn.prepare(
+        "SELECT er.predicate, m.id, m.type, m.content \
+         FROM entity_relations er \
+         JOIN memories m ON er.object_id = m.id \
+         WHERE er.subject_id = ?1",
+    )?;
+    let rows: Vec<RelationEntry> = stmt
+        .query_map(params![memory_id], |row| {
+            let content: String = row.get(3)?;
+            Ok(RelationEntry {
+                predicate: row.get(0)?,
+                object: RelationTarget {
+                    id: row.get(1)?,
+                    memory_type: row.get(2)?,
+                    preview: truncate_preview(&content, 100),
+                },
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if rows.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(rows))
+    }
+}
+
+/// Vector KNN search via sqlite-vec.
+fn vector_search(
+    conn: &Connection,
     embedding: &[f32],
     limit: usize,
 ) -> Result<Vec<(String, f64)>> {
@@ -508,59 +1689,124 @@ fn vector_search(
 ///
 /// Returns (id, rank) pairs. FTS5 rank is negative (more negative = better),
 /// so we negate it for consistent ordering.
-fn fts_search(conn: &Connection, query_text: &str, limit: usize) -> Result<Vec<(String, f64)>> {
+fn fts_search(
+    conn: &Connection,
+    query_text: &str,
+    stopwords: &[String],
+    limit: usize,
+) -> Result<Vec<(String, f64)>> {
     // Escape the query for FTS5: wrap each word in double quotes to avoid syntax errors
-    let escaped = escape_fts_query(query_text);
+    let escaped = escape_fts_query(query_text, stopwords);
     if escaped.is_empty() {
         return Ok(Vec::new());
     }
 
+    fts_match(conn, &escaped, limit)
+}
+
+/// Run an FTS5 MATCH expression, treating a rejected expression as "no matches".
+///
+/// Escaping should prevent syntax errors, but if FTS5 still refuses the query the
+/// vector half of a hybrid search can carry the recall instead of failing it.
+fn fts_match(conn: &Connection, match_expr: &str, limit: usize) -> Result<Vec<(String, f64)>> {
     let mut stmt = conn.prepare(
         "SELECT id, rank FROM memories_fts \
          WHERE memories_fts MATCH ?1 ORDER BY rank LIMIT ?2",
     )?;
     let results = stmt
-        .query_map(params![escaped, limit as i64], |row| {
+        .query_map(params![match_expr, limit as i64], |row| {
             Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?))
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
-    Ok(results)
+        })
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>());
+    match results {
+        Ok(results) => Ok(results),
+        Err(e @ rusqlite::Error::SqliteFailure(..)) => {
+            tracing::debug!(query = match_expr, error = %e, "FTS query rejected; using no keyword results");
+            Ok(Vec::new())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Excerpt of one memory's content around the matches of `match_expr`.
+///
+/// A lookup failure only costs the snippet, so it is logged and mapped to `None`.
+fn fts_snippet(conn: &Connection, match_expr: &str, memory_id: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT snippet(memories_fts, 0, ?1, ?1, '...', ?2) FROM memories_fts \
+         WHERE memories_fts MATCH ?3 AND id = ?4",
+        params![SNIPPET_MARKER, SNIPPET_TOKENS, match_expr, memory_id],
+        |row| row.get(0),
+    )
+    .optional()
+    .unwrap_or_else(|e| {
+        tracing::debug!(id = memory_id, error = %e, "FTS snippet lookup failed");
+        None
+    })
 }
 
 /// Escape a user query for FTS5 MATCH syntax.
 ///
 /// Wraps each whitespace-delimited word in double quotes and joins with spaces
-/// so FTS5 treats them as individual terms (implicit AND). Strips empty tokens.
-fn escape_fts_query(query: &str) -> String {
-    query
-        .split_whitespace()
-        .map(|word| {
-            // Strip any existing quotes and wrap in fresh ones
-            let clean = word.replace('"', "");
-            format!("\"{clean}\"")
-        })
-        .filter(|w| w != "\"\"")
-        .collect::<Vec<_>>()
-        .join(" ")
+/// so FTS5 treats them as individual terms (implicit AND). A word with a leading
+/// `-` becomes a trailing `NOT "word"` clause instead. Strips empty tokens and any
+/// word in `stopwords` (case-insensitive). FTS5's NOT needs a left operand, so
+/// exclusions are dropped when no positive term remains.
+fn escape_fts_query(query: &str, stopwords: &[String]) -> String {
+    let mut required = Vec::new();
+    let mut excluded = Vec::new();
+    for word in query.split_whitespace() {
+        let (negated, word) = match word.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, word),
+        };
+        if stopwords.iter().any(|s| s.eq_ignore_ascii_case(word)) {
+            continue;
+        }
+        // Strip any existing quotes and wrap in fresh ones
+        let clean = word.replace('"', "");
+        if clean.is_empty() {
+            continue;
+        }
+        let term = format!("\"{clean}\"");
+        if negated {
+            excluded.push(term);
+        } else {
+            required.push(term);
+        }
+    }
+
+    if required.is_empty() {
+        return String::new();
+    }
+    let mut expr = required.join(" ");
+    for term in excluded {
+        expr.push_str(" NOT ");
+        expr.push_str(&term);
+    }
+    expr
 }
 
 /// Reciprocal Rank Fusion merge.
 ///
 /// Combines ranked lists from vector and FTS search. Documents appearing in
 /// both lists get additive scores; those in only one list get a single score.
+/// Each list's reciprocal ranks are scaled by its weight.
 fn rrf_merge(
     vec_results: &[(String, f64)],
     fts_results: &[(String, f64)],
     k: usize,
+    vec_weight: f64,
+    fts_weight: f64,
 ) -> Vec<(String, f64)> {
     let mut scores: HashMap<String, f64> = HashMap::new();
 
     for (rank, (id, _distance)) in vec_results.iter().enumerate() {
-        *scores.entry(id.clone()).or_insert(0.0) += 1.0 / (k as f64 + rank as f64);
+        *scores.entry(id.clone()).or_insert(0.0) += vec_weight / (k as f64 + rank as f64);
     }
 
     for (rank, (id, _rank_score)) in fts_results.iter().enumerate() {
-        *scores.entry(id.clone()).or_insert(0.0) += 1.0 / (k as f64 + rank as f64);
+        *scores.entry(id.clone()).or_insert(0.0) += fts_weight / (k as f64 + rank as f64);
     }
 
     let mut merged: Vec<(String, f64)> = scores.into_iter().collect();
@@ -568,6 +1814,78 @@ fn rrf_merge(
     merged
 }
 
+/// Exponential age decay `0.5^(age_days / half_life_days)` for a `created_at` timestamp.
+///
+/// Unparseable or future timestamps get no decay.
+fn recency_factor(created_at: &str, now: chrono::DateTime<chrono::Utc>, half_life_days: f64) -> f64 {
+    let Ok(created) = chrono::DateTime::parse_from_rfc3339(created_at) else {
+        return 1.0;
+    };
+    let age_days = (now - created.with_timezone(&chrono::Utc)).num_seconds().max(0) as f64 / 86_400.0;
+    0.5f64.powf(age_days / half_life_days)
+}
+
+/// Fetch stored embeddings for `ids`; memories without a vector row are left out.
+fn fetch_embeddings(conn: &Connection, ids: &[&str]) -> Result<HashMap<String, Vec<f32>>> {
+    let mut stmt = conn.prepare_cached("SELECT embedding FROM memories_vec WHERE id = ?1")?;
+    let mut map = HashMap::new();
+    for id in ids {
+        let bytes: Option<Vec<u8>> = stmt.query_row(params![id], |row| row.get(0)).optional()?;
+        if let Some(bytes) = bytes {
+            map.insert(id.to_string(), super::bytes_to_embedding(&bytes));
+        }
+    }
+    Ok(map)
+}
+
+/// Cosine similarity of two embeddings, 0.0 if either is zero.
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f64, 0.0f64, 0.0f64);
+    for (x, y) in a.iter().zip(b) {
+        dot += f64::from(*x) * f64::from(*y);
+        norm_a += f64::from(*x) * f64::from(*x);
+        norm_b += f64::from(*y) * f64::from(*y);
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Greedy maximal-marginal-relevance ordering of score-sorted `ranked`.
+///
+/// Relevance is each score divided by the top score, so it shares cosine's 0..1 range.
+/// Candidates without an embedding are never penalized. Scores are kept as-is; only
+/// the order changes.
+fn mmr_rerank(
+    ranked: Vec<(MemoryRow, f64)>,
+    embeddings: &HashMap<String, Vec<f32>>,
+    lambda: f64,
+) -> Vec<(MemoryRow, f64)> {
+    let top = ranked.first().map_or(0.0, |(_, s)| *s);
+    let mut remaining = ranked;
+    let mut selected: Vec<(MemoryRow, f64)> = Vec::with_capacity(remaining.len());
+    while !remaining.is_empty() {
+        let mut best = (0, f64::NEG_INFINITY);
+        for (i, (mem, score)) in remaining.iter().enumerate() {
+            let relevance = if top > 0.0 { score / top } else { 0.0 };
+            let redundancy = embeddings.get(&mem.id).map_or(0.0, |emb| {
+                selected
+                    .iter()
+                    .filter_map(|(s, _)| embeddings.get(&s.id))
+                    .map(|other| cosine_similarity(emb, other))
+                    .fold(0.0, f64::max)
+            });
+            let mmr = lambda * relevance - (1.0 - lambda) * redundancy;
+            if mmr > best.1 {
+                best = (i, mmr);
+            }
+        }
+        selected.push(remaining.remove(best.0));
+    }
+    selected
+}
+
 /// Batch-fetch memory records by IDs.
 fn fetch_memories(conn: &Connection, ids: &[&str]) -> Result<HashMap<String, MemoryRow>> {
     if ids.is_empty() {
@@ -578,7 +1896,7 @@ fn fetch_memories(conn: &Connection, ids: &[&str]) -> Result<HashMap<String, Mem
     let placeholders: Vec<String> = (1..=ids.len()).map(|i| format!("?{i}")).collect();
     let sql = format!(
         "SELECT id, type, content, source_group, scope, confidence, access_count, \
-         superseded_by, created_at, metadata \
+         superseded_by, created_at, metadata, source_uri, importance, session_id, expires_at, tags \
          FROM memories WHERE id IN ({})",
         placeholders.join(", ")
     );
@@ -602,6 +1920,14 @@ fn fetch_memories(conn: &Connection, ids: &[&str]) -> Result<HashMap<String, Mem
                 superseded_by: row.get(7)?,
                 created_at: row.get(8)?,
                 metadata: metadata_str.and_then(|s| serde_json::from_str(&s).ok()),
+                source_uri: row.get(10)?,
+                importance: row.get(11)?,
+                session_id: row.get(12)?,
+                expires_at: row.get(13)?,
+                tags: row
+                    .get::<_, Option<String>>(14)?
+                    .and_then(|s| serde_json::from_str(&s).ok())
+                    .unwrap_or_default(),
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
@@ -613,23 +1939,25 @@ fn fetch_memories(conn: &Connection, ids: &[&str]) -> Result<HashMap<String, Mem
     Ok(map)
 }
 
-/// Batch update access_count and last_accessed for returned results.
-fn update_access(conn: &Connection, ids: &[&str]) -> Result<()> {
+/// Batch update access_count and last_accessed for returned results, adding
+/// `reinforce_amount` to each one's confidence (capped at 1.0).
+pub(crate) fn update_access(conn: &Connection, ids: &[&str], reinforce_amount: f64) -> Result<()> {
     if ids.is_empty() {
         return Ok(());
     }
     let now = chrono::Utc::now().to_rfc3339();
     let mut stmt = conn.prepare(
-        "UPDATE memories SET access_count = access_count + 1, last_accessed = ?1 WHERE id = ?2",
+        "UPDATE memories SET access_count = access_count + 1, last_accessed = ?1, \
+         confidence = MIN(confidence + ?2, 1.0) WHERE id = ?3",
     )?;
     for id in ids {
-        stmt.execute(params![now, id])?;
+        stmt.execute(params![now, reinforce_amount, id])?;
     }
     Ok(())
 }
 
 /// Truncate content to max_chars, appending "..." if truncated.
-fn truncate_preview(content: &str, max_chars: usize) -> String {
+pub(crate) fn truncate_preview(content: &str, max_chars: usize) -> String {
     if content.len() <= max_chars {
         content.to_string()
     } else {
@@ -647,15 +1975,10 @@ fn truncate_preview(content: &str, max_chars: usize) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db;
     use crate::memory::store;
 
     fn test_db() -> Connection {
-        db::load_sqlite_vec();
-        let conn = Connection::open_in_memory().unwrap();
-        conn.pragma_update(None, "foreign_keys", "ON").unwrap();
-        crate::db::schema::init_schema(&conn).unwrap();
-        conn
+        crate::db::open_memory_database().unwrap()
     }
 
     /// Unit vector along dimension 0.
@@ -700,19 +2023,109 @@ mod tests {
 
     fn default_filter(group: &str) -> SearchFilter {
         SearchFilter {
-            memory_type: None,
-            scope: None,
-            group: group.to_string(),
             min_confidence: 0.1,
+            ..SearchFilter::for_group(group)
         }
     }
 
     fn default_config() -> SearchConfig {
-        SearchConfig {
-            max_results: 5,
-            token_budget: 4000,
-            rrf_k: 60,
+        SearchConfig::default()
+    }
+
+    #[test]
+    fn test_candidate_limits_follow_per_retriever_multipliers() {
+        let filter = SearchFilter {
+            exclude_ids: vec!["a".into(), "b".into()],
+            ..default_filter("default")
+        };
+
+        // The shared default reproduces a single pool for both retrievers
+        let limits = CandidateLimits::new(&default_config(), &filter);
+        assert_eq!(limits, CandidateLimits { vector: 17, fts: 17 });
+
+        let config = SearchConfig {
+            max_results: 10,
+            vector_candidate_multiplier: 3,
+            fts_candidate_multiplier: 1,
+            ..default_config()
+        };
+        let limits = CandidateLimits::new(&config, &filter);
+        assert_eq!(limits, CandidateLimits { vector: 32, fts: 12 });
+
+        let balanced = SearchConfig {
+            balance_sources: true,
+            ..config.clone()
+        };
+        let limits = CandidateLimits::new(&balanced, &filter);
+        assert_eq!(limits, CandidateLimits { vector: 16, fts: 6 });
+
+        // Balancing only applies when both retrievers run
+        let vector_only = SearchConfig {
+            mode: SearchMode::Vector,
+            ..balanced
+        };
+        assert_eq!(CandidateLimits::new(&vector_only, &filter).vector, 32);
+    }
+
+    #[test]
+    fn test_balanced_sources_keep_strong_vector_hit() {
+        let mut conn = test_db();
+        // Best vector match, but no keyword overlap with the query
+        let strong = insert_test_memory(
+            &mut conn,
+            "Rollout runbook for the payments service",
+            MemoryType::Semantic,
+            Scope::Global,
+            "default",
+            1.0,
+            &embedding_a(),
+        );
+        // Weak vector matches that also weakly match the keyword (long content)
+        for i in 0..5 {
+            let mut emb = vec![0.0f32; 384];
+            emb[0] = 0.5;
+            emb[10 + i] = 0.866;
+            insert_test_memory(
+                &mut conn,
+                &format!("Meeting notes {i}: we talked about lunch, hiring, budgets, the offsite, and also deploy"),
+                MemoryType::Semantic,
+                Scope::Global,
+                "default",
+                1.0,
+                &emb,
+            );
+        }
+        // Strong keyword matches with unrelated embeddings
+        for i in 0..3 {
+            let mut emb = vec![0.0f32; 384];
+            emb[100 + i] = 1.0;
+            insert_test_memory(
+                &mut conn,
+                &format!("deploy checklist {i}"),
+                MemoryType::Semantic,
+                Scope::Global,
+                "default",
+                1.0,
+                &emb,
+            );
         }
+
+        let config = SearchConfig {
+            max_results: 2,
+            ..default_config()
+        };
+        let filter = default_filter("default");
+
+        // Unbalanced, weak hits found by both retrievers outrank the vector-only hit
+        let response = recall_by_query(&conn, &embedding_a(), "deploy", &filter, &config).unwrap();
+        assert!(response.results.iter().all(|r| r.id != strong));
+
+        let balanced = SearchConfig {
+            balance_sources: true,
+            ..config
+        };
+        let response = recall_by_query(&conn, &embedding_a(), "deploy", &filter, &balanced).unwrap();
+        assert!(response.results.iter().any(|r| r.id == strong));
     }
 
     #[test]
@@ -744,52 +2157,600 @@ mod tests {
         assert!(results[0].1 < 0.01); // very close distance
     }
 
-    #[test]
-    fn test_fts_search_matches_keywords() {
+    /// Helper: alpha (embedding_a) and beta (embedding_b, mentions Python), searched
+    /// with embedding_a and the keyword "python" in the given mode.
+    fn search_in_mode(mode: SearchMode) -> (RecallResponse, String, String) {
         let mut conn = test_db();
         let id_a = insert_test_memory(
             &mut conn,
-            "The quantum computer operates at very low temperatures",
+            "Alpha memory about Rust",
             MemoryType::Semantic,
             Scope::Global,
             "default",
             1.0,
             &embedding_a(),
         );
-        let _id_b = insert_test_memory(
+        let id_b = insert_test_memory(
             &mut conn,
-            "Rust is a systems programming language",
+            "Beta memory about Python",
             MemoryType::Semantic,
             Scope::Global,
             "default",
             1.0,
             &embedding_b(),
         );
-
-        let results = fts_search(&conn, "quantum computer", 10).unwrap();
-        assert!(!results.is_empty());
-        assert_eq!(results[0].0, id_a);
+        let config = SearchConfig {
+            mode,
+            ..default_config()
+        };
+        let embedding = if mode == SearchMode::Keyword { Vec::new() } else { embedding_a() };
+        let response =
+            recall_by_query(&conn, &embedding, "python", &default_filter("default"), &config).unwrap();
+        (response, id_a, id_b)
     }
 
     #[test]
-    fn test_rrf_merge_combines_signals() {
-        let vec_results = vec![
-            ("doc_a".to_string(), 0.1),
-            ("doc_b".to_string(), 0.3),
-            ("doc_c".to_string(), 0.5),
-        ];
-        let fts_results = vec![
-            ("doc_b".to_string(), -5.0),
-            ("doc_a".to_string(), -3.0),
-            ("doc_d".to_string(), -1.0),
-        ];
+    fn test_hybrid_mode_fuses_both_retrievers() {
+        let (response, id_a, id_b) = search_in_mode(SearchMode::Hybrid);
+        let ids: Vec<&str> = response.results.iter().map(|r| r.id.as_str()).collect();
+        assert!(ids.contains(&id_a.as_str()));
+        assert!(ids.contains(&id_b.as_str()));
+        // RRF scores are small sums of 1/(k + rank)
+        assert!(response.results.iter().all(|r| r.score < 0.1));
+    }
 
-        let merged = rrf_merge(&vec_results, &fts_results, 60);
+    #[test]
+    fn test_vector_mode_ranks_by_cosine_similarity() {
+        let (response, id_a, _) = search_in_mode(SearchMode::Vector);
+        assert_eq!(response.results[0].id, id_a);
+        assert!((response.results[0].score - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_keyword_mode_needs_no_embedding() {
+        let (response, _, id_b) = search_in_mode(SearchMode::Keyword);
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.results[0].id, id_b);
+        assert!(response.results[0].score > 0.0);
+    }
+
+    #[test]
+    fn test_importance_weight_reorders_results() {
+        let mut conn = test_db();
+        let id_a = insert_test_memory(
+            &mut conn,
+            "Alpha memory about Rust",
+            MemoryType::Semantic,
+            Scope::Global,
+            "default",
+            1.0,
+            &embedding_a(),
+        );
+        let id_b = insert_test_memory(
+            &mut conn,
+            "Beta memory about Python",
+            MemoryType::Semantic,
+            Scope::Global,
+            "default",
+            1.0,
+            &embedding_b(),
+        );
+        conn.execute("UPDATE memories SET importance = 1.0 WHERE id = ?1", params![id_a])
+            .unwrap();
+        conn.execute("UPDATE memories SET importance = 0.1 WHERE id = ?1", params![id_b])
+            .unwrap();
+        let filter = default_filter("default");
+
+        // Beta matches both retrievers, so it wins on fused rank alone
+        let unweighted =
+            recall_by_query(&conn, &embedding_a(), "python", &filter, &default_config()).unwrap();
+        assert_eq!(unweighted.results[0].id, id_b);
+
+        let config = SearchConfig {
+            importance_beta: 1.0,
+            ..default_config()
+        };
+        let weighted = recall_by_query(&conn, &embedding_a(), "python", &filter, &config).unwrap();
+        assert_eq!(weighted.results[0].id, id_a);
+    }
+
+    #[test]
+    fn test_created_at_range_excludes_older_memories() {
+        let mut conn = test_db();
+        let old = insert_test_memory(
+            &mut conn,
+            "Deployed the billing service last month",
+            MemoryType::Episodic,
+            Scope::Group,
+            "default",
+            1.0,
+            &embedding_a(),
+        );
+        let recent = insert_test_memory(
+            &mut conn,
+            "Deployed the billing service yesterday",
+            MemoryType::Episodic,
+            Scope::Group,
+            "default",
+            1.0,
+            &embedding_b(),
+        );
+        for (id, created_at) in [(&old, "2025-01-01T00:00:00+00:00"), (&recent, "2025-03-10T00:00:00+00:00")] {
+            conn.execute(
+                "UPDATE memories SET created_at = ?1 WHERE id = ?2",
+                params![created_at, id],
+            )
+            .unwrap();
+        }
+
+        let filter = SearchFilter {
+            created_after: Some("2025-03-01T00:00:00Z".into()),
+            ..default_filter("default")
+        };
+        let response = recall_by_query(&conn, &embedding_a(), "billing", &filter, &default_config()).unwrap();
+        let ids: Vec<&str> = response.results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec![recent.as_str()]);
+
+        // Offsets are compared as instants, not strings
+        let filter = SearchFilter {
+            created_before: Some("2025-01-01T01:00:00+01:00".into()),
+            ..default_filter("default")
+        };
+        let response = recall_by_query(&conn, &embedding_a(), "billing", &filter, &default_config()).unwrap();
+        let ids: Vec<&str> = response.results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec![old.as_str()]);
+
+        let filter = SearchFilter {
+            created_after: Some("last week".into()),
+            ..default_filter("default")
+        };
+        let err = recall_by_query(&conn, &embedding_a(), "billing", &filter, &default_config())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("invalid created_after 'last week'"), "got: {err}");
+    }
+
+    #[test]
+    fn test_recency_half_life_favors_newer_memories() {
+        let mut conn = test_db();
+        let older = insert_test_memory(
+            &mut conn,
+            "Deployed the billing service to production",
+            MemoryType::Episodic,
+            Scope::Group,
+            "default",
+            1.0,
+            &embedding_a(),
+        );
+        // Cosine 0.8 to the query, versus 1.0 for the older memory
+        let mut emb_newer = vec![0.0f32; 384];
+        emb_newer[0] = 0.8;
+        emb_newer[1] = 0.6;
+        let newer = insert_test_memory(
+            &mut conn,
+            "Rolled back the billing service deploy",
+            MemoryType::Episodic,
+            Scope::Group,
+            "default",
+            1.0,
+            &emb_newer,
+        );
+        let thirty_days_ago = (chrono::Utc::now() - chrono::Duration::days(30)).to_rfc3339();
+        conn.execute(
+            "UPDATE memories SET created_at = ?1 WHERE id = ?2",
+            params![thirty_days_ago, older],
+        )
+        .unwrap();
+
+        let ranked = |recency_half_life_days: Option<f64>| {
+            let config = SearchConfig {
+                mode: SearchMode::Vector,
+                recency_half_life_days,
+                ..default_config()
+            };
+            recall_by_query(&conn, &embedding_a(), "billing", &default_filter("default"), &config)
+                .unwrap()
+                .results
+                .into_iter()
+                .map(|r| r.id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ranked(None), vec![older.clone(), newer.clone()]);
+        assert_eq!(ranked(Some(7.0)), vec![newer, older]);
+    }
+
+    #[test]
+    fn test_mmr_demotes_near_duplicates() {
+        let mut conn = test_db();
+        let original = insert_test_memory(
+            &mut conn,
+            "Billing runs on the first of the month",
+            MemoryType::Semantic,
+            Scope::Group,
+            "default",
+            1.0,
+            &embedding_a(),
+        );
+        // Cosine 0.9 to the original: close, but under the 0.92 dedup threshold
+        let mut emb_paraphrase = vec![0.0f32; 384];
+        emb_paraphrase[0] = 0.9;
+        emb_paraphrase[1] = 0.436;
+        let paraphrase = insert_test_memory(
+            &mut conn,
+            "Billing is run monthly, on day one",
+            MemoryType::Semantic,
+            Scope::Group,
+            "default",
+            1.0,
+            &emb_paraphrase,
+        );
+        let mut emb_distinct = vec![0.0f32; 384];
+        emb_distinct[0] = 0.5;
+        emb_distinct[50] = 0.866;
+        let distinct = insert_test_memory(
+            &mut conn,
+            "Billing disputes go to the finance team",
+            MemoryType::Semantic,
+            Scope::Group,
+            "default",
+            1.0,
+            &emb_distinct,
+        );
+
+        let top_two = |mmr_lambda: Option<f64>| {
+            let config = SearchConfig {
+                max_results: 2,
+                mode: SearchMode::Vector,
+                mmr_lambda,
+                ..default_config()
+            };
+            recall_by_query(&conn, &embedding_a(), "billing", &default_filter("default"), &config)
+                .unwrap()
+                .results
+                .into_iter()
+                .map(|r| r.id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(top_two(None), vec![original.clone(), paraphrase]);
+        assert_eq!(top_two(Some(0.3)), vec![original, distinct]);
+    }
+
+    #[test]
+    fn test_expired_memories_are_not_recalled() {
+        let mut conn = test_db();
+        let hour_ago = (chrono::Utc::now() - chrono::Duration::hours(1)).to_rfc3339();
+        let next_week = (chrono::Utc::now() + chrono::Duration::days(7)).to_rfc3339();
+        let memory = store::NewMemory {
+            content: "User is currently debugging the billing export",
+            memory_type: MemoryType::Episodic,
+            scope: Scope::Group,
+            group: Some("default"),
+            confidence: 1.0,
+            importance: store::DEFAULT_IMPORTANCE,
+            metadata: None,
+            supersedes: None,
+            source_uri: None,
+            id: None,
+            session_id: None,
+            expires_at: Some(&hour_ago),
+            tags: None,
+        };
+        store::store_memory_with(&mut conn, &memory, &embedding_a(), &store::StoreOptions::default())
+            .unwrap();
+        let current = store::NewMemory {
+            content: "User is planning the billing migration",
+            expires_at: Some(&next_week),
+            ..memory
+        };
+        let current = store::store_memory_with(&mut conn, &current, &embedding_b(), &store::StoreOptions::default())
+            .unwrap()
+            .id;
+
+        let response =
+            recall_by_query(&conn, &embedding_a(), "billing", &default_filter("default"), &default_config())
+                .unwrap();
+        let ids: Vec<&str> = response.results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec![current.as_str()]);
+    }
+
+    #[test]
+    fn test_tag_filter_requires_every_tag() {
+        let mut conn = test_db();
+        let mut store_tagged = |content: &str, tags: &[&str], embedding: &[f32]| {
+            let tags: Vec<String> = tags.iter().map(|t| t.to_string()).collect();
+            let memory = store::NewMemory {
+                content,
+                memory_type: MemoryType::Semantic,
+                scope: Scope::Group,
+                group: Some("default"),
+                confidence: 1.0,
+                importance: store::DEFAULT_IMPORTANCE,
+                metadata: None,
+                supersedes: None,
+                source_uri: None,
+                id: None,
+                session_id: None,
+                expires_at: None,
+                tags: Some(&tags),
+            };
+            store::store_memory_with(&mut conn, &memory, embedding, &store::StoreOptions::default())
+                .unwrap()
+                .id
+        };
+        let incident = store_tagged("Billing outage on the invoice export", &["billing", "incident"], &embedding_a());
+        let process = store_tagged("Billing runs on the first of the month", &[" billing ", "process"], &embedding_b());
+
+        let search = |tags: &[&str]| {
+            let filter = SearchFilter {
+                tags: tags.iter().map(|t| t.to_string()).collect(),
+                ..default_filter("default")
+            };
+            let mut ids: Vec<String> = recall_by_query(&conn, &embedding_a(), "billing", &filter, &default_config())
+                .unwrap()
+                .results
+                .into_iter()
+                .map(|r| r.id)
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        // Tags are trimmed on write, so " billing " matches "billing"
+        let mut both = vec![incident.clone(), process.clone()];
+        both.sort();
+        assert_eq!(search(&["billing"]), both);
+        assert_eq!(search(&["billing", "incident"]), vec![incident]);
+        assert!(search(&["incident", "process"]).is_empty());
+    }
+
+    #[test]
+    fn test_metadata_filter_matches_all_pairs() {
+        let mut conn = test_db();
+        let slack = insert_test_memory(
+            &mut conn,
+            "Billing outage discussed in the incident channel",
+            MemoryType::Episodic,
+            Scope::Group,
+            "default",
+            1.0,
+            &embedding_a(),
+        );
+        let email = insert_test_memory(
+            &mut conn,
+            "Billing outage follow-up sent to customers",
+            MemoryType::Episodic,
+            Scope::Group,
+            "default",
+            1.0,
+            &embedding_b(),
+        );
+        let mut emb_c = vec![0.0f32; 384];
+        emb_c[200] = 1.0;
+        insert_test_memory(
+            &mut conn,
+            "Billing outage postmortem scheduled",
+            MemoryType::Episodic,
+            Scope::Group,
+            "default",
+            1.0,
+            &emb_c,
+        );
+        for (id, metadata) in [
+            (&slack, r#"{"source":"slack","priority":"high"}"#),
+            (&email, r#"{"source":"email","priority":"high"}"#),
+        ] {
+            conn.execute("UPDATE memories SET metadata = ?1 WHERE id = ?2", params![metadata, id])
+                .unwrap();
+        }
+
+        let search = |pairs: &[(&str, &str)]| {
+            let filter = SearchFilter {
+                metadata_filters: pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+                ..default_filter("default")
+            };
+            let mut ids: Vec<String> = recall_by_query(&conn, &embedding_a(), "billing", &filter, &default_config())
+                .unwrap()
+                .results
+                .into_iter()
+                .map(|r| r.id)
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        assert_eq!(search(&[("source", "slack")]), vec![slack.clone()]);
+        // The memory without metadata is excluded once any pair is required
+        let mut both = vec![slack.clone(), email.clone()];
+        both.sort();
+        assert_eq!(search(&[("priority", "high")]), both);
+        assert!(search(&[("source", "slack"), ("priority", "low")]).is_empty());
+        assert!(search(&[("channel", "ops")]).is_empty());
+    }
+
+    #[test]
+    fn test_offset_pages_are_disjoint() {
+        let mut conn = test_db();
+        // Distinct embeddings so none of the four dedup into another
+        for i in 0..4 {
+            let mut emb = vec![0.0f32; 384];
+            emb[10 + i] = 1.0;
+            insert_test_memory(
+                &mut conn,
+                &format!("Billing service note {i}"),
+                MemoryType::Semantic,
+                Scope::Group,
+                "default",
+                1.0,
+                &emb,
+            );
+        }
+
+        let page = |offset: usize| {
+            let config = SearchConfig {
+                max_results: 2,
+                offset,
+                ..default_config()
+            };
+            recall_by_query(&conn, &embedding_a(), "billing", &default_filter("default"), &config)
+                .unwrap()
+                .results
+                .into_iter()
+                .map(|r| r.id)
+                .collect::<Vec<_>>()
+        };
+        let first = page(0);
+        let second = page(2);
+        assert_eq!(first.len(), 2);
+        assert_eq!(second.len(), 2);
+        assert!(first.iter().all(|id| !second.contains(id)), "{first:?} / {second:?}");
+
+        // Each page touched only its own memories
+        let accessed: i64 = conn
+            .query_row("SELECT SUM(access_count) FROM memories", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(accessed, 4);
+    }
+
+    #[test]
+    fn test_search_mode_parse() {
+        assert_eq!("vector".parse::<SearchMode>().unwrap(), SearchMode::Vector);
+        assert_eq!("keyword_only".parse::<SearchMode>().unwrap(), SearchMode::Keyword);
+        assert_eq!(SearchMode::default(), SearchMode::Hybrid);
+        assert!("semantic".parse::<SearchMode>().is_err());
+    }
+
+    #[test]
+    fn test_malformed_fts_query_falls_back_to_vector() {
+        let mut conn = test_db();
+        let id_a = insert_test_memory(
+            &mut conn,
+            "Alpha memory about Rust",
+            MemoryType::Semantic,
+            Scope::Global,
+            "default",
+            1.0,
+            &embedding_a(),
+        );
+
+        // A bare operator is an FTS5 syntax error; it must not fail the search
+        assert!(fts_match(&conn, "AND", 10).unwrap().is_empty());
+        assert!(fts_match(&conn, "\"unterminated", 10).unwrap().is_empty());
+
+        for query in ["*", "AND", "\"", "(", "-"] {
+            let response = recall_by_query(
+                &conn,
+                &embedding_a(),
+                query,
+                &default_filter("default"),
+                &default_config(),
+            )
+            .unwrap();
+            assert_eq!(response.results[0].id, id_a, "query {query:?}");
+        }
+    }
+
+    #[test]
+    fn test_fts_search_matches_keywords() {
+        let mut conn = test_db();
+        let id_a = insert_test_memory(
+            &mut conn,
+            "The quantum computer operates at very low temperatures",
+            MemoryType::Semantic,
+            Scope::Global,
+            "default",
+            1.0,
+            &embedding_a(),
+        );
+        let _id_b = insert_test_memory(
+            &mut conn,
+            "Rust is a systems programming language",
+            MemoryType::Semantic,
+            Scope::Global,
+            "default",
+            1.0,
+            &embedding_b(),
+        );
+
+        let results = fts_search(&conn, "quantum computer", &[], 10).unwrap();
+        assert!(!results.is_empty());
+        assert_eq!(results[0].0, id_a);
+    }
+
+    #[test]
+    fn test_keyword_hits_carry_marked_snippets() {
+        let mut conn = test_db();
+        let keyword_hit = insert_test_memory(
+            &mut conn,
+            "The lab bought a quantum computer for the chemistry group",
+            MemoryType::Semantic,
+            Scope::Global,
+            "default",
+            1.0,
+            &embedding_b(),
+        );
+        let vector_hit = insert_test_memory(
+            &mut conn,
+            "Lunch is served at noon",
+            MemoryType::Semantic,
+            Scope::Global,
+            "default",
+            1.0,
+            &embedding_a(),
+        );
+
+        let response =
+            recall_by_query(&conn, &embedding_a(), "quantum", &default_filter("default"), &default_config())
+                .unwrap();
+        let snippet_of = |id: &str| {
+            response.results.iter().find(|r| r.id == id).unwrap().snippet.clone()
+        };
+
+        let snippet = snippet_of(&keyword_hit).expect("keyword hit has a snippet");
+        assert!(snippet.contains("**quantum**"), "got: {snippet}");
+        assert_eq!(snippet_of(&vector_hit), None);
+    }
+
+    #[test]
+    fn test_rrf_merge_combines_signals() {
+        let vec_results = vec![
+            ("doc_a".to_string(), 0.1),
+            ("doc_b".to_string(), 0.3),
+            ("doc_c".to_string(), 0.5),
+        ];
+        let fts_results = vec![
+            ("doc_b".to_string(), -5.0),
+            ("doc_a".to_string(), -3.0),
+            ("doc_d".to_string(), -1.0),
+        ];
+
+        let merged = rrf_merge(&vec_results, &fts_results, 60, 1.0, 1.0);
 
         // doc_a and doc_b appear in both lists, should score higher
         let scores: HashMap<String, f64> = merged.into_iter().collect();
         assert!(scores["doc_a"] > scores["doc_c"]); // doc_a in both, doc_c in one
         assert!(scores["doc_b"] > scores["doc_d"]); // doc_b in both, doc_d in one
+
+        // A shared hit ranked low in both lists beats a top vector-only hit at equal
+        // weights, but not once vector ranks count double
+        let filler = |prefix: &'static str| (0..40).map(move |i| (format!("{prefix}_{i}"), 0.0));
+        let vec_results: Vec<(String, f64)> = std::iter::once(("vec_only".to_string(), 0.0))
+            .chain(filler("vec").skip(1))
+            .chain(std::iter::once(("shared".to_string(), 0.0)))
+            .collect();
+        let fts_results: Vec<(String, f64)> = filler("fts")
+            .chain(std::iter::once(("shared".to_string(), 0.0)))
+            .collect();
+        assert_eq!(vec_results[40].0, "shared");
+        assert_eq!(fts_results[40].0, "shared");
+
+        let even: HashMap<String, f64> =
+            rrf_merge(&vec_results, &fts_results, 60, 1.0, 1.0).into_iter().collect();
+        assert!(even["shared"] > even["vec_only"]);
+        let weighted: HashMap<String, f64> =
+            rrf_merge(&vec_results, &fts_results, 60, 2.0, 1.0).into_iter().collect();
+        assert!(weighted["vec_only"] > weighted["shared"]);
     }
 
     #[test]
@@ -834,6 +2795,88 @@ mod tests {
         assert!(!ids.contains(&id_old.as_str()));
     }
 
+    #[test]
+    fn test_include_superseded_marks_replaced_results() {
+        let mut conn = test_db();
+        let id_old = insert_test_memory(
+            &mut conn,
+            "Old fact about Rust",
+            MemoryType::Semantic,
+            Scope::Global,
+            "default",
+            1.0,
+            &embedding_a(),
+        );
+        let id_new = store::store_memory(
+            &mut conn,
+            "Updated fact about Rust",
+            MemoryType::Semantic,
+            Scope::Global,
+            Some("default"),
+            1.0,
+            None,
+            Some(&id_old),
+            &embedding_b(),
+            0.92,
+        )
+        .unwrap()
+        .id;
+
+        let filter = SearchFilter {
+            include_superseded: true,
+            ..default_filter("default")
+        };
+        let response =
+            recall_by_query(&conn, &embedding_a(), "fact about Rust", &filter, &default_config())
+                .unwrap();
+
+        let old = response
+            .results
+            .iter()
+            .find(|r| r.id == id_old)
+            .expect("superseded memory should be included");
+        assert_eq!(old.superseded_by.as_deref(), Some(id_new.as_str()));
+
+        let new = response.results.iter().find(|r| r.id == id_new).unwrap();
+        assert!(new.superseded_by.is_none());
+    }
+
+    #[test]
+    fn test_exclude_ids_returns_disjoint_batches() {
+        let mut conn = test_db();
+        for i in 0..6 {
+            // Each shares dimension 0 with the query but is orthogonal to the others' extras
+            let mut embedding = embedding_a();
+            embedding[10 + i] = 1.0;
+            insert_test_memory(
+                &mut conn,
+                &format!("Deploy note number {i}"),
+                MemoryType::Semantic,
+                Scope::Global,
+                "default",
+                1.0,
+                &embedding,
+            );
+        }
+        let config = SearchConfig {
+            max_results: 3,
+            ..default_config()
+        };
+
+        let first = recall_by_query(&conn, &embedding_a(), "deploy note", &default_filter("default"), &config)
+            .unwrap();
+        let first_ids: Vec<String> = first.results.iter().map(|r| r.id.clone()).collect();
+        assert_eq!(first_ids.len(), 3);
+
+        let filter = SearchFilter {
+            exclude_ids: first_ids.clone(),
+            ..default_filter("default")
+        };
+        let second = recall_by_query(&conn, &embedding_a(), "deploy note", &filter, &config).unwrap();
+        assert_eq!(second.results.len(), 3);
+        assert!(second.results.iter().all(|r| !first_ids.contains(&r.id)));
+    }
+
     #[test]
     fn test_post_filter_by_type() {
         let mut conn = test_db();
@@ -858,9 +2901,7 @@ mod tests {
 
         let filter = SearchFilter {
             memory_type: Some(MemoryType::Semantic),
-            scope: None,
-            group: "default".to_string(),
-            min_confidence: 0.1,
+            ..default_filter("default")
         };
 
         let response =
@@ -909,6 +2950,96 @@ mod tests {
         assert!(!ids.contains(&id_group.as_str()));
     }
 
+    #[test]
+    fn test_session_scope_visible_only_to_its_session() {
+        let mut conn = test_db();
+        let memory = store::NewMemory {
+            content: "We agreed to skip the flaky test for now",
+            memory_type: MemoryType::Episodic,
+            scope: Scope::Session,
+            group: Some("default"),
+            confidence: 1.0,
+            importance: store::DEFAULT_IMPORTANCE,
+            metadata: None,
+            supersedes: None,
+            source_uri: None,
+            id: None,
+            session_id: Some("conv-1"),
+            expires_at: None,
+            tags: None,
+        };
+        let id = store::store_memory_with(&mut conn, &memory, &embedding_a(), &store::StoreOptions::default())
+            .unwrap()
+            .id;
+
+        let recall = |session_id: Option<&str>| {
+            let filter = SearchFilter {
+                session_id: session_id.map(String::from),
+                ..default_filter("default")
+            };
+            recall_by_query(&conn, &embedding_a(), "flaky test", &filter, &default_config())
+                .unwrap()
+                .results
+                .into_iter()
+                .map(|r| r.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(recall(Some("conv-1")), vec![id]);
+        assert!(recall(Some("conv-2")).is_empty());
+        assert!(recall(None).is_empty());
+
+        // Session scope needs a session to belong to
+        let orphan = store::NewMemory { session_id: None, ..memory };
+        let err = store::store_memory_with(&mut conn, &orphan, &embedding_b(), &store::StoreOptions::default())
+            .unwrap_err();
+        assert!(err.to_string().contains("session_id"));
+    }
+
+    #[test]
+    fn test_post_filter_by_group_list() {
+        let mut conn = test_db();
+        let mut ids = Vec::new();
+        for (i, group) in ["project-a", "project-b", "project-c"].iter().enumerate() {
+            let mut emb = vec![0.0f32; 384];
+            emb[i] = 1.0;
+            ids.push(insert_test_memory(
+                &mut conn,
+                &format!("Deployment notes for {group}"),
+                MemoryType::Episodic,
+                Scope::Group,
+                group,
+                1.0,
+                &emb,
+            ));
+        }
+        let id_global = insert_test_memory(
+            &mut conn,
+            "Deployment checklist for every project",
+            MemoryType::Procedural,
+            Scope::Global,
+            "default",
+            1.0,
+            &embedding_b(),
+        );
+
+        let filter = SearchFilter {
+            groups: Some(vec!["project-a".into(), "project-b".into()]),
+            ..default_filter("default")
+        };
+        let config = SearchConfig {
+            max_results: 10,
+            ..default_config()
+        };
+        let response =
+            recall_by_query(&conn, &embedding_a(), "deployment", &filter, &config).unwrap();
+
+        let returned: Vec<&str> = response.results.iter().map(|r| r.id.as_str()).collect();
+        assert!(returned.contains(&ids[0].as_str()));
+        assert!(returned.contains(&ids[1].as_str()));
+        assert!(!returned.contains(&ids[2].as_str()));
+        assert!(returned.contains(&id_global.as_str()));
+    }
+
     #[test]
     fn test_confidence_floor() {
         let mut conn = test_db();
@@ -965,7 +3096,7 @@ mod tests {
         let config = SearchConfig {
             max_results: 10,
             token_budget: 50, // Very tight budget — ~200 chars
-            rrf_k: 60,
+            ..default_config()
         };
 
         let response = recall_by_query(
@@ -993,16 +3124,90 @@ mod tests {
                 score: 0.03,
                 created_at: "2026-01-01T00:00:00Z".to_string(),
                 metadata: None,
+                source_uri: None,
+                superseded_by: None,
                 relations: None,
+                snippet: None,
             }],
             total_matched: 1,
             token_estimate: 35,
+            requested_max_results: None,
+            degraded: false,
         };
 
-        let summary = to_summary(&response);
-        assert_eq!(summary.results.len(), 1);
-        assert!(summary.results[0].preview.len() <= 83); // 80 + "..."
-        assert!(summary.results[0].preview.ends_with("..."));
+        let summary = to_summary(&response);
+        assert_eq!(summary.results.len(), 1);
+        assert!(summary.results[0].preview.len() <= 83); // 80 + "..."
+        assert!(summary.results[0].preview.ends_with("..."));
+        // Healthy responses don't mention degradation at all
+        assert!(serde_json::to_value(&summary).unwrap().get("degraded").is_none());
+
+        let degraded = to_summary(&RecallResponse {
+            degraded: true,
+            ..response
+        });
+        assert_eq!(serde_json::to_value(&degraded).unwrap()["degraded"], true);
+    }
+
+    #[test]
+    fn test_project_fields() {
+        let response = RecallResponse {
+            results: vec![SearchResult {
+                id: "test-id".to_string(),
+                memory_type: "semantic".to_string(),
+                content: "Some content".to_string(),
+                confidence: 0.9,
+                score: 0.03,
+                created_at: "2026-01-01T00:00:00Z".to_string(),
+                metadata: Some(serde_json::json!({"k": "v"})),
+                source_uri: None,
+                superseded_by: None,
+                relations: None,
+                snippet: None,
+            }],
+            total_matched: 1,
+            token_estimate: 3,
+            requested_max_results: None,
+            degraded: false,
+        };
+
+        let projected = project_fields(&response, &["id".into(), "score".into()]).unwrap();
+        let result = projected["results"][0].as_object().unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result["id"], "test-id");
+        assert_eq!(projected["total_matched"], 1);
+
+        let err = project_fields(&response, &["id".into(), "embedding".into()]).unwrap_err();
+        assert!(err.to_string().contains("unknown field 'embedding'"));
+        assert!(project_fields(&response, &[]).is_err());
+    }
+
+    #[test]
+    fn test_resolve_memory_id_by_prefix() {
+        let conn = test_db();
+        for id in ["0195aa01-1111", "0195aa01-2222", "0195bb02-3333", "50%_off"] {
+            conn.execute(
+                "INSERT INTO memories (id, type, content, scope, created_at, updated_at) \
+                 VALUES (?1, 'semantic', 'content', 'global', '2026-01-01', '2026-01-01')",
+                params![id],
+            )
+            .unwrap();
+        }
+
+        assert_eq!(resolve_memory_id(&conn, "0195aa01-2222").unwrap(), "0195aa01-2222");
+        assert_eq!(resolve_memory_id(&conn, "0195b").unwrap(), "0195bb02-3333");
+
+        let err = resolve_memory_id(&conn, "0195aa").unwrap_err().to_string();
+        assert!(err.contains("ambiguous"));
+        assert!(err.contains("0195aa01-1111") && err.contains("0195aa01-2222"));
+
+        assert!(resolve_memory_id(&conn, "ffff").unwrap_err().to_string().contains("memory not found"));
+        // LIKE wildcards in the input are matched literally
+        assert!(resolve_memory_id(&conn, "0195_a").is_err());
+        assert_eq!(resolve_memory_id(&conn, "50%").unwrap(), "50%_off");
+
+        let inspected = inspect_memory(&conn, "0195b", false, false).unwrap();
+        assert_eq!(inspected.memory.id, "0195bb02-3333");
     }
 
     #[test]
@@ -1036,6 +3241,41 @@ mod tests {
         assert_eq!(response.results[1].id, id_a);
     }
 
+    #[test]
+    fn test_recall_reinforces_confidence_up_to_one() {
+        let mut conn = test_db();
+        let id = insert_test_memory(
+            &mut conn,
+            "Decayed but useful memory",
+            MemoryType::Semantic,
+            Scope::Global,
+            "default",
+            0.5,
+            &embedding_a(),
+        );
+        let config = SearchConfig {
+            reinforce_amount: 0.3,
+            ..default_config()
+        };
+        let confidence = |conn: &Connection| -> f64 {
+            conn.query_row(
+                "SELECT confidence FROM memories WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+
+        recall_by_query(&conn, &embedding_a(), "useful", &default_filter("default"), &config)
+            .unwrap();
+        assert!((confidence(&conn) - 0.8).abs() < 1e-9);
+
+        // A second recall would reach 1.1; it stops at 1.0
+        recall_by_query(&conn, &embedding_a(), "useful", &default_filter("default"), &config)
+            .unwrap();
+        assert_eq!(confidence(&conn), 1.0);
+    }
+
     #[test]
     fn test_access_tracking() {
         let mut conn = test_db();
@@ -1090,6 +3330,65 @@ mod tests {
         assert!(last_accessed.is_some());
     }
 
+    #[test]
+    fn test_query_log_is_opt_in() {
+        let mut conn = test_db();
+        insert_test_memory(
+            &mut conn,
+            "Loggable memory about Rust",
+            MemoryType::Semantic,
+            Scope::Global,
+            "default",
+            1.0,
+            &embedding_a(),
+        );
+        let filter = default_filter("default");
+
+        recall_by_query(&conn, &embedding_a(), "rust", &filter, &default_config()).unwrap();
+        assert!(top_queries(&conn, 10).unwrap().is_empty());
+
+        let config = SearchConfig {
+            log_queries: true,
+            mode: SearchMode::Keyword,
+            ..default_config()
+        };
+        recall_by_query(&conn, &[], "rust", &filter, &config).unwrap();
+        recall_by_query(&conn, &[], "rust", &filter, &config).unwrap();
+        recall_by_query(&conn, &[], "haskell", &filter, &config).unwrap();
+
+        let top = top_queries(&conn, 10).unwrap();
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].query, "rust");
+        assert_eq!(top[0].count, 2);
+        assert_eq!(top[0].avg_results, 1.0);
+        assert_eq!(top[1].query, "haskell");
+        assert_eq!(top[1].empty_count, 1);
+    }
+
+    #[test]
+    fn test_query_log_failure_does_not_break_recall() {
+        let mut conn = test_db();
+        insert_test_memory(
+            &mut conn,
+            "Memory about Rust",
+            MemoryType::Semantic,
+            Scope::Global,
+            "default",
+            1.0,
+            &embedding_a(),
+        );
+        conn.execute_batch("DROP TABLE recall_log").unwrap();
+
+        let config = SearchConfig {
+            log_queries: true,
+            ..default_config()
+        };
+        let response =
+            recall_by_query(&conn, &embedding_a(), "rust", &default_filter("default"), &config)
+                .unwrap();
+        assert_eq!(response.results.len(), 1);
+    }
+
     #[test]
     fn test_empty_results() {
         let conn = test_db();
@@ -1149,6 +3448,230 @@ mod tests {
         assert_eq!(log[0].operation, "create");
     }
 
+    #[test]
+    fn test_inspect_memories_batch() {
+        let mut conn = test_db();
+        let first = insert_test_memory(
+            &mut conn,
+            "First batch memory",
+            MemoryType::Semantic,
+            Scope::Global,
+            "default",
+            1.0,
+            &embedding_a(),
+        );
+        let second = insert_test_memory(
+            &mut conn,
+            "Second batch memory",
+            MemoryType::Episodic,
+            Scope::Group,
+            "default",
+            1.0,
+            &embedding_b(),
+        );
+
+        // A unique prefix resolves like it does for a single inspect
+        let ids = vec![
+            second.clone(),
+            "nonexistent-id".to_string(),
+            first[..first.len() - 4].to_string(),
+        ];
+        let response = inspect_memories(&conn, &ids, false, true).unwrap();
+        assert_eq!(response.results.len(), 2);
+        assert_eq!(response.results[0].memory.id, second);
+        assert_eq!(response.results[1].memory.id, first);
+        assert!(response.results.iter().all(|r| r.log.is_some() && r.relations.is_none()));
+        assert_eq!(response.not_found, vec!["nonexistent-id".to_string()]);
+    }
+
+    #[test]
+    fn test_merge_inspect_responses_across_databases() {
+        let mut conn_a = test_db();
+        let mut conn_b = test_db();
+        let id_a = insert_test_memory(
+            &mut conn_a,
+            "Stored in the main database",
+            MemoryType::Semantic,
+            Scope::Global,
+            "default",
+            1.0,
+            &embedding_a(),
+        );
+        let id_b = insert_test_memory(
+            &mut conn_b,
+            "Stored in a group shard",
+            MemoryType::Semantic,
+            Scope::Group,
+            "project-b",
+            1.0,
+            &embedding_b(),
+        );
+
+        let ids = vec![id_b.clone(), id_a.clone(), "missing".to_string()];
+        let responses = vec![
+            inspect_memories(&conn_a, &ids, false, false).unwrap(),
+            inspect_memories(&conn_b, &ids, false, false).unwrap(),
+        ];
+        let merged = merge_inspect_responses(responses, &ids);
+        let found: Vec<&str> = merged.results.iter().map(|r| r.memory.id.as_str()).collect();
+        assert_eq!(found, vec![id_b.as_str(), id_a.as_str()]);
+        assert_eq!(merged.not_found, vec!["missing".to_string()]);
+    }
+
+    #[test]
+    fn test_touch_memories_bumps_access_and_reports_missing() {
+        let mut conn_a = test_db();
+        let mut conn_b = test_db();
+        let id_a = insert_test_memory(
+            &mut conn_a,
+            "Touched in the main database",
+            MemoryType::Semantic,
+            Scope::Global,
+            "default",
+            1.0,
+            &embedding_a(),
+        );
+        let id_b = insert_test_memory(
+            &mut conn_b,
+            "Touched in a group shard",
+            MemoryType::Episodic,
+            Scope::Group,
+            "project-b",
+            1.0,
+            &embedding_b(),
+        );
+
+        let ids = vec![id_a.clone(), id_b.clone(), "missing".to_string()];
+        let responses = vec![
+            touch_memories(&conn_a, &ids).unwrap(),
+            touch_memories(&conn_b, &ids).unwrap(),
+        ];
+        let merged = merge_touch_responses(responses);
+        assert_eq!(merged.touched, 2);
+        assert_eq!(merged.not_found, vec!["missing".to_string()]);
+
+        let (count, last_accessed): (i64, Option<String>) = conn_a
+            .query_row(
+                "SELECT access_count, last_accessed FROM memories WHERE id = ?1",
+                params![id_a],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(count, 1);
+        assert!(last_accessed.is_some());
+    }
+
+    #[test]
+    fn test_stored_embedding_round_trips() {
+        let mut conn = test_db();
+        let id = insert_test_memory(
+            &mut conn,
+            "Memory with a vector",
+            MemoryType::Semantic,
+            Scope::Global,
+            "default",
+            1.0,
+            &embedding_a(),
+        );
+
+        let stored = stored_embedding(&conn, &id).unwrap().unwrap();
+        assert_eq!(stored.dimension, 384);
+        assert_eq!(stored.values, embedding_a());
+        assert!((stored.norm - 1.0).abs() < 1e-6);
+        assert!(stored_embedding(&conn, "nonexistent-id").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_changed_since_includes_superseded() {
+        let mut conn = test_db();
+        let id_a = insert_test_memory(
+            &mut conn,
+            "Old fact",
+            MemoryType::Semantic,
+            Scope::Global,
+            "default",
+            1.0,
+            &embedding_a(),
+        );
+        let id_b = insert_test_memory(
+            &mut conn,
+            "Unchanged fact",
+            MemoryType::Semantic,
+            Scope::Global,
+            "default",
+            1.0,
+            &embedding_b(),
+        );
+        conn.execute("UPDATE memories SET updated_at = '2020-01-01T00:00:00+00:00'", [])
+            .unwrap();
+
+        let changes = changed_since(&conn, "2020-06-01T00:00:00Z").unwrap();
+        assert!(changes.memories.is_empty());
+        assert!(changes.cursor.as_str() > "2020-06-01");
+
+        let mut emb_c = vec![0.0f32; 384];
+        emb_c[200] = 1.0;
+        let id_c = store::store_memory(
+            &mut conn, "New fact", MemoryType::Semantic, Scope::Global,
+            Some("default"), 1.0, None, Some(&id_a), &emb_c, 0.92,
+        )
+        .unwrap()
+        .id;
+
+        let changes = changed_since(&conn, "2020-06-01T00:00:00Z").unwrap();
+        let ids: Vec<&str> = changes.memories.iter().map(|m| m.memory.id.as_str()).collect();
+        assert!(ids.contains(&id_a.as_str()));
+        assert!(ids.contains(&id_c.as_str()));
+        assert!(!ids.contains(&id_b.as_str()));
+        let old = changes.memories.iter().find(|m| m.memory.id == id_a).unwrap();
+        assert!(old.superseded);
+
+        assert!(changed_since(&conn, "yesterday").is_err());
+    }
+
+    #[test]
+    fn test_log_entries_after_streams_forward() {
+        let mut conn = test_db();
+        insert_test_memory(
+            &mut conn,
+            "Before watching",
+            MemoryType::Semantic,
+            Scope::Global,
+            "default",
+            1.0,
+            &embedding_a(),
+        );
+        let start = max_log_id(&conn).unwrap();
+        assert!(start > 0);
+
+        let id_b = insert_test_memory(
+            &mut conn,
+            "Group B event",
+            MemoryType::Episodic,
+            Scope::Group,
+            "project-b",
+            1.0,
+            &embedding_b(),
+        );
+
+        let events = log_entries_after(&conn, start, &LogFilter::default()).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].memory_id, id_b);
+        assert_eq!(events[0].entry.operation, "create");
+
+        let other_group = LogFilter {
+            group: Some("project-a".into()),
+            ..Default::default()
+        };
+        assert!(log_entries_after(&conn, start, &other_group).unwrap().is_empty());
+
+        let updates = LogFilter {
+            operation: Some("update".into()),
+            ..Default::default()
+        };
+        assert!(log_entries_after(&conn, start, &updates).unwrap().is_empty());
+    }
+
     #[test]
     fn test_inspect_memory_not_found() {
         let conn = test_db();
@@ -1157,6 +3680,181 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("memory not found"));
     }
 
+    /// Helper: insert a semantic memory attributed to `source_uri`.
+    fn insert_sourced_memory(
+        conn: &mut Connection,
+        content: &str,
+        source_uri: &str,
+        embedding: &[f32],
+    ) -> String {
+        let memory = store::NewMemory {
+            content,
+            memory_type: MemoryType::Semantic,
+            scope: Scope::Global,
+            group: Some("default"),
+            confidence: 1.0,
+            importance: store::DEFAULT_IMPORTANCE,
+            metadata: None,
+            supersedes: None,
+            source_uri: Some(source_uri),
+            id: None,
+            session_id: None,
+            expires_at: None,
+            tags: None,
+        };
+        store::store_memory_with(conn, &memory, embedding, &store::StoreOptions::default())
+            .unwrap()
+            .id
+    }
+
+    #[test]
+    fn test_source_uri_round_trips_and_filters() {
+        let mut conn = test_db();
+        let id_doc = insert_sourced_memory(
+            &mut conn,
+            "Deploys go through the staging cluster",
+            "https://wiki.example.com/deploy",
+            &embedding_a(),
+        );
+        insert_sourced_memory(
+            &mut conn,
+            "Deploys need two approvals",
+            "slack://C123/p456",
+            &embedding_b(),
+        );
+
+        let filter = SearchFilter {
+            source_uri: Some("https://wiki.example.com/deploy".into()),
+            ..default_filter("default")
+        };
+        let response =
+            recall_by_query(&conn, &embedding_a(), "deploys", &filter, &default_config()).unwrap();
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.results[0].id, id_doc);
+        assert_eq!(
+            response.results[0].source_uri.as_deref(),
+            Some("https://wiki.example.com/deploy")
+        );
+
+        let inspected = inspect_memory(&conn, &id_doc, false, false).unwrap();
+        assert_eq!(
+            inspected.memory.source_uri.as_deref(),
+            Some("https://wiki.example.com/deploy")
+        );
+    }
+
+    #[test]
+    fn test_list_memories_by_source_uri() {
+        let mut conn = test_db();
+        let id_a = insert_sourced_memory(&mut conn, "First fact", "doc://a", &embedding_a());
+        insert_sourced_memory(&mut conn, "Second fact", "doc://b", &embedding_b());
+
+        let all = list_memories(&conn, &ListFilter::default()).unwrap();
+        assert_eq!(all.len(), 2);
+
+        let filter = ListFilter {
+            source_uri: Some("doc://a".into()),
+            ..Default::default()
+        };
+        let listed = list_memories(&conn, &filter).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, id_a);
+    }
+
+    #[test]
+    fn test_list_memories_filters_sorts_and_pages() {
+        let mut conn = test_db();
+        let vector = |dim: usize| {
+            let mut v = vec![0.0f32; 384];
+            v[dim] = 1.0;
+            v
+        };
+        let low = insert_test_memory(&mut conn, "Low fact", MemoryType::Semantic, Scope::Global, "default", 0.3, &vector(1));
+        let high = insert_test_memory(&mut conn, "High fact", MemoryType::Semantic, Scope::Global, "default", 0.9, &vector(2));
+        let mid = insert_test_memory(&mut conn, "Mid fact", MemoryType::Semantic, Scope::Global, "default", 0.6, &vector(3));
+        insert_test_memory(&mut conn, "An event", MemoryType::Episodic, Scope::Group, "default", 1.0, &vector(4));
+
+        let filter = ListFilter {
+            memory_type: Some(MemoryType::Semantic),
+            sort: ListSort::Confidence,
+            limit: Some(2),
+            ..Default::default()
+        };
+        let first: Vec<String> = list_memories(&conn, &filter).unwrap().into_iter().map(|m| m.id).collect();
+        assert_eq!(first, vec![high.clone(), mid.clone()]);
+
+        let second_page = ListFilter { offset: 2, ..filter.clone() };
+        let second: Vec<String> =
+            list_memories(&conn, &second_page).unwrap().into_iter().map(|m| m.id).collect();
+        assert_eq!(second, vec![low.clone()]);
+
+        // Confidence range narrows further
+        let ranged = ListFilter {
+            min_confidence: Some(0.5),
+            max_confidence: Some(0.8),
+            limit: None,
+            ..filter.clone()
+        };
+        let ranged: Vec<String> = list_memories(&conn, &ranged).unwrap().into_iter().map(|m| m.id).collect();
+        assert_eq!(ranged, vec![mid.clone()]);
+
+        // Merging two "databases" re-sorts before paging
+        let merged = merge_listings(
+            vec![
+                list_memories(&conn, &ListFilter { offset: 0, limit: Some(3), ..filter.clone() }).unwrap()[..1].to_vec(),
+                list_memories(&conn, &ListFilter { offset: 0, limit: Some(3), ..filter.clone() }).unwrap()[1..].to_vec(),
+            ],
+            &ListFilter { offset: 1, ..filter },
+        );
+        let merged: Vec<String> = merged.into_iter().map(|m| m.id).collect();
+        assert_eq!(merged, vec![mid, low]);
+    }
+
+    #[test]
+    fn test_list_memories_hides_other_sessions_and_bad_dates() {
+        let mut conn = test_db();
+        let memory = store::NewMemory {
+            content: "Session note",
+            memory_type: MemoryType::Episodic,
+            scope: Scope::Session,
+            group: Some("default"),
+            confidence: 1.0,
+            importance: store::DEFAULT_IMPORTANCE,
+            metadata: None,
+            supersedes: None,
+            source_uri: None,
+            id: None,
+            session_id: Some("chat-1"),
+            expires_at: None,
+            tags: None,
+        };
+        store::store_memory_with(&mut conn, &memory, &embedding_a(), &store::StoreOptions::default()).unwrap();
+
+        assert!(list_memories(&conn, &ListFilter::default()).unwrap().is_empty());
+        let own = ListFilter {
+            session_id: Some("chat-1".into()),
+            ..Default::default()
+        };
+        assert_eq!(list_memories(&conn, &own).unwrap().len(), 1);
+        let everything = ListFilter {
+            all_sessions: true,
+            ..Default::default()
+        };
+        assert_eq!(list_memories(&conn, &everything).unwrap().len(), 1);
+
+        let future = ListFilter {
+            all_sessions: true,
+            created_after: Some("2999-01-01T00:00:00Z".into()),
+            ..Default::default()
+        };
+        assert!(list_memories(&conn, &future).unwrap().is_empty());
+        let bad = ListFilter {
+            created_before: Some("yesterday".into()),
+            ..Default::default()
+        };
+        assert!(list_memories(&conn, &bad).is_err());
+    }
+
     #[test]
     fn test_truncate_preview() {
         assert_eq!(truncate_preview("short", 80), "short");
@@ -1168,10 +3866,28 @@ mod tests {
 
     #[test]
     fn test_escape_fts_query() {
-        assert_eq!(escape_fts_query("hello world"), "\"hello\" \"world\"");
-        assert_eq!(escape_fts_query("rust OR python"), "\"rust\" \"OR\" \"python\"");
-        assert_eq!(escape_fts_query("  spaces  "), "\"spaces\"");
-        assert_eq!(escape_fts_query(""), "");
+        assert_eq!(escape_fts_query("hello world", &[]), "\"hello\" \"world\"");
+        assert_eq!(escape_fts_query("rust OR python", &[]), "\"rust\" \"OR\" \"python\"");
+        assert_eq!(escape_fts_query("  spaces  ", &[]), "\"spaces\"");
+        assert_eq!(escape_fts_query("", &[]), "");
+        assert_eq!(escape_fts_query("deploy -rollback", &[]), "\"deploy\" NOT \"rollback\"");
+        assert_eq!(
+            escape_fts_query("-rollback deploy prod -hotfix", &[]),
+            "\"deploy\" \"prod\" NOT \"rollback\" NOT \"hotfix\""
+        );
+        // Bare or empty exclusions and exclusion-only queries yield no invalid syntax
+        assert_eq!(escape_fts_query("deploy - -\"\"", &[]), "\"deploy\"");
+        assert_eq!(escape_fts_query("-rollback", &[]), "");
+    }
+
+    #[test]
+    fn test_escape_fts_query_drops_stopwords() {
+        let stopwords = vec!["the".to_string(), "of".to_string()];
+        assert_eq!(
+            escape_fts_query("The history of Rust", &stopwords),
+            "\"history\" \"Rust\""
+        );
+        assert_eq!(escape_fts_query("the of", &stopwords), "");
     }
 
     #[test]
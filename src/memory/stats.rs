@@ -1,12 +1,13 @@
 //! Memory store statistics and aggregation queries.
 //!
-//! Provides [`memory_stats`] which returns counts by type, scope, relation totals,
-//! database size, and timestamp ranges, with an optional group filter.
+//! Provides [`memory_stats`] which returns counts by type, scope, and tag, relation totals,
+//! estimated token footprint, database size, and timestamp ranges, with an optional
+//! group filter, plus [`confidence_histogram`] for health reports.
 
 use anyhow::Result;
 use rusqlite::{params, Connection};
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 
 /// Response from memory_stats.
@@ -22,8 +23,16 @@ pub struct StatsResponse {
     pub by_type: HashMap<String, u64>,
     /// Count of active memories grouped by scope (`"global"`, `"group"`).
     pub by_scope: HashMap<String, u64>,
+    /// Estimated tokens in active memories' content, by type, using the same
+    /// `bytes / 4` estimate as recall token budgeting.
+    pub tokens_by_type: HashMap<String, u64>,
+    /// Count of active memories carrying each tag.
+    pub tags: BTreeMap<String, u64>,
     /// Total number of entity relation triples.
     pub entity_relations: u64,
+    /// Mean importance of active memories, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_importance: Option<f64>,
     /// Database file size in bytes.
     pub db_size_bytes: u64,
     /// ISO 8601 timestamp of the oldest memory, if any.
@@ -46,8 +55,11 @@ pub fn memory_stats(
     let (total, active, superseded) = count_memories(conn, group)?;
     let by_type = count_by_type(conn, group)?;
     let by_scope = count_by_scope(conn, group)?;
+    let tokens_by_type = estimate_tokens_by_type(conn, group)?;
+    let tags = list_tags(conn, group)?;
     let entity_relations = count_relations(conn)?;
     let (oldest, newest) = memory_time_range(conn, group)?;
+    let avg_importance = average_importance(conn, group)?;
 
     let db_size_bytes = db_path
         .and_then(|p| std::fs::metadata(p).ok())
@@ -60,13 +72,80 @@ pub fn memory_stats(
         superseded_memories: superseded,
         by_type,
         by_scope,
+        tokens_by_type,
+        tags,
         entity_relations,
+        avg_importance,
         db_size_bytes,
         oldest_memory: oldest,
         newest_memory: newest,
     })
 }
 
+impl StatsResponse {
+    /// Add another database's statistics to this one (used for sharded storage).
+    pub fn merge(mut self, other: StatsResponse) -> Self {
+        // Weight each side's mean by its active count before the counts are summed
+        self.avg_importance = match (self.avg_importance, other.avg_importance) {
+            (Some(a), Some(b)) => {
+                let (na, nb) = (self.active_memories as f64, other.active_memories as f64);
+                Some((a * na + b * nb) / (na + nb))
+            }
+            (a, b) => a.or(b),
+        };
+        self.total_memories += other.total_memories;
+        self.active_memories += other.active_memories;
+        self.superseded_memories += other.superseded_memories;
+        for (k, v) in other.by_type {
+            *self.by_type.entry(k).or_insert(0) += v;
+        }
+        for (k, v) in other.by_scope {
+            *self.by_scope.entry(k).or_insert(0) += v;
+        }
+        for (k, v) in other.tokens_by_type {
+            *self.tokens_by_type.entry(k).or_insert(0) += v;
+        }
+        for (k, v) in other.tags {
+            *self.tags.entry(k).or_insert(0) += v;
+        }
+        self.entity_relations += other.entity_relations;
+        self.db_size_bytes += other.db_size_bytes;
+        self.oldest_memory = match (self.oldest_memory, other.oldest_memory) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.newest_memory = match (self.newest_memory, other.newest_memory) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        self
+    }
+}
+
+/// Number of equal-width buckets in [`confidence_histogram`].
+pub const CONFIDENCE_BUCKETS: usize = 10;
+
+/// Count active memories by confidence, in [`CONFIDENCE_BUCKETS`] equal-width buckets.
+///
+/// Bucket `i` covers `[i/10, (i+1)/10)`; a confidence of exactly 1.0 lands in the last bucket.
+pub fn confidence_histogram(conn: &Connection) -> Result<[u64; CONFIDENCE_BUCKETS]> {
+    let mut stmt = conn.prepare(
+        "SELECT MIN(CAST(confidence * ?1 AS INTEGER), ?1 - 1), COUNT(*) FROM memories \
+         WHERE superseded_by IS NULL GROUP BY 1",
+    )?;
+    let rows = stmt
+        .query_map(params![CONFIDENCE_BUCKETS as i64], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut buckets = [0; CONFIDENCE_BUCKETS];
+    for (bucket, count) in rows {
+        buckets[bucket.clamp(0, CONFIDENCE_BUCKETS as i64 - 1) as usize] += count as u64;
+    }
+    Ok(buckets)
+}
+
 /// Total, active, and superseded counts.
 fn count_memories(conn: &Connection, group: Option<&str>) -> Result<(u64, u64, u64)> {
     let (where_clause, param) = group_filter(group);
@@ -149,6 +228,65 @@ fn count_by_scope(conn: &Connection, group: Option<&str>) -> Result<HashMap<Stri
     Ok(map)
 }
 
+/// Distinct tags on active memories, with how many memories carry each.
+pub fn list_tags(conn: &Connection, group: Option<&str>) -> Result<BTreeMap<String, u64>> {
+    let (where_clause, param) = group_filter(group);
+    let where_clause = if where_clause.is_empty() {
+        "WHERE superseded_by IS NULL AND tags IS NOT NULL".to_string()
+    } else {
+        format!("{where_clause} AND superseded_by IS NULL AND tags IS NOT NULL")
+    };
+    let sql = format!(
+        "SELECT tag.value, COUNT(*) FROM memories, json_each(memories.tags) AS tag \
+         {where_clause} GROUP BY tag.value"
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows: Vec<(String, i64)> = if let Some(ref g) = param {
+        stmt.query_map(params![g], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?
+    } else {
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    Ok(rows.into_iter().map(|(tag, count)| (tag, count as u64)).collect())
+}
+
+/// Estimated token count of active memories' content, by type.
+///
+/// Each memory contributes `content bytes / 4`, matching the recall budget estimate.
+fn estimate_tokens_by_type(conn: &Connection, group: Option<&str>) -> Result<HashMap<String, u64>> {
+    let (where_clause, param) = group_filter(group);
+    let active = if where_clause.is_empty() {
+        "WHERE superseded_by IS NULL"
+    } else {
+        "AND superseded_by IS NULL"
+    };
+    let sql = format!(
+        "SELECT type, SUM(LENGTH(CAST(content AS BLOB)) / 4) FROM memories {where_clause} {active} \
+         GROUP BY type"
+    );
+
+    let mut map = HashMap::new();
+    for t in &["episodic", "semantic", "procedural", "entity"] {
+        map.insert(t.to_string(), 0);
+    }
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows: Vec<(String, i64)> = if let Some(ref g) = param {
+        stmt.query_map(params![g], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?
+    } else {
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    for (t, tokens) in rows {
+        map.insert(t, tokens as u64);
+    }
+    Ok(map)
+}
+
 /// Count total entity relations.
 fn count_relations(conn: &Connection) -> Result<u64> {
     let count: i64 = conn.query_row(
@@ -178,6 +316,24 @@ fn memory_time_range(
     Ok((oldest, newest))
 }
 
+/// Mean importance of active memories (`None` when there are none).
+fn average_importance(conn: &Connection, group: Option<&str>) -> Result<Option<f64>> {
+    let (where_clause, param) = group_filter(group);
+    let active = if where_clause.is_empty() {
+        "WHERE superseded_by IS NULL"
+    } else {
+        "AND superseded_by IS NULL"
+    };
+    let sql = format!("SELECT AVG(importance) FROM memories {where_clause} {active}");
+
+    let avg: Option<f64> = if let Some(ref g) = param {
+        conn.query_row(&sql, params![g], |row| row.get(0))?
+    } else {
+        conn.query_row(&sql, [], |row| row.get(0))?
+    };
+    Ok(avg)
+}
+
 /// Build a WHERE clause for optional group filtering.
 ///
 /// When a group is provided, includes memories from that group plus global-scope memories.
@@ -194,16 +350,11 @@ fn group_filter(group: Option<&str>) -> (String, Option<String>) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db;
     use crate::memory::store;
     use crate::memory::types::{MemoryType, Scope};
 
     fn test_db() -> Connection {
-        db::load_sqlite_vec();
-        let conn = Connection::open_in_memory().unwrap();
-        conn.pragma_update(None, "foreign_keys", "ON").unwrap();
-        crate::db::schema::init_schema(&conn).unwrap();
-        conn
+        crate::db::open_memory_database().unwrap()
     }
 
     fn embedding(dim: usize) -> Vec<f32> {
@@ -230,6 +381,7 @@ mod tests {
         assert_eq!(stats.by_type["semantic"], 0);
         assert!(stats.oldest_memory.is_none());
         assert!(stats.newest_memory.is_none());
+        assert!(stats.avg_importance.is_none());
     }
 
     #[test]
@@ -267,6 +419,53 @@ mod tests {
         assert_eq!(stats.superseded_memories, 1);
     }
 
+    #[test]
+    fn test_stats_average_importance() {
+        let mut conn = test_db();
+        let id = insert(&mut conn, "Key fact", MemoryType::Semantic, Scope::Global, "default", 0);
+        insert(&mut conn, "Minor fact", MemoryType::Semantic, Scope::Global, "default", 1);
+        conn.execute("UPDATE memories SET importance = 0.9 WHERE id = ?1", params![id])
+            .unwrap();
+
+        let stats = memory_stats(&conn, None, None).unwrap();
+        assert!((stats.avg_importance.unwrap() - 0.7).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stats_token_footprint() {
+        let mut conn = test_db();
+        // 40 bytes -> 10 tokens; 12 bytes -> 3 tokens ("é" is 2 bytes)
+        insert(&mut conn, &"a".repeat(40), MemoryType::Semantic, Scope::Global, "default", 0);
+        insert(&mut conn, "Fact: café!", MemoryType::Semantic, Scope::Global, "default", 1);
+        let old = insert(&mut conn, &"b".repeat(80), MemoryType::Episodic, Scope::Group, "default", 2);
+        conn.execute("UPDATE memories SET superseded_by = 'forgotten' WHERE id = ?1", params![old])
+            .unwrap();
+
+        let stats = memory_stats(&conn, None, None).unwrap();
+        assert_eq!(stats.tokens_by_type["semantic"], 13);
+        // Superseded memories are never recalled, so they don't count
+        assert_eq!(stats.tokens_by_type["episodic"], 0);
+        assert_eq!(stats.tokens_by_type["procedural"], 0);
+    }
+
+    #[test]
+    fn test_confidence_histogram() {
+        let mut conn = test_db();
+        let low = insert(&mut conn, "Fading fact", MemoryType::Semantic, Scope::Global, "default", 0);
+        let mid = insert(&mut conn, "Middling fact", MemoryType::Semantic, Scope::Global, "default", 1);
+        insert(&mut conn, "Sure fact", MemoryType::Semantic, Scope::Global, "default", 2);
+        let gone = insert(&mut conn, "Forgotten fact", MemoryType::Semantic, Scope::Global, "default", 3);
+        conn.execute("UPDATE memories SET confidence = 0.04 WHERE id = ?1", params![low])
+            .unwrap();
+        conn.execute("UPDATE memories SET confidence = 0.55 WHERE id = ?1", params![mid])
+            .unwrap();
+        conn.execute("UPDATE memories SET superseded_by = 'forgotten' WHERE id = ?1", params![gone])
+            .unwrap();
+
+        let buckets = confidence_histogram(&conn).unwrap();
+        assert_eq!(buckets, [1, 0, 0, 0, 0, 1, 0, 0, 0, 1]);
+    }
+
     #[test]
     fn test_stats_group_filter() {
         let mut conn = test_db();
@@ -301,4 +500,38 @@ mod tests {
         let stats = memory_stats(&conn, None, None).unwrap();
         assert_eq!(stats.entity_relations, 1);
     }
+
+    #[test]
+    fn test_list_tags_counts_active_memories() {
+        let mut conn = test_db();
+        let tagged = |content: &str, tags: &[&str], dim: usize, conn: &mut Connection| {
+            let tags: Vec<String> = tags.iter().map(|t| t.to_string()).collect();
+            let memory = store::NewMemory {
+                content,
+                memory_type: MemoryType::Semantic,
+                scope: Scope::Global,
+                group: Some("default"),
+                confidence: 1.0,
+                importance: store::DEFAULT_IMPORTANCE,
+                metadata: None,
+                supersedes: None,
+                source_uri: None,
+                id: None,
+                session_id: None,
+                expires_at: None,
+                tags: Some(&tags),
+            };
+            store::store_memory_with(conn, &memory, &embedding(dim), &store::StoreOptions::default())
+                .unwrap()
+                .id
+        };
+        tagged("Invoices go out monthly", &["billing", "finance"], 0, &mut conn);
+        tagged("Refunds need approval", &["billing"], 1, &mut conn);
+        insert(&mut conn, "Untagged fact", MemoryType::Semantic, Scope::Global, "default", 2);
+
+        let stats = memory_stats(&conn, None, None).unwrap();
+        assert_eq!(stats.tags.get("billing"), Some(&2));
+        assert_eq!(stats.tags.get("finance"), Some(&1));
+        assert_eq!(stats.tags.len(), 2);
+    }
 }
@@ -1,15 +1,110 @@
 //! Write path — embedding, deduplication, storage, and audit logging.
 //!
-//! [`store_memory`] is the single entry point. It runs the full pipeline inside a
-//! transaction: dedup check via vector similarity, insert into the memories table, sync
-//! FTS5 index, insert embedding vector, handle supersession, and write an audit log.
+//! [`store_memory`] (or [`store_memory_with`]) is the single entry point; [`store_memory_batch`]
+//! runs the same path for many memories in one transaction. It runs the full pipeline inside a
+//! transaction: exact-duplicate check via content hash, dedup check via vector similarity,
+//! insert into the memories table, sync FTS5 index, insert embedding vector, handle
+//! supersession, and write an audit log. [`store_exact_duplicate`] runs just the hash
+//! check so callers can skip embedding exact repeats. New memories get a UUID v7 unless
+//! the caller supplies its own ID, in which case deduplication is skipped.
+//! [`update_memory`] edits an existing memory in place, keeping its ID.
 
 use anyhow::{bail, Result};
 use rusqlite::{params, Connection, Transaction};
 use serde::Serialize;
 
+use crate::config::OverLengthPolicy;
 use crate::memory::types::{MemoryType, Scope};
 
+/// Importance given to memories stored without one.
+pub const DEFAULT_IMPORTANCE: f64 = 0.5;
+
+/// Longest caller-supplied memory ID accepted.
+pub const MAX_ID_LEN: usize = 128;
+
+/// Embeddings with an L2 norm below this are treated as degenerate.
+pub const MIN_EMBEDDING_NORM: f32 = 1e-6;
+
+/// Write-path failures that callers may want to distinguish from database errors.
+#[derive(Debug, thiserror::Error)]
+pub enum StoreError {
+    /// The embedding is (near-)zero, so every similarity against it is meaningless.
+    #[error(
+        "embedding is degenerate (L2 norm {norm:e}); the provider produced no usable \
+         signal for this content, so it cannot be deduplicated or recalled"
+    )]
+    DegenerateEmbedding { norm: f32 },
+    /// The content is shorter than the configured `storage.min_content_chars`.
+    #[error("content is {len} characters, shorter than the minimum of {min}")]
+    ContentTooShort { len: usize, min: usize },
+    /// The content is longer than `storage.max_content_chars` and the policy is `"reject"`.
+    #[error("content is {len} characters, longer than the maximum of {max}")]
+    ContentTooLong { len: usize, max: usize },
+    /// A caller-supplied ID is empty, too long, or contains whitespace or control characters.
+    #[error("memory ID {id:?} is invalid: {reason}")]
+    InvalidId { id: String, reason: String },
+    /// A caller-supplied ID is already used by another memory.
+    #[error("a memory with ID '{id}' already exists")]
+    IdExists { id: String },
+    /// Session scope was requested without a session ID to scope it to.
+    #[error("session-scoped memories need a session_id")]
+    SessionRequired,
+    /// A tag is empty or only whitespace.
+    #[error("tags must not be empty")]
+    EmptyTag,
+    /// `expires_at` is not an RFC 3339 timestamp.
+    #[error("expires_at {value:?} is invalid: {reason} (expected RFC 3339)")]
+    InvalidExpiry { value: String, reason: String },
+}
+
+/// Write-path tuning, usually derived from [`LociConfig`](crate::config::LociConfig).
+#[derive(Debug, Clone)]
+pub struct StoreOptions {
+    /// Cosine similarity at or above which a same-type memory counts as a duplicate.
+    pub dedup_threshold: f64,
+    /// Confidence added to a memory each time a write is deduplicated into it (capped at 1.0).
+    pub dedup_confidence_boost: f64,
+    /// Minimum trimmed content length in characters (0 disables the check).
+    pub min_content_chars: usize,
+    /// Maximum content length in characters (0 disables the check).
+    pub max_content_chars: usize,
+    /// What happens to content over `max_content_chars`.
+    pub over_length_policy: OverLengthPolicy,
+    /// Skip both dedup gates, exact content and vector similarity, so every write
+    /// creates a new memory.
+    pub skip_dedup: bool,
+    /// Run every check and write, then roll back instead of committing.
+    pub dry_run: bool,
+}
+
+impl Default for StoreOptions {
+    fn default() -> Self {
+        Self {
+            dedup_threshold: 0.92,
+            dedup_confidence_boost: 0.1,
+            min_content_chars: 0,
+            max_content_chars: 100_000,
+            over_length_policy: OverLengthPolicy::Reject,
+            skip_dedup: false,
+            dry_run: false,
+        }
+    }
+}
+
+impl From<&crate::config::LociConfig> for StoreOptions {
+    fn from(config: &crate::config::LociConfig) -> Self {
+        Self {
+            dedup_threshold: config.retrieval.dedup_threshold,
+            dedup_confidence_boost: config.retrieval.dedup_confidence_boost,
+            min_content_chars: config.storage.min_content_chars,
+            max_content_chars: config.storage.max_content_chars,
+            over_length_policy: config.storage.over_length_policy,
+            skip_dedup: false,
+            dry_run: false,
+        }
+    }
+}
+
 /// Result returned from a store operation.
 #[derive(Debug, Serialize)]
 pub struct StoreMemoryResult {
@@ -20,10 +115,84 @@ pub struct StoreMemoryResult {
     pub memory_type: String,
     /// `true` if an existing near-duplicate was updated instead of creating a new record.
     pub deduplicated: bool,
+    /// Cosine similarity to the matched memory, set only for deduplicated writes
+    /// (`1.0` for exact content matches).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f64>,
     /// ID of the memory that was superseded by this one, if any.
     pub superseded: Option<String>,
 }
 
+/// What a store would have done, reported for a dry run (see [`StoreOptions::dry_run`]).
+#[derive(Debug, Serialize)]
+pub struct StorePreview {
+    /// Always `true`; nothing was persisted.
+    pub dry_run: bool,
+    /// `true` if the write would have been folded into an existing memory.
+    pub would_deduplicate: bool,
+    /// ID of the existing memory it would be folded into.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_id: Option<String>,
+    /// Cosine similarity to the matched memory.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f64>,
+    /// Memory type as a string.
+    #[serde(rename = "type")]
+    pub memory_type: String,
+    /// Scope the memory would be stored with.
+    pub scope: String,
+    /// ID of the memory that would be superseded, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub would_supersede: Option<String>,
+}
+
+impl StorePreview {
+    /// Describe a rolled-back store result.
+    pub fn new(result: StoreMemoryResult, scope: Scope) -> Self {
+        Self {
+            dry_run: true,
+            would_deduplicate: result.deduplicated,
+            matched_id: result.deduplicated.then_some(result.id),
+            similarity: result.similarity,
+            memory_type: result.memory_type,
+            scope: scope.as_str().to_string(),
+            would_supersede: result.superseded,
+        }
+    }
+}
+
+/// A memory to be written by [`store_memory_with`].
+#[derive(Debug, Clone, Copy)]
+pub struct NewMemory<'a> {
+    /// Natural language content.
+    pub content: &'a str,
+    /// Cognitive category.
+    pub memory_type: MemoryType,
+    /// Visibility scope.
+    pub scope: Scope,
+    /// Owning `source_group`, if any.
+    pub group: Option<&'a str>,
+    /// Initial confidence in `[0.0, 1.0]`.
+    pub confidence: f64,
+    /// How important the memory is to surface, in `[0.0, 1.0]`. Does not decay.
+    pub importance: f64,
+    /// Arbitrary JSON metadata.
+    pub metadata: Option<&'a serde_json::Value>,
+    /// ID of a memory this one replaces.
+    pub supersedes: Option<&'a str>,
+    /// Document, URL, or message ID this memory was derived from.
+    pub source_uri: Option<&'a str>,
+    /// Caller-supplied primary key (e.g. an external system's ID). `None` generates a
+    /// UUID v7. An explicit ID bypasses deduplication and fails if already taken.
+    pub id: Option<&'a str>,
+    /// Conversation the memory belongs to; required when `scope` is [`Scope::Session`].
+    pub session_id: Option<&'a str>,
+    /// RFC 3339 time after which the memory is no longer recalled; stored in UTC.
+    pub expires_at: Option<&'a str>,
+    /// Labels for tag-filtered recall; trimmed and de-duplicated on write.
+    pub tags: Option<&'a [String]>,
+}
+
 /// Full write path: dedup check → insert or update → FTS sync → vec insert → audit log.
 ///
 /// All operations run inside a transaction for atomicity.
@@ -38,53 +207,151 @@ pub fn store_memory(
     supersedes: Option<&str>,
     embedding: &[f32],
     dedup_threshold: f64,
+) -> Result<StoreMemoryResult> {
+    let memory = NewMemory {
+        content,
+        memory_type,
+        scope,
+        group,
+        confidence,
+        importance: DEFAULT_IMPORTANCE,
+        metadata,
+        supersedes,
+        source_uri: None,
+        id: None,
+        session_id: None,
+        expires_at: None,
+        tags: None,
+    };
+    let options = StoreOptions {
+        dedup_threshold,
+        ..Default::default()
+    };
+    store_memory_with(conn, &memory, embedding, &options)
+}
+
+/// Same as [`store_memory`], taking the memory fields as a [`NewMemory`].
+pub fn store_memory_with(
+    conn: &mut Connection,
+    memory: &NewMemory,
+    embedding: &[f32],
+    options: &StoreOptions,
 ) -> Result<StoreMemoryResult> {
     let tx = conn.transaction()?;
+    let result = write_memory(&tx, memory, embedding, options)?;
+    finish(tx, options)?;
+    Ok(result)
+}
 
-    // 1. Dedup gate
-    if let Some(existing_id) = check_dedup(&tx, memory_type, embedding, dedup_threshold)? {
-        update_dedup_match(&tx, &existing_id)?;
-        write_audit_log(
-            &tx,
-            "update",
-            &existing_id,
-            Some(&serde_json::json!({"reason": "deduplication"})),
-        )?;
-        tx.commit()?;
-        return Ok(StoreMemoryResult {
-            id: existing_id,
-            memory_type: memory_type.as_str().to_string(),
-            deduplicated: true,
-            superseded: None,
-        });
+/// Store many memories in one transaction, returning results in input order.
+///
+/// `embeddings[i]` belongs to `memories[i]`. Each memory runs the full write path, so
+/// it is deduplicated against the database and against earlier memories in the batch.
+/// The batch is all-or-nothing: the first failure rolls everything back, and the error
+/// names its index.
+pub fn store_memory_batch(
+    conn: &mut Connection,
+    memories: &[NewMemory],
+    embeddings: &[Vec<f32>],
+    options: &StoreOptions,
+) -> Result<Vec<StoreMemoryResult>> {
+    if memories.len() != embeddings.len() {
+        bail!(
+            "batch has {} memories but {} embeddings",
+            memories.len(),
+            embeddings.len()
+        );
+    }
+
+    let tx = conn.transaction()?;
+    let mut results = Vec::with_capacity(memories.len());
+    for (index, (memory, embedding)) in memories.iter().zip(embeddings).enumerate() {
+        let result = write_memory(&tx, memory, embedding, options)
+            .map_err(|e| e.context(format!("memory {index}")))?;
+        results.push(result);
+    }
+    finish(tx, options)?;
+    Ok(results)
+}
+
+/// The write path inside a caller-owned transaction; the caller commits or rolls back.
+fn write_memory(
+    tx: &Transaction,
+    memory: &NewMemory,
+    embedding: &[f32],
+    options: &StoreOptions,
+) -> Result<StoreMemoryResult> {
+    let memory_type = memory.memory_type;
+    validate_content(memory.content, options.min_content_chars)?;
+    validate_session(memory)?;
+    validate_embedding(embedding)?;
+    let expires_at = normalize_expiry(memory.expires_at)?;
+    let tags = normalize_tags(memory.tags)?;
+    let truncated = limit_length(memory, options)?;
+    let memory = match &truncated {
+        Some((content, metadata)) => NewMemory {
+            content,
+            metadata: Some(metadata),
+            ..*memory
+        },
+        None => *memory,
+    };
+
+    if let Some(id) = memory.id {
+        validate_id(id)?;
     }
 
-    // 2. Generate UUID v7
-    let id = uuid::Uuid::now_v7().to_string();
+    let hash = super::content_hash(memory.content);
+
+    // 1. Dedup gate: exact content first, then vector similarity. A caller-supplied ID
+    //    names exactly one memory, so it is never folded into another.
+    if memory.id.is_none() && !options.skip_dedup {
+        if let Some(existing_id) = find_by_content_hash(tx, &memory, &hash)? {
+            let result =
+                record_dedup_hit(tx, existing_id, &memory, options, "exact_duplicate", 1.0, None)?;
+            return Ok(result);
+        }
+        if let Some((existing_id, similarity)) =
+            check_dedup(tx, &memory, embedding, options.dedup_threshold)?
+        {
+            let result = record_dedup_hit(
+                tx,
+                existing_id,
+                &memory,
+                options,
+                "deduplication",
+                similarity,
+                Some(options.dedup_threshold),
+            )?;
+            return Ok(result);
+        }
+    }
+
+    // 2. Use the caller's ID if it is free, else generate a UUID v7
+    let id = match memory.id {
+        Some(id) => {
+            if id_exists(tx, id)? {
+                return Err(StoreError::IdExists { id: id.to_string() }.into());
+            }
+            id.to_string()
+        }
+        None => uuid::Uuid::now_v7().to_string(),
+    };
 
     // 3. Insert into memories table
-    let rowid = insert_memory(
-        &tx,
-        &id,
-        memory_type,
-        content,
-        scope,
-        group,
-        confidence,
-        metadata,
-    )?;
+    let rowid = insert_memory(tx, &id, &memory, &hash, expires_at.as_deref(), tags.as_deref())?;
 
     // 4. Sync FTS5 index
-    insert_fts(&tx, rowid, content, &id, memory_type)?;
+    insert_fts(tx, rowid, memory.content, &id, memory_type)?;
 
     // 5. Insert embedding vector
-    insert_vec(&tx, &id, embedding)?;
+    insert_vec(tx, &id, embedding)?;
 
     // 6. Handle supersession
-    let superseded = if let Some(old_id) = supersedes {
-        set_superseded(&tx, old_id, &id)?;
+    let superseded = if let Some(old_id) = memory.supersedes {
+        set_superseded(tx, old_id, &id)?;
         write_audit_log(
-            &tx,
+            tx,
             "supersede",
             old_id,
             Some(&serde_json::json!({"superseded_by": &id})),
@@ -95,28 +362,201 @@ pub fn store_memory(
     };
 
     // 7. Audit log for the new memory
-    write_audit_log(&tx, "create", &id, None)?;
-
-    tx.commit()?;
+    write_audit_log(tx, "create", &id, None)?;
 
     Ok(StoreMemoryResult {
         id,
         memory_type: memory_type.as_str().to_string(),
         deduplicated: false,
+        similarity: None,
         superseded,
     })
 }
 
+/// Exact-duplicate fast path, run before embedding.
+///
+/// If an active memory of the same type has identical (whitespace-normalized) content,
+/// records a dedup hit on it and returns the result; otherwise returns `None` and the
+/// caller should embed and call [`store_memory_with`]. Always `None` for writes with
+/// an explicit ID or [`StoreOptions::skip_dedup`].
+pub fn store_exact_duplicate(
+    conn: &mut Connection,
+    memory: &NewMemory,
+    options: &StoreOptions,
+) -> Result<Option<StoreMemoryResult>> {
+    validate_content(memory.content, options.min_content_chars)?;
+    validate_session(memory)?;
+    normalize_expiry(memory.expires_at)?;
+    normalize_tags(memory.tags)?;
+    let truncated = limit_length(memory, options)?;
+    if memory.id.is_some() || options.skip_dedup {
+        return Ok(None);
+    }
+
+    let content = truncated.as_ref().map_or(memory.content, |(c, _)| c.as_str());
+    let tx = conn.transaction()?;
+    let hash = super::content_hash(content);
+    let Some(existing_id) = find_by_content_hash(&tx, memory, &hash)? else {
+        return Ok(None);
+    };
+    let result = record_dedup_hit(&tx, existing_id, memory, options, "exact_duplicate", 1.0, None)?;
+    finish(tx, options)?;
+    Ok(Some(result))
+}
+
+/// Reject content below the configured minimum length.
+fn validate_content(content: &str, min_chars: usize) -> Result<(), StoreError> {
+    let len = content.trim().chars().count();
+    if len < min_chars {
+        return Err(StoreError::ContentTooShort { len, min: min_chars });
+    }
+    Ok(())
+}
+
+/// Reject session scope without a session to scope it to.
+fn validate_session(memory: &NewMemory) -> Result<(), StoreError> {
+    if memory.scope == Scope::Session && memory.session_id.is_none_or(str::is_empty) {
+        return Err(StoreError::SessionRequired);
+    }
+    Ok(())
+}
+
+/// Parse `expires_at` and re-render it in UTC, so stored expiries compare uniformly.
+fn normalize_expiry(expires_at: Option<&str>) -> Result<Option<String>, StoreError> {
+    expires_at
+        .map(|value| {
+            chrono::DateTime::parse_from_rfc3339(value)
+                .map(|t| t.with_timezone(&chrono::Utc).to_rfc3339())
+                .map_err(|e| StoreError::InvalidExpiry {
+                    value: value.to_string(),
+                    reason: e.to_string(),
+                })
+        })
+        .transpose()
+}
+
+/// Trim and de-duplicate tags, keeping first-seen order, as the JSON stored in `tags`.
+///
+/// An empty list stores no tags.
+fn normalize_tags(tags: Option<&[String]>) -> Result<Option<String>> {
+    let Some(tags) = tags else {
+        return Ok(None);
+    };
+    let mut normalized: Vec<&str> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return Err(StoreError::EmptyTag.into());
+        }
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    if normalized.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::to_string(&normalized)?))
+}
+
+/// Commit the write, or roll it back for a dry run.
+fn finish(tx: Transaction, options: &StoreOptions) -> Result<()> {
+    if options.dry_run {
+        tx.rollback()?;
+    } else {
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+/// Apply `max_content_chars`: reject over-length content, or return the truncated
+/// content with metadata recording the original length.
+fn limit_length(
+    memory: &NewMemory,
+    options: &StoreOptions,
+) -> Result<Option<(String, serde_json::Value)>, StoreError> {
+    let len = memory.content.chars().count();
+    Ok(truncate_content(memory.content, options)?
+        .map(|content| (content, with_original_length(memory.metadata, len))))
+}
+
+/// Apply `max_content_chars` to `content`: reject it, or return it truncated.
+/// `None` means it is within the limit.
+fn truncate_content(content: &str, options: &StoreOptions) -> Result<Option<String>, StoreError> {
+    let max = options.max_content_chars;
+    let len = content.chars().count();
+    if max == 0 || len <= max {
+        return Ok(None);
+    }
+    if options.over_length_policy == OverLengthPolicy::Reject {
+        return Err(StoreError::ContentTooLong { len, max });
+    }
+
+    tracing::warn!(len, max, "truncating over-length content");
+    Ok(Some(content.chars().take(max).collect()))
+}
+
+/// `metadata` with `original_length` recording the length of truncated content.
+fn with_original_length(metadata: Option<&serde_json::Value>, len: usize) -> serde_json::Value {
+    let mut metadata = match metadata {
+        Some(serde_json::Value::Object(map)) => serde_json::Value::Object(map.clone()),
+        Some(other) => serde_json::json!({"value": other}),
+        None => serde_json::json!({}),
+    };
+    metadata["original_length"] = len.into();
+    metadata
+}
+
+/// Reject caller-supplied IDs that would be awkward to pass around or display.
+fn validate_id(id: &str) -> Result<(), StoreError> {
+    let reason = if id.is_empty() {
+        "must not be empty".to_string()
+    } else if id.len() > MAX_ID_LEN {
+        format!("must be at most {MAX_ID_LEN} bytes")
+    } else if id.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        "must not contain whitespace or control characters".to_string()
+    } else {
+        return Ok(());
+    };
+    Err(StoreError::InvalidId {
+        id: id.to_string(),
+        reason,
+    })
+}
+
+/// Whether any memory, active or superseded, already uses this ID.
+fn id_exists(conn: &Transaction, id: &str) -> Result<bool> {
+    let exists = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM memories WHERE id = ?1)",
+        params![id],
+        |row| row.get(0),
+    )?;
+    Ok(exists)
+}
+
+/// Reject zero-norm embeddings before they reach the vector index.
+fn validate_embedding(embedding: &[f32]) -> Result<(), StoreError> {
+    let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm < MIN_EMBEDDING_NORM {
+        tracing::warn!(norm, "rejecting degenerate embedding");
+        return Err(StoreError::DegenerateEmbedding { norm });
+    }
+    Ok(())
+}
+
 /// Check for duplicate memories of the same type with cosine similarity above threshold.
 ///
 /// Uses sqlite-vec KNN to find nearest neighbors, then filters by type and threshold.
-/// Returns `Some(existing_id)` if a duplicate is found.
+/// Expired memories and memories outside the write's session (see [`dedup_session`])
+/// are never matched, since a write folded into one could not be recalled by its
+/// caller. Returns `Some((existing_id, cosine_similarity))` if a duplicate is found.
 fn check_dedup(
     conn: &Transaction,
-    memory_type: MemoryType,
+    memory: &NewMemory,
     embedding: &[f32],
     threshold: f64,
-) -> Result<Option<String>> {
+) -> Result<Option<(String, f64)>> {
+    let memory_type = memory.memory_type;
+    let session = dedup_session(memory);
     let embedding_bytes = embedding_to_bytes(embedding);
     let max_distance = super::cosine_threshold_to_l2(threshold);
 
@@ -131,23 +571,48 @@ fn check_dedup(
         .collect::<Result<Vec<_>, _>>()?;
 
     for (candidate_id, distance) in candidates {
+        let similarity = super::l2_to_cosine(distance);
+        tracing::debug!(id = %candidate_id, similarity, threshold, "dedup candidate");
+
         // Results are ordered by distance — stop once we're past the threshold
         if distance > max_distance {
             break;
         }
 
-        // Check if candidate has the same type and is not superseded
-        let row: Option<(String, Option<String>)> = conn
+        // Check if candidate has the same type and is neither superseded nor expired
+        let row: Option<(String, Option<String>, bool, Option<String>)> = conn
             .query_row(
-                "SELECT type, superseded_by FROM memories WHERE id = ?1",
+                "SELECT type, superseded_by, \
+                 expires_at IS NOT NULL AND julianday(expires_at) <= julianday('now'), \
+                 CASE WHEN scope = 'session' THEN session_id END \
+                 FROM memories WHERE id = ?1",
                 params![candidate_id],
-                |row| Ok((row.get(0)?, row.get(1)?)),
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
             )
             .optional()?;
 
-        if let Some((candidate_type, superseded_by)) = row {
-            if candidate_type == memory_type.as_str() && superseded_by.is_none() {
-                return Ok(Some(candidate_id));
+        if let Some((candidate_type, superseded_by, expired, candidate_session)) = row {
+            if candidate_type != memory_type.as_str() {
+                tracing::trace!(
+                    id = %candidate_id,
+                    similarity,
+                    candidate_type = %candidate_type,
+                    memory_type = %memory_type,
+                    "dedup candidate skipped: type mismatch"
+                );
+                continue;
+            }
+            if expired {
+                tracing::trace!(id = %candidate_id, similarity, "dedup candidate skipped: expired");
+                continue;
+            }
+            if candidate_session.as_deref() != session {
+                tracing::trace!(id = %candidate_id, similarity, "dedup candidate skipped: other session");
+                continue;
+            }
+            if superseded_by.is_none() {
+                tracing::debug!(id = %candidate_id, similarity, threshold, "dedup match");
+                return Ok(Some((candidate_id, similarity)));
             }
         }
     }
@@ -155,42 +620,262 @@ fn check_dedup(
     Ok(None)
 }
 
-/// Bump an existing memory's confidence and access count (dedup match).
-fn update_dedup_match(conn: &Transaction, memory_id: &str) -> Result<()> {
+/// Session a write may be deduplicated within: its own session for session scope,
+/// `None` otherwise.
+///
+/// Session memories only match memories of the same session, and other scopes never
+/// match session memories, so one conversation can't fold into another's.
+fn dedup_session<'a>(memory: &NewMemory<'a>) -> Option<&'a str> {
+    if memory.scope == Scope::Session {
+        memory.session_id
+    } else {
+        None
+    }
+}
+
+/// Find an active, unexpired memory of the same type and session (see
+/// [`dedup_session`]) whose content hash matches.
+fn find_by_content_hash(
+    conn: &Transaction,
+    memory: &NewMemory,
+    hash: &str,
+) -> Result<Option<String>> {
+    let id = conn
+        .query_row(
+            "SELECT id FROM memories \
+             WHERE content_hash = ?1 AND type = ?2 AND superseded_by IS NULL \
+             AND (expires_at IS NULL OR julianday(expires_at) > julianday('now')) \
+             AND CASE WHEN ?3 IS NULL THEN scope != 'session' \
+                 ELSE scope = 'session' AND session_id = ?3 END \
+             ORDER BY created_at LIMIT 1",
+            params![hash, memory.memory_type.as_str(), dedup_session(memory)],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(id)
+}
+
+/// Update the matched memory and log why the write was folded into it.
+///
+/// `threshold` is the cosine cutoff the match cleared, recorded for vector dedups. The
+/// details also record both contents' lengths in characters, so a surprising match can
+/// be judged from the audit log alone.
+fn record_dedup_hit(
+    conn: &Transaction,
+    existing_id: String,
+    memory: &NewMemory,
+    options: &StoreOptions,
+    reason: &str,
+    similarity: f64,
+    threshold: Option<f64>,
+) -> Result<StoreMemoryResult> {
+    let memory_type = memory.memory_type;
+    let existing_length: i64 = conn.query_row(
+        "SELECT length(content) FROM memories WHERE id = ?1",
+        params![existing_id],
+        |row| row.get(0),
+    )?;
+    update_dedup_match(conn, &existing_id, memory.importance, options.dedup_confidence_boost)?;
+    let mut details = serde_json::json!({
+        "reason": reason,
+        "similarity": similarity,
+        "incoming_length": memory.content.chars().count(),
+        "existing_length": existing_length,
+    });
+    if let Some(threshold) = threshold {
+        details["threshold"] = threshold.into();
+    }
+    write_audit_log(conn, "update", &existing_id, Some(&details))?;
+    Ok(StoreMemoryResult {
+        id: existing_id,
+        memory_type: memory_type.as_str().to_string(),
+        deduplicated: true,
+        similarity: Some(similarity),
+        superseded: None,
+    })
+}
+
+/// Bump an existing memory's confidence (by `boost`) and access count (dedup match).
+///
+/// Importance is raised to the repeat's importance if that is higher, never lowered.
+fn update_dedup_match(conn: &Transaction, memory_id: &str, importance: f64, boost: f64) -> Result<()> {
     let now = chrono::Utc::now().to_rfc3339();
     conn.execute(
-        "UPDATE memories SET updated_at = ?1, confidence = MIN(confidence + 0.1, 1.0), access_count = access_count + 1 WHERE id = ?2",
-        params![now, memory_id],
+        "UPDATE memories SET updated_at = ?1, confidence = MIN(confidence + ?4, 1.0), access_count = access_count + 1, \
+         importance = MAX(importance, ?3) WHERE id = ?2",
+        params![now, memory_id, importance, boost],
     )?;
     Ok(())
 }
 
+/// Fields to change with [`update_memory`]; `None` leaves a field as it is.
+#[derive(Debug, Default)]
+pub struct MemoryUpdate<'a> {
+    /// Replacement content. Requires `embedding`.
+    pub content: Option<&'a str>,
+    /// Embedding of the replacement content.
+    pub embedding: Option<&'a [f32]>,
+    /// Replacement confidence in `[0.0, 1.0]`.
+    pub confidence: Option<f64>,
+    /// Replacement metadata; the old metadata is discarded, not merged.
+    pub metadata: Option<&'a serde_json::Value>,
+}
+
+/// Result returned from [`update_memory`].
+#[derive(Debug, Serialize)]
+pub struct UpdateMemoryResult {
+    /// ID of the edited memory (unchanged by the edit).
+    pub id: String,
+    /// Names of the fields that were changed.
+    pub updated: Vec<&'static str>,
+}
+
+/// Edit an active memory in place, keeping its ID.
+///
+/// New content replaces the memory's `memories_vec` row and FTS entry in the same
+/// transaction, so all three stay in sync. Bumps `updated_at` and writes an `update`
+/// audit entry. Superseded and forgotten memories can't be edited.
+pub fn update_memory(
+    conn: &mut Connection,
+    memory_id: &str,
+    update: &MemoryUpdate,
+    options: &StoreOptions,
+) -> Result<UpdateMemoryResult> {
+    if update.content.is_some() != update.embedding.is_some() {
+        bail!("content and embedding must be updated together");
+    }
+    if let Some(confidence) = update.confidence
+        && !(0.0..=1.0).contains(&confidence)
+    {
+        bail!("confidence must be between 0.0 and 1.0, got {confidence}");
+    }
+    // Over-length content follows `over_length_policy`, as it does for new memories
+    let truncated = match update.content {
+        Some(content) => {
+            validate_content(content, options.min_content_chars)?;
+            truncate_content(content, options)?.map(|t| (t, content.chars().count()))
+        }
+        None => None,
+    };
+    let new_content = truncated.as_ref().map(|(t, _)| t.as_str()).or(update.content);
+    if let Some(embedding) = update.embedding {
+        validate_embedding(embedding)?;
+    }
+
+    let tx = conn.transaction()?;
+
+    type Current = (i64, String, String, Option<String>, Option<String>);
+    let (rowid, old_content, memory_type, superseded_by, old_metadata): Current = tx
+        .query_row(
+            "SELECT rowid, content, type, superseded_by, metadata FROM memories WHERE id = ?1",
+            params![memory_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )
+        .optional()?
+        .ok_or_else(|| anyhow::anyhow!("memory not found: {memory_id}"))?;
+    if let Some(superseded_by) = superseded_by {
+        bail!("memory {memory_id} is superseded by {superseded_by}; only active memories can be edited");
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut updated = Vec::new();
+
+    // Truncation records the original length in the new (or else the current) metadata
+    let old_metadata: Option<serde_json::Value> =
+        old_metadata.and_then(|s| serde_json::from_str(&s).ok());
+    let metadata = match &truncated {
+        Some((_, len)) => Some(with_original_length(update.metadata.or(old_metadata.as_ref()), *len)),
+        None => update.metadata.cloned(),
+    };
+
+    if let (Some(content), Some(embedding)) = (new_content, update.embedding) {
+        tx.execute(
+            "UPDATE memories SET content = ?1, content_hash = ?2 WHERE id = ?3",
+            params![content, super::content_hash(content), memory_id],
+        )?;
+        // External-content FTS rows are replaced, not updated
+        tx.execute(
+            "INSERT INTO memories_fts(memories_fts, rowid, content, id, type) VALUES('delete', ?1, ?2, ?3, ?4)",
+            params![rowid, old_content, memory_id, memory_type],
+        )?;
+        tx.execute(
+            "INSERT INTO memories_fts (rowid, content, id, type) VALUES (?1, ?2, ?3, ?4)",
+            params![rowid, content, memory_id, memory_type],
+        )?;
+        tx.execute("DELETE FROM memories_vec WHERE id = ?1", params![memory_id])?;
+        insert_vec(&tx, memory_id, embedding)?;
+        updated.push("content");
+    }
+    if let Some(confidence) = update.confidence {
+        tx.execute(
+            "UPDATE memories SET confidence = ?1 WHERE id = ?2",
+            params![confidence, memory_id],
+        )?;
+        updated.push("confidence");
+    }
+    if let Some(metadata) = &metadata {
+        tx.execute(
+            "UPDATE memories SET metadata = ?1 WHERE id = ?2",
+            params![serde_json::to_string(metadata)?, memory_id],
+        )?;
+        updated.push("metadata");
+    }
+
+    if !updated.is_empty() {
+        tx.execute(
+            "UPDATE memories SET updated_at = ?1 WHERE id = ?2",
+            params![now, memory_id],
+        )?;
+        write_audit_log(
+            &tx,
+            "update",
+            memory_id,
+            Some(&serde_json::json!({
+                "action": "edit",
+                "fields": &updated,
+                "previous_content": update.content.map(|_| &old_content),
+            })),
+        )?;
+    }
+
+    tx.commit()?;
+
+    Ok(UpdateMemoryResult {
+        id: memory_id.to_string(),
+        updated,
+    })
+}
+
 /// Insert a new memory row. Returns the SQLite rowid for FTS5 sync.
 fn insert_memory(
     conn: &Transaction,
     id: &str,
-    memory_type: MemoryType,
-    content: &str,
-    scope: Scope,
-    group: Option<&str>,
-    confidence: f64,
-    metadata: Option<&serde_json::Value>,
+    memory: &NewMemory,
+    content_hash: &str,
+    expires_at: Option<&str>,
+    tags: Option<&str>,
 ) -> Result<i64> {
     let now = chrono::Utc::now().to_rfc3339();
-    let metadata_json = metadata.map(|m| serde_json::to_string(m)).transpose()?;
+    let metadata_json = memory.metadata.map(serde_json::to_string).transpose()?;
 
     conn.execute(
-        "INSERT INTO memories (id, type, content, source_group, scope, confidence, access_count, created_at, updated_at, metadata) \
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0, ?7, ?7, ?8)",
+        "INSERT INTO memories (id, type, content, source_group, scope, confidence, access_count, created_at, updated_at, metadata, source_uri, content_hash, importance, session_id, expires_at, tags) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0, ?7, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
         params![
             id,
-            memory_type.as_str(),
-            content,
-            group,
-            scope.as_str(),
-            confidence,
+            memory.memory_type.as_str(),
+            memory.content,
+            memory.group,
+            memory.scope.as_str(),
+            memory.confidence,
             now,
             metadata_json,
+            memory.source_uri,
+            content_hash,
+            memory.importance,
+            memory.session_id,
+            expires_at,
+            tags,
         ],
     )?;
 
@@ -225,10 +910,12 @@ fn insert_vec(conn: &Transaction, id: &str, embedding: &[f32]) -> Result<()> {
 }
 
 /// Mark an old memory as superseded by a new one.
+///
+/// Bumps `updated_at` so incremental sync picks up the change.
 fn set_superseded(conn: &Transaction, old_id: &str, new_id: &str) -> Result<()> {
     let rows = conn.execute(
-        "UPDATE memories SET superseded_by = ?1 WHERE id = ?2",
-        params![new_id, old_id],
+        "UPDATE memories SET superseded_by = ?1, updated_at = ?3 WHERE id = ?2",
+        params![new_id, old_id, chrono::Utc::now().to_rfc3339()],
     )?;
     if rows == 0 {
         bail!("supersedes target not found: {old_id}");
@@ -263,14 +950,9 @@ use rusqlite::OptionalExtension;
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db;
 
     fn test_db() -> Connection {
-        db::load_sqlite_vec();
-        let conn = Connection::open_in_memory().unwrap();
-        conn.pragma_update(None, "foreign_keys", "ON").unwrap();
-        crate::db::schema::init_schema(&conn).unwrap();
-        conn
+        crate::db::open_memory_database().unwrap()
     }
 
     /// Unit vector along dimension 0.
@@ -298,6 +980,491 @@ mod tests {
         v
     }
 
+    #[test]
+    fn test_store_rejects_zero_embedding() {
+        let mut conn = test_db();
+        let zero = vec![0.0f32; 384];
+
+        let err = store_memory(
+            &mut conn,
+            ":)",
+            MemoryType::Semantic,
+            Scope::Global,
+            Some("default"),
+            1.0,
+            None,
+            None,
+            &zero,
+            0.92,
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<StoreError>(),
+            Some(StoreError::DegenerateEmbedding { .. })
+        ));
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_store_enforces_min_content_chars() {
+        let mut conn = test_db();
+        let options = StoreOptions {
+            min_content_chars: 5,
+            ..Default::default()
+        };
+
+        let short = NewMemory {
+            content: " ok ",
+            memory_type: MemoryType::Episodic,
+            scope: Scope::Group,
+            group: Some("default"),
+            confidence: 1.0,
+            importance: DEFAULT_IMPORTANCE,
+            metadata: None,
+            supersedes: None,
+            source_uri: None,
+            id: None,
+            session_id: None,
+            expires_at: None,
+            tags: None,
+        };
+        let err = store_memory_with(&mut conn, &short, &embedding_a(), &options).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<StoreError>(),
+            Some(StoreError::ContentTooShort { len: 2, min: 5 })
+        ));
+
+        let long_enough = NewMemory {
+            content: "Deployed v2",
+            ..short
+        };
+        assert!(store_memory_with(&mut conn, &long_enough, &embedding_a(), &options).is_ok());
+    }
+
+    #[test]
+    fn test_store_rejects_over_length_content() {
+        let mut conn = test_db();
+        let options = StoreOptions {
+            max_content_chars: 10,
+            ..Default::default()
+        };
+        let at_limit = NewMemory {
+            content: "exactly 10",
+            memory_type: MemoryType::Semantic,
+            scope: Scope::Global,
+            group: Some("default"),
+            confidence: 1.0,
+            importance: DEFAULT_IMPORTANCE,
+            metadata: None,
+            supersedes: None,
+            source_uri: None,
+            id: None,
+            session_id: None,
+            expires_at: None,
+            tags: None,
+        };
+        assert!(store_memory_with(&mut conn, &at_limit, &embedding_a(), &options).is_ok());
+
+        let over = NewMemory {
+            content: "eleven char",
+            ..at_limit
+        };
+        // Rejected before embedding, and again on the full write path
+        let err = store_exact_duplicate(&mut conn, &over, &options).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<StoreError>(),
+            Some(StoreError::ContentTooLong { len: 11, max: 10 })
+        ));
+        let err = store_memory_with(&mut conn, &over, &embedding_b(), &options).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<StoreError>(),
+            Some(StoreError::ContentTooLong { len: 11, max: 10 })
+        ));
+    }
+
+    #[test]
+    fn test_store_truncates_over_length_content() {
+        let mut conn = test_db();
+        let options = StoreOptions {
+            max_content_chars: 10,
+            over_length_policy: OverLengthPolicy::Truncate,
+            ..Default::default()
+        };
+        let metadata = serde_json::json!({"source": "chat"});
+        let at_limit = NewMemory {
+            content: "exactly 10",
+            memory_type: MemoryType::Semantic,
+            scope: Scope::Global,
+            group: Some("default"),
+            confidence: 1.0,
+            importance: DEFAULT_IMPORTANCE,
+            metadata: Some(&metadata),
+            supersedes: None,
+            source_uri: None,
+            id: None,
+            session_id: None,
+            expires_at: None,
+            tags: None,
+        };
+        let kept = store_memory_with(&mut conn, &at_limit, &embedding_a(), &options).unwrap();
+
+        let over = NewMemory {
+            content: "éléven chär",
+            ..at_limit
+        };
+        let cut = store_memory_with(&mut conn, &over, &embedding_b(), &options).unwrap();
+
+        let read = |id: &str| -> (String, serde_json::Value) {
+            conn.query_row(
+                "SELECT content, metadata FROM memories WHERE id = ?1",
+                params![id],
+                |row| {
+                    let metadata: String = row.get(1)?;
+                    Ok((row.get(0)?, serde_json::from_str(&metadata).unwrap()))
+                },
+            )
+            .unwrap()
+        };
+        let (content, stored) = read(&kept.id);
+        assert_eq!(content, "exactly 10");
+        assert_eq!(stored, metadata);
+
+        let (content, stored) = read(&cut.id);
+        assert_eq!(content, "éléven chä");
+        assert_eq!(stored["source"], "chat");
+        assert_eq!(stored["original_length"], 11);
+    }
+
+    #[test]
+    fn test_exact_duplicate_skips_embedding() {
+        let mut conn = test_db();
+        let memory = NewMemory {
+            content: "The API uses port 8080",
+            memory_type: MemoryType::Semantic,
+            scope: Scope::Global,
+            group: Some("default"),
+            confidence: 0.8,
+            importance: DEFAULT_IMPORTANCE,
+            metadata: None,
+            supersedes: None,
+            source_uri: None,
+            id: None,
+            session_id: None,
+            expires_at: None,
+            tags: None,
+        };
+        let options = StoreOptions::default();
+
+        // Nothing stored yet: the caller has to embed
+        assert!(store_exact_duplicate(&mut conn, &memory, &options).unwrap().is_none());
+        let first = store_memory_with(&mut conn, &memory, &embedding_a(), &options).unwrap();
+
+        // Same text with different whitespace is caught without an embedding
+        let repeat = NewMemory {
+            content: "  The API uses\nport 8080 ",
+            ..memory
+        };
+        let hit = store_exact_duplicate(&mut conn, &repeat, &options).unwrap().unwrap();
+        assert!(hit.deduplicated);
+        assert_eq!(hit.id, first.id);
+
+        // A different type is not an exact duplicate
+        let other_type = NewMemory {
+            memory_type: MemoryType::Procedural,
+            ..memory
+        };
+        assert!(store_exact_duplicate(&mut conn, &other_type, &options).unwrap().is_none());
+
+        let confidence: f64 = conn
+            .query_row("SELECT confidence FROM memories WHERE id = ?1", params![first.id], |row| row.get(0))
+            .unwrap();
+        assert!((confidence - 0.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_skip_dedup_stores_exact_repeats() {
+        let mut conn = test_db();
+        let memory = NewMemory {
+            content: "Standup moved to 10am",
+            memory_type: MemoryType::Episodic,
+            scope: Scope::Group,
+            group: Some("default"),
+            confidence: 1.0,
+            importance: DEFAULT_IMPORTANCE,
+            metadata: None,
+            supersedes: None,
+            source_uri: None,
+            id: None,
+            session_id: None,
+            expires_at: None,
+            tags: None,
+        };
+        let options = StoreOptions {
+            skip_dedup: true,
+            ..Default::default()
+        };
+
+        let first = store_memory_with(&mut conn, &memory, &embedding_a(), &options).unwrap();
+        assert!(store_exact_duplicate(&mut conn, &memory, &options).unwrap().is_none());
+        let second = store_memory_with(&mut conn, &memory, &embedding_a(), &options).unwrap();
+
+        assert!(!second.deduplicated);
+        assert_ne!(first.id, second.id);
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_expired_memories_are_not_dedup_targets() {
+        let mut conn = test_db();
+        let yesterday = (chrono::Utc::now() - chrono::Duration::days(1)).to_rfc3339();
+        let expired = NewMemory {
+            content: "User is debugging the login flow",
+            memory_type: MemoryType::Episodic,
+            scope: Scope::Group,
+            group: Some("default"),
+            confidence: 1.0,
+            importance: DEFAULT_IMPORTANCE,
+            metadata: None,
+            supersedes: None,
+            source_uri: None,
+            id: None,
+            session_id: None,
+            expires_at: Some(&yesterday),
+            tags: None,
+        };
+        let options = StoreOptions::default();
+        let first = store_memory_with(&mut conn, &expired, &embedding_a(), &options).unwrap();
+
+        // Exact repeat: the hash gate skips the expired row
+        let repeat = NewMemory {
+            expires_at: None,
+            ..expired
+        };
+        assert!(store_exact_duplicate(&mut conn, &repeat, &options).unwrap().is_none());
+        let second = store_memory_with(&mut conn, &repeat, &embedding_a(), &options).unwrap();
+        assert!(!second.deduplicated);
+        assert_ne!(second.id, first.id);
+
+        // Near-duplicate of an expired row: the vector gate skips it too
+        let mut conn = test_db();
+        store_memory_with(&mut conn, &expired, &embedding_a(), &options).unwrap();
+        let similar = NewMemory {
+            content: "User is debugging the login page",
+            expires_at: None,
+            ..expired
+        };
+        let result = store_memory_with(&mut conn, &similar, &embedding_a_similar(), &options).unwrap();
+        assert!(!result.deduplicated);
+    }
+
+    #[test]
+    fn test_dedup_confidence_boost_is_configurable() {
+        let mut conn = test_db();
+        let memory = NewMemory {
+            content: "Builds run on every push",
+            memory_type: MemoryType::Semantic,
+            scope: Scope::Global,
+            group: Some("default"),
+            confidence: 0.5,
+            importance: DEFAULT_IMPORTANCE,
+            metadata: None,
+            supersedes: None,
+            source_uri: None,
+            id: None,
+            session_id: None,
+            expires_at: None,
+            tags: None,
+        };
+        let confidence = |conn: &Connection, id: &str| -> (f64, u32) {
+            conn.query_row(
+                "SELECT confidence, access_count FROM memories WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap()
+        };
+
+        let weak = StoreOptions {
+            dedup_confidence_boost: 0.03,
+            ..Default::default()
+        };
+        let first = store_memory_with(&mut conn, &memory, &embedding_a(), &weak).unwrap();
+        store_memory_with(&mut conn, &memory, &embedding_a(), &weak).unwrap();
+        let (value, accesses) = confidence(&conn, &first.id);
+        assert!((value - 0.53).abs() < 1e-9, "confidence {value}");
+        assert_eq!(accesses, 1);
+
+        // Zero boost only counts the access
+        let none = StoreOptions {
+            dedup_confidence_boost: 0.0,
+            ..Default::default()
+        };
+        store_memory_with(&mut conn, &memory, &embedding_a(), &none).unwrap();
+        let (value, accesses) = confidence(&conn, &first.id);
+        assert!((value - 0.53).abs() < 1e-9, "confidence {value}");
+        assert_eq!(accesses, 2);
+    }
+
+    #[test]
+    fn test_dry_run_persists_nothing() {
+        let mut conn = test_db();
+        let memory = NewMemory {
+            content: "Staging deploys need approval",
+            memory_type: MemoryType::Procedural,
+            scope: Scope::Global,
+            group: Some("default"),
+            confidence: 1.0,
+            importance: DEFAULT_IMPORTANCE,
+            metadata: None,
+            supersedes: None,
+            source_uri: None,
+            id: None,
+            session_id: None,
+            expires_at: None,
+            tags: None,
+        };
+        let dry_run = StoreOptions {
+            dry_run: true,
+            ..Default::default()
+        };
+        let counts = |conn: &Connection| -> (i64, i64) {
+            conn.query_row(
+                "SELECT (SELECT COUNT(*) FROM memories), (SELECT COUNT(*) FROM memory_log)",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap()
+        };
+
+        let preview = StorePreview::new(
+            store_memory_with(&mut conn, &memory, &embedding_a(), &dry_run).unwrap(),
+            memory.scope,
+        );
+        assert!(!preview.would_deduplicate);
+        assert_eq!(preview.matched_id, None);
+        assert_eq!(preview.scope, "global");
+        assert_eq!(counts(&conn), (0, 0));
+
+        // Against a real memory, the dry run reports the match but doesn't touch it
+        let stored = store_memory_with(&mut conn, &memory, &embedding_a(), &StoreOptions::default()).unwrap();
+        let before = counts(&conn);
+        let similar = NewMemory {
+            content: "Staging deploys need an approval",
+            ..memory
+        };
+        let preview = StorePreview::new(
+            store_memory_with(&mut conn, &similar, &embedding_a_similar(), &dry_run).unwrap(),
+            memory.scope,
+        );
+        assert!(preview.would_deduplicate);
+        assert_eq!(preview.matched_id.as_deref(), Some(stored.id.as_str()));
+        assert!(preview.similarity.unwrap() > 0.92);
+        assert_eq!(counts(&conn), before);
+        assert!(store_exact_duplicate(&mut conn, &memory, &dry_run).unwrap().is_some());
+        assert_eq!(counts(&conn), before);
+
+        let confidence: f64 = conn
+            .query_row("SELECT confidence FROM memories WHERE id = ?1", params![stored.id], |row| row.get(0))
+            .unwrap();
+        assert_eq!(confidence, 1.0);
+        let access_count: i64 = conn
+            .query_row("SELECT access_count FROM memories WHERE id = ?1", params![stored.id], |row| row.get(0))
+            .unwrap();
+        assert_eq!(access_count, 0);
+    }
+
+    #[test]
+    fn test_exact_duplicate_wins_over_dissimilar_embedding() {
+        let mut conn = test_db();
+        let memory = NewMemory {
+            content: "Deploys happen on Tuesdays",
+            memory_type: MemoryType::Semantic,
+            scope: Scope::Global,
+            group: Some("default"),
+            confidence: 1.0,
+            importance: DEFAULT_IMPORTANCE,
+            metadata: None,
+            supersedes: None,
+            source_uri: None,
+            id: None,
+            session_id: None,
+            expires_at: None,
+            tags: None,
+        };
+        let options = StoreOptions::default();
+
+        let first = store_memory_with(&mut conn, &memory, &embedding_a(), &options).unwrap();
+        // Orthogonal embedding would never pass the vector gate
+        let second = store_memory_with(&mut conn, &memory, &embedding_b(), &options).unwrap();
+        assert!(second.deduplicated);
+        assert_eq!(second.id, first.id);
+    }
+
+    #[test]
+    fn test_store_with_external_id() {
+        let mut conn = test_db();
+        let options = StoreOptions::default();
+        let memory = NewMemory {
+            content: "Roadmap page for Q3",
+            memory_type: MemoryType::Semantic,
+            scope: Scope::Global,
+            group: Some("default"),
+            confidence: 1.0,
+            importance: DEFAULT_IMPORTANCE,
+            metadata: None,
+            supersedes: None,
+            source_uri: None,
+            id: Some("notion:1a2b3c"),
+            session_id: None,
+            expires_at: None,
+            tags: None,
+        };
+
+        let result = store_memory_with(&mut conn, &memory, &embedding_a(), &options).unwrap();
+        assert_eq!(result.id, "notion:1a2b3c");
+        assert!(!result.deduplicated);
+
+        // Identical content under a new ID is stored separately, not deduplicated
+        let other = NewMemory {
+            id: Some("notion:4d5e6f"),
+            ..memory
+        };
+        assert!(store_exact_duplicate(&mut conn, &other, &options).unwrap().is_none());
+        let result = store_memory_with(&mut conn, &other, &embedding_a(), &options).unwrap();
+        assert_eq!(result.id, "notion:4d5e6f");
+
+        // Reusing an ID is an error
+        let err = store_memory_with(&mut conn, &memory, &embedding_a(), &options).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<StoreError>(),
+            Some(StoreError::IdExists { .. })
+        ));
+
+        for bad in ["", "has space", &"x".repeat(MAX_ID_LEN + 1)] {
+            let invalid = NewMemory {
+                id: Some(bad),
+                ..memory
+            };
+            let err = store_memory_with(&mut conn, &invalid, &embedding_b(), &options).unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<StoreError>(),
+                Some(StoreError::InvalidId { .. })
+            ));
+        }
+
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 2);
+    }
+
     #[test]
     fn test_store_new_memory() {
         let mut conn = test_db();
@@ -371,6 +1538,7 @@ mod tests {
         )
         .unwrap();
         assert!(!result1.deduplicated);
+        assert!(result1.similarity.is_none());
 
         // Store second with very similar embedding — should dedup
         let result2 = store_memory(
@@ -389,6 +1557,23 @@ mod tests {
 
         assert!(result2.deduplicated);
         assert_eq!(result2.id, result1.id);
+        let similarity = result2.similarity.unwrap();
+        assert!(similarity > 0.92 && similarity < 1.0, "similarity {similarity}");
+
+        // The audit log records why the write was folded in
+        let details: String = conn
+            .query_row(
+                "SELECT details FROM memory_log WHERE memory_id = ?1 AND operation = 'update'",
+                params![result1.id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let details: serde_json::Value = serde_json::from_str(&details).unwrap();
+        assert_eq!(details["reason"], "deduplication");
+        assert_eq!(details["similarity"].as_f64(), Some(similarity));
+        assert_eq!(details["threshold"].as_f64(), Some(0.92));
+        assert_eq!(details["incoming_length"], "Rust is great indeed".len());
+        assert_eq!(details["existing_length"], "Rust is great".len());
 
         // Verify confidence was boosted
         let confidence: f64 = conn
@@ -411,6 +1596,36 @@ mod tests {
         assert_eq!(access_count, 1);
     }
 
+    #[test]
+    fn test_disabled_dedup_keeps_near_duplicates() {
+        let mut conn = test_db();
+
+        let store = |conn: &mut Connection, content: &str, embedding: &[f32]| {
+            store_memory(
+                conn,
+                content,
+                MemoryType::Semantic,
+                Scope::Global,
+                Some("default"),
+                1.0,
+                None,
+                None,
+                embedding,
+                1.0, // no near-duplicate reaches this threshold
+            )
+            .unwrap()
+        };
+        let result1 = store(&mut conn, "Rust is great", &embedding_a());
+        let result2 = store(&mut conn, "Rust is great indeed", &embedding_a_similar());
+
+        assert!(!result2.deduplicated);
+        assert_ne!(result2.id, result1.id);
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 2);
+    }
+
     #[test]
     fn test_dedup_different_type_no_dedup() {
         let mut conn = test_db();
@@ -711,4 +1926,129 @@ mod tests {
         assert!(!result3.deduplicated);
         assert_ne!(result3.id, result1.id);
     }
+
+    #[test]
+    fn test_update_memory_follows_over_length_policy() {
+        let mut conn = test_db();
+        let metadata = serde_json::json!({"source": "chat"});
+        let id = store_memory(
+            &mut conn,
+            "short",
+            MemoryType::Semantic,
+            Scope::Global,
+            Some("default"),
+            1.0,
+            Some(&metadata),
+            None,
+            &embedding_a(),
+            0.92,
+        )
+        .unwrap()
+        .id;
+
+        let new_embedding = embedding_b();
+        let update = MemoryUpdate {
+            content: Some("éléven chär"),
+            embedding: Some(&new_embedding),
+            ..Default::default()
+        };
+        let reject = StoreOptions {
+            max_content_chars: 10,
+            ..Default::default()
+        };
+        let err = update_memory(&mut conn, &id, &update, &reject).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<StoreError>(),
+            Some(StoreError::ContentTooLong { len: 11, max: 10 })
+        ));
+
+        let truncate = StoreOptions {
+            over_length_policy: OverLengthPolicy::Truncate,
+            ..reject
+        };
+        let result = update_memory(&mut conn, &id, &update, &truncate).unwrap();
+        assert_eq!(result.updated, vec!["content", "metadata"]);
+
+        let (content, stored): (String, String) = conn
+            .query_row(
+                "SELECT content, metadata FROM memories WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        let stored: serde_json::Value = serde_json::from_str(&stored).unwrap();
+        assert_eq!(content, "éléven chä");
+        assert_eq!(stored["source"], "chat");
+        assert_eq!(stored["original_length"], 11);
+    }
+
+    #[test]
+    fn test_update_memory_resyncs_content_vector_and_fts() {
+        let mut conn = test_db();
+        let id = store_memory(
+            &mut conn,
+            "Teh deploy runs on fridays",
+            MemoryType::Semantic,
+            Scope::Global,
+            Some("default"),
+            1.0,
+            None,
+            None,
+            &embedding_a(),
+            0.92,
+        )
+        .unwrap()
+        .id;
+
+        let new_embedding = embedding_b();
+        let update = MemoryUpdate {
+            content: Some("The deploy runs on Thursdays"),
+            embedding: Some(&new_embedding),
+            ..Default::default()
+        };
+        let result = update_memory(&mut conn, &id, &update, &StoreOptions::default()).unwrap();
+        assert_eq!(result.id, id);
+        assert_eq!(result.updated, vec!["content"]);
+
+        let content: String = conn
+            .query_row("SELECT content FROM memories WHERE id = ?1", params![id], |row| row.get(0))
+            .unwrap();
+        assert_eq!(content, "The deploy runs on Thursdays");
+
+        // The nearest vector to the new embedding is the edited memory, at distance ~0
+        let query = embedding_b();
+        let (nearest, distance): (String, f64) = conn
+            .query_row(
+                "SELECT id, distance FROM memories_vec WHERE embedding MATCH ?1 ORDER BY distance LIMIT 1",
+                params![embedding_to_bytes(&query)],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(nearest, id);
+        assert!(distance < 1e-6);
+        let vec_rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM memories_vec WHERE id = ?1", params![id], |row| row.get(0))
+            .unwrap();
+        assert_eq!(vec_rows, 1);
+
+        let fts_hits = |term: &str| -> i64 {
+            conn.query_row(
+                "SELECT COUNT(*) FROM memories_fts WHERE memories_fts MATCH ?1",
+                params![term],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        assert_eq!(fts_hits("thursdays"), 1);
+        assert_eq!(fts_hits("fridays"), 0);
+
+        let op: String = conn
+            .query_row(
+                "SELECT operation FROM memory_log WHERE memory_id = ?1 ORDER BY id DESC LIMIT 1",
+                params![id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(op, "update");
+    }
 }
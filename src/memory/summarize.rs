@@ -0,0 +1,61 @@
+//! Summarization of compacted episodic memories.
+//!
+//! [`compact_episodic_with`](super::maintenance::compact_episodic_with) hands each
+//! week's memories to a [`Summarizer`] and stores whatever it returns as the summary.
+//! [`ConcatSummarizer`] joins and truncates the contents; implement the trait to plug in
+//! something smarter, such as an LLM behind a remote API.
+
+use anyhow::Result;
+
+use crate::config::MaintenanceConfig;
+
+/// Turns the contents of a group of memories into one summary.
+///
+/// Must be `Send + Sync` so one summarizer can be shared across threads.
+pub trait Summarizer: Send + Sync {
+    /// Summarize `contents`, given oldest first.
+    fn summarize(&self, contents: &[&str]) -> Result<String>;
+}
+
+/// The default summarizer: contents joined by a separator, truncated to a length.
+#[derive(Debug, Clone)]
+pub struct ConcatSummarizer {
+    /// Placed between consecutive contents.
+    pub separator: String,
+    /// The joined text is cut to this many characters, plus `"..."`.
+    pub max_chars: usize,
+}
+
+impl From<&MaintenanceConfig> for ConcatSummarizer {
+    fn from(config: &MaintenanceConfig) -> Self {
+        Self {
+            separator: config.compaction_separator.clone(),
+            max_chars: config.compaction_max_chars,
+        }
+    }
+}
+
+impl Summarizer for ConcatSummarizer {
+    fn summarize(&self, contents: &[&str]) -> Result<String> {
+        let combined = contents.join(&self.separator);
+        Ok(super::search::truncate_preview(&combined, self.max_chars))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concat_summarizer_joins_and_truncates() {
+        let summarizer = ConcatSummarizer {
+            separator: " | ".into(),
+            max_chars: 12,
+        };
+        assert_eq!(summarizer.summarize(&["one", "two"]).unwrap(), "one | two");
+        assert_eq!(
+            summarizer.summarize(&["first", "second", "third"]).unwrap(),
+            "first | seco..."
+        );
+    }
+}
@@ -8,11 +8,14 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::config::MaintenanceConfig;
+
 /// The four cognitive memory types, inspired by cognitive science.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum MemoryType {
-    /// Events, decisions, session logs — fast decay, group-scoped by default.
+    /// Events, decisions, session logs — fast decay, group-scoped by default
+    /// (session-scoped when stored with a session ID).
     Episodic,
     /// Facts, knowledge, preferences — slow decay, global-scoped by default.
     Semantic,
@@ -23,6 +26,14 @@ pub enum MemoryType {
 }
 
 impl MemoryType {
+    /// All memory types, in canonical order.
+    pub const ALL: [MemoryType; 4] = [
+        Self::Episodic,
+        Self::Semantic,
+        Self::Procedural,
+        Self::Entity,
+    ];
+
     /// SQL-compatible string representation.
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -33,13 +44,55 @@ impl MemoryType {
         }
     }
 
-    /// Default scope for this memory type.
-    pub fn default_scope(&self) -> Scope {
+    /// Default scope for this memory type. Episodic memories stored during a
+    /// session (`in_session`) stay within it.
+    pub fn default_scope(&self, in_session: bool) -> Scope {
         match self {
+            Self::Episodic if in_session => Scope::Session,
             Self::Episodic => Scope::Group,
             Self::Semantic | Self::Procedural | Self::Entity => Scope::Global,
         }
     }
+
+    /// Short human description of what belongs in this memory type.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Episodic => "Events, decisions, and session logs — what happened and when",
+            Self::Semantic => "Facts, knowledge, and preferences that stay true across sessions",
+            Self::Procedural => "Workflows, patterns, and how-to guides",
+            Self::Entity => "People, places, projects, and things that relations can link",
+        }
+    }
+}
+
+/// Server-side semantics of a memory type, as returned by `describe_memory_types`.
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryTypeInfo {
+    /// The memory type being described.
+    #[serde(rename = "type")]
+    pub memory_type: MemoryType,
+    /// Scope applied when `store_memory` is called without an explicit scope.
+    pub default_scope: Scope,
+    /// Confidence multiplier applied once per `interval_days` of elapsed time, pro rata.
+    pub decay_factor: f64,
+    /// Days of elapsed time over which `decay_factor` applies once in full.
+    pub interval_days: u64,
+    /// Human description of what belongs in this type.
+    pub description: &'static str,
+}
+
+/// Describe every memory type using the configured maintenance parameters.
+pub fn describe_memory_types(config: &MaintenanceConfig) -> Vec<MemoryTypeInfo> {
+    MemoryType::ALL
+        .iter()
+        .map(|&memory_type| MemoryTypeInfo {
+            memory_type,
+            default_scope: memory_type.default_scope(false),
+            decay_factor: config.decay_factor(memory_type),
+            interval_days: config.interval_days,
+            description: memory_type.description(),
+        })
+        .collect()
 }
 
 impl std::fmt::Display for MemoryType {
@@ -70,6 +123,9 @@ pub enum Scope {
     Global,
     /// Visible only within the owning `source_group` — used for episodic events.
     Group,
+    /// Visible only within the owning `session_id` — short-term memory for a single
+    /// conversation, cleaned up aggressively.
+    Session,
 }
 
 impl Scope {
@@ -77,6 +133,7 @@ impl Scope {
         match self {
             Self::Global => "global",
             Self::Group => "group",
+            Self::Session => "session",
         }
     }
 }
@@ -94,6 +151,7 @@ impl std::str::FromStr for Scope {
         match s {
             "global" => Ok(Self::Global),
             "group" => Ok(Self::Group),
+            "session" => Ok(Self::Session),
             _ => Err(format!("unknown scope: {s}")),
         }
     }
@@ -111,10 +169,13 @@ pub struct Memory {
     pub content: String,
     /// Group that owns this memory (e.g. project name). `None` for global memories.
     pub source_group: Option<String>,
-    /// Visibility scope — `Global` or `Group`.
+    /// Visibility scope — `Global`, `Group`, or `Session`.
     pub scope: Scope,
     /// Confidence score in `[0.0, 1.0]`, decays over time.
     pub confidence: f64,
+    /// Retrieval salience in `[0.0, 1.0]`; unlike confidence, it does not decay.
+    #[serde(default = "default_importance")]
+    pub importance: f64,
     /// Number of times this memory has been returned in search results.
     pub access_count: u32,
     /// ISO 8601 timestamp of the last recall, or `None` if never accessed.
@@ -127,6 +188,59 @@ pub struct Memory {
     pub superseded_by: Option<String>,
     /// Arbitrary JSON metadata (e.g. `{"summary": true}`).
     pub metadata: Option<serde_json::Value>,
+    /// Document, URL, or message ID this memory was derived from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_uri: Option<String>,
+    /// SHA-256 of the whitespace-normalized content (see [`content_hash`](crate::memory::content_hash)).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    /// Conversation this memory was stored in; required for session scope.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// RFC 3339 time after which the memory is no longer recalled and may be purged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    /// Labels for filtering recall, e.g. `["billing", "incident"]`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Stored vector, present only in exports made with `--with-embeddings`.
+    /// Serialized as base64 of the little-endian f32 bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "embedding_base64")]
+    pub embedding: Option<Vec<f32>>,
+}
+
+/// Serde adapter writing an embedding as base64 of its little-endian f32 bytes,
+/// which is about a third the size of a JSON number array.
+mod embedding_base64 {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(embedding: &Option<Vec<f32>>, s: S) -> Result<S::Ok, S::Error> {
+        match embedding {
+            Some(vector) => {
+                let bytes: Vec<u8> = vector.iter().flat_map(|x| x.to_le_bytes()).collect();
+                s.serialize_some(&STANDARD.encode(bytes))
+            }
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Vec<f32>>, D::Error> {
+        let Some(encoded) = Option::<String>::deserialize(d)? else {
+            return Ok(None);
+        };
+        let bytes = STANDARD.decode(encoded).map_err(serde::de::Error::custom)?;
+        if bytes.len() % 4 != 0 {
+            return Err(serde::de::Error::custom("embedding is not a whole number of f32 values"));
+        }
+        Ok(Some(crate::memory::bytes_to_embedding(&bytes)))
+    }
+}
+
+/// Importance assumed for records exported before the field existed.
+fn default_importance() -> f64 {
+    crate::memory::store::DEFAULT_IMPORTANCE
 }
 
 /// A directed relationship between two entity memories.
@@ -143,3 +257,42 @@ pub struct EntityRelation {
     /// ISO 8601 creation timestamp.
     pub created_at: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describe_uses_configured_decay_factors() {
+        let config = MaintenanceConfig {
+            episodic_decay_factor: 0.5,
+            semantic_decay_factor: 0.9,
+            interval_days: 3,
+            ..Default::default()
+        };
+
+        let types = describe_memory_types(&config);
+        assert_eq!(types.len(), MemoryType::ALL.len());
+        assert!(types.iter().all(|info| info.interval_days == 3));
+
+        let episodic = &types[0];
+        assert_eq!(episodic.memory_type, MemoryType::Episodic);
+        assert_eq!(episodic.default_scope, Scope::Group);
+        assert_eq!(episodic.decay_factor, 0.5);
+
+        for info in &types[1..] {
+            assert_eq!(info.default_scope, Scope::Global);
+            assert_eq!(info.decay_factor, 0.9);
+            assert!(!info.description.is_empty());
+        }
+    }
+
+    #[test]
+    fn session_scope_round_trips_and_is_episodic_default_in_session() {
+        assert_eq!("session".parse::<Scope>().unwrap(), Scope::Session);
+        assert_eq!(Scope::Session.as_str(), "session");
+        assert_eq!(MemoryType::Episodic.default_scope(true), Scope::Session);
+        assert_eq!(MemoryType::Episodic.default_scope(false), Scope::Group);
+        assert_eq!(MemoryType::Semantic.default_scope(true), Scope::Global);
+    }
+}
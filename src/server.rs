@@ -5,24 +5,37 @@
 
 use crate::config::LociConfig;
 use crate::db;
+use crate::db::shard::Databases;
 use crate::embedding;
 use crate::tools::LociTools;
 use anyhow::Result;
 use rmcp::ServiceExt;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long shutdown waits for in-flight database work before exiting anyway.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+/// How often the maintenance scheduler checks whether a database is due.
+const MAINTENANCE_POLL: Duration = Duration::from_secs(60 * 60);
 
 /// Shared setup: open DB, create embedding provider, check model version.
-/// Returns (db, embedding, config) wrapped in Arc for sharing.
+/// Returns (databases, embedding, config) wrapped in Arc for sharing.
 fn setup_shared_state(
     config: LociConfig,
 ) -> Result<(
-    Arc<Mutex<rusqlite::Connection>>,
+    Arc<Databases>,
     Arc<dyn embedding::EmbeddingProvider>,
     Arc<LociConfig>,
 )> {
     let db_path = config.resolved_db_path();
-    let conn = db::open_database(&db_path)?;
-    tracing::info!(db = %db_path.display(), "database ready");
+    let options = db::OpenOptions::from(&config);
+    let conn = db::open_database_with(&db_path, &options)?;
+    tracing::info!(
+        db = %db_path.display(),
+        shard_by_group = config.storage.shard_by_group,
+        "database ready"
+    );
 
     // Check for embedding model mismatch
     if let Ok(Some(stored_model)) = db::migrations::get_embedding_model(&conn) {
@@ -35,30 +48,184 @@ fn setup_shared_state(
         }
     }
 
-    let db = Arc::new(Mutex::new(conn));
+    check_model_hash(&conn, &config);
 
-    let provider = embedding::create_provider(&config.embedding)?;
-    let embedding: Arc<dyn embedding::EmbeddingProvider> = Arc::from(provider);
-    tracing::info!("embedding provider ready");
+    let encryption_key = options.encryption_key.clone();
+    let db = if config.storage.shard_by_group {
+        Databases::sharded(&db_path, conn, options)
+    } else {
+        Databases::single(&db_path, conn)
+    };
+    let db = Arc::new(db.with_read_pool(config.server.read_connections, encryption_key));
+
+    let embedding: Arc<dyn embedding::EmbeddingProvider> =
+        match embedding::create_provider(&config.embedding) {
+            Ok(provider) => {
+                tracing::info!("embedding provider ready");
+                Arc::from(provider)
+            }
+            Err(e) if config.retrieval.keyword_fallback => {
+                tracing::warn!(
+                    error = %e,
+                    "embedding provider unavailable — recall falls back to keyword search, stores will fail"
+                );
+                Arc::new(embedding::UnavailableProvider::new(e.to_string()))
+            }
+            Err(e) => return Err(e),
+        };
 
     let config = Arc::new(config);
 
     Ok((db, embedding, config))
 }
 
+/// Record the model file's hash on first sight, and warn when it has since changed.
+///
+/// Catches a swapped `model.onnx` that the name check above can't see. Failures only
+/// warn; they never stop the server from starting.
+fn check_model_hash(conn: &rusqlite::Connection, config: &LociConfig) {
+    let current = match embedding::model_file_hash(&config.embedding) {
+        Ok(Some(hash)) => hash,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!(error = %e, "could not hash embedding model file");
+            return;
+        }
+    };
+    match db::migrations::get_embedding_model_hash(conn) {
+        Ok(Some(stored)) if stored != current => tracing::warn!(
+            stored = %stored,
+            current = %current,
+            "embedding model file changed — run `loci re-embed` to update all vectors"
+        ),
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            if let Err(e) = db::migrations::set_embedding_model_hash(conn, &current) {
+                tracing::warn!(error = %e, "could not record embedding model hash");
+            }
+        }
+        Err(e) => tracing::warn!(error = %e, "could not read stored embedding model hash"),
+    }
+}
+
+/// Run decay, compaction, promotion, and cleanup in the background when
+/// `maintenance.enabled` is set.
+///
+/// Polls every [`MAINTENANCE_POLL`] and only touches databases whose last run (kept in
+/// `schema_meta`) is at least `maintenance.interval_days` old, so restarts don't re-run
+/// early. A failed cycle is logged and retried on the next poll.
+fn spawn_maintenance_scheduler(
+    dbs: Arc<Databases>,
+    embedding: Arc<dyn embedding::EmbeddingProvider>,
+    config: Arc<LociConfig>,
+) {
+    if !config.maintenance.enabled {
+        return;
+    }
+    tracing::info!(
+        interval_days = config.maintenance.interval_days,
+        "automatic maintenance enabled"
+    );
+
+    tokio::spawn(async move {
+        let mut poll = tokio::time::interval(MAINTENANCE_POLL);
+        poll.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            poll.tick().await;
+            let dbs = Arc::clone(&dbs);
+            let embedding = Arc::clone(&embedding);
+            let config = Arc::clone(&config);
+            let cycle = tokio::task::spawn_blocking(move || -> Result<()> {
+                for handle in dbs.all()? {
+                    if let Err(e) = run_scheduled_maintenance(&handle, embedding.as_ref(), &config)
+                    {
+                        tracing::warn!(db = %handle.path.display(), error = %e, "scheduled maintenance failed");
+                    }
+                }
+                Ok(())
+            });
+            match cycle.await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => tracing::warn!(error = %e, "scheduled maintenance failed"),
+                Err(e) => tracing::warn!(error = %e, "scheduled maintenance task failed"),
+            }
+        }
+    });
+}
+
+/// Run one maintenance cycle on `handle` if it is due.
+///
+/// [`run_maintenance`](crate::memory::maintenance::run_maintenance) takes the connection
+/// lock separately for each phase, so tool calls get a turn in between; cleanup runs as
+/// a final phase here.
+fn run_scheduled_maintenance(
+    handle: &db::shard::DbHandle,
+    embedding: &dyn embedding::EmbeddingProvider,
+    config: &LociConfig,
+) -> Result<()> {
+    use crate::memory::maintenance;
+
+    let lock = || {
+        handle
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))
+    };
+
+    if !maintenance::maintenance_due(&*lock()?, &config.maintenance, chrono::Utc::now())? {
+        return Ok(());
+    }
+
+    let report = maintenance::run_maintenance(
+        &handle.conn,
+        embedding,
+        &config.embedding.content_template,
+        &config.maintenance,
+    )?;
+    let (expired, cleanup) =
+        maintenance::with_maintenance_lock(&mut *lock()?, &config.maintenance, "cleanup", |conn| {
+            let expired = maintenance::purge_expired(conn)?;
+            Ok((expired, maintenance::cleanup_stale(conn, &config.maintenance, false)?))
+        })?;
+    maintenance::record_maintenance_run(&*lock()?, chrono::Utc::now())?;
+
+    tracing::info!(
+        db = %handle.path.display(),
+        decayed = report.decay.affected_by_type.values().sum::<usize>(),
+        summaries_created = report.compact.summaries_created,
+        semantics_created = report.promote.semantics_created,
+        expired,
+        cleaned_up = cleanup.deleted,
+        "scheduled maintenance complete"
+    );
+    Ok(())
+}
+
 /// Start the MCP server over stdio transport.
 pub async fn serve_stdio(config: LociConfig) -> Result<()> {
     tracing::info!("starting Loci MCP server on stdio");
 
     let (db, embedding, config) = setup_shared_state(config)?;
+    spawn_maintenance_scheduler(Arc::clone(&db), Arc::clone(&embedding), Arc::clone(&config));
 
-    let tools = LociTools::new(db, embedding, config);
+    let tools = LociTools::new(Arc::clone(&db), embedding, config);
     let transport = rmcp::transport::stdio();
 
     let server = tools.serve(transport).await?;
     tracing::info!("MCP server running — waiting for client");
 
-    server.waiting().await?;
+    // Stop taking requests on SIGINT/SIGTERM; the flush below still runs
+    let cancel = server.cancellation_token();
+    tokio::spawn(async move {
+        let signal = shutdown_signal().await;
+        tracing::info!(signal, "shutting down stdio server");
+        cancel.cancel();
+    });
+
+    let reason = server.waiting().await?;
+    tracing::info!(reason = ?reason, "MCP server stopped");
+
+    flush_databases(db).await;
     tracing::info!("MCP server shut down");
 
     Ok(())
@@ -69,10 +236,24 @@ pub async fn serve_sse(config: LociConfig) -> Result<()> {
     let host = config.server.host.clone();
     let port = config.server.port;
     let bind_addr = format!("{host}:{port}");
+    let sse_path = config.server.sse_path.clone();
+    if !sse_path.starts_with('/') || sse_path == "/" {
+        anyhow::bail!(
+            "server.sse_path must start with '/' and not be the root path, got '{sse_path}'"
+        );
+    }
+
+    let unix_socket = config.server.unix_socket.as_deref().map(crate::config::expand_tilde);
+    let auth_token = config.server.auth_token.clone();
+    if auth_token.as_deref().is_some_and(str::is_empty) {
+        anyhow::bail!("server.auth_token must not be empty; remove it to disable authentication");
+    }
 
     tracing::info!(addr = %bind_addr, "starting Loci MCP server on SSE/HTTP");
 
     let (db, embedding, config) = setup_shared_state(config)?;
+    spawn_maintenance_scheduler(Arc::clone(&db), Arc::clone(&embedding), Arc::clone(&config));
+    let dbs = Arc::clone(&db);
 
     let service = rmcp::transport::streamable_http_server::StreamableHttpService::new(
         move || Ok(LociTools::new(db.clone(), embedding.clone(), config.clone())),
@@ -81,19 +262,228 @@ pub async fn serve_sse(config: LociConfig) -> Result<()> {
         Default::default(),
     );
 
-    let router = axum::Router::new().nest_service("/mcp", service);
+    let router = require_auth(
+        axum::Router::new().nest_service(&sse_path, service),
+        auth_token.as_deref(),
+    );
 
-    let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
-    tracing::info!(addr = %bind_addr, "MCP server listening at http://{bind_addr}/mcp");
+    match unix_socket {
+        Some(socket_path) => serve_unix_socket(router, &socket_path, &sse_path).await?,
+        None => {
+            let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
+            tracing::info!(addr = %bind_addr, "MCP server listening at http://{bind_addr}{sse_path}");
 
-    axum::serve(listener, router)
-        .with_graceful_shutdown(async {
-            tokio::signal::ctrl_c()
-                .await
-                .expect("failed to listen for ctrl-c");
-            tracing::info!("shutting down SSE server");
-        })
-        .await?;
+            axum::serve(listener, router)
+                .with_graceful_shutdown(sse_shutdown())
+                .await?;
+        }
+    }
+
+    flush_databases(dbs).await;
 
     Ok(())
 }
+
+/// Reject requests without `Authorization: Bearer <token>` when a token is configured.
+fn require_auth(router: axum::Router, token: Option<&str>) -> axum::Router {
+    match token {
+        Some(token) => {
+            tracing::info!("bearer token authentication enabled");
+            router.layer(axum::middleware::from_fn_with_state(
+                Arc::new(token_digest(token)),
+                check_bearer,
+            ))
+        }
+        None => router,
+    }
+}
+
+/// Middleware: pass the request through if its bearer token matches, else answer 401.
+async fn check_bearer(
+    axum::extract::State(expected): axum::extract::State<Arc<[u8; 32]>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    use axum::http::{header, StatusCode};
+    use axum::response::IntoResponse;
+
+    let presented = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match presented {
+        Some(token) if digests_match(&token_digest(token), &expected) => next.run(request).await,
+        _ => {
+            tracing::warn!(path = %request.uri().path(), "rejected request without a valid bearer token");
+            (StatusCode::UNAUTHORIZED, [(header::WWW_AUTHENTICATE, "Bearer")]).into_response()
+        }
+    }
+}
+
+/// Tokens are compared by SHA-256 digest, so the comparison never depends on their length.
+fn token_digest(token: &str) -> [u8; 32] {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(token.as_bytes()).into()
+}
+
+/// Constant-time equality: every byte is compared, so timing doesn't reveal how much matched.
+fn digests_match(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    std::hint::black_box(diff) == 0
+}
+
+/// Serve `router` on a Unix domain socket at `socket_path`, removing the socket file on exit.
+///
+/// A leftover socket from a previous run is replaced; any other file at the path is an error.
+#[cfg(unix)]
+async fn serve_unix_socket(
+    router: axum::Router,
+    socket_path: &std::path::Path,
+    sse_path: &str,
+) -> Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    if let Ok(metadata) = std::fs::symlink_metadata(socket_path) {
+        if !metadata.file_type().is_socket() {
+            anyhow::bail!(
+                "server.unix_socket path {} exists and is not a socket",
+                socket_path.display()
+            );
+        }
+        std::fs::remove_file(socket_path)?;
+    }
+
+    let listener = tokio::net::UnixListener::bind(socket_path)?;
+    tracing::info!(
+        socket = %socket_path.display(),
+        "MCP server listening at unix:{}{sse_path}",
+        socket_path.display()
+    );
+
+    let result = axum::serve(listener, router)
+        .with_graceful_shutdown(sse_shutdown())
+        .await;
+
+    if let Err(e) = std::fs::remove_file(socket_path) {
+        tracing::warn!(socket = %socket_path.display(), error = %e, "failed to remove socket file");
+    }
+    Ok(result?)
+}
+
+#[cfg(not(unix))]
+async fn serve_unix_socket(
+    _router: axum::Router,
+    _socket_path: &std::path::Path,
+    _sse_path: &str,
+) -> Result<()> {
+    anyhow::bail!("server.unix_socket is only supported on Unix")
+}
+
+/// Resolve on a shutdown signal, logging it for the SSE server.
+async fn sse_shutdown() {
+    let signal = shutdown_signal().await;
+    tracing::info!(signal, "shutting down SSE server");
+}
+
+/// Resolve when the process receives SIGINT (Ctrl-C) or, on Unix, SIGTERM.
+async fn shutdown_signal() -> &'static str {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to listen for ctrl-c");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => "SIGINT",
+        _ = terminate => "SIGTERM",
+    }
+}
+
+/// Wait for in-flight database work, then checkpoint every WAL.
+///
+/// Tool handlers run their writes in `spawn_blocking` while holding the connection
+/// lock, so taking each lock means any half-finished transaction has committed.
+/// Gives up after [`SHUTDOWN_GRACE`] so a stuck task can't block exit forever.
+async fn flush_databases(dbs: Arc<Databases>) {
+    let flush = tokio::task::spawn_blocking(move || -> Result<()> {
+        for handle in dbs.all()? {
+            let conn = handle
+                .conn
+                .lock()
+                .map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
+            if db::checkpoint_wal(&conn)? {
+                tracing::info!(db = %handle.path.display(), "WAL checkpointed");
+            } else {
+                tracing::warn!(db = %handle.path.display(), "WAL checkpoint incomplete (database busy)");
+            }
+        }
+        Ok(())
+    });
+
+    match tokio::time::timeout(SHUTDOWN_GRACE, flush).await {
+        Ok(Ok(Ok(()))) => {}
+        Ok(Ok(Err(e))) => tracing::warn!(error = %e, "database flush failed"),
+        Ok(Err(e)) => tracing::warn!(error = %e, "database flush task failed"),
+        Err(_) => tracing::warn!(
+            grace_secs = SHUTDOWN_GRACE.as_secs(),
+            "timed out waiting for in-flight database work"
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serve `/mcp` (answering "ok") behind `require_auth` on an ephemeral port.
+    async fn spawn_protected(token: Option<&str>) -> String {
+        let router = axum::Router::new().route("/mcp", axum::routing::post(|| async { "ok" }));
+        let router = require_auth(router, token);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        format!("http://{addr}/mcp")
+    }
+
+    #[tokio::test]
+    async fn test_auth_token_gates_requests() {
+        let url = spawn_protected(Some("s3cret")).await;
+        let client = reqwest::Client::new();
+
+        let missing = client.post(&url).send().await.unwrap();
+        assert_eq!(missing.status(), reqwest::StatusCode::UNAUTHORIZED);
+        assert_eq!(missing.headers()["www-authenticate"], "Bearer");
+
+        let wrong = client.post(&url).bearer_auth("s3cre").send().await.unwrap();
+        assert_eq!(wrong.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let ok = client.post(&url).bearer_auth("s3cret").send().await.unwrap();
+        assert_eq!(ok.status(), reqwest::StatusCode::OK);
+        assert_eq!(ok.text().await.unwrap(), "ok");
+    }
+
+    #[tokio::test]
+    async fn test_no_auth_token_leaves_endpoint_open() {
+        let url = spawn_protected(None).await;
+        let response = reqwest::Client::new().post(&url).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
+    #[test]
+    fn test_digests_match_is_exact() {
+        assert!(digests_match(&token_digest("abc"), &token_digest("abc")));
+        assert!(!digests_match(&token_digest("abc"), &token_digest("abd")));
+        assert!(!digests_match(&token_digest("abc"), &token_digest("abc ")));
+    }
+}
@@ -6,8 +6,8 @@ use serde::{Deserialize, Serialize};
 /// Parameters for the `forget_memory` MCP tool.
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ForgetMemoryParams {
-    /// ID of the memory to forget.
-    #[schemars(description = "ID of the memory to forget")]
+    /// ID (or unique ID prefix) of the memory to forget.
+    #[schemars(description = "ID of the memory to forget, or a unique prefix of it")]
     pub memory_id: String,
 
     /// Optional reason for forgetting (recorded in audit log).
@@ -0,0 +1,22 @@
+//! MCP `forget_relation` tool parameter definition.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Parameters for the `forget_relation` MCP tool.
+///
+/// Deletes one exact (subject, predicate, object) triple; the entities are untouched.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ForgetRelationParams {
+    /// ID of the source entity memory.
+    #[schemars(description = "ID of the subject entity memory")]
+    pub subject_id: String,
+
+    /// Relationship label of the edge to remove.
+    #[schemars(description = "Predicate of the relation to remove (e.g. 'works_at')")]
+    pub predicate: String,
+
+    /// ID of the target entity memory.
+    #[schemars(description = "ID of the object entity memory")]
+    pub object_id: String,
+}
@@ -0,0 +1,66 @@
+//! MCP `list_memories` tool parameter definition.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Parameters for the `list_memories` MCP tool.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListMemoriesParams {
+    /// Filter by memory type: `"episodic"`, `"semantic"`, `"procedural"`, `"entity"`.
+    #[schemars(
+        description = "Filter by memory type: 'episodic', 'semantic', 'procedural', 'entity'"
+    )]
+    pub r#type: Option<String>,
+
+    /// Filter by scope: `"global"`, `"group"`, or `"session"`.
+    #[schemars(description = "Filter by scope: 'global', 'group', or 'session'")]
+    pub scope: Option<String>,
+
+    /// Group whose group-scoped memories are listed alongside global ones.
+    #[schemars(
+        description = "Group/project whose group-scoped memories are listed alongside global ones. Defaults to the server's default group."
+    )]
+    pub group: Option<String>,
+
+    /// The caller's session; session-scoped memories from it become visible.
+    #[schemars(
+        description = "ID of the current conversation. Session-scoped memories are only listed for the session that stored them."
+    )]
+    pub session_id: Option<String>,
+
+    /// Minimum confidence (0.0–1.0).
+    #[schemars(description = "Only list memories with at least this confidence (0.0-1.0)")]
+    pub min_confidence: Option<f64>,
+
+    /// Maximum confidence (0.0–1.0).
+    #[schemars(description = "Only list memories with at most this confidence (0.0-1.0)")]
+    pub max_confidence: Option<f64>,
+
+    /// Only list memories created at or after this RFC 3339 timestamp.
+    #[schemars(
+        description = "Only list memories created at or after this RFC 3339 timestamp, e.g. '2025-03-01T00:00:00Z'"
+    )]
+    pub created_after: Option<String>,
+
+    /// Only list memories created at or before this RFC 3339 timestamp.
+    #[schemars(
+        description = "Only list memories created at or before this RFC 3339 timestamp, e.g. '2025-03-08T00:00:00Z'"
+    )]
+    pub created_before: Option<String>,
+
+    /// Sort order: `"created_at"` (default), `"confidence"`, or `"access_count"`.
+    #[schemars(
+        description = "Sort order, always descending: 'created_at' (newest first, default), 'confidence', or 'access_count'"
+    )]
+    pub sort_by: Option<String>,
+
+    /// Maximum number of memories to return (1-100). Defaults to 20.
+    #[schemars(description = "Maximum number of memories to return (1-100). Defaults to 20.")]
+    pub limit: Option<usize>,
+
+    /// Memories to skip before the returned page. Defaults to 0.
+    #[schemars(
+        description = "Number of memories to skip, for fetching the next page: pass the previous offset plus limit while has_more is true. Defaults to 0."
+    )]
+    pub offset: Option<usize>,
+}
@@ -6,15 +6,27 @@ use serde::{Deserialize, Serialize};
 /// Parameters for the `memory_inspect` MCP tool.
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct MemoryInspectParams {
-    /// ID of the memory to inspect.
-    #[schemars(description = "ID of the memory to inspect")]
+    /// ID (or unique ID prefix) of the memory to inspect.
+    #[schemars(description = "ID of the memory to inspect, or a unique prefix of it")]
     pub memory_id: String,
 
     /// Include outbound entity relations (default: `true`).
     #[schemars(description = "If true, include related entities in the response. Defaults to true.")]
     pub include_relations: Option<bool>,
 
+    /// Include relations where this memory is the object (default: `false`).
+    #[schemars(
+        description = "If true, add 'incoming_relations': relations pointing at this memory (e.g. who manages this person). Defaults to false."
+    )]
+    pub include_incoming: Option<bool>,
+
     /// Include audit log entries for this memory (default: `false`).
     #[schemars(description = "If true, include audit log entries for this memory. Defaults to false.")]
     pub include_log: Option<bool>,
+
+    /// Include the stored embedding vector, its dimension and norm (default: `false`).
+    #[schemars(
+        description = "If true, include the stored embedding vector with its dimension and L2 norm, for debugging retrieval. Defaults to false."
+    )]
+    pub include_vector: Option<bool>,
 }
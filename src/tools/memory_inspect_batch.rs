@@ -0,0 +1,24 @@
+//! MCP `memory_inspect_batch` tool parameter definition.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Parameters for the `memory_inspect_batch` MCP tool.
+///
+/// Like `memory_inspect`, for several memories in one call.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct MemoryInspectBatchParams {
+    /// IDs (or unique ID prefixes) of the memories to inspect.
+    #[schemars(
+        description = "IDs of the memories to inspect, or unique prefixes of them. IDs that match nothing are listed in 'not_found'."
+    )]
+    pub memory_ids: Vec<String>,
+
+    /// Include outbound entity relations for every memory (default: `true`).
+    #[schemars(description = "If true, include related entities for each memory. Defaults to true.")]
+    pub include_relations: Option<bool>,
+
+    /// Include audit log entries for every memory (default: `false`).
+    #[schemars(description = "If true, include audit log entries for each memory. Defaults to false.")]
+    pub include_log: Option<bool>,
+}
@@ -5,76 +5,196 @@
 //! state and exposes all tools via the `#[tool_router]` macro from `rmcp`.
 
 pub mod forget_memory;
+pub mod forget_relation;
+pub mod list_memories;
 pub mod memory_inspect;
+pub mod memory_inspect_batch;
 pub mod memory_stats;
+pub mod query_relations;
 pub mod recall_memory;
+pub mod reclassify_memory;
+pub mod restore_memory;
 pub mod store_memory;
+pub mod store_memory_batch;
 pub mod store_relation;
+pub mod store_relations;
+pub mod touch_memory;
+pub mod update_memory;
 
 use forget_memory::ForgetMemoryParams;
+use forget_relation::ForgetRelationParams;
+use list_memories::ListMemoriesParams;
 use memory_inspect::MemoryInspectParams;
+use memory_inspect_batch::MemoryInspectBatchParams;
 use memory_stats::MemoryStatsParams;
+use query_relations::QueryRelationsParams;
 use recall_memory::RecallMemoryParams;
+use reclassify_memory::ReclassifyMemoryParams;
+use restore_memory::RestoreMemoryParams;
 use rmcp::handler::server::tool::ToolRouter;
 use rmcp::handler::server::wrapper::Parameters;
 use rmcp::{tool, tool_handler, tool_router, ServerHandler};
-use rusqlite::Connection;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use store_memory::StoreMemoryParams;
+use store_memory_batch::StoreMemoryBatchParams;
 use store_relation::StoreRelationParams;
+use store_relations::StoreRelationsParams;
+use touch_memory::TouchMemoryParams;
+use update_memory::UpdateMemoryParams;
 
 use crate::config::LociConfig;
+use crate::db::shard::Databases;
 use crate::embedding::EmbeddingProvider;
 use crate::memory::types::{MemoryType, Scope};
 
-/// The Loci MCP tool handler. Holds shared state (db connection, embedding provider,
+/// Most memories one `list_memories` page may return.
+const MAX_LIST_LIMIT: usize = 100;
+
+/// Deepest traversal `query_relations` accepts, keeping fan-out on dense graphs bounded.
+const MAX_TRAVERSAL_DEPTH: usize = 5;
+
+/// The Loci MCP tool handler. Holds shared state (database routing, embedding provider,
 /// config) and exposes all MCP tools via the `#[tool_router]` macro.
 #[derive(Clone)]
 pub struct LociTools {
     tool_router: ToolRouter<Self>,
-    db: Arc<Mutex<Connection>>,
+    dbs: Arc<Databases>,
     embedding: Arc<dyn EmbeddingProvider>,
     config: Arc<LociConfig>,
 }
 
+/// Time spent per stage of a tool call, echoed back when called with `debug: true`.
+#[derive(Debug, Default)]
+struct RequestTiming {
+    /// Time in the embedding call (zero if it was skipped).
+    embed: Duration,
+    /// Time in database work.
+    db: Duration,
+    /// Dimension of the embedding produced, if one was.
+    embedding_dim: Option<usize>,
+}
+
+impl RequestTiming {
+    /// Add `timing` and `embedding_dim` to a serialized JSON object response.
+    fn attach(&self, output: String) -> Result<String, String> {
+        let mut value: serde_json::Value =
+            serde_json::from_str(&output).map_err(|e| format!("serialization failed: {e}"))?;
+        if let Some(object) = value.as_object_mut() {
+            object.insert(
+                "timing".into(),
+                serde_json::json!({
+                    "embed_ms": self.embed.as_secs_f64() * 1000.0,
+                    "db_ms": self.db.as_secs_f64() * 1000.0,
+                }),
+            );
+            if let Some(dim) = self.embedding_dim {
+                object.insert("embedding_dim".into(), dim.into());
+            }
+        }
+        serde_json::to_string(&value).map_err(|e| format!("serialization failed: {e}"))
+    }
+
+    /// Attach timings only when the caller asked for them.
+    fn finish(&self, debug: bool, output: String) -> Result<String, String> {
+        if debug { self.attach(output) } else { Ok(output) }
+    }
+}
+
+/// The validated, defaulted fields of a store request.
+struct StoreFields {
+    memory_type: MemoryType,
+    scope: Scope,
+    confidence: f64,
+    importance: f64,
+}
+
+/// Serialize a recall response as a summary, a field projection, or in full.
+fn render_recall(
+    response: &crate::memory::search::RecallResponse,
+    summary_only: bool,
+    fields: Option<&[String]>,
+) -> Result<String, String> {
+    if summary_only {
+        let summary = crate::memory::search::to_summary(response);
+        return serde_json::to_string(&summary).map_err(|e| format!("serialization failed: {e}"));
+    }
+    if let Some(fields) = fields {
+        let projected = crate::memory::search::project_fields(response, fields)
+            .map_err(|e| format!("invalid fields: {e}"))?;
+        return serde_json::to_string(&projected).map_err(|e| format!("serialization failed: {e}"));
+    }
+    serde_json::to_string(response).map_err(|e| format!("serialization failed: {e}"))
+}
+
 #[tool_router]
 impl LociTools {
     pub fn new(
-        db: Arc<Mutex<Connection>>,
+        dbs: Arc<Databases>,
         embedding: Arc<dyn EmbeddingProvider>,
         config: Arc<LociConfig>,
     ) -> Self {
+        let mut tool_router = Self::tool_router();
+        if !config.server.allow_maintenance_tool {
+            tool_router.remove_route("run_maintenance");
+        }
         Self {
-            tool_router: Self::tool_router(),
-            db,
+            tool_router,
+            dbs,
             embedding,
             config,
         }
     }
 
-    /// Store a new memory in the cognitive memory system.
-    #[tool(description = "Store a new memory. Types: episodic (events/experiences), semantic (facts/knowledge), procedural (how-to/processes), entity (people/places/things).")]
-    async fn store_memory(
-        &self,
-        Parameters(params): Parameters<StoreMemoryParams>,
-    ) -> Result<String, String> {
-        // 1. Validate inputs
+    /// Parse and range-check the type, scope, confidence, and importance of a store request.
+    fn store_fields(&self, params: &StoreMemoryParams) -> Result<StoreFields, String> {
         let memory_type: MemoryType = params.r#type.parse().map_err(|e: String| e)?;
 
         let scope = match &params.scope {
             Some(s) => s.parse::<Scope>().map_err(|e: String| e)?,
-            None => memory_type.default_scope(),
+            None => memory_type.default_scope(params.session_id.is_some()),
         };
 
-        let confidence = params.confidence.unwrap_or(1.0);
+        let confidence = params
+            .confidence
+            .unwrap_or_else(|| self.config.retrieval.default_confidence(memory_type));
         if !(0.0..=1.0).contains(&confidence) {
             return Err("confidence must be between 0.0 and 1.0".into());
         }
 
+        let importance = params
+            .importance
+            .unwrap_or(crate::memory::store::DEFAULT_IMPORTANCE);
+        if !(0.0..=1.0).contains(&importance) {
+            return Err("importance must be between 0.0 and 1.0".into());
+        }
+
         if params.content.is_empty() {
             return Err("content must not be empty".into());
         }
 
+        Ok(StoreFields {
+            memory_type,
+            scope,
+            confidence,
+            importance,
+        })
+    }
+
+    /// Store a new memory in the cognitive memory system.
+    #[tool(description = "Store a new memory. Types: episodic (events/experiences), semantic (facts/knowledge), procedural (how-to/processes), entity (people/places/things).")]
+    async fn store_memory(
+        &self,
+        Parameters(params): Parameters<StoreMemoryParams>,
+    ) -> Result<String, String> {
+        // 1. Validate inputs
+        let StoreFields {
+            memory_type,
+            scope,
+            confidence,
+            importance,
+        } = self.store_fields(&params)?;
+
         let group = params
             .group
             .as_deref()
@@ -88,52 +208,243 @@ impl LociTools {
             "store_memory called"
         );
 
-        // 2. Embed content (CPU-heavy → spawn_blocking)
-        let embedding_provider = Arc::clone(&self.embedding);
-        let content_for_embed = params.content.clone();
-        let embedding = tokio::task::spawn_blocking(move || {
-            embedding_provider.embed(&content_for_embed)
-        })
-        .await
-        .map_err(|e| format!("embedding task failed: {e}"))?
-        .map_err(|e| format!("embedding failed: {e}"))?;
-
-        // 3. Run write path (sync DB ops → spawn_blocking)
-        let db = Arc::clone(&self.db);
-        let dedup_threshold = self.config.retrieval.dedup_threshold;
+        let db = self
+            .dbs
+            .for_write(scope, group)
+            .map_err(|e| format!("store failed: {e}"))?
+            .conn;
+        let dry_run = params.dry_run.unwrap_or(false);
+        let options = crate::memory::store::StoreOptions {
+            dry_run,
+            skip_dedup: params.skip_dedup.unwrap_or(false),
+            ..crate::memory::store::StoreOptions::from(self.config.as_ref())
+        };
         let content = params.content;
         let metadata = params.metadata;
         let supersedes = params.supersedes;
+        let source_uri = params.source_uri;
+        let id = params.id;
+        let session_id = params.session_id;
+        let expires_at = params.expires_at;
+        let tags = params.tags;
         let group_owned = group.to_string();
+        let debug = params.debug.unwrap_or(false);
+        let mut timing = RequestTiming::default();
+
+        // 2. Exact repeats are caught by content hash before paying for an embedding
+        let started = Instant::now();
+        let exact = {
+            let db = Arc::clone(&db);
+            let content = content.clone();
+            let group_owned = group_owned.clone();
+            let options = options.clone();
+            let id = id.clone();
+            let session_id = session_id.clone();
+            let expires_at = expires_at.clone();
+            let tags = tags.clone();
+            tokio::task::spawn_blocking(move || {
+                let mut conn = db
+                    .lock()
+                    .map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
+                let memory = crate::memory::store::NewMemory {
+                    content: &content,
+                    memory_type,
+                    scope,
+                    group: Some(&group_owned),
+                    confidence,
+                    importance,
+                    metadata: None,
+                    supersedes: None,
+                    source_uri: None,
+                    id: id.as_deref(),
+                    session_id: session_id.as_deref(),
+                    expires_at: expires_at.as_deref(),
+                    tags: tags.as_deref(),
+                };
+                crate::memory::store::store_exact_duplicate(&mut conn, &memory, &options)
+            })
+            .await
+            .map_err(|e| format!("db task failed: {e}"))?
+            .map_err(|e| format!("store failed: {e}"))?
+        };
+        timing.db += started.elapsed();
+        if let Some(result) = exact {
+            tracing::info!(id = %result.id, dry_run, "exact duplicate, skipped embedding");
+            let output = if dry_run {
+                serde_json::to_string(&crate::memory::store::StorePreview::new(result, scope))
+            } else {
+                serde_json::to_string(&result)
+            }
+            .map_err(|e| format!("serialization failed: {e}"))?;
+            return timing.finish(debug, output);
+        }
+
+        // 3. Embed content (CPU-heavy, runs on the blocking pool)
+        let started = Instant::now();
+        let content_for_embed = crate::embedding::render_content_template(
+            &self.config.embedding.content_template,
+            &content,
+            memory_type,
+            scope,
+            Some(&group_owned),
+        );
+        let embedding = Arc::clone(&self.embedding)
+            .embed_async(content_for_embed)
+            .await
+            .map_err(|e| format!("embedding failed: {e}"))?;
+        timing.embed = started.elapsed();
+        timing.embedding_dim = Some(embedding.len());
 
+        // 4. Run write path (sync DB ops → spawn_blocking)
+        let started = Instant::now();
         let result = tokio::task::spawn_blocking(move || {
             let mut conn = db
                 .lock()
                 .map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
-            crate::memory::store::store_memory(
-                &mut conn,
-                &content,
+            let memory = crate::memory::store::NewMemory {
+                content: &content,
                 memory_type,
                 scope,
-                Some(&group_owned),
+                group: Some(&group_owned),
                 confidence,
-                metadata.as_ref(),
-                supersedes.as_deref(),
-                &embedding,
-                dedup_threshold,
-            )
+                importance,
+                metadata: metadata.as_ref(),
+                supersedes: supersedes.as_deref(),
+                source_uri: source_uri.as_deref(),
+                id: id.as_deref(),
+                session_id: session_id.as_deref(),
+                expires_at: expires_at.as_deref(),
+                tags: tags.as_deref(),
+            };
+            crate::memory::store::store_memory_with(&mut conn, &memory, &embedding, &options)
         })
         .await
         .map_err(|e| format!("db task failed: {e}"))?
         .map_err(|e| format!("store failed: {e}"))?;
+        timing.db += started.elapsed();
 
         tracing::info!(
             id = %result.id,
             deduplicated = result.deduplicated,
+            dry_run,
             "memory stored"
         );
 
-        serde_json::to_string(&result).map_err(|e| format!("serialization failed: {e}"))
+        let output = if dry_run {
+            serde_json::to_string(&crate::memory::store::StorePreview::new(result, scope))
+        } else {
+            serde_json::to_string(&result)
+        }
+        .map_err(|e| format!("serialization failed: {e}"))?;
+        timing.finish(debug, output)
+    }
+
+    /// Store many memories in one call and one transaction.
+    #[tool(description = "Store many memories at once, e.g. when ingesting a transcript. Each item takes store_memory's parameters (dry_run, debug and skip_dedup are not supported). All items are embedded together and written in one transaction; each is still deduplicated against existing memories and earlier items in the batch. One invalid item fails the whole batch with its index. Returns one store result per item, in input order.")]
+    async fn store_memory_batch(
+        &self,
+        Parameters(params): Parameters<StoreMemoryBatchParams>,
+    ) -> Result<String, String> {
+        if params.memories.is_empty() {
+            return Err("memories must not be empty".into());
+        }
+
+        // 1. Validate every item and route the batch to a single database
+        let mut fields = Vec::with_capacity(params.memories.len());
+        let mut groups = Vec::with_capacity(params.memories.len());
+        let mut handle = None;
+        for (index, memory) in params.memories.iter().enumerate() {
+            if memory.dry_run.is_some() || memory.debug.is_some() || memory.skip_dedup.is_some() {
+                return Err(format!(
+                    "memory {index}: dry_run, debug and skip_dedup are not supported in a batch"
+                ));
+            }
+            let item = self
+                .store_fields(memory)
+                .map_err(|e| format!("memory {index}: {e}"))?;
+            let group = memory
+                .group
+                .clone()
+                .unwrap_or_else(|| self.config.storage.default_group.clone());
+            let item_handle = self
+                .dbs
+                .for_write(item.scope, &group)
+                .map_err(|e| format!("memory {index}: {e}"))?;
+            let first = handle.get_or_insert_with(|| item_handle.clone());
+            if first.path != item_handle.path {
+                return Err(format!(
+                    "memory {index}: belongs in a different group shard; \
+                     a batch can only store into one database"
+                ));
+            }
+            fields.push(item);
+            groups.push(group);
+        }
+        let db = handle.expect("non-empty batch").conn;
+
+        tracing::info!(count = params.memories.len(), "store_memory_batch called");
+
+        // 2. Embed everything in one batch
+        let texts = params
+            .memories
+            .iter()
+            .zip(&fields)
+            .zip(&groups)
+            .map(|((memory, item), group)| {
+                crate::embedding::render_content_template(
+                    &self.config.embedding.content_template,
+                    &memory.content,
+                    item.memory_type,
+                    item.scope,
+                    Some(group),
+                )
+            })
+            .collect();
+        let embeddings = Arc::clone(&self.embedding)
+            .embed_batch_async(texts)
+            .await
+            .map_err(|e| format!("embedding failed: {e}"))?;
+
+        // 3. Write all items in one transaction
+        let options = crate::memory::store::StoreOptions::from(self.config.as_ref());
+        let memories = params.memories;
+        let results = tokio::task::spawn_blocking(move || {
+            let mut conn = db
+                .lock()
+                .map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
+            let batch: Vec<_> = memories
+                .iter()
+                .zip(&fields)
+                .zip(&groups)
+                .map(|((memory, item), group)| crate::memory::store::NewMemory {
+                    content: &memory.content,
+                    memory_type: item.memory_type,
+                    scope: item.scope,
+                    group: Some(group),
+                    confidence: item.confidence,
+                    importance: item.importance,
+                    metadata: memory.metadata.as_ref(),
+                    supersedes: memory.supersedes.as_deref(),
+                    source_uri: memory.source_uri.as_deref(),
+                    id: memory.id.as_deref(),
+                    session_id: memory.session_id.as_deref(),
+                    expires_at: memory.expires_at.as_deref(),
+                    tags: memory.tags.as_deref(),
+                })
+                .collect();
+            crate::memory::store::store_memory_batch(&mut conn, &batch, &embeddings, &options)
+        })
+        .await
+        .map_err(|e| format!("db task failed: {e}"))?
+        .map_err(|e| format!("store_memory_batch failed: {e:#}"))?;
+
+        tracing::info!(
+            stored = results.iter().filter(|r| !r.deduplicated).count(),
+            deduplicated = results.iter().filter(|r| r.deduplicated).count(),
+            "memory batch stored"
+        );
+
+        serde_json::to_string(&results).map_err(|e| format!("serialization failed: {e}"))
     }
 
     /// Search and retrieve memories using natural language queries.
@@ -147,47 +458,79 @@ impl LociTools {
             return Err("either 'query' or 'ids' must be provided".into());
         }
 
+        if params.groups.is_some() && params.group.is_some() {
+            return Err("use either 'group' or 'groups', not both".into());
+        }
+        if params.groups.as_ref().is_some_and(|g| g.is_empty()) {
+            return Err("'groups' must not be empty".into());
+        }
         let group = params
             .group
             .as_deref()
             .unwrap_or(&self.config.storage.default_group)
             .to_string();
         let summary_only = params.summary_only.unwrap_or(false);
+        if summary_only && params.fields.is_some() {
+            return Err("'fields' cannot be combined with 'summary_only'".into());
+        }
+        if let Some(ref fields) = params.fields {
+            crate::memory::search::validate_fields(fields)
+                .map_err(|e| format!("invalid fields: {e}"))?;
+        }
+        let fields = params.fields;
+        let debug = params.debug.unwrap_or(false);
+        let mut timing = RequestTiming::default();
 
         // ID hydration mode
         if let Some(ids) = params.ids {
             tracing::info!(count = ids.len(), "recall_memory: hydrating by IDs");
-            let db = Arc::clone(&self.db);
+            let dbs = Arc::clone(&self.dbs);
+            let started = Instant::now();
             let response = tokio::task::spawn_blocking(move || {
-                let conn = db.lock().map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
-                crate::memory::search::recall_by_ids(&conn, &ids)
+                let mut responses = Vec::new();
+                for handle in dbs.all()? {
+                    let conn = handle
+                        .conn
+                        .lock()
+                        .map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
+                    responses.push(crate::memory::search::recall_by_ids(&conn, &ids)?);
+                }
+                Ok::<_, anyhow::Error>(crate::memory::search::merge_id_responses(responses, &ids))
             })
             .await
             .map_err(|e| format!("task failed: {e}"))?
             .map_err(|e| format!("recall failed: {e}"))?;
+            timing.db = started.elapsed();
 
-            if summary_only {
-                let summary = crate::memory::search::to_summary(&response);
-                return serde_json::to_string(&summary)
-                    .map_err(|e| format!("serialization failed: {e}"));
-            }
-            return serde_json::to_string(&response)
-                .map_err(|e| format!("serialization failed: {e}"));
+            let output = render_recall(&response, summary_only, fields.as_deref())?;
+            return timing.finish(debug, output);
         }
 
         // Query search mode
         let query = params.query.unwrap(); // safe: validated above
-        tracing::info!(query = %query, "recall_memory: hybrid search");
+        let mode = params
+            .mode
+            .as_deref()
+            .map(|m| m.parse::<crate::memory::search::SearchMode>())
+            .transpose()?
+            .unwrap_or_default();
+        tracing::info!(query = %query, mode = mode.as_str(), "recall_memory: search");
 
-        // Embed the query
-        let embedding_provider = Arc::clone(&self.embedding);
-        let query_for_embed = query.clone();
-        let query_embedding = tokio::task::spawn_blocking(move || {
-            embedding_provider.embed(&query_for_embed)
-        })
+        // Embed the query (keyword mode doesn't use it)
+        let started = Instant::now();
+        let embedded = crate::memory::recall::embed_query(
+            Arc::clone(&self.embedding),
+            &self.config,
+            &query,
+            &group,
+            mode,
+        )
         .await
-        .map_err(|e| format!("embedding task failed: {e}"))?
-        .map_err(|e| format!("embedding failed: {e}"))?;
+        .map_err(|e| e.to_string())?;
+        timing.embed = started.elapsed();
+        if !embedded.embedding.is_empty() {
+            timing.embedding_dim = Some(embedded.embedding.len());
+        }
 
         // Parse optional filters
         let memory_type = params
@@ -204,10 +547,18 @@ impl LociTools {
             .transpose()
             .map_err(|e| e)?;
 
-        let max_results = params
+        let requested_max_results = params
             .max_results
-            .unwrap_or(self.config.retrieval.default_max_results)
-            .clamp(1, 20);
+            .unwrap_or(self.config.retrieval.default_max_results);
+        let ceiling = self.config.retrieval.max_results_ceiling.max(1);
+        let max_results = requested_max_results.clamp(1, ceiling);
+        if max_results != requested_max_results {
+            tracing::warn!(
+                requested = requested_max_results,
+                used = max_results,
+                "recall_memory: max_results clamped"
+            );
+        }
 
         let token_budget = params
             .token_budget
@@ -215,36 +566,83 @@ impl LociTools {
 
         let min_confidence = params.min_confidence.unwrap_or(0.1);
 
+        for (field, value) in [
+            ("created_after", params.created_after.as_deref()),
+            ("created_before", params.created_before.as_deref()),
+        ] {
+            crate::memory::search::parse_timestamp_bound(field, value).map_err(|e| e.to_string())?;
+        }
+
         let rrf_k = self.config.retrieval.rrf_k;
+        let vec_weight = params.vec_weight.unwrap_or(self.config.retrieval.vec_weight);
+        let fts_weight = params.fts_weight.unwrap_or(self.config.retrieval.fts_weight);
+        if ![vec_weight, fts_weight].iter().all(|w| w.is_finite() && *w >= 0.0) {
+            return Err("'vec_weight' and 'fts_weight' must be non-negative numbers".into());
+        }
+        let mmr_lambda = params.mmr_lambda.or(self.config.retrieval.mmr_lambda);
+        if mmr_lambda.is_some_and(|l| !(0.0..=1.0).contains(&l)) {
+            return Err("'mmr_lambda' must be between 0.0 and 1.0".into());
+        }
+        let recency_half_life_days = params
+            .recency_half_life_days
+            .or(self.config.retrieval.recency_half_life_days);
+        if recency_half_life_days.is_some_and(|h| !(h.is_finite() && h > 0.0)) {
+            return Err("'recency_half_life_days' must be a positive number".into());
+        }
 
         let filter = crate::memory::search::SearchFilter {
             memory_type,
             scope,
             group,
+            groups: params.groups,
+            session_id: params.session_id,
             min_confidence,
+            source_uri: params.source_uri,
+            include_superseded: params.include_superseded.unwrap_or(false),
+            exclude_ids: params.exclude_ids.unwrap_or_default(),
+            created_after: params.created_after,
+            created_before: params.created_before,
+            metadata_filters: params.metadata.unwrap_or_default().into_iter().collect(),
+            tags: params.tags.unwrap_or_default(),
         };
 
         let search_config = crate::memory::search::SearchConfig {
             max_results,
             token_budget,
             rrf_k,
+            stopwords: self.config.retrieval.fts_stopwords.clone(),
+            mode: embedded.mode,
+            importance_beta: self.config.retrieval.importance_weight,
+            log_queries: self.config.retrieval.log_queries,
+            balance_sources: self.config.retrieval.balance_sources,
+            vector_candidate_multiplier: self.config.retrieval.vector_candidate_multiplier,
+            fts_candidate_multiplier: self.config.retrieval.fts_candidate_multiplier,
+            vec_weight,
+            fts_weight,
+            offset: params.offset.unwrap_or(0),
+            mmr_lambda,
+            recency_half_life_days,
+            track_access: true,
+            reinforce_amount: self.config.maintenance.access_reinforcement(),
         };
 
-        // Run hybrid search
-        let db = Arc::clone(&self.db);
-        let response = tokio::task::spawn_blocking(move || {
-            let conn = db.lock().map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
-            crate::memory::search::recall_by_query(
-                &conn,
-                &query_embedding,
-                &query,
-                &filter,
-                &search_config,
-            )
-        })
+        // Run the search against every database these groups can see
+        let started = Instant::now();
+        let mut response = crate::memory::recall::search_databases(
+            Arc::clone(&self.dbs),
+            query,
+            embedded.embedding,
+            filter,
+            search_config,
+        )
         .await
-        .map_err(|e| format!("search task failed: {e}"))?
         .map_err(|e| format!("search failed: {e}"))?;
+        timing.db = started.elapsed();
+
+        if max_results != requested_max_results {
+            response.requested_max_results = Some(requested_max_results);
+        }
+        response.degraded = embedded.degraded;
 
         tracing::info!(
             results = response.results.len(),
@@ -253,12 +651,98 @@ impl LociTools {
             "recall_memory complete"
         );
 
-        if summary_only {
-            let summary = crate::memory::search::to_summary(&response);
-            return serde_json::to_string(&summary)
-                .map_err(|e| format!("serialization failed: {e}"));
+        let output = render_recall(&response, summary_only, fields.as_deref())?;
+        timing.finish(debug, output)
+    }
+
+    /// Browse memories without a query.
+    #[tool(description = "List memories without a search query, for browsing. Filter by type, scope, group, confidence range, and creation date; sort by created_at (default), confidence, or access_count, newest/highest first. Page with limit and offset while has_more is true. Results have score 0 and do not count as accesses.")]
+    async fn list_memories(
+        &self,
+        Parameters(params): Parameters<ListMemoriesParams>,
+    ) -> Result<String, String> {
+        let memory_type = params
+            .r#type
+            .as_deref()
+            .map(str::parse::<MemoryType>)
+            .transpose()?;
+        let scope = params.scope.as_deref().map(str::parse::<Scope>).transpose()?;
+        let sort = params
+            .sort_by
+            .as_deref()
+            .map(str::parse::<crate::memory::search::ListSort>)
+            .transpose()?
+            .unwrap_or_default();
+        for (field, value) in [
+            ("min_confidence", params.min_confidence),
+            ("max_confidence", params.max_confidence),
+        ] {
+            if value.is_some_and(|v| !(0.0..=1.0).contains(&v)) {
+                return Err(format!("{field} must be between 0.0 and 1.0"));
+            }
+        }
+        if let (Some(min), Some(max)) = (params.min_confidence, params.max_confidence)
+            && min > max
+        {
+            return Err("min_confidence must not exceed max_confidence".into());
+        }
+        let limit = params.limit.unwrap_or(20);
+        if !(1..=MAX_LIST_LIMIT).contains(&limit) {
+            return Err(format!("limit must be between 1 and {MAX_LIST_LIMIT}"));
         }
+        let offset = params.offset.unwrap_or(0);
+        let group = params
+            .group
+            .unwrap_or_else(|| self.config.storage.default_group.clone());
+
+        tracing::info!(group = %group, limit, offset, ?sort, "list_memories called");
+
+        // Fetch one extra memory to tell whether another page follows
+        let filter = crate::memory::search::ListFilter {
+            memory_type,
+            scope,
+            group: Some(group.clone()),
+            session_id: params.session_id,
+            min_confidence: params.min_confidence,
+            max_confidence: params.max_confidence,
+            created_after: params.created_after,
+            created_before: params.created_before,
+            sort,
+            limit: Some(limit + 1),
+            offset,
+            ..Default::default()
+        };
 
+        let dbs = Arc::clone(&self.dbs);
+        let mut memories = tokio::task::spawn_blocking(move || {
+            let handles = dbs.for_group(&group)?;
+            if handles.len() == 1 {
+                let conn = handles[0].read()?;
+                return crate::memory::search::list_memories(&conn, &filter);
+            }
+            let per_db = crate::memory::search::ListFilter {
+                limit: Some(offset + limit + 1),
+                offset: 0,
+                ..filter.clone()
+            };
+            let mut listings = Vec::new();
+            for handle in &handles {
+                let conn = handle.read()?;
+                listings.push(crate::memory::search::list_memories(&conn, &per_db)?);
+            }
+            Ok(crate::memory::search::merge_listings(listings, &filter))
+        })
+        .await
+        .map_err(|e| format!("task failed: {e}"))?
+        .map_err(|e| format!("list failed: {e}"))?;
+
+        let has_more = memories.len() > limit;
+        memories.truncate(limit);
+        let response = crate::memory::search::ListResponse {
+            results: memories.into_iter().map(Into::into).collect(),
+            offset,
+            has_more,
+        };
         serde_json::to_string(&response).map_err(|e| format!("serialization failed: {e}"))
     }
 
@@ -279,12 +763,14 @@ impl LociTools {
             "forget_memory called"
         );
 
-        let db = Arc::clone(&self.db);
+        let dbs = Arc::clone(&self.dbs);
         let memory_id = params.memory_id;
         let reason = params.reason;
 
         let result = tokio::task::spawn_blocking(move || {
-            let mut conn = db
+            let (handle, memory_id) = dbs.resolve(&memory_id)?;
+            let mut conn = handle
+                .conn
                 .lock()
                 .map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
             crate::memory::forget::forget_memory(
@@ -307,6 +793,174 @@ impl LociTools {
         serde_json::to_string(&result).map_err(|e| format!("serialization failed: {e}"))
     }
 
+    /// Edit a memory's content, confidence, or metadata in place.
+    #[tool(description = "Edit an existing memory in place, e.g. to fix a typo, instead of storing a superseding memory. Changed content is re-embedded and re-indexed; the ID stays the same. Only active memories can be edited. Returns the list of updated fields.")]
+    async fn update_memory(
+        &self,
+        Parameters(params): Parameters<UpdateMemoryParams>,
+    ) -> Result<String, String> {
+        if params.memory_id.is_empty() {
+            return Err("memory_id must not be empty".into());
+        }
+        if params.content.is_none() && params.confidence.is_none() && params.metadata.is_none() {
+            return Err("nothing to update: set content, confidence, or metadata".into());
+        }
+
+        tracing::info!(
+            id = %params.memory_id,
+            content = params.content.is_some(),
+            "update_memory called"
+        );
+
+        // Find the memory first: its type, scope, and group feed the content template
+        let dbs = Arc::clone(&self.dbs);
+        let memory_id = params.memory_id;
+        let (handle, memory_id, memory_type, scope, group) = tokio::task::spawn_blocking(move || {
+            let (handle, memory_id) = dbs.resolve(&memory_id)?;
+            let conn = handle
+                .conn
+                .lock()
+                .map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
+            let (memory_type, scope, group): (String, String, Option<String>) = conn.query_row(
+                "SELECT type, scope, source_group FROM memories WHERE id = ?1",
+                rusqlite::params![memory_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )?;
+            drop(conn);
+            let memory_type: MemoryType = memory_type.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+            let scope: Scope = scope.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+            Ok::<_, anyhow::Error>((handle, memory_id, memory_type, scope, group))
+        })
+        .await
+        .map_err(|e| format!("task failed: {e}"))?
+        .map_err(|e| format!("update failed: {e}"))?;
+
+        let embedding = match &params.content {
+            Some(content) => {
+                let text = crate::embedding::render_content_template(
+                    &self.config.embedding.content_template,
+                    content,
+                    memory_type,
+                    scope,
+                    group.as_deref(),
+                );
+                Some(
+                    Arc::clone(&self.embedding)
+                        .embed_async(text)
+                        .await
+                        .map_err(|e| format!("embedding failed: {e}"))?,
+                )
+            }
+            None => None,
+        };
+
+        let options = crate::memory::store::StoreOptions::from(self.config.as_ref());
+        let content = params.content;
+        let confidence = params.confidence;
+        let metadata = params.metadata;
+        let result = tokio::task::spawn_blocking(move || {
+            let mut conn = handle
+                .conn
+                .lock()
+                .map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
+            let update = crate::memory::store::MemoryUpdate {
+                content: content.as_deref(),
+                embedding: embedding.as_deref(),
+                confidence,
+                metadata: metadata.as_ref(),
+            };
+            crate::memory::store::update_memory(&mut conn, &memory_id, &update, &options)
+        })
+        .await
+        .map_err(|e| format!("task failed: {e}"))?
+        .map_err(|e| format!("update failed: {e}"))?;
+
+        tracing::info!(id = %result.id, updated = ?result.updated, "memory updated");
+
+        serde_json::to_string(&result).map_err(|e| format!("serialization failed: {e}"))
+    }
+
+    /// Restore a soft-deleted memory.
+    #[tool(description = "Undo a soft forget_memory, making the memory recallable again. Only memories forgotten with a soft delete can be restored; hard-deleted memories are gone, and memories superseded by a newer version stay superseded.")]
+    async fn restore_memory(
+        &self,
+        Parameters(params): Parameters<RestoreMemoryParams>,
+    ) -> Result<String, String> {
+        if params.memory_id.is_empty() {
+            return Err("memory_id must not be empty".into());
+        }
+
+        tracing::info!(id = %params.memory_id, "restore_memory called");
+
+        let dbs = Arc::clone(&self.dbs);
+        let memory_id = params.memory_id;
+        let reason = params.reason;
+
+        let result = tokio::task::spawn_blocking(move || {
+            let (handle, memory_id) = dbs.resolve(&memory_id)?;
+            let mut conn = handle
+                .conn
+                .lock()
+                .map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
+            crate::memory::forget::restore_memory(&mut conn, &memory_id, reason.as_deref())
+        })
+        .await
+        .map_err(|e| format!("task failed: {e}"))?
+        .map_err(|e| format!("restore failed: {e}"))?;
+
+        tracing::info!(id = %result.id, "memory restored");
+
+        serde_json::to_string(&result).map_err(|e| format!("serialization failed: {e}"))
+    }
+
+    /// Change a memory's type in place.
+    #[tool(description = "Change a memory's type (e.g. an episodic note that turned out to be a durable semantic fact) without re-storing it. If the memory has its old type's default scope, the scope moves to the new type's default. Affects decay rate and dedup grouping from now on.")]
+    async fn reclassify_memory(
+        &self,
+        Parameters(params): Parameters<ReclassifyMemoryParams>,
+    ) -> Result<String, String> {
+        if params.memory_id.is_empty() {
+            return Err("memory_id must not be empty".into());
+        }
+        let memory_type: MemoryType = params.r#type.parse().map_err(|e: String| e)?;
+
+        tracing::info!(
+            id = %params.memory_id,
+            memory_type = %memory_type,
+            "reclassify_memory called"
+        );
+
+        let dbs = Arc::clone(&self.dbs);
+        let memory_id = params.memory_id;
+
+        let result = tokio::task::spawn_blocking(move || {
+            let (handle, memory_id) = dbs.resolve(&memory_id)?;
+            let mut conn = handle
+                .conn
+                .lock()
+                .map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
+            // With sharding, scope decides which file holds the memory, so it stays put
+            crate::memory::reclassify::reclassify_memory(
+                &mut conn,
+                &memory_id,
+                memory_type,
+                dbs.is_sharded(),
+            )
+        })
+        .await
+        .map_err(|e| format!("task failed: {e}"))?
+        .map_err(|e| format!("reclassify failed: {e}"))?;
+
+        tracing::info!(
+            id = %result.id,
+            previous_type = %result.previous_type,
+            memory_type = %result.memory_type,
+            "memory reclassified"
+        );
+
+        serde_json::to_string(&result).map_err(|e| format!("serialization failed: {e}"))
+    }
+
     /// Get statistics about the memory store.
     #[tool(description = "Get memory store statistics: counts by type and scope, entity relations count, storage size, oldest/newest timestamps.")]
     async fn memory_stats(
@@ -315,15 +969,25 @@ impl LociTools {
     ) -> Result<String, String> {
         tracing::info!("memory_stats called");
 
-        let db = Arc::clone(&self.db);
+        let dbs = Arc::clone(&self.dbs);
         let group = params.group;
-        let db_path = self.config.resolved_db_path();
 
         let result = tokio::task::spawn_blocking(move || {
-            let conn = db
-                .lock()
-                .map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
-            crate::memory::stats::memory_stats(&conn, group.as_deref(), Some(&db_path))
+            let handles = match group.as_deref() {
+                Some(g) => dbs.for_group(g)?,
+                None => dbs.all()?,
+            };
+            let mut combined: Option<crate::memory::stats::StatsResponse> = None;
+            for handle in handles {
+                let conn = handle.read()?;
+                let stats =
+                    crate::memory::stats::memory_stats(&conn, group.as_deref(), Some(&handle.path))?;
+                combined = Some(match combined {
+                    Some(total) => total.merge(stats),
+                    None => stats,
+                });
+            }
+            combined.ok_or_else(|| anyhow::anyhow!("no database available"))
         })
         .await
         .map_err(|e| format!("task failed: {e}"))?
@@ -342,12 +1006,28 @@ impl LociTools {
 
         let include_relations = params.include_relations.unwrap_or(true);
         let include_log = params.include_log.unwrap_or(false);
+        let include_vector = params.include_vector.unwrap_or(false);
+        let include_incoming = params.include_incoming.unwrap_or(false);
         let memory_id = params.memory_id;
 
-        let db = Arc::clone(&self.db);
+        let dbs = Arc::clone(&self.dbs);
         let response = tokio::task::spawn_blocking(move || {
-            let conn = db.lock().map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
-            crate::memory::search::inspect_memory(&conn, &memory_id, include_relations, include_log)
+            let (handle, memory_id) = dbs.resolve(&memory_id)?;
+            let conn = handle.read()?;
+            let mut response = crate::memory::search::inspect_memory(
+                &conn,
+                &memory_id,
+                include_relations,
+                include_log,
+            )?;
+            if include_incoming {
+                response.incoming_relations =
+                    crate::memory::search::incoming_relations(&conn, &memory_id)?;
+            }
+            if include_vector {
+                response.embedding = crate::memory::search::stored_embedding(&conn, &memory_id)?;
+            }
+            Ok::<_, anyhow::Error>(response)
         })
         .await
         .map_err(|e| format!("task failed: {e}"))?
@@ -356,8 +1036,133 @@ impl LociTools {
         serde_json::to_string(&response).map_err(|e| format!("serialization failed: {e}"))
     }
 
+    /// Inspect several memories by ID in one call.
+    #[tool(description = "Inspect several memories by ID at once. Returns the same details as memory_inspect for each found memory, in request order, plus a 'not_found' list. Use it to review a set of recall results without one call per ID.")]
+    async fn memory_inspect_batch(
+        &self,
+        Parameters(params): Parameters<MemoryInspectBatchParams>,
+    ) -> Result<String, String> {
+        if params.memory_ids.is_empty() {
+            return Err("'memory_ids' must not be empty".into());
+        }
+        if params.memory_ids.iter().any(|id| id.is_empty()) {
+            return Err("memory IDs must not be empty".into());
+        }
+        tracing::info!(count = params.memory_ids.len(), "memory_inspect_batch called");
+
+        let include_relations = params.include_relations.unwrap_or(true);
+        let include_log = params.include_log.unwrap_or(false);
+        let memory_ids = params.memory_ids;
+
+        let dbs = Arc::clone(&self.dbs);
+        let response = tokio::task::spawn_blocking(move || {
+            // One connection per database for the whole batch
+            let mut responses = Vec::new();
+            for handle in dbs.all()? {
+                let conn = handle.read()?;
+                responses.push(crate::memory::search::inspect_memories(
+                    &conn,
+                    &memory_ids,
+                    include_relations,
+                    include_log,
+                )?);
+            }
+            Ok::<_, anyhow::Error>(crate::memory::search::merge_inspect_responses(
+                responses,
+                &memory_ids,
+            ))
+        })
+        .await
+        .map_err(|e| format!("task failed: {e}"))?
+        .map_err(|e| format!("inspect failed: {e}"))?;
+
+        serde_json::to_string(&response).map_err(|e| format!("serialization failed: {e}"))
+    }
+
+    /// Record an access to memories without returning their content.
+    #[tool(description = "Mark memories as still relevant without fetching them: bumps access count and last-accessed time, which slows their fading and protects them from cleanup. Use it for memories already in context. Returns the number touched and any IDs not found.")]
+    async fn touch_memory(
+        &self,
+        Parameters(params): Parameters<TouchMemoryParams>,
+    ) -> Result<String, String> {
+        if params.memory_ids.is_empty() {
+            return Err("'memory_ids' must not be empty".into());
+        }
+        if params.memory_ids.iter().any(|id| id.is_empty()) {
+            return Err("memory IDs must not be empty".into());
+        }
+        tracing::info!(count = params.memory_ids.len(), "touch_memory called");
+
+        let memory_ids = params.memory_ids;
+        let dbs = Arc::clone(&self.dbs);
+        let response = tokio::task::spawn_blocking(move || {
+            let mut responses = Vec::new();
+            for handle in dbs.all()? {
+                let conn = handle
+                    .conn
+                    .lock()
+                    .map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
+                responses.push(crate::memory::search::touch_memories(&conn, &memory_ids)?);
+            }
+            Ok::<_, anyhow::Error>(crate::memory::search::merge_touch_responses(responses))
+        })
+        .await
+        .map_err(|e| format!("task failed: {e}"))?
+        .map_err(|e| format!("touch failed: {e}"))?;
+
+        serde_json::to_string(&response).map_err(|e| format!("serialization failed: {e}"))
+    }
+
+    /// Describe each memory type's default scope and decay behavior.
+    #[tool(description = "Describe the memory types this server supports: default scope, configured decay factor (applied once per interval_days of elapsed time, pro rata), and what belongs in each. Use it to choose the right type before storing.")]
+    async fn describe_memory_types(&self) -> Result<String, String> {
+        tracing::info!("describe_memory_types called");
+
+        let types = crate::memory::types::describe_memory_types(&self.config.maintenance);
+
+        serde_json::to_string(&types).map_err(|e| format!("serialization failed: {e}"))
+    }
+
+    /// Run the decay, compaction, and promotion cycle over every database.
+    #[tool(description = "Run memory maintenance: confidence decay, compaction of old episodic memories into summaries, and promotion of repeated episodic patterns to semantic memories. Returns per-phase results and timings. Fails if another maintenance run is in progress.")]
+    async fn run_maintenance(&self) -> Result<String, String> {
+        tracing::info!("run_maintenance called");
+
+        let dbs = Arc::clone(&self.dbs);
+        let embedding = Arc::clone(&self.embedding);
+        let config = Arc::clone(&self.config);
+
+        let report = tokio::task::spawn_blocking(move || {
+            let mut combined: Option<crate::memory::maintenance::MaintenanceReport> = None;
+            for handle in dbs.all()? {
+                let report = crate::memory::maintenance::run_maintenance(
+                    &handle.conn,
+                    embedding.as_ref(),
+                    &config.embedding.content_template,
+                    &config.maintenance,
+                )?;
+                combined = Some(match combined {
+                    Some(total) => total.merge(report),
+                    None => report,
+                });
+            }
+            combined.ok_or_else(|| anyhow::anyhow!("no database available"))
+        })
+        .await
+        .map_err(|e| format!("task failed: {e}"))?
+        .map_err(|e| format!("maintenance failed: {e}"))?;
+
+        tracing::info!(
+            summaries_created = report.compact.summaries_created,
+            semantics_created = report.promote.semantics_created,
+            "maintenance complete"
+        );
+
+        serde_json::to_string(&report).map_err(|e| format!("serialization failed: {e}"))
+    }
+
     /// Store a relationship between two entity memories.
-    #[tool(description = "Create a relationship between two entity memories (e.g. 'works_at', 'manages', 'part_of'). Both IDs must refer to entity-type memories. Idempotent on (subject, predicate, object).")]
+    #[tool(description = "Create a relationship between two entity memories (e.g. 'works_at', 'manages', 'part_of'). Both IDs must refer to entity-type memories. Idempotent on (subject, predicate, object). The server may restrict predicates to an allowlist and allow only one object for single-valued predicates.")]
     async fn store_relation(
         &self,
         Parameters(params): Parameters<StoreRelationParams>,
@@ -371,6 +1176,9 @@ impl LociTools {
         if params.object_id.is_empty() {
             return Err("object_id must not be empty".into());
         }
+        if params.inverse_predicate.as_deref() == Some("") {
+            return Err("inverse_predicate must not be empty".into());
+        }
 
         tracing::info!(
             subject = %params.subject_id,
@@ -379,16 +1187,50 @@ impl LociTools {
             "store_relation called"
         );
 
-        let db = Arc::clone(&self.db);
+        let dbs = Arc::clone(&self.dbs);
+        let rules = self.config.relations.clone();
         let subject_id = params.subject_id;
         let predicate = params.predicate;
         let object_id = params.object_id;
+        let inverse_predicate = if params.bidirectional.unwrap_or(false) || params.inverse_predicate.is_some() {
+            Some(params.inverse_predicate.unwrap_or_else(|| {
+                crate::memory::relations::reverse_predicate(&rules, &predicate).to_string()
+            }))
+        } else {
+            None
+        };
 
         let result = tokio::task::spawn_blocking(move || {
-            let conn = db
+            // Relations are foreign keys, so both entities must live in the same file
+            let (handle, subject_id) = dbs.resolve(&subject_id)?;
+            let (object_handle, object_id) = dbs.resolve(&object_id)?;
+            if handle.path != object_handle.path {
+                anyhow::bail!(
+                    "subject and object are stored in different group shards; \
+                     relations can only link memories in the same database"
+                );
+            }
+            let mut conn = handle
+                .conn
                 .lock()
                 .map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
-            crate::memory::relations::store_relation(&conn, &subject_id, &predicate, &object_id)
+            match inverse_predicate {
+                Some(inverse_predicate) => crate::memory::relations::store_bidirectional_relation(
+                    &mut conn,
+                    &subject_id,
+                    &predicate,
+                    &object_id,
+                    &inverse_predicate,
+                    &rules,
+                ),
+                None => crate::memory::relations::store_relation_with(
+                    &conn,
+                    &subject_id,
+                    &predicate,
+                    &object_id,
+                    &rules,
+                ),
+            }
         })
         .await
         .map_err(|e| format!("task failed: {e}"))?
@@ -402,6 +1244,173 @@ impl LociTools {
 
         serde_json::to_string(&result).map_err(|e| format!("serialization failed: {e}"))
     }
+
+    /// Delete one entity relationship.
+    #[tool(description = "Remove a mistaken or outdated relationship between two entity memories. Deletes only the exact (subject_id, predicate, object_id) triple; both entities are kept. Returns 'removed' (false if no such relation existed) and the deleted relation's 'id'.")]
+    async fn forget_relation(
+        &self,
+        Parameters(params): Parameters<ForgetRelationParams>,
+    ) -> Result<String, String> {
+        if params.subject_id.is_empty() {
+            return Err("subject_id must not be empty".into());
+        }
+        if params.predicate.is_empty() {
+            return Err("predicate must not be empty".into());
+        }
+        if params.object_id.is_empty() {
+            return Err("object_id must not be empty".into());
+        }
+
+        tracing::info!(
+            subject = %params.subject_id,
+            predicate = %params.predicate,
+            object = %params.object_id,
+            "forget_relation called"
+        );
+
+        let dbs = Arc::clone(&self.dbs);
+        let subject_id = params.subject_id;
+        let predicate = params.predicate;
+        let object_id = params.object_id;
+
+        let result = tokio::task::spawn_blocking(move || {
+            let (handle, subject_id) = dbs.resolve(&subject_id)?;
+            let (object_handle, object_id) = dbs.resolve(&object_id)?;
+            // Endpoints in different shards can't share a relation
+            if handle.path != object_handle.path {
+                return Ok(crate::memory::relations::ForgetRelationResult {
+                    id: None,
+                    removed: false,
+                });
+            }
+            let mut conn = handle
+                .conn
+                .lock()
+                .map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
+            crate::memory::relations::forget_relation(&mut conn, &subject_id, &predicate, &object_id)
+        })
+        .await
+        .map_err(|e| format!("task failed: {e}"))?
+        .map_err(|e| format!("forget_relation failed: {e}"))?;
+
+        tracing::info!(removed = result.removed, "relation forgotten");
+
+        serde_json::to_string(&result).map_err(|e| format!("serialization failed: {e}"))
+    }
+
+    /// Store many entity relationships in one atomic batch.
+    #[tool(description = "Create many relationships between entity memories in one call, for bulk knowledge-graph ingestion. All endpoints are validated first; one invalid triple fails the whole batch with its index. Returns a per-triple result with dedup flags.")]
+    async fn store_relations(
+        &self,
+        Parameters(params): Parameters<StoreRelationsParams>,
+    ) -> Result<String, String> {
+        if params.relations.is_empty() {
+            return Err("relations must not be empty".into());
+        }
+        if let Some(index) = params
+            .relations
+            .iter()
+            .position(|r| r.bidirectional.is_some() || r.inverse_predicate.is_some())
+        {
+            return Err(format!(
+                "relation {index}: bidirectional and inverse_predicate are not supported in a batch; \
+                 list the reverse triple explicitly"
+            ));
+        }
+
+        tracing::info!(count = params.relations.len(), "store_relations called");
+
+        let dbs = Arc::clone(&self.dbs);
+        let rules = self.config.relations.clone();
+        let relations = params.relations;
+
+        let result = tokio::task::spawn_blocking(move || {
+            // Resolve every endpoint; all must live in one file so the batch stays atomic
+            let mut handle = None;
+            let mut resolved = Vec::with_capacity(relations.len());
+            for (index, relation) in relations.iter().enumerate() {
+                let (subject_handle, subject_id) = dbs
+                    .resolve(&relation.subject_id)
+                    .map_err(|e| anyhow::anyhow!("relation {index}: subject {e}"))?;
+                let (object_handle, object_id) = dbs
+                    .resolve(&relation.object_id)
+                    .map_err(|e| anyhow::anyhow!("relation {index}: object {e}"))?;
+                let first = handle.get_or_insert_with(|| subject_handle.clone());
+                if first.path != subject_handle.path || first.path != object_handle.path {
+                    anyhow::bail!(
+                        "relation {index}: endpoints are stored in a different group shard; \
+                         a batch can only link memories in the same database"
+                    );
+                }
+                resolved.push((subject_id, relation.predicate.as_str(), object_id));
+            }
+
+            let handle = handle.expect("non-empty batch");
+            let mut conn = handle
+                .conn
+                .lock()
+                .map_err(|e| anyhow::anyhow!("db lock poisoned: {e}"))?;
+            let batch: Vec<_> = resolved
+                .iter()
+                .map(|(subject_id, predicate, object_id)| crate::memory::relations::NewRelation {
+                    subject_id,
+                    predicate,
+                    object_id,
+                })
+                .collect();
+            crate::memory::relations::store_relations_batch(&mut conn, &batch, &rules)
+        })
+        .await
+        .map_err(|e| format!("task failed: {e}"))?
+        .map_err(|e| format!("store_relations failed: {e}"))?;
+
+        tracing::info!(
+            created = result.created,
+            deduplicated = result.deduplicated,
+            "relations stored"
+        );
+
+        serde_json::to_string(&result).map_err(|e| format!("serialization failed: {e}"))
+    }
+
+    /// Traverse the entity relation graph from one entity.
+    #[tool(description = "Find entities connected to an entity through stored relations, up to max_depth hops (e.g. who Alice works with, transitively). Optionally follow only one predicate, and choose outgoing, incoming, or both edge directions. Returns each reached entity once, at its shortest distance, with a preview, its depth, and the path of predicates taken.")]
+    async fn query_relations(
+        &self,
+        Parameters(params): Parameters<QueryRelationsParams>,
+    ) -> Result<String, String> {
+        let max_depth = params.max_depth.unwrap_or(1);
+        if !(1..=MAX_TRAVERSAL_DEPTH).contains(&max_depth) {
+            return Err(format!("max_depth must be between 1 and {MAX_TRAVERSAL_DEPTH}"));
+        }
+        let direction = match params.direction.as_deref() {
+            Some(direction) => direction.parse::<crate::memory::relations::Direction>()?,
+            None => crate::memory::relations::Direction::default(),
+        };
+        tracing::info!(id = %params.entity_id, max_depth, ?direction, "query_relations called");
+
+        let dbs = Arc::clone(&self.dbs);
+        let entity_id = params.entity_id;
+        let predicate = params.predicate;
+        let (entity_id, reached) = tokio::task::spawn_blocking(move || {
+            let (handle, entity_id) = dbs.resolve(&entity_id)?;
+            let conn = handle.read()?;
+            let reached = crate::memory::relations::traverse_relations(
+                &conn,
+                &entity_id,
+                predicate.as_deref(),
+                max_depth,
+                direction,
+            )?;
+            Ok::<_, anyhow::Error>((entity_id, reached))
+        })
+        .await
+        .map_err(|e| format!("task failed: {e}"))?
+        .map_err(|e| format!("query_relations failed: {e}"))?;
+
+        let output = serde_json::json!({ "entity_id": entity_id, "reached": reached });
+        serde_json::to_string(&output).map_err(|e| format!("serialization failed: {e}"))
+    }
 }
 
 #[tool_handler]
@@ -0,0 +1,30 @@
+//! MCP `query_relations` tool parameter definition.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Parameters for the `query_relations` MCP tool.
+///
+/// Walks the entity graph breadth-first from one entity.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct QueryRelationsParams {
+    /// ID (or unique prefix) of the entity memory to start from.
+    #[schemars(description = "ID of the entity memory to start from")]
+    pub entity_id: String,
+
+    /// Only follow edges with this predicate.
+    #[schemars(
+        description = "Only follow relations with this predicate (e.g. 'works_with'). Defaults to every predicate."
+    )]
+    pub predicate: Option<String>,
+
+    /// Maximum number of hops. Defaults to 1.
+    #[schemars(description = "Maximum number of hops from the start entity, 1-5. Defaults to 1.")]
+    pub max_depth: Option<usize>,
+
+    /// `"outgoing"`, `"incoming"`, or `"both"`. Defaults to `"outgoing"`.
+    #[schemars(
+        description = "Which edges to follow: 'outgoing' (subject to object, the default), 'incoming' (object to subject), or 'both'"
+    )]
+    pub direction: Option<String>,
+}
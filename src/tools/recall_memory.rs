@@ -1,5 +1,7 @@
 //! MCP `recall_memory` tool parameter definition.
 
+use std::collections::BTreeMap;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -26,24 +28,62 @@ pub struct RecallMemoryParams {
     )]
     pub r#type: Option<String>,
 
-    /// Filter by scope: `"global"` or `"group"`.
-    #[schemars(description = "Filter by scope: 'global' or 'group'")]
+    /// Filter by scope: `"global"`, `"group"`, or `"session"`.
+    #[schemars(description = "Filter by scope: 'global', 'group', or 'session'")]
     pub scope: Option<String>,
 
+    /// The caller's session; session-scoped memories from it become visible.
+    #[schemars(
+        description = "ID of the current conversation. Session-scoped memories are only returned to the session that stored them."
+    )]
+    pub session_id: Option<String>,
+
     /// Filter by group/project name.
     #[schemars(description = "Filter by group/project name")]
     pub group: Option<String>,
 
-    /// Maximum number of results to return (1–20). Defaults to 5.
-    #[schemars(description = "Maximum number of results to return (1-20). Defaults to 5.")]
+    /// Several groups whose group-scoped memories are visible (instead of `group`).
+    #[schemars(
+        description = "Return group-scoped memories from exactly these groups (plus global memories). Use instead of 'group'."
+    )]
+    pub groups: Option<Vec<String>>,
+
+    /// Maximum number of results to return (1 to `retrieval.max_results_ceiling`). Defaults to 5.
+    #[schemars(
+        description = "Maximum number of results to return (1-20 unless the server raises the ceiling). Defaults to 5. Out-of-range values are clamped and the response includes requested_max_results."
+    )]
     pub max_results: Option<usize>,
 
+    /// Ranked matches to skip before the returned page. Defaults to 0.
+    #[schemars(
+        description = "Number of ranked matches to skip, for fetching the next page: pass the previous offset plus max_results. Defaults to 0."
+    )]
+    pub offset: Option<usize>,
+
+    /// Maximal-marginal-relevance trade-off (0.0-1.0); lower values demote near-duplicates.
+    #[schemars(
+        description = "Diversify results with maximal marginal relevance: 1.0 ranks by relevance only, lower values push paraphrases of already-returned memories down (e.g. 0.7). Defaults to the server's retrieval.mmr_lambda (off)."
+    )]
+    pub mmr_lambda: Option<f64>,
+
+    /// Half-life in days for recency weighting; each result's score halves per half-life of age.
+    #[schemars(
+        description = "Rank newer memories higher: each result's score is halved for every this-many days of age (e.g. 14). Defaults to the server's retrieval.recency_half_life_days (off)."
+    )]
+    pub recency_half_life_days: Option<f64>,
+
     /// If `true`, return only compact summaries for token efficiency.
     #[schemars(
         description = "If true, return only summaries (id, type, truncated content, score) for token efficiency. Use recall_memory with ids or memory_inspect to get full details."
     )]
     pub summary_only: Option<bool>,
 
+    /// Result fields to return, e.g. `["id", "score"]`. Defaults to all fields.
+    #[schemars(
+        description = "Only include these fields in each result: id, type, content, confidence, score, created_at, metadata, source_uri, superseded_by, relations. Defaults to all fields. Cannot be combined with summary_only."
+    )]
+    pub fields: Option<Vec<String>>,
+
     /// Token budget limit for the response. Defaults to 4000.
     #[schemars(description = "Token budget limit for the response. Defaults to 4000.")]
     pub token_budget: Option<usize>,
@@ -51,4 +91,66 @@ pub struct RecallMemoryParams {
     /// Minimum confidence threshold (0.0–1.0). Defaults to 0.1.
     #[schemars(description = "Minimum confidence threshold (0.0-1.0). Defaults to 0.1.")]
     pub min_confidence: Option<f64>,
+
+    /// If `true`, include superseded/forgotten memories, marked with `superseded_by`.
+    #[schemars(
+        description = "If true, include superseded and forgotten memories in results, each marked with its superseded_by value. Defaults to false."
+    )]
+    pub include_superseded: Option<bool>,
+
+    /// Memory IDs to leave out of query results, e.g. ones from an earlier recall.
+    #[schemars(
+        description = "Memory IDs to leave out of query results, e.g. ones already returned by an earlier recall. Ignored for 'ids' hydration."
+    )]
+    pub exclude_ids: Option<Vec<String>>,
+
+    /// Retrieval mode: `"hybrid"` (default), `"vector"`, or `"keyword"`.
+    #[schemars(
+        description = "Retrieval mode: 'hybrid' (default, vector + keyword fused with RRF), 'vector' (semantic only, scored by cosine similarity), or 'keyword' (BM25 only, no embedding)"
+    )]
+    pub mode: Option<String>,
+
+    /// Weight of vector ranks in hybrid fusion. Defaults to `retrieval.vec_weight`.
+    #[schemars(
+        description = "Hybrid mode only: multiplier on the semantic (vector) ranking's contribution. Defaults to the server's retrieval.vec_weight (1.0)."
+    )]
+    pub vec_weight: Option<f64>,
+
+    /// Weight of keyword ranks in hybrid fusion. Defaults to `retrieval.fts_weight`.
+    #[schemars(
+        description = "Hybrid mode only: multiplier on the keyword (BM25) ranking's contribution. Raise it to favor exact identifiers. Defaults to the server's retrieval.fts_weight (1.0)."
+    )]
+    pub fts_weight: Option<f64>,
+
+    /// Only return memories created at or after this RFC 3339 timestamp.
+    #[schemars(
+        description = "Only return memories created at or after this RFC 3339 timestamp, e.g. '2025-03-01T00:00:00Z'"
+    )]
+    pub created_after: Option<String>,
+
+    /// Only return memories created at or before this RFC 3339 timestamp.
+    #[schemars(
+        description = "Only return memories created at or before this RFC 3339 timestamp, e.g. '2025-03-08T00:00:00Z'"
+    )]
+    pub created_before: Option<String>,
+
+    /// Only return memories whose metadata has every one of these keys set to the given string.
+    #[schemars(
+        description = "Only return memories whose metadata has each of these keys equal to the given string value, e.g. {\"source\": \"slack\"}. Memories without the key are excluded."
+    )]
+    pub metadata: Option<BTreeMap<String, String>>,
+
+    /// Only return memories carrying every one of these tags.
+    #[schemars(description = "Only return memories tagged with all of these tags")]
+    pub tags: Option<Vec<String>>,
+
+    /// Only return memories derived from this source URI.
+    #[schemars(description = "Only return memories derived from this source URI")]
+    pub source_uri: Option<String>,
+
+    /// If `true`, include embedding/database timings and the embedding dimension.
+    #[schemars(
+        description = "If true, add a 'timing' object (embed_ms, db_ms) and 'embedding_dim' to the response for latency diagnosis. Defaults to false."
+    )]
+    pub debug: Option<bool>,
 }
@@ -0,0 +1,18 @@
+//! MCP `reclassify_memory` tool parameter definition.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Parameters for the `reclassify_memory` MCP tool.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ReclassifyMemoryParams {
+    /// ID (or unique ID prefix) of the memory to reclassify.
+    #[schemars(description = "ID of the memory to reclassify, or a unique prefix of it")]
+    pub memory_id: String,
+
+    /// New memory type: `"episodic"`, `"semantic"`, `"procedural"`, or `"entity"`.
+    #[schemars(
+        description = "New memory type: episodic, semantic, procedural, or entity. Changes the memory's decay rate and dedup grouping from now on."
+    )]
+    pub r#type: String,
+}
@@ -0,0 +1,16 @@
+//! MCP `restore_memory` tool parameter definition.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Parameters for the `restore_memory` MCP tool.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RestoreMemoryParams {
+    /// ID (or unique ID prefix) of the forgotten memory to restore.
+    #[schemars(description = "ID of the forgotten memory to restore, or a unique prefix of it")]
+    pub memory_id: String,
+
+    /// Optional reason for restoring (recorded in audit log).
+    #[schemars(description = "Why this memory is being restored")]
+    pub reason: Option<String>,
+}
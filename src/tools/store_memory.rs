@@ -20,16 +20,30 @@ pub struct StoreMemoryParams {
     #[schemars(description = "Optional group/project this memory belongs to")]
     pub group: Option<String>,
 
-    /// Visibility scope: `"global"` or `"group"`. Defaults based on type.
+    /// Visibility scope: `"global"`, `"group"`, or `"session"`. Defaults based on type.
     #[schemars(
-        description = "Visibility scope: 'global' (all groups) or 'group' (only this group). Defaults based on type."
+        description = "Visibility scope: 'global' (all groups), 'group' (only this group), or 'session' (only this conversation; requires session_id). Defaults based on type; episodic memories stored with a session_id default to 'session'."
     )]
     pub scope: Option<String>,
 
-    /// Initial confidence score in `[0.0, 1.0]`. Defaults to `1.0`.
-    #[schemars(description = "Initial confidence score 0.0-1.0. Defaults to 1.0.")]
+    /// Conversation this memory belongs to; required for session scope.
+    #[schemars(
+        description = "ID of the current conversation. Session-scoped memories are recalled only by the same session_id and are cleaned up quickly."
+    )]
+    pub session_id: Option<String>,
+
+    /// Initial confidence score in `[0.0, 1.0]`. Defaults to the configured per-type default.
+    #[schemars(
+        description = "Initial confidence score 0.0-1.0. Defaults to the server's per-type default (1.0 unless configured)."
+    )]
     pub confidence: Option<f64>,
 
+    /// Retrieval salience in `[0.0, 1.0]`. Defaults to `0.5`. Unlike confidence, it never decays.
+    #[schemars(
+        description = "How important this memory is to surface, 0.0-1.0. Defaults to 0.5. Unlike confidence, importance does not decay, and high-importance memories are exempt from cleanup."
+    )]
+    pub importance: Option<f64>,
+
     /// Optional JSON metadata blob for type-specific data.
     #[schemars(description = "Optional JSON metadata blob for type-specific data")]
     pub metadata: Option<serde_json::Value>,
@@ -39,4 +53,46 @@ pub struct StoreMemoryParams {
         description = "ID of memory this replaces. The old memory's superseded_by will be set to the new ID."
     )]
     pub supersedes: Option<String>,
+
+    /// Document, URL, or message ID this memory was derived from.
+    #[schemars(
+        description = "Optional document path, URL, or message ID this memory was derived from, for later citation"
+    )]
+    pub source_uri: Option<String>,
+
+    /// Caller-supplied memory ID; a UUID v7 is generated when omitted.
+    #[schemars(
+        description = "Optional ID to store the memory under, e.g. an external system's stable identifier. Must be unused, non-empty, at most 128 bytes, with no whitespace. Explicit IDs skip deduplication. Defaults to a generated UUID v7."
+    )]
+    pub id: Option<String>,
+
+    /// RFC 3339 time after which the memory is no longer recalled and may be purged.
+    #[schemars(
+        description = "Optional RFC 3339 expiry, e.g. '2025-03-09T00:00:00Z', for memories that are only briefly relevant. After it passes the memory is no longer recalled, and `loci cleanup` deletes it."
+    )]
+    pub expires_at: Option<String>,
+
+    /// Labels for filtering recall with `recall_memory`'s `tags`.
+    #[schemars(
+        description = "Optional labels, e.g. ['billing', 'incident']. recall_memory can require tags to narrow results."
+    )]
+    pub tags: Option<Vec<String>>,
+
+    /// If `true`, store verbatim even when a duplicate memory already exists.
+    #[schemars(
+        description = "If true, skip deduplication entirely and always store a new memory, even if an exact or near-duplicate already exists. Defaults to false."
+    )]
+    pub skip_dedup: Option<bool>,
+
+    /// If `true`, run every check but persist nothing.
+    #[schemars(
+        description = "If true, run the full store pipeline (validation, embedding, dedup check) and roll it back. Returns 'would_deduplicate', 'matched_id' and the assigned 'scope' without writing anything. Defaults to false."
+    )]
+    pub dry_run: Option<bool>,
+
+    /// If `true`, include embedding/database timings and the embedding dimension.
+    #[schemars(
+        description = "If true, add a 'timing' object (embed_ms, db_ms) and 'embedding_dim' to the response for latency diagnosis. Defaults to false."
+    )]
+    pub debug: Option<bool>,
 }
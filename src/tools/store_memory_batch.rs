@@ -0,0 +1,18 @@
+//! MCP `store_memory_batch` tool parameter definition.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::store_memory::StoreMemoryParams;
+
+/// Parameters for the `store_memory_batch` MCP tool.
+///
+/// Stores many memories at once; all-or-nothing.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct StoreMemoryBatchParams {
+    /// Memories to store, each shaped like `store_memory`'s parameters.
+    #[schemars(
+        description = "Memories to store, each with store_memory's parameters (content, type, group, scope, ...). If any item is invalid, nothing is stored and the error names its index."
+    )]
+    pub memories: Vec<StoreMemoryParams>,
+}
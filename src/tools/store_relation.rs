@@ -20,4 +20,16 @@ pub struct StoreRelationParams {
     /// ID of the target entity memory.
     #[schemars(description = "ID of the object entity memory")]
     pub object_id: String,
+
+    /// Also store the reverse edge (object → subject).
+    #[schemars(
+        description = "If true, also store the reverse relation (object_id, inverse_predicate, subject_id) in the same transaction, so it shows up from both entities. Defaults to false."
+    )]
+    pub bidirectional: Option<bool>,
+
+    /// Predicate of the reverse edge; implies `bidirectional`.
+    #[schemars(
+        description = "Predicate for the reverse relation (e.g. 'managed_by' for 'manages'); implies bidirectional. Defaults to the configured inverse predicate, else the same predicate."
+    )]
+    pub inverse_predicate: Option<String>,
 }
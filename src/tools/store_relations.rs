@@ -0,0 +1,18 @@
+//! MCP `store_relations` tool parameter definition.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::store_relation::StoreRelationParams;
+
+/// Parameters for the `store_relations` MCP tool.
+///
+/// Creates many relations at once; all-or-nothing.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct StoreRelationsParams {
+    /// Triples to create, each shaped like `store_relation`'s parameters.
+    #[schemars(
+        description = "Relations to create, each with subject_id, predicate, and object_id. If any endpoint is invalid, nothing is stored and the error names its index."
+    )]
+    pub relations: Vec<StoreRelationParams>,
+}
@@ -0,0 +1,14 @@
+//! MCP `touch_memory` tool parameter definition.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Parameters for the `touch_memory` MCP tool.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct TouchMemoryParams {
+    /// Full IDs of the memories to mark as accessed.
+    #[schemars(
+        description = "IDs of memories to mark as still relevant. IDs that match nothing are listed in 'not_found'."
+    )]
+    pub memory_ids: Vec<String>,
+}
@@ -0,0 +1,26 @@
+//! MCP `update_memory` tool parameter definition.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Parameters for the `update_memory` MCP tool.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct UpdateMemoryParams {
+    /// ID (or unique ID prefix) of the memory to edit.
+    #[schemars(description = "ID of the memory to edit, or a unique prefix of it")]
+    pub memory_id: String,
+
+    /// Replacement content; the memory is re-embedded.
+    #[schemars(
+        description = "Corrected content. The memory is re-embedded and re-indexed but keeps its ID."
+    )]
+    pub content: Option<String>,
+
+    /// Replacement confidence score in `[0.0, 1.0]`.
+    #[schemars(description = "New confidence score 0.0-1.0")]
+    pub confidence: Option<f64>,
+
+    /// Replacement JSON metadata blob.
+    #[schemars(description = "New JSON metadata blob; replaces the existing metadata entirely")]
+    pub metadata: Option<serde_json::Value>,
+}
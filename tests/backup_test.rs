@@ -0,0 +1,66 @@
+mod helpers;
+
+use helpers::test_embedding;
+use loci::db;
+use loci::memory::store::store_memory;
+use loci::memory::types::{MemoryType, Scope};
+
+fn count(conn: &rusqlite::Connection, table: &str) -> i64 {
+    conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| row.get(0))
+        .unwrap()
+}
+
+#[test]
+fn backup_of_populated_db_opens_with_same_memories() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let db_path = tmp.path().join("memory.db");
+    let mut conn = db::open_database(&db_path).unwrap();
+
+    let contents = [
+        "Rust is a systems language",
+        "The deploy runs on Thursdays",
+        "Alice leads the platform team",
+    ];
+    for (seed, content) in contents.into_iter().enumerate() {
+        store_memory(
+            &mut conn, content, MemoryType::Semantic, Scope::Global,
+            Some("default"), 1.0, None, None, &test_embedding(seed as u8 * 10), 0.92,
+        ).unwrap();
+    }
+
+    let backup_path = tmp.path().join("snapshot.db");
+    let size = db::backup_to(&conn, &backup_path, None).unwrap();
+    assert!(size > 0);
+    assert_eq!(size, std::fs::metadata(&backup_path).unwrap().len());
+
+    let backup = db::open_database(&backup_path).unwrap();
+    assert_eq!(count(&backup, "memories"), 3);
+    assert_eq!(count(&backup, "memories_vec"), count(&conn, "memories_vec"));
+
+    // An existing file is never overwritten
+    assert!(db::backup_to(&conn, &backup_path, None).is_err());
+}
+
+#[cfg(feature = "sqlcipher")]
+#[test]
+fn backup_of_encrypted_db_is_keyed() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let keyed = db::OpenOptions {
+        encryption_key: Some("correct horse".into()),
+        ..Default::default()
+    };
+    let mut conn = db::open_database_with(tmp.path().join("memory.db"), &keyed).unwrap();
+    store_memory(
+        &mut conn, "Encrypted at rest", MemoryType::Semantic, Scope::Global,
+        Some("default"), 1.0, None, None, &test_embedding(1), 0.92,
+    ).unwrap();
+
+    let backup_path = tmp.path().join("snapshot.db");
+    db::backup_to(&conn, &backup_path, Some("correct horse")).unwrap();
+
+    let err = db::open_database(&backup_path).unwrap_err();
+    assert!(format!("{err:#}").contains("encryption key is wrong"), "{err:#}");
+    let backup = db::open_database_with(&backup_path, &keyed).unwrap();
+    assert_eq!(count(&backup, "memories"), 1);
+    assert_eq!(count(&backup, "memories_vec"), 1);
+}
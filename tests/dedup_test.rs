@@ -1,7 +1,7 @@
 mod helpers;
 
 use helpers::{similar_embedding, test_db, test_embedding};
-use loci::memory::store::store_memory;
+use loci::memory::store::{store_memory, store_memory_with, NewMemory, StoreOptions, DEFAULT_IMPORTANCE};
 use loci::memory::types::{MemoryType, Scope};
 
 #[test]
@@ -121,3 +121,67 @@ fn dedup_does_not_merge_distant_embeddings() {
     assert!(!result_b.deduplicated);
     assert_ne!(result_b.id, result_a.id);
 }
+
+#[test]
+fn dedup_stays_within_a_session() {
+    let mut conn = test_db();
+    let emb = test_embedding(0);
+    let options = StoreOptions::default();
+    let in_session_a = NewMemory {
+        content: "User is debugging the payment webhook",
+        memory_type: MemoryType::Episodic,
+        scope: Scope::Session,
+        group: Some("default"),
+        confidence: 0.8,
+        importance: DEFAULT_IMPORTANCE,
+        metadata: None,
+        supersedes: None,
+        source_uri: None,
+        id: None,
+        session_id: Some("session-a"),
+        expires_at: None,
+        tags: None,
+    };
+
+    let a = store_memory_with(&mut conn, &in_session_a, &emb, &options).unwrap();
+    let b = store_memory_with(
+        &mut conn,
+        &NewMemory {
+            session_id: Some("session-b"),
+            ..in_session_a
+        },
+        &emb,
+        &options,
+    )
+    .unwrap();
+    assert!(!b.deduplicated);
+    assert_ne!(a.id, b.id);
+
+    // A group-scoped write doesn't fold into either session's memory
+    let group = store_memory_with(
+        &mut conn,
+        &NewMemory {
+            scope: Scope::Group,
+            session_id: None,
+            ..in_session_a
+        },
+        &emb,
+        &options,
+    )
+    .unwrap();
+    assert!(!group.deduplicated);
+
+    // The same session still deduplicates
+    let repeat = store_memory_with(&mut conn, &in_session_a, &emb, &options).unwrap();
+    assert!(repeat.deduplicated);
+    assert_eq!(repeat.id, a.id);
+
+    let confidence: f64 = conn
+        .query_row(
+            "SELECT confidence FROM memories WHERE id = ?1",
+            [&b.id],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert!((confidence - 0.8).abs() < 1e-9, "session B's memory must not be boosted");
+}
@@ -0,0 +1,261 @@
+mod helpers;
+
+use std::sync::Arc;
+
+use helpers::{test_db, test_embedding};
+use loci::memory::export::{export_data, import_data, parse_export, write_jsonl};
+use loci::memory::relations::store_relation;
+use loci::memory::store::store_memory;
+use loci::memory::types::{MemoryType, Scope};
+
+struct FixedEmbedding(Vec<f32>);
+
+impl loci::embedding::EmbeddingProvider for FixedEmbedding {
+    fn embed(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
+        Ok(self.0.clone())
+    }
+}
+
+#[tokio::test]
+async fn jsonl_export_round_trips_through_import() {
+    let mut source = test_db();
+    for i in 0..300usize {
+        // Seeds repeat after 256, so the wrapped-around memories get a different type
+        // to stay clear of vector dedup
+        let memory_type = if i < 256 { MemoryType::Semantic } else { MemoryType::Episodic };
+        store_memory(
+            &mut source,
+            &format!("Exported fact number {i}"),
+            memory_type,
+            Scope::Group,
+            Some("export"),
+            0.5 + (i % 5) as f64 / 10.0,
+            None,
+            None,
+            &test_embedding(i as u8),
+            0.92,
+        )
+        .unwrap();
+    }
+    let alice_id = store_memory(
+        &mut source,
+        "Alice is a software engineer",
+        MemoryType::Entity,
+        Scope::Global,
+        Some("default"),
+        1.0,
+        None,
+        None,
+        &test_embedding(0),
+        0.92,
+    )
+    .unwrap()
+    .id;
+    let acme_id = store_memory(
+        &mut source,
+        "Acme Corp is a tech company",
+        MemoryType::Entity,
+        Scope::Global,
+        Some("default"),
+        1.0,
+        None,
+        None,
+        &test_embedding(100),
+        0.92,
+    )
+    .unwrap()
+    .id;
+    store_relation(&source, &alice_id, "works_at", &acme_id).unwrap();
+
+    let mut out = Vec::new();
+    let counts = write_jsonl(&source, &mut out, false).unwrap();
+    assert_eq!(counts.memories, 302);
+    assert_eq!(counts.relations, 1);
+
+    let text = String::from_utf8(out).unwrap();
+    assert_eq!(text.lines().count(), 303);
+    assert!(text.lines().all(|line| serde_json::from_str::<serde_json::Value>(line).is_ok()));
+
+    let data = parse_export(&text).unwrap();
+    assert_eq!(data.memories.len(), 302);
+    assert_eq!(data.relations.len(), 1);
+
+    let mut target = test_db();
+    let report = import_data(
+        &mut target,
+        &data,
+        Arc::new(FixedEmbedding(test_embedding(7))),
+        "{content}",
+    )
+    .await
+    .unwrap();
+    assert_eq!(report.imported, 302);
+    assert_eq!(report.skipped, 0);
+    assert_eq!(report.relations_created, 1);
+
+    let imported = export_data(&target, false).unwrap();
+    let mut before: Vec<_> = data
+        .memories
+        .iter()
+        .map(|m| (m.id.clone(), m.content.clone(), m.memory_type, m.confidence))
+        .collect();
+    let mut after: Vec<_> = imported
+        .memories
+        .iter()
+        .map(|m| (m.id.clone(), m.content.clone(), m.memory_type, m.confidence))
+        .collect();
+    before.sort_by(|a, b| a.0.cmp(&b.0));
+    after.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(before, after);
+
+    assert_eq!(imported.relations.len(), 1);
+    let relation = &imported.relations[0];
+    assert_eq!(
+        (relation.subject_id.as_str(), relation.predicate.as_str(), relation.object_id.as_str()),
+        (alice_id.as_str(), "works_at", acme_id.as_str())
+    );
+}
+
+#[test]
+fn parse_export_accepts_pretty_json() {
+    let mut conn = test_db();
+    store_memory(
+        &mut conn,
+        "Pretty JSON still imports",
+        MemoryType::Semantic,
+        Scope::Global,
+        Some("default"),
+        1.0,
+        None,
+        None,
+        &test_embedding(1),
+        0.92,
+    )
+    .unwrap();
+
+    let text = serde_json::to_string_pretty(&export_data(&conn, false).unwrap()).unwrap();
+    let data = parse_export(&text).unwrap();
+    assert_eq!(data.memories.len(), 1);
+    assert_eq!(data.memories[0].content, "Pretty JSON still imports");
+}
+
+/// Memories stored with distinct vectors, exported with embeddings as JSONL.
+fn export_with_embeddings(count: u8) -> (rusqlite::Connection, loci::memory::export::ExportData) {
+    let mut source = test_db();
+    for i in 0..count {
+        store_memory(
+            &mut source,
+            &format!("Embedded fact number {i}"),
+            MemoryType::Semantic,
+            Scope::Global,
+            Some("default"),
+            1.0,
+            None,
+            None,
+            &test_embedding(i),
+            0.92,
+        )
+        .unwrap();
+    }
+    let mut out = Vec::new();
+    write_jsonl(&source, &mut out, true).unwrap();
+    let data = parse_export(&String::from_utf8(out).unwrap()).unwrap();
+    (source, data)
+}
+
+#[tokio::test]
+async fn exported_embeddings_are_reused_on_import() {
+    let (source, data) = export_with_embeddings(50);
+    assert_eq!(data.embedding_model.as_deref(), Some("all-MiniLM-L6-v2"));
+    assert!(data.memories.iter().all(|m| m.embedding.is_some()));
+
+    let mut target = test_db();
+    let report = import_data(
+        &mut target,
+        &data,
+        Arc::new(FixedEmbedding(test_embedding(7))),
+        "{content}",
+    )
+    .await
+    .unwrap();
+    assert_eq!(report.imported, 50);
+    assert_eq!(report.embeddings_reused, 50);
+
+    let by_content = |conn: &rusqlite::Connection| {
+        let mut vectors: Vec<(String, Vec<f32>)> = export_data(conn, true)
+            .unwrap()
+            .memories
+            .into_iter()
+            .map(|m| (m.content, m.embedding.unwrap()))
+            .collect();
+        vectors.sort_by(|a, b| a.0.cmp(&b.0));
+        vectors
+    };
+    assert_eq!(by_content(&source), by_content(&target));
+}
+
+#[tokio::test]
+async fn embeddings_from_another_model_are_recomputed() {
+    let (_source, data) = export_with_embeddings(3);
+
+    let mut target = test_db();
+    loci::db::migrations::set_embedding_model(&target, "some-other-model").unwrap();
+    let report = import_data(
+        &mut target,
+        &data,
+        Arc::new(FixedEmbedding(test_embedding(7))),
+        "{content}",
+    )
+    .await
+    .unwrap();
+    assert_eq!(report.imported, 3);
+    assert_eq!(report.embeddings_reused, 0);
+
+    let imported = export_data(&target, true).unwrap();
+    assert!(imported.memories.iter().all(|m| m.embedding.as_deref() == Some(&test_embedding(7)[..])));
+}
+
+#[tokio::test]
+async fn import_stores_content_repeats_under_their_own_ids() {
+    let (_source, data) = export_with_embeddings(3);
+
+    // The target already holds the same text under a different ID
+    let mut target = test_db();
+    store_memory(
+        &mut target,
+        "Embedded fact number 0",
+        MemoryType::Semantic,
+        Scope::Global,
+        Some("default"),
+        1.0,
+        None,
+        None,
+        &test_embedding(0),
+        0.92,
+    )
+    .unwrap();
+
+    let report = import_data(
+        &mut target,
+        &data,
+        Arc::new(FixedEmbedding(test_embedding(7))),
+        "{content}",
+    )
+    .await
+    .unwrap();
+    assert_eq!(report.imported, 3);
+    assert_eq!(report.skipped, 0);
+    assert_eq!(export_data(&target, false).unwrap().memories.len(), 4);
+
+    // Importing again skips every memory by ID
+    let again = import_data(
+        &mut target,
+        &data,
+        Arc::new(FixedEmbedding(test_embedding(7))),
+        "{content}",
+    )
+    .await
+    .unwrap();
+    assert_eq!(again.imported, 0);
+    assert_eq!(again.skipped, 3);
+}
@@ -26,6 +26,8 @@ fn decay_reduces_confidence() {
         &mut conn, "Old event", MemoryType::Episodic, Scope::Group,
         Some("default"), 1.0, None, None, &test_embedding(0), 0.92,
     ).unwrap().id;
+    // A full decay interval has passed since it was stored
+    backdate_memory(&conn, &id, config.interval_days as i64);
 
     let result = apply_decay(&conn, &config).unwrap();
     let total: usize = result.affected_by_type.values().sum();
@@ -35,7 +37,10 @@ fn decay_reduces_confidence() {
         .query_row("SELECT confidence FROM memories WHERE id = ?1", [&id], |row| row.get(0))
         .unwrap();
     assert!(confidence < 1.0, "confidence should have decreased from 1.0");
-    assert_eq!(confidence, config.episodic_decay_factor, "should match episodic decay factor");
+    assert!(
+        (confidence - config.episodic_decay_factor).abs() < 0.001,
+        "should match episodic decay factor"
+    );
 }
 
 #[test]
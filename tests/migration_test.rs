@@ -43,3 +43,143 @@ fn manual_v1_db_upgrades_correctly() {
     assert_eq!(get_schema_version(&conn).unwrap(), CURRENT_SCHEMA_VERSION);
     assert!(get_embedding_model(&conn).unwrap().is_some());
 }
+
+/// Create an on-disk database with only the v1 schema applied.
+fn v1_db_on_disk(db_path: &std::path::Path) {
+    db::load_sqlite_vec();
+    let conn = rusqlite::Connection::open(db_path).unwrap();
+    db::schema::init_schema(&conn).unwrap();
+}
+
+#[test]
+fn open_backs_up_db_before_migrating() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let db_path = tmp.path().join("memory.db");
+    v1_db_on_disk(&db_path);
+
+    let conn = db::open_database(&db_path).unwrap();
+    assert_eq!(get_schema_version(&conn).unwrap(), CURRENT_SCHEMA_VERSION);
+
+    let backup_path = tmp.path().join("memory.db.v1.bak");
+    assert!(backup_path.exists());
+    let backup = rusqlite::Connection::open(&backup_path).unwrap();
+    assert_eq!(get_schema_version(&backup).unwrap(), 1);
+}
+
+#[test]
+fn open_skips_backup_when_disabled() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let db_path = tmp.path().join("memory.db");
+    v1_db_on_disk(&db_path);
+
+    let options = db::OpenOptions {
+        backup_before_migrate: false,
+        ..Default::default()
+    };
+    db::open_database_with(&db_path, &options).unwrap();
+    assert!(!tmp.path().join("memory.db.v1.bak").exists());
+}
+
+#[test]
+fn open_without_integrity_check() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let db_path = tmp.path().join("memory.db");
+    v1_db_on_disk(&db_path);
+
+    let options = db::OpenOptions {
+        integrity_check: false,
+        ..Default::default()
+    };
+    let conn = db::open_database_with(&db_path, &options).unwrap();
+    assert_eq!(get_schema_version(&conn).unwrap(), CURRENT_SCHEMA_VERSION);
+}
+
+#[test]
+fn fresh_db_is_not_backed_up() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let db_path = tmp.path().join("memory.db");
+    db::open_database(&db_path).unwrap();
+    assert!(!tmp.path().join("memory.db.v1.bak").exists());
+}
+
+#[test]
+fn open_rejects_db_from_newer_version() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let db_path = tmp.path().join("memory.db");
+    {
+        let conn = db::open_database(&db_path).unwrap();
+        conn.execute(
+            "UPDATE schema_meta SET value = ?1 WHERE key = 'schema_version'",
+            [(CURRENT_SCHEMA_VERSION + 1).to_string()],
+        )
+        .unwrap();
+    }
+
+    let err = db::open_database(&db_path).unwrap_err();
+    assert!(format!("{err:#}").contains("newer Loci version"));
+}
+
+#[test]
+fn open_rejects_mismatched_embedding_dimension() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let db_path = tmp.path().join("memory.db");
+    db::open_database(&db_path).unwrap();
+
+    let options = db::OpenOptions {
+        embedding_dim: Some(768),
+        ..Default::default()
+    };
+    let err = db::open_database_with(&db_path, &options).unwrap_err();
+    let message = format!("{err:#}");
+    assert!(message.contains("384-dimensional"), "{message}");
+    assert!(message.contains("loci re-embed"), "{message}");
+
+    // Skipping the check (as re-embed does) still opens it
+    let options = db::OpenOptions {
+        embedding_dim: None,
+        ..Default::default()
+    };
+    db::open_database_with(&db_path, &options).unwrap();
+}
+
+#[cfg(feature = "sqlcipher")]
+#[test]
+fn encrypted_db_requires_key() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let db_path = tmp.path().join("memory.db");
+    let keyed = |key: &str| db::OpenOptions {
+        encryption_key: Some(key.into()),
+        ..Default::default()
+    };
+    db::open_database_with(&db_path, &keyed("correct horse")).unwrap();
+
+    let err = db::open_database(&db_path).unwrap_err();
+    assert!(format!("{err:#}").contains("encryption key is wrong"), "{err:#}");
+
+    let err = db::open_database_with(&db_path, &keyed("wrong")).unwrap_err();
+    assert!(format!("{err:#}").contains("encryption key is wrong"), "{err:#}");
+
+    let conn = db::open_database_with(&db_path, &keyed("correct horse")).unwrap();
+    assert_eq!(get_schema_version(&conn).unwrap(), CURRENT_SCHEMA_VERSION);
+}
+
+#[test]
+fn downgrade_to_v1_removes_embedding_model_and_backs_up() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let db_path = tmp.path().join("memory.db");
+    drop(db::open_database(&db_path).unwrap());
+
+    let from = db::downgrade_database(&db_path, 1, &db::OpenOptions::default()).unwrap();
+    assert_eq!(from, CURRENT_SCHEMA_VERSION);
+
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    assert_eq!(get_schema_version(&conn).unwrap(), 1);
+    assert!(get_embedding_model(&conn).unwrap().is_none());
+    drop(conn);
+    assert!(tmp.path().join(format!("memory.db.v{CURRENT_SCHEMA_VERSION}.bak")).exists());
+
+    // Opening with this binary upgrades it again
+    let conn = db::open_database(&db_path).unwrap();
+    assert_eq!(get_schema_version(&conn).unwrap(), CURRENT_SCHEMA_VERSION);
+    assert!(get_embedding_model(&conn).unwrap().is_some());
+}
@@ -2,8 +2,9 @@ mod helpers;
 
 use helpers::{test_db, test_embedding};
 use loci::memory::forget::forget_memory;
-use loci::memory::relations::store_relation;
-use loci::memory::search::inspect_memory;
+use loci::config::RelationsConfig;
+use loci::memory::relations::{store_bidirectional_relation, store_relation};
+use loci::memory::search::{incoming_relations, inspect_memory};
 use loci::memory::store::store_memory;
 use loci::memory::types::{MemoryType, Scope};
 
@@ -33,6 +34,38 @@ fn store_and_inspect_relation() {
     assert_eq!(relations[0].predicate, "works_at");
 }
 
+#[test]
+fn bidirectional_relation_is_queryable_from_both_sides() {
+    let mut conn = test_db();
+
+    let alice_id = store_memory(
+        &mut conn, "Alice is an engineering manager", MemoryType::Entity, Scope::Global,
+        Some("default"), 1.0, None, None, &test_embedding(0), 0.92,
+    ).unwrap().id;
+    let bob_id = store_memory(
+        &mut conn, "Bob is a backend engineer", MemoryType::Entity, Scope::Global,
+        Some("default"), 1.0, None, None, &test_embedding(100), 0.92,
+    ).unwrap().id;
+
+    store_bidirectional_relation(
+        &mut conn, &alice_id, "manages", &bob_id, "managed_by", &RelationsConfig::default(),
+    ).unwrap();
+
+    // Each side sees its own outbound edge
+    let alice = inspect_memory(&conn, &alice_id, true, false).unwrap();
+    assert_eq!(alice.relations.unwrap()[0].predicate, "manages");
+    let bob = inspect_memory(&conn, &bob_id, true, false).unwrap();
+    let bob_relations = bob.relations.unwrap();
+    assert_eq!(bob_relations[0].predicate, "managed_by");
+    assert_eq!(bob_relations[0].object.id, alice_id);
+
+    // ...and the other's edge as incoming
+    let incoming = incoming_relations(&conn, &bob_id).unwrap().unwrap();
+    assert_eq!(incoming.len(), 1);
+    assert_eq!(incoming[0].predicate, "manages");
+    assert_eq!(incoming[0].subject.id, alice_id);
+}
+
 #[test]
 fn relation_dedup_is_idempotent() {
     let mut conn = test_db();
@@ -79,3 +112,37 @@ fn cascade_delete_removes_relations() {
         .unwrap();
     assert_eq!(count, 0, "cascade should remove relation when entity deleted");
 }
+
+#[test]
+fn incoming_relations_list_every_subject() {
+    let mut conn = test_db();
+
+    let a = store_memory(
+        &mut conn, "Ana is a developer", MemoryType::Entity, Scope::Global,
+        Some("default"), 1.0, None, None, &test_embedding(0), 0.92,
+    ).unwrap().id;
+    let b = store_memory(
+        &mut conn, "Ben is a team lead", MemoryType::Entity, Scope::Global,
+        Some("default"), 1.0, None, None, &test_embedding(100), 0.92,
+    ).unwrap().id;
+    let c = store_memory(
+        &mut conn, "Cleo is a tester", MemoryType::Entity, Scope::Global,
+        Some("default"), 1.0, None, None, &test_embedding(200), 0.92,
+    ).unwrap().id;
+
+    store_relation(&conn, &a, "reports_to", &b).unwrap();
+    store_relation(&conn, &c, "reports_to", &b).unwrap();
+
+    // Inspect stays outbound-only unless incoming relations are asked for
+    let inspect = inspect_memory(&conn, &b, true, false).unwrap();
+    assert!(inspect.relations.is_none());
+    assert!(inspect.incoming_relations.is_none());
+
+    let incoming = incoming_relations(&conn, &b).unwrap().unwrap();
+    let mut subjects: Vec<&str> = incoming.iter().map(|r| r.subject.id.as_str()).collect();
+    subjects.sort();
+    let mut expected = vec![a.as_str(), c.as_str()];
+    expected.sort();
+    assert_eq!(subjects, expected);
+    assert!(incoming.iter().all(|r| r.predicate == "reports_to"));
+}
@@ -1,4 +1,11 @@
+mod helpers;
+
+use helpers::{test_db, test_embedding};
 use loci::db;
+use loci::memory::forget::forget_memory;
+use loci::memory::stats::memory_stats;
+use loci::memory::store::store_memory;
+use loci::memory::types::{MemoryType, Scope};
 use tempfile::TempDir;
 
 #[test]
@@ -50,3 +57,90 @@ fn busy_timeout_is_set() {
         .unwrap();
     assert_eq!(timeout, 5000);
 }
+
+#[test]
+fn checkpoint_truncates_wal() {
+    let tmp = TempDir::new().unwrap();
+    let db_path = tmp.path().join("test.db");
+    let wal_path = tmp.path().join("test.db-wal");
+
+    let conn = db::open_database(&db_path).unwrap();
+    conn.execute(
+        "INSERT INTO schema_meta (key, value) VALUES ('checkpoint_probe', 'x')",
+        [],
+    )
+    .unwrap();
+    assert!(std::fs::metadata(&wal_path).unwrap().len() > 0);
+
+    assert!(db::checkpoint_wal(&conn).unwrap());
+    assert_eq!(std::fs::metadata(&wal_path).unwrap().len(), 0);
+}
+
+#[test]
+fn reindex_restores_keyword_search_after_fts_drift() {
+    let mut conn = test_db();
+    let id = store_memory(
+        &mut conn, "Quokkas live on Rottnest Island", MemoryType::Semantic, Scope::Global,
+        Some("default"), 1.0, None, None, &test_embedding(0), 0.92,
+    ).unwrap().id;
+    let keyword_hits = |conn: &rusqlite::Connection| -> i64 {
+        conn.query_row(
+            "SELECT COUNT(*) FROM memories_fts WHERE memories_fts MATCH 'quokkas'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap()
+    };
+
+    // Drop the FTS row behind the memories table's back
+    let rowid: i64 = conn
+        .query_row("SELECT rowid FROM memories WHERE id = ?1", [&id], |row| row.get(0))
+        .unwrap();
+    conn.execute(
+        "INSERT INTO memories_fts(memories_fts, rowid, content, id, type) \
+         VALUES('delete', ?1, 'Quokkas live on Rottnest Island', ?2, 'semantic')",
+        rusqlite::params![rowid, id],
+    )
+    .unwrap();
+    assert_eq!(keyword_hits(&conn), 0);
+    let report = db::check_database_health(&conn).unwrap();
+    assert_eq!((report.memory_count, report.fts_count), (1, 0));
+
+    assert_eq!(db::rebuild_fts(&conn).unwrap(), 1);
+    assert_eq!(keyword_hits(&conn), 1);
+    assert_eq!(db::fts_row_count(&conn).unwrap(), 1);
+}
+
+#[test]
+fn vacuum_shrinks_db_after_mass_delete() {
+    let tmp = TempDir::new().unwrap();
+    let db_path = tmp.path().join("test.db");
+    let mut conn = db::open_database(&db_path).unwrap();
+
+    let mut ids = Vec::new();
+    for i in 0..200u8 {
+        let content = format!("Memory {i}: {}", "padding ".repeat(250));
+        ids.push(
+            store_memory(
+                &mut conn, &content, MemoryType::Episodic, Scope::Global,
+                Some("default"), 1.0, None, None, &test_embedding(i), 0.92,
+            ).unwrap().id,
+        );
+    }
+    assert!(db::checkpoint_wal(&conn).unwrap());
+    let db_size = |conn: &rusqlite::Connection| {
+        memory_stats(conn, None, Some(&db_path)).unwrap().db_size_bytes
+    };
+    let populated = db_size(&conn);
+
+    for id in &ids {
+        forget_memory(&mut conn, id, None, true).unwrap();
+    }
+    assert!(db::checkpoint_wal(&conn).unwrap());
+    // Deleted pages stay in the file until it is vacuumed
+    let before_vacuum = db_size(&conn);
+    assert!(before_vacuum >= populated);
+
+    db::vacuum(&conn).unwrap();
+    assert!(db_size(&conn) < before_vacuum);
+}
@@ -0,0 +1,226 @@
+mod helpers;
+
+use helpers::test_embedding;
+use loci::db::shard::Databases;
+use loci::db::{self, OpenOptions};
+use loci::memory::store;
+use loci::memory::types::{MemoryType, Scope};
+
+fn sharded(tmp: &tempfile::TempDir) -> Databases {
+    let main_path = tmp.path().join("memory.db");
+    let options = OpenOptions::default();
+    let conn = db::open_database_with(&main_path, &options).unwrap();
+    Databases::sharded(&main_path, conn, options)
+}
+
+fn store_in(dbs: &Databases, content: &str, scope: Scope, group: &str, seed: u8) -> String {
+    let handle = dbs.for_write(scope, group).unwrap();
+    let mut conn = handle.conn.lock().unwrap();
+    store::store_memory(
+        &mut conn, content, MemoryType::Semantic, scope, Some(group),
+        1.0, None, None, &test_embedding(seed), 0.92,
+    )
+    .unwrap()
+    .id
+}
+
+#[test]
+fn group_memories_are_written_to_their_shard() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let dbs = sharded(&tmp);
+
+    store_in(&dbs, "Group A event", Scope::Group, "project-a", 1);
+    store_in(&dbs, "Global fact", Scope::Global, "project-a", 2);
+
+    assert!(tmp.path().join("groups").join("project-a.db").exists());
+
+    let shard = dbs.for_write(Scope::Group, "project-a").unwrap();
+    let shard_count: i64 = shard
+        .conn
+        .lock()
+        .unwrap()
+        .query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(shard_count, 1);
+
+    let main = dbs.for_write(Scope::Global, "project-a").unwrap();
+    assert_eq!(main.path, tmp.path().join("memory.db"));
+    let main_count: i64 = main
+        .conn
+        .lock()
+        .unwrap()
+        .query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(main_count, 1);
+}
+
+#[test]
+fn group_reads_include_main_database() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let dbs = sharded(&tmp);
+    store_in(&dbs, "Group A event", Scope::Group, "project-a", 1);
+
+    let handles = dbs.for_group("project-a").unwrap();
+    assert_eq!(handles.len(), 2);
+    assert_eq!(handles[1].path, tmp.path().join("memory.db"));
+}
+
+#[test]
+fn reads_never_create_shards() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let dbs = sharded(&tmp);
+    store_in(&dbs, "Group A event", Scope::Group, "project-a", 1);
+
+    // A typo'd group reads only the main database and leaves no file behind
+    let handles = dbs.for_group("projcet-a").unwrap();
+    assert_eq!(handles.len(), 1);
+    assert_eq!(handles[0].path, tmp.path().join("memory.db"));
+    assert_eq!(dbs.for_groups(&["projcet-a".into(), "project-a".into()]).unwrap().len(), 2);
+    assert!(!tmp.path().join("groups").join("projcet-a.db").exists());
+    assert_eq!(dbs.all().unwrap().len(), 2);
+}
+
+#[test]
+fn resolve_finds_memory_in_any_shard() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let dbs = sharded(&tmp);
+
+    let id_a = store_in(&dbs, "Group A event", Scope::Group, "project-a", 1);
+    let id_b = store_in(&dbs, "Group B event", Scope::Group, "project-b", 2);
+
+    assert_eq!(dbs.all().unwrap().len(), 3);
+    let (found, id) = dbs.resolve(&id_b).unwrap();
+    assert_eq!(found.path, tmp.path().join("groups").join("project-b.db"));
+    assert_eq!(id, id_b);
+    let (found, _) = dbs.resolve(&id_a).unwrap();
+    assert_eq!(found.path, tmp.path().join("groups").join("project-a.db"));
+    assert!(dbs.resolve("missing").is_err());
+}
+
+#[test]
+fn unsafe_group_names_are_rejected() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let dbs = sharded(&tmp);
+
+    assert!(dbs.for_write(Scope::Group, "../escape").is_err());
+    assert!(dbs.for_write(Scope::Group, ".hidden").is_err());
+    // Global writes never touch a shard, so the name doesn't matter
+    assert!(dbs.for_write(Scope::Global, "../escape").is_ok());
+}
+
+#[test]
+fn single_mode_routes_everything_to_main() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let main_path = tmp.path().join("memory.db");
+    let conn = db::open_database_with(&main_path, &OpenOptions::default()).unwrap();
+    let dbs = Databases::single(&main_path, conn);
+
+    assert_eq!(dbs.for_write(Scope::Group, "project-a").unwrap().path, main_path);
+    assert_eq!(dbs.for_group("project-a").unwrap().len(), 1);
+    assert!(!tmp.path().join("groups").exists());
+}
+
+#[test]
+fn multi_group_reads_include_each_shard_once_plus_main() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let dbs = sharded(&tmp);
+    store_in(&dbs, "Group A event", Scope::Group, "project-a", 1);
+    store_in(&dbs, "Group B event", Scope::Group, "project-b", 2);
+
+    let handles = dbs
+        .for_groups(&["project-a".into(), "project-b".into(), "project-a".into()])
+        .unwrap();
+    assert_eq!(handles.len(), 3);
+    assert_eq!(handles[0].path, tmp.path().join("groups").join("project-a.db"));
+    assert_eq!(handles[1].path, tmp.path().join("groups").join("project-b.db"));
+    assert_eq!(handles[2].path, tmp.path().join("memory.db"));
+}
+
+/// Embeds every text as the same fixed vector.
+struct FixedEmbedding(Vec<f32>);
+
+impl loci::embedding::EmbeddingProvider for FixedEmbedding {
+    fn embed(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
+        Ok(self.0.clone())
+    }
+}
+
+#[tokio::test]
+async fn typed_recall_searches_the_group_shard_and_main() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let dbs = std::sync::Arc::new(sharded(&tmp));
+
+    let group_id = store_in(&dbs, "Deploy runbook for project A", Scope::Group, "project-a", 1);
+    let global_id = store_in(&dbs, "Deploys go out on Tuesdays", Scope::Global, "project-a", 1);
+    store_in(&dbs, "Deploy notes for project B", Scope::Group, "project-b", 1);
+
+    let filter = loci::memory::search::SearchFilter {
+        group: "project-a".into(),
+        ..Default::default()
+    };
+    let response = loci::memory::recall::recall(
+        dbs,
+        std::sync::Arc::new(FixedEmbedding(test_embedding(1))),
+        &loci::config::LociConfig::default(),
+        "deploy",
+        filter,
+        loci::memory::search::SearchConfig::default(),
+    )
+    .await
+    .unwrap();
+
+    let mut ids: Vec<String> = response.results.into_iter().map(|r| r.id).collect();
+    ids.sort();
+    let mut expected = vec![group_id, global_id];
+    expected.sort();
+    assert_eq!(ids, expected);
+    assert!(!response.degraded);
+}
+
+#[test]
+fn pooled_recalls_run_concurrently() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let main_path = tmp.path().join("memory.db");
+    let conn = db::open_database_with(&main_path, &OpenOptions::default()).unwrap();
+    let dbs = std::sync::Arc::new(Databases::single(&main_path, conn).with_read_pool(2, None));
+    store_in(&dbs, "Deploys go out on Tuesdays", Scope::Global, "default", 1);
+
+    let handle = dbs.for_write(Scope::Global, "default").unwrap();
+    // Hold the write connection throughout: pooled reads must not need it
+    let _writer = handle.conn.lock().unwrap();
+
+    let barrier = std::sync::Arc::new(std::sync::Barrier::new(2));
+    let spans: Vec<_> = (0..2)
+        .map(|_| {
+            let handle = handle.clone();
+            let barrier = std::sync::Arc::clone(&barrier);
+            std::thread::spawn(move || {
+                barrier.wait();
+                let conn = handle.read().unwrap();
+                let started = std::time::Instant::now();
+                let response = loci::memory::search::recall_by_query(
+                    &conn,
+                    &test_embedding(1),
+                    "deploys",
+                    &loci::memory::search::SearchFilter::default(),
+                    &loci::memory::search::SearchConfig {
+                        track_access: false,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+                assert_eq!(response.results.len(), 1);
+                // Keep the connection checked out long enough to observe overlap
+                std::thread::sleep(std::time::Duration::from_millis(200));
+                (started, std::time::Instant::now())
+            })
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|thread| thread.join().unwrap())
+        .collect();
+
+    let latest_start = spans.iter().map(|(start, _)| *start).max().unwrap();
+    let earliest_end = spans.iter().map(|(_, end)| *end).min().unwrap();
+    assert!(latest_start < earliest_end, "recalls were serialized: {spans:?}");
+}
@@ -50,11 +50,13 @@ fn superseded_memory_excluded_from_search() {
         scope: None,
         group: "default".to_string(),
         min_confidence: 0.0,
+        ..Default::default()
     };
     let config = SearchConfig {
         max_results: 10,
         token_budget: 10000,
         rrf_k: 60,
+        ..Default::default()
     };
 
     let response = recall_by_query(&conn, &emb_a, "user prefers", &filter, &config).unwrap();
@@ -1,7 +1,8 @@
 mod helpers;
 
-use helpers::{insert_memory, test_db, test_embedding};
+use helpers::{insert_memory, similar_embedding, test_db, test_embedding};
 use loci::memory::search::{recall_by_ids, recall_by_query, to_summary, SearchConfig, SearchFilter};
+use loci::memory::store::{store_memory_batch, NewMemory, StoreOptions};
 use loci::memory::types::{MemoryType, Scope};
 
 #[test]
@@ -27,11 +28,13 @@ fn store_and_recall_by_query() {
         scope: None,
         group: "project-x".to_string(),
         min_confidence: 0.0,
+        ..Default::default()
     };
     let config = SearchConfig {
         max_results: 10,
         token_budget: 10000,
         rrf_k: 60,
+        ..Default::default()
     };
 
     let response = recall_by_query(&conn, &emb_a, "deployed friday", &filter, &config).unwrap();
@@ -72,11 +75,13 @@ fn recall_with_type_filter() {
         scope: None,
         group: "default".to_string(),
         min_confidence: 0.0,
+        ..Default::default()
     };
     let config = SearchConfig {
         max_results: 10,
         token_budget: 10000,
         rrf_k: 60,
+        ..Default::default()
     };
 
     let response = recall_by_query(&conn, &test_embedding(100), "semantic", &filter, &config).unwrap();
@@ -101,11 +106,13 @@ fn summary_only_truncates_content() {
         scope: None,
         group: "default".to_string(),
         min_confidence: 0.0,
+        ..Default::default()
     };
     let config = SearchConfig {
         max_results: 10,
         token_budget: 10000,
         rrf_k: 60,
+        ..Default::default()
     };
 
     let response = recall_by_query(&conn, &emb, "test", &filter, &config).unwrap();
@@ -114,3 +121,50 @@ fn summary_only_truncates_content() {
     // Preview should be truncated (80 chars + "...")
     assert!(summary.results[0].preview.len() <= 83);
 }
+
+#[test]
+fn store_memory_batch_stores_in_order_and_dedups_within_batch() {
+    let mut conn = test_db();
+    let contents = [
+        "User prefers dark mode",
+        "Deploys happen on Fridays",
+        "The API gateway runs on port 8443",
+        "Alice owns the billing service",
+        "User prefers a dark theme",
+    ];
+    let memories: Vec<NewMemory> = contents
+        .iter()
+        .map(|content| NewMemory {
+            content,
+            memory_type: MemoryType::Semantic,
+            scope: Scope::Global,
+            group: Some("default"),
+            confidence: 1.0,
+            importance: 0.5,
+            metadata: None,
+            supersedes: None,
+            source_uri: None,
+            id: None,
+            session_id: None,
+            expires_at: None,
+            tags: None,
+        })
+        .collect();
+    // The last memory is a paraphrase of the first
+    let mut embeddings: Vec<Vec<f32>> = (0..4).map(|i| test_embedding(i * 50)).collect();
+    embeddings.push(similar_embedding(&embeddings[0]));
+
+    let results = store_memory_batch(&mut conn, &memories, &embeddings, &StoreOptions::default()).unwrap();
+
+    assert_eq!(results.len(), 5);
+    assert!(results[..4].iter().all(|r| !r.deduplicated));
+    assert!(results[4].deduplicated, "paraphrase should fold into the earlier item");
+    assert_eq!(results[4].id, results[0].id);
+
+    let ids: Vec<String> = results[..4].iter().map(|r| r.id.clone()).collect();
+    let hydrated = recall_by_ids(&conn, &ids).unwrap();
+    let stored: Vec<&str> = hydrated.results.iter().map(|r| r.content.as_str()).collect();
+    for content in &contents[..4] {
+        assert!(stored.contains(content), "missing {content}");
+    }
+}